@@ -0,0 +1,114 @@
+//! The hash functions used to fold map indices and fingerprint contents, selectable with [`HashKind`],
+//! to tune the collision behavior and speed for a map size.
+
+use serde::{Deserialize, Serialize};
+use xxhash_rust::xxh3::xxh3_64;
+
+/// The FNV-1a 64 bit offset basis
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+/// The FNV-1a 64 bit prime
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+/// The multiplier of `MurmurHash64A`
+const MURMUR_M: u64 = 0xc6a4_a793_5bd1_e995;
+/// The shift of `MurmurHash64A`
+const MURMUR_R: u32 = 47;
+
+/// A hash function, for indices and contents
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashKind {
+    /// FNV-1a, simple and fast on short inputs, but with weaker mixing of the low bits
+    Fnv,
+    /// XXH3, fast on any input length and well distributed, the default
+    XxHash,
+    /// `MurmurHash64A`, well distributed, a middle ground for short inputs
+    Murmur,
+}
+
+impl Default for HashKind {
+    fn default() -> Self {
+        Self::XxHash
+    }
+}
+
+impl HashKind {
+    /// Hashes the given bytes
+    #[must_use]
+    pub fn hash_bytes(self, bytes: &[u8]) -> u64 {
+        match self {
+            HashKind::Fnv => fnv1a_64(bytes),
+            HashKind::XxHash => xxh3_64(bytes),
+            HashKind::Murmur => murmur64a(bytes),
+        }
+    }
+
+    /// Hashes an index, or any other integer
+    #[must_use]
+    pub fn hash_u64(self, val: u64) -> u64 {
+        self.hash_bytes(&val.to_le_bytes())
+    }
+}
+
+/// The 64 bit FNV-1a hash of the given bytes
+#[must_use]
+pub fn fnv1a_64(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(FNV_OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// The `MurmurHash64A` hash of the given bytes, with seed `0`
+#[must_use]
+pub fn murmur64a(bytes: &[u8]) -> u64 {
+    let mut hash = (bytes.len() as u64).wrapping_mul(MURMUR_M);
+
+    let chunks = bytes.chunks_exact(8);
+    let rest = chunks.remainder();
+    for chunk in chunks {
+        let mut k = u64::from_le_bytes(chunk.try_into().unwrap());
+        k = k.wrapping_mul(MURMUR_M);
+        k ^= k >> MURMUR_R;
+        k = k.wrapping_mul(MURMUR_M);
+        hash ^= k;
+        hash = hash.wrapping_mul(MURMUR_M);
+    }
+    if !rest.is_empty() {
+        for (i, byte) in rest.iter().enumerate() {
+            hash ^= u64::from(*byte) << (8 * i);
+        }
+        hash = hash.wrapping_mul(MURMUR_M);
+    }
+
+    hash ^= hash >> MURMUR_R;
+    hash = hash.wrapping_mul(MURMUR_M);
+    hash ^ (hash >> MURMUR_R)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bolts::hash::{fnv1a_64, HashKind};
+
+    #[test]
+    fn test_hash_kinds() {
+        // Known FNV-1a values
+        assert_eq!(fnv1a_64(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a_64(b"a"), 0xaf63_dc4c_8601_ec8c);
+
+        let kinds = [HashKind::Fnv, HashKind::XxHash, HashKind::Murmur];
+        let buckets = kinds.map(|kind| {
+            assert_eq!(kind.hash_bytes(b"libafl"), kind.hash_bytes(b"libafl"));
+            assert_ne!(kind.hash_bytes(b"libafl"), kind.hash_bytes(b"libafm"));
+
+            // Fold 1024 indices into 64 buckets
+            let mut buckets = [0_usize; 64];
+            for idx in 0..1024 {
+                buckets[(kind.hash_u64(idx) % 64) as usize] += 1;
+            }
+            assert!(buckets.iter().all(|count| *count > 0));
+            buckets
+        });
+        // Each kind collides differently
+        assert_ne!(buckets[0], buckets[1]);
+        assert_ne!(buckets[0], buckets[2]);
+        assert_ne!(buckets[1], buckets[2]);
+    }
+}
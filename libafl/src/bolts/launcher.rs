@@ -17,7 +17,9 @@ use crate::bolts::os::{dup2, fork, ForkResult};
 #[cfg(feature = "std")]
 use crate::{
     bolts::{os::Cores, shmem::ShMemProvider},
-    events::{EventConfig, LlmpRestartingEventManager, ManagerKind, RestartingMgr},
+    events::{
+        CorpusSharingPolicy, EventConfig, LlmpRestartingEventManager, ManagerKind, RestartingMgr,
+    },
     inputs::Input,
     monitors::Monitor,
     observers::ObserversTuple,
@@ -82,6 +84,13 @@ where
     /// Then, clients launched by this [`Launcher`] can connect to the original `broker`.
     #[builder(default = true)]
     spawn_broker: bool,
+    /// The base seed of the whole run, to give each client a deterministic seed derived from its core.
+    /// See [`LlmpRestartingEventManager::client_seed`] and [`LlmpRestartingEventManager::seed_rand`].
+    #[builder(default = None)]
+    base_seed: Option<u64>,
+    /// The policy of the broker deciding which new testcases get forwarded to the other clients
+    #[builder(default = CorpusSharingPolicy::All)]
+    corpus_sharing_policy: CorpusSharingPolicy,
     #[builder(setter(skip), default = PhantomData)]
     phantom_data: PhantomData<(&'a I, &'a OT, &'a S, &'a SP)>,
 }
@@ -171,6 +180,7 @@ where
                                 cpu_core: Some(*bind_to),
                             })
                             .configuration(self.configuration)
+                            .base_seed(self.base_seed)
                             .build()
                             .launch()?;
 
@@ -194,6 +204,7 @@ where
                 .kind(ManagerKind::Broker)
                 .remote_broker_addr(self.remote_broker_addr)
                 .configuration(self.configuration)
+                .corpus_sharing_policy(self.corpus_sharing_policy)
                 .build()
                 .launch()?;
 
@@ -239,6 +250,7 @@ where
                         cpu_core: Some(CoreId { id: core_id }),
                     })
                     .configuration(self.configuration)
+                    .base_seed(self.base_seed)
                     .build()
                     .launch()?;
 
@@ -292,6 +304,7 @@ where
                 .kind(ManagerKind::Broker)
                 .remote_broker_addr(self.remote_broker_addr)
                 .configuration(self.configuration)
+                .corpus_sharing_policy(self.corpus_sharing_policy)
                 .build()
                 .launch()?;
 
@@ -1920,6 +1920,21 @@ where
     pub fn loop_forever<F>(&mut self, on_new_msg: &mut F, sleep_time: Option<Duration>)
     where
         F: FnMut(ClientId, Tag, Flags, &[u8]) -> Result<LlmpMsgHookResult, Error>,
+    {
+        self.loop_forever_with(on_new_msg, &mut |_| Ok(()), sleep_time);
+    }
+
+    /// Loops like [`LlmpBroker::loop_forever`], calling `after_once` after each round of handled
+    /// messages, for example to send replies to them using [`LlmpBroker::send_buf`].
+    /// Never returns. Panics on error.
+    pub fn loop_forever_with<F, A>(
+        &mut self,
+        on_new_msg: &mut F,
+        after_once: &mut A,
+        sleep_time: Option<Duration>,
+    ) where
+        F: FnMut(ClientId, Tag, Flags, &[u8]) -> Result<LlmpMsgHookResult, Error>,
+        A: FnMut(&mut Self) -> Result<(), Error>,
     {
         #[cfg(unix)]
         if let Err(_e) = unsafe { setup_signal_handler(&mut GLOBAL_SIGHANDLER_STATE) } {
@@ -1931,6 +1946,7 @@ where
         while !self.is_shutting_down() {
             self.once(on_new_msg)
                 .expect("An error occurred when brokering. Exiting.");
+            after_once(self).expect("An error occurred when brokering. Exiting.");
 
             #[cfg(feature = "std")]
             if let Some(time) = sleep_time {
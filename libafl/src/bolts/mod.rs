@@ -5,6 +5,7 @@ pub mod compress;
 pub mod cpu;
 #[cfg(feature = "std")]
 pub mod fs;
+pub mod hash;
 #[cfg(feature = "std")]
 pub mod launcher;
 pub mod llmp;
@@ -244,6 +244,12 @@ pub unsafe fn setup_signal_handler<T: 'static + Handler>(handler: &mut T) -> Res
     Ok(())
 }
 
+/// Returns `true` if a handler got registered for this signal using [`setup_signal_handler()`]
+#[must_use]
+pub fn signal_handler_installed(signal: Signal) -> bool {
+    unsafe { SIGNAL_HANDLERS[signal as usize].is_some() }
+}
+
 /// Function to get the current [`ucontext_t`] for this process.
 /// This calls the libc `getcontext` function under the hood.
 /// It can be useful, for example for `dump_regs`.
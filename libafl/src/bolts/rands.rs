@@ -11,6 +11,15 @@ use rand_core::{self, impls::fill_bytes_via_next, RngCore};
 
 const HASH_CONST: u64 = 0xa5b35705;
 
+/// The `SplitMix64` finalizer, mapping close inputs to well-separated outputs
+#[allow(clippy::unreadable_literal)]
+pub(crate) const fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
 /// The standard rand implementation for `LibAFL`.
 /// It is usually the right choice, with very good speed and a reasonable randomness.
 /// Not cryptographically secure (which is not what you want during fuzzing ;) )
@@ -76,6 +85,38 @@ pub trait Rand: Debug + Serialize + DeserializeOwned {
         // return the item chosen
         iter.nth(index).unwrap()
     }
+
+    /// Choose an index into `weights` at random, each with a probability proportional to its weight.
+    /// Returns `None` if all weights are `0`, or there are none.
+    fn choose_weighted(&mut self, weights: &[u64]) -> Option<usize> {
+        let total: u64 = weights.iter().sum();
+        if total == 0 {
+            return None;
+        }
+        let mut pick = self.below(total);
+        for (idx, weight) in weights.iter().enumerate() {
+            if pick < *weight {
+                return Some(idx);
+            }
+            pick -= weight;
+        }
+        unreachable!()
+    }
+
+    /// Derives an independent, but deterministic, stream from this rand, for example one per client.
+    /// The new stream is seeded from the current state of this rand and the `stream_id`, mixed
+    /// using `SplitMix64`, so different ids yield well-separated streams, and the same state and id
+    /// always yield the same stream. This rand is left untouched.
+    #[must_use]
+    fn split(&self, stream_id: u64) -> Self
+    where
+        Self: Clone,
+    {
+        let mut rand = self.clone();
+        let state = rand.next();
+        rand.set_seed(splitmix64(state ^ splitmix64(stream_id)));
+        rand
+    }
 }
 
 // helper macro for deriving Default
@@ -389,6 +430,8 @@ mod tests {
         assert_eq!(rand.below(1), 0);
         assert_eq!(rand.between(10, 10), 10);
         assert!(rand.between(11, 20) > 10);
+        assert_eq!(rand.choose_weighted(&[0, 3, 0]), Some(1));
+        assert_eq!(rand.choose_weighted(&[0, 0]), None);
     }
 
     #[test]
@@ -414,6 +457,23 @@ mod tests {
         test_single_rand(&mut rand);
     }
 
+    #[test]
+    fn test_rand_split() {
+        let base = StdRand::with_seed(1337);
+        let mut first = base.split(0);
+        let mut second = base.split(1);
+        let mut first_again = base.split(0);
+
+        // On average, half of the bits of two independent streams differ
+        let mut differing_bits = 0;
+        for _ in 0..1000 {
+            let value = first.next();
+            assert_eq!(value, first_again.next());
+            differing_bits += (value ^ second.next()).count_ones();
+        }
+        assert!((30_000..34_000).contains(&differing_bits));
+    }
+
     #[test]
     #[cfg(feature = "rand_trait")]
     fn test_rgn_core_support() {
@@ -1,6 +1,9 @@
 //! Poor-rust-man's downcasts for stuff we send over the wire (or shared maps)
 
-use serde::{de::DeserializeSeed, Deserialize, Deserializer, Serialize, Serializer};
+use serde::{
+    de::{DeserializeOwned, DeserializeSeed},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
 
 use alloc::boxed::Box;
 use core::{
@@ -8,6 +11,8 @@ use core::{
     fmt::Debug,
 };
 
+use crate::Error;
+
 // yolo
 
 /// Get a `type_id` from its previously unpacked `u64`.
@@ -58,6 +63,95 @@ where
     }
 }
 
+/// Versioning for [`SerdeAny`] types, so that states saved by older releases can still be loaded
+/// after the layout of a metadata changed.
+/// Register implementors using [`RegistryBuilder::register_versioned`], or `impl_serdeany!(Type, versioned)`.
+pub trait SerdeAnyVersioned: Sized + DeserializeOwned {
+    /// The current version of the serialized layout. Bump it each time the layout changes.
+    const VERSION: u32 = 0;
+
+    /// Upgrades `bytes`, serialized (using `postcard`) by version `old_version` of this type.
+    /// The default implementation assumes the layout did not change.
+    fn migrate(_old_version: u32, bytes: &[u8]) -> Result<Self, Error> {
+        Ok(postcard::from_bytes(bytes)?)
+    }
+}
+
+/// The start of the deserialization error for types missing from the registry.
+/// The error is created with [`serde::de::Error::custom`], so it travels through any deserializer
+/// keeping custom messages, and [`unknown_serde_type`] turns it back into the type name.
+/// `postcard` drops custom messages, so its errors can not be told apart.
+const UNKNOWN_SERDE_TYPE_PREFIX: &str = "Cannot deserialize the unregistered SerdeAny type `";
+
+/// The deserialization error for the type `type_name`, missing from the registry
+#[must_use]
+pub fn unknown_serde_type_error<E>(type_name: &str) -> E
+where
+    E: serde::de::Error,
+{
+    E::custom(format_args!("{}{}`", UNKNOWN_SERDE_TYPE_PREFIX, type_name))
+}
+
+/// The name of the unregistered type, if `msg` is the message of an error made by
+/// [`unknown_serde_type_error`]
+#[must_use]
+pub fn unknown_serde_type(msg: &str) -> Option<&str> {
+    let start = msg.find(UNKNOWN_SERDE_TYPE_PREFIX)? + UNKNOWN_SERDE_TYPE_PREFIX.len();
+    let len = msg[start..].find('`')?;
+    Some(&msg[start..start + len])
+}
+
+/// `true` if each serialized [`SerdeAny`] carries its type name, with the `serdeany_type_names`
+/// feature, so that types missing from the registry of another build can be reported by name.
+pub const SERIALIZE_TYPE_NAMES: bool = cfg!(feature = "serdeany_type_names");
+
+/// The serialized layouts of a [`SerdeAny`] in a [`SerdeAnyMap`], all starting with the type id.
+/// They have different lengths, so each build reads the layouts of the others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SerdeAnyEntryLayout {
+    /// `(id, value)`, for types that are not versioned, as written before versioning existed
+    Inline,
+    /// `(id, version, bytes)`, for [`SerdeAnyVersioned`] types
+    Versioned,
+    /// `(id, type name, version, bytes)`, for all types, with [`SERIALIZE_TYPE_NAMES`]
+    Named,
+}
+
+impl SerdeAnyEntryLayout {
+    /// The layout this build writes without type names, for a versioned type or not
+    #[must_use]
+    pub fn of(versioned: bool) -> Self {
+        if versioned {
+            Self::Versioned
+        } else {
+            Self::Inline
+        }
+    }
+
+    /// The layout with `len` elements
+    #[must_use]
+    pub fn from_len(len: usize) -> Self {
+        match len {
+            2 => Self::Inline,
+            3 => Self::Versioned,
+            _ => Self::Named,
+        }
+    }
+
+    /// The number of elements of this layout
+    #[must_use]
+    pub fn element_count(self) -> usize {
+        match self {
+            Self::Inline => 2,
+            Self::Versioned => 3,
+            Self::Named => 4,
+        }
+    }
+}
+
+/// Callback for [`SerdeAny`] migration from an older version, see [`SerdeAnyVersioned`].
+pub type MigrateCallback<B> = fn(u32, &[u8]) -> Result<Box<B>, Error>;
+
 /// Callback for [`SerdeAny`] deserialization.
 pub type DeserializeCallback<B> =
     fn(&mut dyn erased_serde::Deserializer) -> Result<Box<B>, erased_serde::Error>;
@@ -95,7 +189,7 @@ macro_rules! create_serde_registry_for_trait {
         /// A [`crate::bolts::serdeany`] module.
         pub mod $mod_name {
 
-            use alloc::boxed::Box;
+            use alloc::{boxed::Box, string::String, vec::Vec};
             use core::any::{Any, TypeId};
             use core::fmt;
             use postcard;
@@ -105,7 +199,8 @@ macro_rules! create_serde_registry_for_trait {
             use hashbrown::HashMap;
 
             use $crate::bolts::serdeany::{
-                pack_type_id, unpack_type_id, DeserializeCallback, DeserializeCallbackSeed,
+                pack_type_id, unknown_serde_type_error, unpack_type_id, DeserializeCallback,
+                DeserializeCallbackSeed, MigrateCallback, SerdeAnyEntryLayout, SerdeAnyVersioned,
             };
             use $crate::Error;
 
@@ -124,24 +219,78 @@ macro_rules! create_serde_registry_for_trait {
                 where
                     V: serde::de::SeqAccess<'de>,
                 {
-                    let id: u64 = visitor.next_element()?.unwrap();
+                    let id: u64 = visitor
+                        .next_element()?
+                        .ok_or_else(|| serde::de::Error::invalid_length(0, &self))?;
+                    // The layouts are told apart by their length, see `SerdeAnyEntryLayout`.
+                    // Formats not reporting it, like json, are read with the layout of this build.
+                    let layout = match visitor.size_hint() {
+                        Some(remaining) => SerdeAnyEntryLayout::from_len(remaining + 1),
+                        None if $crate::bolts::serdeany::SERIALIZE_TYPE_NAMES => {
+                            SerdeAnyEntryLayout::Named
+                        }
+                        None => SerdeAnyEntryLayout::of(RegistryBuilder::version(id).is_some()),
+                    };
+                    let type_name: Option<String> = if layout == SerdeAnyEntryLayout::Named {
+                        visitor.next_element()?
+                    } else {
+                        None
+                    };
+
                     let cb = unsafe {
-                        *REGISTRY
+                        match REGISTRY
                             .deserializers
                             .as_ref()
-                            .unwrap()
-                            .get(&id)
-                            .expect("Cannot deserialize an unregistered type")
+                            .and_then(|deserializers| deserializers.get(&id))
+                        {
+                            Some(cb) => *cb,
+                            None => {
+                                return Err(unknown_serde_type_error(
+                                    &type_name.unwrap_or_else(|| format!("#{:#x}", id)),
+                                ))
+                            }
+                        }
                     };
-                    let seed = DeserializeCallbackSeed::<dyn $trait_name> { cb };
-                    let obj: Self::Value = visitor.next_element_seed(seed)?.unwrap();
-                    Ok(obj)
+
+                    if layout == SerdeAnyEntryLayout::Inline {
+                        let seed = DeserializeCallbackSeed::<dyn $trait_name> { cb };
+                        return visitor
+                            .next_element_seed(seed)?
+                            .ok_or_else(|| serde::de::Error::invalid_length(1, &self));
+                    }
+
+                    let version: u32 = visitor
+                        .next_element()?
+                        .ok_or_else(|| serde::de::Error::invalid_length(1, &self))?;
+                    let bytes: Vec<u8> = visitor
+                        .next_element()?
+                        .ok_or_else(|| serde::de::Error::invalid_length(2, &self))?;
+                    if let Some((current_version, migrate)) = RegistryBuilder::version(id) {
+                        if version != current_version {
+                            return migrate(version, &bytes).map_err(|err| {
+                                serde::de::Error::custom(format!(
+                                    "Migrating `{}` from version {} failed: {}",
+                                    RegistryBuilder::type_name(id).unwrap_or_default(),
+                                    version,
+                                    err
+                                ))
+                            });
+                        }
+                    }
+
+                    let mut deserializer = postcard::Deserializer::from_bytes(&bytes);
+                    cb(&mut <dyn erased_serde::Deserializer>::erase(
+                        &mut deserializer,
+                    ))
+                    .map_err(serde::de::Error::custom)
                 }
             }
 
             #[allow(unused_qualifications)]
             struct Registry {
                 deserializers: Option<HashMap<u64, DeserializeCallback<dyn $trait_name>>>,
+                type_names: Option<HashMap<u64, &'static str>>,
+                versions: Option<HashMap<u64, (u32, MigrateCallback<dyn $trait_name>)>>,
                 finalized: bool,
             }
 
@@ -153,10 +302,27 @@ macro_rules! create_serde_registry_for_trait {
                 {
                     assert!(!self.finalized, "Registry is already finalized!");
 
+                    let id = unpack_type_id(TypeId::of::<T>());
                     let deserializers = self.deserializers.get_or_insert_with(HashMap::default);
-                    deserializers.insert(unpack_type_id(TypeId::of::<T>()), |de| {
-                        Ok(Box::new(erased_serde::deserialize::<T>(de)?))
-                    });
+                    deserializers
+                        .insert(id, |de| Ok(Box::new(erased_serde::deserialize::<T>(de)?)));
+                    let type_names = self.type_names.get_or_insert_with(HashMap::default);
+                    type_names.insert(id, core::any::type_name::<T>());
+                }
+
+                pub fn register_versioned<T>(&mut self)
+                where
+                    T: $trait_name + Serialize + SerdeAnyVersioned,
+                {
+                    self.register::<T>();
+
+                    let versions = self.versions.get_or_insert_with(HashMap::default);
+                    versions.insert(
+                        unpack_type_id(TypeId::of::<T>()),
+                        (T::VERSION, |old_version, bytes| {
+                            Ok(Box::new(T::migrate(old_version, bytes)?))
+                        }),
+                    );
                 }
 
                 pub fn finalize(&mut self) {
@@ -166,6 +332,8 @@ macro_rules! create_serde_registry_for_trait {
 
             static mut REGISTRY: Registry = Registry {
                 deserializers: None,
+                type_names: None,
+                versions: None,
                 finalized: false,
             };
 
@@ -186,12 +354,59 @@ macro_rules! create_serde_registry_for_trait {
                     }
                 }
 
+                /// Register a given struct type for trait object (de)serialization,
+                /// migrating data serialized by older versions of the type on deserialization.
+                pub fn register_versioned<T>()
+                where
+                    T: $trait_name + Serialize + SerdeAnyVersioned,
+                {
+                    unsafe {
+                        REGISTRY.register_versioned::<T>();
+                    }
+                }
+
                 /// Finalize the registry, no more registrations are allowed after this call
                 pub fn finalize() {
                     unsafe {
                         REGISTRY.finalize();
                     }
                 }
+
+                /// The names of all types registered so far.
+                /// Compare the output of two builds to debug why a saved state can't be loaded.
+                #[must_use]
+                pub fn registered_type_names() -> Vec<&'static str> {
+                    unsafe {
+                        REGISTRY
+                            .type_names
+                            .as_ref()
+                            .map(|type_names| type_names.values().copied().collect())
+                            .unwrap_or_default()
+                    }
+                }
+
+                /// The name of the registered type with the given (unpacked) type id, if any.
+                #[must_use]
+                pub fn type_name(id: u64) -> Option<&'static str> {
+                    unsafe {
+                        REGISTRY
+                            .type_names
+                            .as_ref()
+                            .and_then(|type_names| type_names.get(&id).copied())
+                    }
+                }
+
+                /// The current version and the migration of the versioned type with the given
+                /// (unpacked) type id, if any.
+                #[must_use]
+                pub fn version(id: u64) -> Option<(u32, MigrateCallback<dyn $trait_name>)> {
+                    unsafe {
+                        REGISTRY
+                            .versions
+                            .as_ref()
+                            .and_then(|versions| versions.get(&id).copied())
+                    }
+                }
             }
 
             /// A (de)serializable anymap containing (de)serializable trait objects registered
@@ -591,10 +806,32 @@ macro_rules! create_serde_registry_for_trait {
             {
                 use serde::ser::SerializeSeq;
 
+                use $crate::bolts::serdeany::SerdeAnyEntryLayout;
+
                 let id = $crate::bolts::serdeany::unpack_type_id(self.type_id());
-                let mut seq = se.serialize_seq(Some(2))?;
+                let version = $mod_name::RegistryBuilder::version(id).map(|(v, _)| v);
+                let layout = if $crate::bolts::serdeany::SERIALIZE_TYPE_NAMES {
+                    SerdeAnyEntryLayout::Named
+                } else {
+                    SerdeAnyEntryLayout::of(version.is_some())
+                };
+
+                let mut seq = se.serialize_seq(Some(layout.element_count()))?;
                 seq.serialize_element(&id)?;
-                seq.serialize_element(&$crate::bolts::serdeany::Wrap(self))?;
+                if layout == SerdeAnyEntryLayout::Inline {
+                    seq.serialize_element(&$crate::bolts::serdeany::Wrap(self))?;
+                    return seq.end();
+                }
+                if layout == SerdeAnyEntryLayout::Named {
+                    let type_name =
+                        $mod_name::RegistryBuilder::type_name(id).unwrap_or("<unregistered>");
+                    seq.serialize_element(type_name)?;
+                }
+                // The value is stored as bytes, so that older versions can be handed to the migration
+                let bytes = postcard::to_allocvec(&$crate::bolts::serdeany::Wrap(self))
+                    .map_err(serde::ser::Error::custom)?;
+                seq.serialize_element(&version.unwrap_or(0))?;
+                seq.serialize_element(&bytes)?;
                 seq.end()
             }
         }
@@ -614,11 +851,12 @@ macro_rules! create_serde_registry_for_trait {
 create_serde_registry_for_trait!(serdeany_registry, crate::bolts::serdeany::SerdeAny);
 pub use serdeany_registry::*;
 
-/// Implement a [`SerdeAny`], registering it in the [`RegistryBuilder`]
+/// Implement a [`SerdeAny`], registering it in the [`RegistryBuilder`].
+/// Use `impl_serdeany!(Type, versioned)` for types implementing [`SerdeAnyVersioned`].
 #[cfg(feature = "std")]
 #[macro_export]
 macro_rules! impl_serdeany {
-    ($struct_name:ident) => {
+    (@serdeany $struct_name:ident) => {
         impl $crate::bolts::serdeany::SerdeAny for $struct_name {
             fn as_any(&self) -> &dyn ::core::any::Any {
                 self
@@ -634,6 +872,18 @@ macro_rules! impl_serdeany {
                 self
             }
         }
+    };
+    ($struct_name:ident, versioned) => {
+        $crate::impl_serdeany!(@serdeany $struct_name);
+
+        #[allow(non_snake_case)]
+        #[$crate::ctor]
+        fn $struct_name() {
+            $crate::bolts::serdeany::RegistryBuilder::register_versioned::<$struct_name>();
+        }
+    };
+    ($struct_name:ident) => {
+        $crate::impl_serdeany!(@serdeany $struct_name);
 
         #[allow(non_snake_case)]
         #[$crate::ctor]
@@ -647,6 +897,9 @@ macro_rules! impl_serdeany {
 #[cfg(not(feature = "std"))]
 #[macro_export]
 macro_rules! impl_serdeany {
+    ($struct_name:ident, versioned) => {
+        $crate::impl_serdeany!($struct_name);
+    };
     ($struct_name:ident) => {
         impl $crate::bolts::serdeany::SerdeAny for $struct_name {
             fn as_any(&self) -> &dyn ::core::any::Any {
@@ -665,3 +918,215 @@ macro_rules! impl_serdeany {
         }
     };
 }
+
+#[cfg(feature = "std")]
+#[cfg(test)]
+mod tests {
+    use core::any::TypeId;
+    use hashbrown::HashMap;
+    use serde::{ser::SerializeSeq, Deserialize, Serialize, Serializer};
+
+    use crate::{
+        bolts::serdeany::{
+            unpack_type_id, RegistryBuilder, SerdeAnyMap, SerdeAnyVersioned, SERIALIZE_TYPE_NAMES,
+        },
+        mutators::Tokens,
+        Error,
+    };
+
+    /// A raw metadata entry in the `(id, value)` layout of unversioned types
+    struct RawInlineMetadata<T> {
+        id: u64,
+        value: T,
+    }
+
+    impl<T> Serialize for RawInlineMetadata<T>
+    where
+        T: Serialize,
+    {
+        fn serialize<S>(&self, se: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let mut seq = se.serialize_seq(Some(2))?;
+            seq.serialize_element(&self.id)?;
+            seq.serialize_element(&self.value)?;
+            seq.end()
+        }
+    }
+
+    /// A raw metadata entry, as written by another build, with a type name if it is given
+    struct RawMetadata {
+        id: u64,
+        type_name: Option<&'static str>,
+        version: u32,
+        bytes: Vec<u8>,
+    }
+
+    impl Serialize for RawMetadata {
+        fn serialize<S>(&self, se: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let mut seq = se.serialize_seq(Some(3 + usize::from(self.type_name.is_some())))?;
+            seq.serialize_element(&self.id)?;
+            if let Some(type_name) = self.type_name {
+                seq.serialize_element(type_name)?;
+            }
+            seq.serialize_element(&self.version)?;
+            seq.serialize_element(&self.bytes)?;
+            seq.end()
+        }
+    }
+
+    #[derive(Serialize)]
+    struct RawSerdeAnyMap<E> {
+        map: HashMap<u64, E>,
+    }
+
+    impl<E> RawSerdeAnyMap<E>
+    where
+        E: Serialize,
+    {
+        fn new(id: u64, entry: E) -> Self {
+            let mut map = HashMap::new();
+            map.insert(id, entry);
+            Self { map }
+        }
+
+        fn with_entry(id: u64, entry: E) -> Vec<u8> {
+            postcard::to_allocvec(&Self::new(id, entry)).unwrap()
+        }
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct PlainMetadata {
+        hits: u64,
+    }
+
+    crate::impl_serdeany!(PlainMetadata);
+
+    #[test]
+    fn test_serdeany_inline_layout() {
+        // Unversioned types keep the layout from before versioning, so old states still load
+        let id = unpack_type_id(TypeId::of::<PlainMetadata>());
+        let blob = RawSerdeAnyMap::with_entry(
+            id,
+            RawInlineMetadata {
+                id,
+                value: PlainMetadata { hits: 3 },
+            },
+        );
+        let map: SerdeAnyMap = postcard::from_bytes(&blob).unwrap();
+        assert_eq!(map.get::<PlainMetadata>().unwrap().hits, 3);
+
+        let serialized = postcard::to_allocvec(&map).unwrap();
+        if !SERIALIZE_TYPE_NAMES {
+            assert_eq!(serialized, blob);
+        }
+        let map: SerdeAnyMap = postcard::from_bytes(&serialized).unwrap();
+        assert_eq!(map.get::<PlainMetadata>().unwrap().hits, 3);
+    }
+
+    #[test]
+    fn test_unknown_serde_type() {
+        assert!(
+            RegistryBuilder::registered_type_names().contains(&core::any::type_name::<Tokens>())
+        );
+
+        let raw = RawSerdeAnyMap::new(
+            0x1337,
+            RawMetadata {
+                id: 0x1337,
+                type_name: Some("other_build::ForeignMetadata"),
+                version: 0,
+                bytes: postcard::to_allocvec(&42_u32).unwrap(),
+            },
+        );
+
+        // The name travels in the error message, which `postcard` drops, but `serde_json` keeps.
+        // Without type names, json is read in the layout of this build, naming the type id.
+        let json = serde_json::to_string(&raw).unwrap();
+        let err: Error = serde_json::from_str::<SerdeAnyMap>(&json)
+            .unwrap_err()
+            .into();
+        let expected = if SERIALIZE_TYPE_NAMES {
+            "other_build::ForeignMetadata"
+        } else {
+            "#0x1337"
+        };
+        match err {
+            Error::UnknownSerdeType(name) => assert_eq!(name, expected),
+            _ => panic!("Unexpected error: {:?}", err),
+        }
+        assert!(
+            postcard::from_bytes::<SerdeAnyMap>(&postcard::to_allocvec(&raw).unwrap()).is_err()
+        );
+
+        // Other errors are not mistaken for unknown types
+        let err: Error = serde_json::from_str::<SerdeAnyMap>("{").unwrap_err().into();
+        assert!(matches!(err, Error::Serialize(_)));
+    }
+
+    /// The layout of [`VersionedMetadata`] in version 1
+    #[derive(Serialize)]
+    struct VersionedMetadataV1 {
+        hits: u32,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct VersionedMetadata {
+        hits: u64,
+        misses: u64,
+    }
+
+    impl SerdeAnyVersioned for VersionedMetadata {
+        const VERSION: u32 = 2;
+
+        fn migrate(old_version: u32, bytes: &[u8]) -> Result<Self, Error> {
+            match old_version {
+                1 => {
+                    let hits: u32 = postcard::from_bytes(bytes)?;
+                    Ok(Self {
+                        hits: hits.into(),
+                        misses: 0,
+                    })
+                }
+                _ => Err(Error::IllegalArgument(format!(
+                    "Unknown version {}",
+                    old_version
+                ))),
+            }
+        }
+    }
+
+    crate::impl_serdeany!(VersionedMetadata, versioned);
+
+    #[test]
+    fn test_serdeany_migration() {
+        // Both with and without the type name
+        let id = unpack_type_id(TypeId::of::<VersionedMetadata>());
+        for type_name in [None, Some(core::any::type_name::<VersionedMetadata>())] {
+            let blob = RawSerdeAnyMap::with_entry(
+                id,
+                RawMetadata {
+                    id,
+                    type_name,
+                    version: 1,
+                    bytes: postcard::to_allocvec(&VersionedMetadataV1 { hits: 7 }).unwrap(),
+                },
+            );
+            let map: SerdeAnyMap = postcard::from_bytes(&blob).unwrap();
+            let meta = map.get::<VersionedMetadata>().unwrap();
+            assert_eq!(meta.hits, 7);
+            assert_eq!(meta.misses, 0);
+        }
+
+        let mut map = SerdeAnyMap::new();
+        map.insert(VersionedMetadata { hits: 7, misses: 0 });
+
+        // Data of the current version roundtrips without migration
+        let map: SerdeAnyMap = postcard::from_bytes(&postcard::to_allocvec(&map).unwrap()).unwrap();
+        assert_eq!(map.get::<VersionedMetadata>().unwrap().hits, 7);
+    }
+}
@@ -0,0 +1,187 @@
+//! A corpus scheduler wrapper fuzzing each new testcase heavily right after it got added.
+
+use core::marker::PhantomData;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    corpus::{Corpus, CorpusScheduler, Testcase},
+    inputs::Input,
+    state::{HasCorpus, HasMetadata},
+    Error,
+};
+
+/// The default number of scheduling rounds of an exploitation burst
+pub const DEFAULT_BURST_ROUNDS: usize = 8;
+
+/// A state metadata, the ongoing exploitation burst of the [`BurstScheduler`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BurstMetadata {
+    /// The testcase being exploited
+    pub idx: usize,
+    /// The scheduling rounds left in the burst
+    pub remaining: usize,
+    /// The current testcase before the burst, restored afterwards for the wrapped scheduler
+    pub resume: Option<usize>,
+}
+
+crate::impl_serdeany!(BurstMetadata);
+
+/// Wraps any [`CorpusScheduler`], and starts an exploitation burst each time a testcase gets
+/// added to the corpus, for example because it found new coverage: the new testcase is scheduled
+/// for the next `burst_rounds` rounds, then the wrapped scheduler takes over again.
+/// The burst is kept in the [`BurstMetadata`] of the state, so it survives restarts.
+#[derive(Debug, Clone)]
+pub struct BurstScheduler<C, CS, I, S>
+where
+    C: Corpus<I>,
+    CS: CorpusScheduler<I, S>,
+    I: Input,
+    S: HasCorpus<C, I> + HasMetadata,
+{
+    base: CS,
+    burst_rounds: usize,
+    phantom: PhantomData<(C, I, S)>,
+}
+
+impl<C, CS, I, S> CorpusScheduler<I, S> for BurstScheduler<C, CS, I, S>
+where
+    C: Corpus<I>,
+    CS: CorpusScheduler<I, S>,
+    I: Input,
+    S: HasCorpus<C, I> + HasMetadata,
+{
+    fn on_add(&self, state: &mut S, idx: usize) -> Result<(), Error> {
+        self.base.on_add(state, idx)?;
+        if self.burst_rounds > 0 {
+            // A burst replacing an ongoing one resumes where the ongoing one started
+            let resume = match state.metadata().get::<BurstMetadata>() {
+                Some(burst) if burst.remaining > 0 => burst.resume,
+                _ => *state.corpus().current(),
+            };
+            state.add_metadata(BurstMetadata {
+                idx,
+                remaining: self.burst_rounds,
+                resume,
+            });
+        }
+        Ok(())
+    }
+
+    fn on_replace(&self, state: &mut S, idx: usize, testcase: &Testcase<I>) -> Result<(), Error> {
+        self.base.on_replace(state, idx, testcase)
+    }
+
+    fn on_remove(
+        &self,
+        state: &mut S,
+        idx: usize,
+        testcase: &Option<Testcase<I>>,
+    ) -> Result<(), Error> {
+        // The exploited testcase is gone, or got moved
+        if let Some(burst) = state.metadata_mut().get_mut::<BurstMetadata>() {
+            if idx <= burst.idx {
+                burst.remaining = 0;
+            }
+        }
+        self.base.on_remove(state, idx, testcase)
+    }
+
+    fn next(&self, state: &mut S) -> Result<usize, Error> {
+        let count = state.corpus().count();
+        let mut resume = None;
+        if let Some(burst) = state.metadata_mut().get_mut::<BurstMetadata>() {
+            if burst.remaining > 0 && burst.idx < count {
+                burst.remaining -= 1;
+                let idx = burst.idx;
+                *state.corpus_mut().current_mut() = Some(idx);
+                return Ok(idx);
+            }
+            burst.remaining = 0;
+            resume = burst.resume.take();
+        }
+
+        // Let the wrapped scheduler go on from where it was before the burst
+        if let Some(resume) = resume {
+            *state.corpus_mut().current_mut() = if resume < count { Some(resume) } else { None };
+        }
+        self.base.next(state)
+    }
+}
+
+impl<C, CS, I, S> BurstScheduler<C, CS, I, S>
+where
+    C: Corpus<I>,
+    CS: CorpusScheduler<I, S>,
+    I: Input,
+    S: HasCorpus<C, I> + HasMetadata,
+{
+    /// Creates a new [`BurstScheduler`], with bursts of [`DEFAULT_BURST_ROUNDS`] rounds
+    #[must_use]
+    pub fn new(base: CS) -> Self {
+        Self::with_burst_rounds(base, DEFAULT_BURST_ROUNDS)
+    }
+
+    /// Creates a new [`BurstScheduler`], scheduling each new testcase for the next
+    /// `burst_rounds` rounds
+    #[must_use]
+    pub fn with_burst_rounds(base: CS, burst_rounds: usize) -> Self {
+        Self {
+            base,
+            burst_rounds,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The number of scheduling rounds of a burst
+    #[must_use]
+    pub fn burst_rounds(&self) -> usize {
+        self.burst_rounds
+    }
+
+    /// The wrapped scheduler
+    #[must_use]
+    pub fn base(&self) -> &CS {
+        &self.base
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::{
+            BurstScheduler, Corpus, CorpusScheduler, InMemoryCorpus, QueueCorpusScheduler, Testcase,
+        },
+        inputs::BytesInput,
+        state::{HasCorpus, StdState},
+    };
+
+    #[test]
+    fn test_burst_scheduler() {
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let scheduler = BurstScheduler::with_burst_rounds(QueueCorpusScheduler::new(), 3);
+        for i in 0..2 {
+            state
+                .corpus_mut()
+                .add(Testcase::new(BytesInput::new(vec![i])))
+                .unwrap();
+        }
+        assert_eq!(scheduler.next(&mut state).unwrap(), 0);
+
+        // A new testcase gets the next 3 rounds, then the queue goes on after testcase 0
+        let idx = state
+            .corpus_mut()
+            .add(Testcase::new(BytesInput::new(vec![2])))
+            .unwrap();
+        scheduler.on_add(&mut state, idx).unwrap();
+        let selected: Vec<usize> = (0..6)
+            .map(|_| scheduler.next(&mut state).unwrap())
+            .collect();
+        assert_eq!(selected, [2, 2, 2, 1, 2, 0]);
+    }
+}
@@ -0,0 +1,218 @@
+//! The compressed in-memory corpus keeps all testcases in memory, storing their inputs
+//! compressed with lz4.
+
+use alloc::{collections::vec_deque::VecDeque, vec::Vec};
+use core::cell::RefCell;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    corpus::{Corpus, Testcase},
+    inputs::Input,
+    Error,
+};
+
+/// A corpus keeping all testcases in memory, with their inputs stored lz4-compressed.
+/// Inputs are decompressed on access, and a maximum number of them is kept decompressed.
+/// The eviction policy is FIFO.
+/// This trades CPU for memory, for campaigns with large, but compressible, inputs.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(bound = "I: serde::de::DeserializeOwned")]
+pub struct CompressedInMemoryCorpus<I>
+where
+    I: Input,
+{
+    entries: Vec<RefCell<Testcase<I>>>,
+    compressed: Vec<Vec<u8>>,
+    cached_indexes: RefCell<VecDeque<usize>>,
+    cache_max_len: usize,
+    current: Option<usize>,
+}
+
+impl<I> Corpus<I> for CompressedInMemoryCorpus<I>
+where
+    I: Input,
+{
+    /// Returns the number of elements
+    #[inline]
+    fn count(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Add an entry to the corpus and return its index
+    #[inline]
+    fn add(&mut self, mut testcase: Testcase<I>) -> Result<usize, Error> {
+        self.compressed.push(Self::compress(&mut testcase)?);
+        self.entries.push(RefCell::new(testcase));
+        Ok(self.entries.len() - 1)
+    }
+
+    /// Replaces the testcase at the given idx
+    #[inline]
+    fn replace(&mut self, idx: usize, mut testcase: Testcase<I>) -> Result<(), Error> {
+        if idx >= self.entries.len() {
+            return Err(Error::KeyNotFound(format!("Index {} out of bounds", idx)));
+        }
+        self.compressed[idx] = Self::compress(&mut testcase)?;
+        self.entries[idx] = RefCell::new(testcase);
+        self.cached_indexes.borrow_mut().retain(|e| *e != idx);
+        Ok(())
+    }
+
+    /// Removes an entry from the corpus, returning it if it was present.
+    #[inline]
+    fn remove(&mut self, idx: usize) -> Result<Option<Testcase<I>>, Error> {
+        if idx >= self.entries.len() {
+            return Ok(None);
+        }
+        let compressed = self.compressed.remove(idx);
+        let mut testcase = self.entries.remove(idx).into_inner();
+        if testcase.input().is_none() {
+            testcase.set_input(Self::decompress(&compressed)?);
+        }
+
+        // Later entries moved one index down
+        let mut cached_indexes = self.cached_indexes.borrow_mut();
+        cached_indexes.retain(|e| *e != idx);
+        for e in cached_indexes.iter_mut() {
+            if *e > idx {
+                *e -= 1;
+            }
+        }
+        Ok(Some(testcase))
+    }
+
+    /// Get by id
+    #[inline]
+    fn get(&self, idx: usize) -> Result<&RefCell<Testcase<I>>, Error> {
+        let testcase = self
+            .entries
+            .get(idx)
+            .ok_or_else(|| Error::KeyNotFound(format!("Index {} out of bounds", idx)))?;
+        if testcase.borrow().input().is_none() {
+            testcase
+                .borrow_mut()
+                .set_input(Self::decompress(&self.compressed[idx])?);
+            let mut cached_indexes = self.cached_indexes.borrow_mut();
+            let mut borrowed_num = 0;
+            while let Some(removed) = self.pop_if_full(&mut cached_indexes) {
+                if let Ok(mut borrowed) = self.entries[removed].try_borrow_mut() {
+                    *borrowed.input_mut() = None;
+                } else {
+                    cached_indexes.push_back(removed);
+                    borrowed_num += 1;
+                    if self.cache_max_len == borrowed_num {
+                        break;
+                    }
+                }
+            }
+            cached_indexes.push_back(idx);
+        }
+        Ok(testcase)
+    }
+
+    /// Current testcase scheduled
+    #[inline]
+    fn current(&self) -> &Option<usize> {
+        &self.current
+    }
+
+    /// Current testcase scheduled (mut)
+    #[inline]
+    fn current_mut(&mut self) -> &mut Option<usize> {
+        &mut self.current
+    }
+}
+
+impl<I> CompressedInMemoryCorpus<I>
+where
+    I: Input,
+{
+    /// Creates a new [`CompressedInMemoryCorpus`], keeping at most `cache_max_len` inputs decompressed.
+    pub fn new(cache_max_len: usize) -> Result<Self, Error> {
+        if cache_max_len == 0 {
+            return Err(Error::IllegalArgument(
+                "The max cache len in CompressedInMemoryCorpus cannot be 0".into(),
+            ));
+        }
+        Ok(Self {
+            entries: vec![],
+            compressed: vec![],
+            cached_indexes: RefCell::new(VecDeque::new()),
+            cache_max_len,
+            current: None,
+        })
+    }
+
+    /// The total size of all compressed inputs, in bytes
+    #[must_use]
+    pub fn compressed_size(&self) -> usize {
+        self.compressed.iter().map(Vec::len).sum()
+    }
+
+    /// The oldest cached index, if the cache is full
+    fn pop_if_full(&self, cached_indexes: &mut VecDeque<usize>) -> Option<usize> {
+        if cached_indexes.len() >= self.cache_max_len {
+            cached_indexes.pop_front()
+        } else {
+            None
+        }
+    }
+
+    /// Takes the input out of the `testcase`, and compresses it
+    fn compress(testcase: &mut Testcase<I>) -> Result<Vec<u8>, Error> {
+        testcase.load_input()?;
+        let input = testcase.input_mut().take().unwrap();
+        let serialized = postcard::to_allocvec(&input)?;
+        Ok(lz4_flex::compress_prepend_size(&serialized))
+    }
+
+    fn decompress(compressed: &[u8]) -> Result<I, Error> {
+        let serialized =
+            lz4_flex::decompress_size_prepended(compressed).map_err(|_| Error::Compression)?;
+        Ok(postcard::from_bytes(&serialized)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        corpus::{CompressedInMemoryCorpus, Corpus, Testcase},
+        inputs::{BytesInput, HasBytesVec},
+    };
+
+    #[test]
+    fn test_compressed_corpus() {
+        let mut corpus = CompressedInMemoryCorpus::<BytesInput>::new(1).unwrap();
+        let mut bytes = vec![0x41; 0x10000];
+        bytes[0x1337] = 0x42;
+        corpus
+            .add(Testcase::new(BytesInput::new(bytes.clone())))
+            .unwrap();
+        corpus
+            .add(Testcase::new(BytesInput::new(vec![1, 2, 3])))
+            .unwrap();
+        assert!(corpus.compressed_size() < bytes.len() / 16);
+
+        // With a cache of one, each access decompresses again
+        for _ in 0..2 {
+            for (idx, expected) in [(0, &bytes[..]), (1, &[1, 2, 3][..])] {
+                let testcase = corpus.get(idx).unwrap().borrow();
+                assert_eq!(testcase.input().as_ref().unwrap().bytes(), expected);
+            }
+        }
+
+        let removed = corpus.remove(0).unwrap().unwrap();
+        assert_eq!(removed.input().as_ref().unwrap().bytes(), &bytes[..]);
+        assert_eq!(
+            corpus
+                .get(0)
+                .unwrap()
+                .borrow()
+                .input()
+                .as_ref()
+                .unwrap()
+                .bytes(),
+            &[1, 2, 3]
+        );
+    }
+}
@@ -0,0 +1,253 @@
+//! A corpus wrapper storing the minimized form of each crash, for a clean, analysis-ready objective corpus.
+
+use alloc::{boxed::Box, vec::Vec};
+use core::{cell::RefCell, fmt, marker::PhantomData};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    corpus::{Corpus, Testcase},
+    inputs::{HasBytesVec, Input},
+    state::HasMetadata,
+    Error,
+};
+
+/// Re-runs an input, for example by running the target in a fresh process,
+/// returning `true` if it still triggers the crash
+pub type CrashOracle<I> = Box<dyn FnMut(&I) -> bool>;
+
+/// Added to each testcase the [`CrashMinimizingCorpus`] stored in minimized form
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct MinimizedCrashMetadata {
+    /// The length of the input, before minimization
+    pub original_len: usize,
+    /// The index of the original input, if it was kept
+    pub original_idx: Option<usize>,
+}
+
+crate::impl_serdeany!(MinimizedCrashMetadata);
+
+/// Minimizes a crashing `input`, removing chunks of decreasing size as long as the `oracle`
+/// reports that the crash still reproduces.
+/// The `oracle` has to report `true` for `input` itself, else the input is returned unchanged.
+pub fn minimize_crash<I, F>(input: &I, mut oracle: F) -> I
+where
+    I: Input + HasBytesVec,
+    F: FnMut(&I) -> bool,
+{
+    let mut current = input.clone();
+    let mut chunk = current.bytes().len() / 2;
+    while chunk > 0 {
+        let mut start = 0;
+        while start < current.bytes().len() {
+            let end = (start + chunk).min(current.bytes().len());
+            let mut candidate = current.clone();
+            candidate.bytes_mut().drain(start..end);
+            if oracle(&candidate) {
+                current = candidate;
+            } else {
+                start += chunk;
+            }
+        }
+        chunk /= 2;
+    }
+    current
+}
+
+/// Wraps a [`Corpus`], usually the objective corpus, and minimizes each testcase using
+/// [`minimize_crash`] before storing it. Optionally, the original input is kept as well.
+/// The [`CrashOracle`] is not serialized: after a restart, set it again using
+/// [`CrashMinimizingCorpus::set_oracle`], else testcases are stored unchanged.
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "I: serde::de::DeserializeOwned")]
+pub struct CrashMinimizingCorpus<C, I>
+where
+    C: Corpus<I>,
+    I: Input + HasBytesVec,
+{
+    inner: C,
+    #[serde(skip)]
+    oracle: Option<CrashOracle<I>>,
+    keep_original: bool,
+    phantom: PhantomData<I>,
+}
+
+impl<C, I> fmt::Debug for CrashMinimizingCorpus<C, I>
+where
+    C: Corpus<I> + fmt::Debug,
+    I: Input + HasBytesVec,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CrashMinimizingCorpus")
+            .field("inner", &self.inner)
+            .field("keep_original", &self.keep_original)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<C, I> Corpus<I> for CrashMinimizingCorpus<C, I>
+where
+    C: Corpus<I>,
+    I: Input + HasBytesVec,
+{
+    /// Returns the number of elements
+    #[inline]
+    fn count(&self) -> usize {
+        self.inner.count()
+    }
+
+    /// Minimizes the input of the testcase, adds it to the inner corpus and returns its index
+    fn add(&mut self, mut testcase: Testcase<I>) -> Result<usize, Error> {
+        let oracle = match self.oracle.as_mut() {
+            Some(oracle) => oracle,
+            None => return self.inner.add(testcase),
+        };
+        let original = testcase.load_input()?.clone();
+        if !oracle(&original) {
+            return self.inner.add(testcase);
+        }
+        let minimized = minimize_crash(&original, oracle);
+        if minimized.bytes().len() == original.bytes().len() {
+            return self.inner.add(testcase);
+        }
+
+        let original_idx = if self.keep_original {
+            Some(self.inner.add(testcase.clone())?)
+        } else {
+            None
+        };
+        testcase.set_input(minimized);
+        testcase.add_metadata(MinimizedCrashMetadata {
+            original_len: original.bytes().len(),
+            original_idx,
+        });
+        self.inner.add(testcase)
+    }
+
+    /// Replaces the testcase at the given idx
+    #[inline]
+    fn replace(&mut self, idx: usize, testcase: Testcase<I>) -> Result<(), Error> {
+        self.inner.replace(idx, testcase)
+    }
+
+    /// Removes an entry from the corpus, returning it if it was present.
+    #[inline]
+    fn remove(&mut self, idx: usize) -> Result<Option<Testcase<I>>, Error> {
+        self.inner.remove(idx)
+    }
+
+    /// Get by id
+    #[inline]
+    fn get(&self, idx: usize) -> Result<&RefCell<Testcase<I>>, Error> {
+        self.inner.get(idx)
+    }
+
+    /// Current testcase scheduled
+    #[inline]
+    fn current(&self) -> &Option<usize> {
+        self.inner.current()
+    }
+
+    /// Current testcase scheduled (mut)
+    #[inline]
+    fn current_mut(&mut self) -> &mut Option<usize> {
+        self.inner.current_mut()
+    }
+
+    /// The testcases the inner corpus evicted
+    #[inline]
+    fn take_evicted(&mut self) -> Vec<(usize, Testcase<I>)> {
+        self.inner.take_evicted()
+    }
+}
+
+impl<C, I> CrashMinimizingCorpus<C, I>
+where
+    C: Corpus<I>,
+    I: Input + HasBytesVec,
+{
+    /// Creates a new [`CrashMinimizingCorpus`], storing the testcases minimized with `oracle` in `inner`
+    #[must_use]
+    pub fn new<O>(inner: C, oracle: O) -> Self
+    where
+        O: FnMut(&I) -> bool + 'static,
+    {
+        Self {
+            inner,
+            oracle: Some(Box::new(oracle)),
+            keep_original: false,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Also stores the original input of each minimized testcase, right before the minimized one
+    #[must_use]
+    pub fn with_keep_original(mut self, keep_original: bool) -> Self {
+        self.keep_original = keep_original;
+        self
+    }
+
+    /// Sets the [`CrashOracle`], for example after deserializing this corpus on restart
+    pub fn set_oracle<O>(&mut self, oracle: O)
+    where
+        O: FnMut(&I) -> bool + 'static,
+    {
+        self.oracle = Some(Box::new(oracle));
+    }
+
+    /// The wrapped corpus
+    #[must_use]
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::rc::Rc;
+    use core::cell::Cell;
+
+    use crate::{
+        corpus::{Corpus, CrashMinimizingCorpus, InMemoryCorpus, MinimizedCrashMetadata, Testcase},
+        executors::ExitKind,
+        inputs::{BytesInput, HasBytesVec},
+        state::HasMetadata,
+    };
+
+    /// Crashes if the input contains `CRASH`
+    fn harness(input: &BytesInput) -> ExitKind {
+        if input.bytes().windows(5).any(|w| w == b"CRASH") {
+            ExitKind::Crash
+        } else {
+            ExitKind::Ok
+        }
+    }
+
+    #[test]
+    fn test_crash_minimizing_corpus() {
+        let mut bytes = vec![0x41; 4096];
+        bytes[1337..1342].copy_from_slice(b"CRASH");
+        let original = BytesInput::new(bytes);
+
+        // The oracle re-runs the harness on each candidate
+        let runs = Rc::new(Cell::new(0));
+        let oracle_runs = runs.clone();
+        let mut corpus = CrashMinimizingCorpus::new(InMemoryCorpus::new(), move |input| {
+            oracle_runs.set(oracle_runs.get() + 1);
+            harness(input) == ExitKind::Crash
+        })
+        .with_keep_original(true);
+        let idx = corpus.add(Testcase::new(original.clone())).unwrap();
+        assert_eq!(corpus.count(), 2);
+        assert!(runs.get() > 1);
+
+        let testcase = corpus.get(idx).unwrap().borrow();
+        let minimized = testcase.input().as_ref().unwrap();
+        assert_eq!(minimized.bytes(), b"CRASH");
+        assert_eq!(harness(minimized), ExitKind::Crash);
+        let meta = testcase.metadata().get::<MinimizedCrashMetadata>().unwrap();
+        assert_eq!(meta.original_len, 4096);
+
+        let kept = corpus.get(meta.original_idx.unwrap()).unwrap().borrow();
+        assert_eq!(kept.input().as_ref().unwrap().bytes(), original.bytes());
+    }
+}
@@ -0,0 +1,221 @@
+//! A corpus wrapper keeping only testcases with distinct coverage.
+
+use alloc::vec::Vec;
+use core::{cell::RefCell, marker::PhantomData};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    corpus::{Corpus, Testcase},
+    feedbacks::MapIndexesMetadata,
+    inputs::Input,
+    state::HasMetadata,
+    Error,
+};
+
+/// The sorted coverage of a testcase, from its [`MapIndexesMetadata`]
+fn fingerprint<I>(testcase: &Testcase<I>) -> Option<Vec<usize>>
+where
+    I: Input,
+{
+    testcase.metadata().get::<MapIndexesMetadata>().map(|meta| {
+        let mut list = meta.list.clone();
+        list.sort_unstable();
+        list
+    })
+}
+
+/// Returns `true` if all entries of the sorted `sub` are in the sorted `sup`
+fn is_subset(sub: &[usize], sup: &[usize]) -> bool {
+    let mut sup = sup.iter();
+    sub.iter().all(|entry| sup.any(|other| other == entry))
+}
+
+/// Wraps a [`Corpus`], and keeps it to testcases with distinct coverage, according to the
+/// [`MapIndexesMetadata`] of each testcase, so the map feedback has to track indexes.
+///
+/// On [`Corpus::add`], a testcase covering only entries an existing testcase covers too is
+/// dropped, returning an [`Error::DuplicateTestcase`] with the index of that existing testcase,
+/// so the fuzzer neither schedules nor shares it. A testcase covering all entries
+/// of an existing testcase, and more, replaces the first such testcase, keeping its index.
+/// Testcases without coverage metadata are always added.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(bound = "I: serde::de::DeserializeOwned")]
+pub struct CoverageDedupCorpus<C, I>
+where
+    C: Corpus<I>,
+    I: Input,
+{
+    inner: C,
+    /// The coverage of each testcase of the inner corpus, by index
+    fingerprints: Vec<Option<Vec<usize>>>,
+    /// The testcases the inner corpus evicted, until taken by [`Corpus::take_evicted`]
+    #[serde(skip)]
+    evicted: Vec<(usize, Testcase<I>)>,
+    phantom: PhantomData<I>,
+}
+
+impl<C, I> Corpus<I> for CoverageDedupCorpus<C, I>
+where
+    C: Corpus<I>,
+    I: Input,
+{
+    /// Returns the number of elements
+    #[inline]
+    fn count(&self) -> usize {
+        self.inner.count()
+    }
+
+    /// Adds the testcase if its coverage is new, see [`CoverageDedupCorpus`]
+    fn add(&mut self, testcase: Testcase<I>) -> Result<usize, Error> {
+        let coverage = if let Some(coverage) = fingerprint(&testcase) {
+            coverage
+        } else {
+            self.fingerprints.push(None);
+            return self.add_inner(testcase);
+        };
+
+        for (idx, existing) in self.fingerprints.iter().enumerate() {
+            if let Some(existing) = existing {
+                if is_subset(&coverage, existing) {
+                    return Err(Error::DuplicateTestcase(idx));
+                }
+            }
+        }
+        let superseded = self.fingerprints.iter().position(|existing| {
+            existing
+                .as_ref()
+                .map_or(false, |existing| is_subset(existing, &coverage))
+        });
+        if let Some(idx) = superseded {
+            self.inner.replace(idx, testcase)?;
+            self.fingerprints[idx] = Some(coverage);
+            Ok(idx)
+        } else {
+            self.fingerprints.push(Some(coverage));
+            self.add_inner(testcase)
+        }
+    }
+
+    /// Replaces the testcase at the given idx
+    fn replace(&mut self, idx: usize, testcase: Testcase<I>) -> Result<(), Error> {
+        let coverage = fingerprint(&testcase);
+        self.inner.replace(idx, testcase)?;
+        self.fingerprints[idx] = coverage;
+        Ok(())
+    }
+
+    /// Removes an entry from the corpus, returning it if it was present.
+    fn remove(&mut self, idx: usize) -> Result<Option<Testcase<I>>, Error> {
+        let removed = self.inner.remove(idx)?;
+        if removed.is_some() {
+            self.fingerprints.remove(idx);
+        }
+        Ok(removed)
+    }
+
+    /// Get by id
+    #[inline]
+    fn get(&self, idx: usize) -> Result<&RefCell<Testcase<I>>, Error> {
+        self.inner.get(idx)
+    }
+
+    /// Current testcase scheduled
+    #[inline]
+    fn current(&self) -> &Option<usize> {
+        self.inner.current()
+    }
+
+    /// Current testcase scheduled (mut)
+    #[inline]
+    fn current_mut(&mut self) -> &mut Option<usize> {
+        self.inner.current_mut()
+    }
+
+    /// The testcases the inner corpus evicted
+    #[inline]
+    fn take_evicted(&mut self) -> Vec<(usize, Testcase<I>)> {
+        core::mem::take(&mut self.evicted)
+    }
+}
+
+impl<C, I> CoverageDedupCorpus<C, I>
+where
+    C: Corpus<I>,
+    I: Input,
+{
+    /// Creates a new [`CoverageDedupCorpus`], wrapping `inner`.
+    /// Testcases already in `inner` are kept, even if their coverage is not distinct.
+    ///
+    /// # Errors
+    /// Fails if a testcase of `inner` can't be retrieved
+    pub fn new(inner: C) -> Result<Self, Error> {
+        let fingerprints = (0..inner.count())
+            .map(|idx| Ok(fingerprint(&*inner.get(idx)?.borrow())))
+            .collect::<Result<_, Error>>()?;
+        Ok(Self {
+            inner,
+            fingerprints,
+            evicted: vec![],
+            phantom: PhantomData,
+        })
+    }
+
+    /// Adds the testcase to the inner corpus, its fingerprint is already pushed.
+    /// Drops the fingerprints of the testcases the inner corpus evicted.
+    fn add_inner(&mut self, testcase: Testcase<I>) -> Result<usize, Error> {
+        let idx = self.inner.add(testcase)?;
+        for (evicted_idx, testcase) in self.inner.take_evicted() {
+            self.fingerprints.remove(evicted_idx);
+            self.evicted.push((evicted_idx, testcase));
+        }
+        Ok(idx)
+    }
+
+    /// The wrapped corpus
+    #[must_use]
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        corpus::{Corpus, CoverageDedupCorpus, InMemoryCorpus, Testcase},
+        feedbacks::MapIndexesMetadata,
+        inputs::{BytesInput, HasBytesVec},
+        state::HasMetadata,
+        Error,
+    };
+
+    fn testcase(bytes: &[u8], coverage: &[usize]) -> Testcase<BytesInput> {
+        let mut testcase = Testcase::new(BytesInput::new(bytes.to_vec()));
+        testcase.add_metadata(MapIndexesMetadata::new(coverage.to_vec()));
+        testcase
+    }
+
+    #[test]
+    fn test_coverage_dedup_corpus() {
+        let mut corpus = CoverageDedupCorpus::new(InMemoryCorpus::new()).unwrap();
+        assert_eq!(corpus.add(testcase(b"a", &[1, 2])).unwrap(), 0);
+        assert_eq!(corpus.add(testcase(b"b", &[3])).unwrap(), 1);
+
+        // A subset of the coverage of "a" is dropped
+        assert!(matches!(
+            corpus.add(testcase(b"c", &[2])),
+            Err(Error::DuplicateTestcase(0))
+        ));
+        assert_eq!(corpus.count(), 2);
+
+        // A superset of the coverage of "b" replaces it
+        assert_eq!(corpus.add(testcase(b"d", &[4, 3])).unwrap(), 1);
+        assert_eq!(corpus.count(), 2);
+        let replaced = corpus.get(1).unwrap().borrow();
+        assert_eq!(replaced.input().as_ref().unwrap().bytes(), b"d");
+        drop(replaced);
+
+        // New coverage is added
+        assert_eq!(corpus.add(testcase(b"e", &[2, 5])).unwrap(), 2);
+        assert_eq!(corpus.count(), 3);
+    }
+}
@@ -0,0 +1,261 @@
+//! A fixed-capacity corpus, keeping up to `N` testcases in an array, for memory-constrained targets.
+
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use serde::{
+    de::Error as _,
+    ser::{SerializeSeq, SerializeStruct},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{corpus::Corpus, corpus::Testcase, inputs::Input, Error};
+
+/// A corpus keeping up to `N` testcases in memory, in an array instead of a growing `Vec`,
+/// so its footprint is known at compile time.
+/// [`Corpus::add`] fails with [`Error::IllegalState`] once the corpus is full: remove an entry,
+/// or pick a bigger `N`, to add more.
+#[derive(Clone, Debug)]
+pub struct FixedCorpus<I, const N: usize>
+where
+    I: Input,
+{
+    /// The first `count` slots are occupied
+    entries: [Option<RefCell<Testcase<I>>>; N],
+    count: usize,
+    current: Option<usize>,
+}
+
+impl<I, const N: usize> Corpus<I> for FixedCorpus<I, N>
+where
+    I: Input,
+{
+    /// Returns the number of elements
+    #[inline]
+    fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Add an entry to the corpus and return its index, fails if the corpus is full
+    #[inline]
+    fn add(&mut self, testcase: Testcase<I>) -> Result<usize, Error> {
+        if self.count >= N {
+            return Err(Error::IllegalState(format!(
+                "The corpus is full, with {} testcases",
+                N
+            )));
+        }
+        self.entries[self.count] = Some(RefCell::new(testcase));
+        self.count += 1;
+        Ok(self.count - 1)
+    }
+
+    /// Replaces the testcase at the given idx
+    #[inline]
+    fn replace(&mut self, idx: usize, testcase: Testcase<I>) -> Result<(), Error> {
+        if idx >= self.count {
+            return Err(Error::KeyNotFound(format!("Index {} out of bounds", idx)));
+        }
+        self.entries[idx] = Some(RefCell::new(testcase));
+        Ok(())
+    }
+
+    /// Removes an entry from the corpus, returning it if it was present.
+    #[inline]
+    fn remove(&mut self, idx: usize) -> Result<Option<Testcase<I>>, Error> {
+        if idx >= self.count {
+            return Ok(None);
+        }
+        let testcase = self.entries[idx].take().map(RefCell::into_inner);
+        // Keep the occupied slots contiguous
+        self.entries[idx..self.count].rotate_left(1);
+        self.count -= 1;
+        Ok(testcase)
+    }
+
+    /// Get by id
+    #[inline]
+    fn get(&self, idx: usize) -> Result<&RefCell<Testcase<I>>, Error> {
+        self.entries[..self.count]
+            .get(idx)
+            .and_then(Option::as_ref)
+            .ok_or_else(|| Error::KeyNotFound(format!("Index {} out of bounds", idx)))
+    }
+
+    /// Current testcase scheduled
+    #[inline]
+    fn current(&self) -> &Option<usize> {
+        &self.current
+    }
+
+    /// Current testcase scheduled (mut)
+    #[inline]
+    fn current_mut(&mut self) -> &mut Option<usize> {
+        &mut self.current
+    }
+}
+
+impl<I, const N: usize> FixedCorpus<I, N>
+where
+    I: Input,
+{
+    /// Creates a new, empty [`FixedCorpus`], with room for `N` testcases
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            entries: [(); N].map(|()| None),
+            count: 0,
+            current: None,
+        }
+    }
+
+    /// The maximum number of testcases, `N`
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns `true` if no more testcases can be added
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.count >= N
+    }
+}
+
+impl<I, const N: usize> Default for FixedCorpus<I, N>
+where
+    I: Input,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The occupied entries of a [`FixedCorpus`], serialized as a sequence
+struct FixedCorpusEntries<'a, I, const N: usize>(&'a FixedCorpus<I, N>)
+where
+    I: Input;
+
+impl<'a, I, const N: usize> Serialize for FixedCorpusEntries<'a, I, N>
+where
+    I: Input,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(self.0.count))?;
+        for testcase in self.0.entries[..self.0.count].iter().flatten() {
+            seq.serialize_element(testcase)?;
+        }
+        seq.end()
+    }
+}
+
+impl<I, const N: usize> Serialize for FixedCorpus<I, N>
+where
+    I: Input,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("FixedCorpus", 2)?;
+        state.serialize_field("entries", &FixedCorpusEntries(self))?;
+        state.serialize_field("current", &self.current)?;
+        state.end()
+    }
+}
+
+/// The serialized form of a [`FixedCorpus`]
+#[derive(Deserialize)]
+#[serde(bound = "I: serde::de::DeserializeOwned")]
+struct FixedCorpusData<I>
+where
+    I: Input,
+{
+    entries: Vec<Testcase<I>>,
+    current: Option<usize>,
+}
+
+impl<'de, I, const N: usize> Deserialize<'de> for FixedCorpus<I, N>
+where
+    I: Input,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let data = FixedCorpusData::<I>::deserialize(deserializer)?;
+        if data.entries.len() > N {
+            return Err(D::Error::custom(format!(
+                "{} testcases do not fit in a corpus for {}",
+                data.entries.len(),
+                N
+            )));
+        }
+        let mut corpus = Self::new();
+        for testcase in data.entries {
+            corpus.add(testcase).map_err(D::Error::custom)?;
+        }
+        corpus.current = data.current;
+        Ok(corpus)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        corpus::{Corpus, FixedCorpus, Testcase},
+        inputs::{BytesInput, HasBytesVec},
+        Error,
+    };
+
+    #[test]
+    fn test_fixed_corpus() {
+        let mut corpus = FixedCorpus::<BytesInput, 3>::new();
+        for i in 0..3 {
+            assert_eq!(
+                corpus.add(Testcase::new(BytesInput::new(vec![i]))).unwrap(),
+                i as usize
+            );
+        }
+        assert!(corpus.is_full());
+
+        // A full corpus refuses new testcases, and keeps the old ones
+        assert!(matches!(
+            corpus.add(Testcase::new(BytesInput::new(vec![3]))),
+            Err(Error::IllegalState(_))
+        ));
+        assert_eq!(corpus.count(), 3);
+
+        // Removing one makes room again
+        let removed = corpus.remove(0).unwrap().unwrap();
+        assert_eq!(removed.input().as_ref().unwrap().bytes(), [0]);
+        assert_eq!(
+            corpus.add(Testcase::new(BytesInput::new(vec![3]))).unwrap(),
+            2
+        );
+        let bytes = |corpus: &FixedCorpus<BytesInput, 3>, idx| {
+            corpus
+                .get(idx)
+                .unwrap()
+                .borrow()
+                .input()
+                .as_ref()
+                .unwrap()
+                .bytes()
+                .to_vec()
+        };
+        assert_eq!(
+            (0..3).map(|idx| bytes(&corpus, idx)).collect::<Vec<_>>(),
+            [[1], [2], [3]]
+        );
+        assert!(corpus.get(3).is_err());
+
+        let serialized = postcard::to_allocvec(&corpus).unwrap();
+        let deserialized: FixedCorpus<BytesInput, 3> = postcard::from_bytes(&serialized).unwrap();
+        assert_eq!(deserialized.count(), 3);
+        assert_eq!(bytes(&deserialized, 2), [3]);
+        assert!(postcard::from_bytes::<FixedCorpus<BytesInput, 2>>(&serialized).is_err());
+    }
+}
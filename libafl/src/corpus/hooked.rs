@@ -0,0 +1,130 @@
+//! A corpus scheduler wrapper, calling a hook each time a testcase gets selected.
+
+use core::{cell::RefCell, fmt, marker::PhantomData};
+
+use crate::{
+    corpus::{CorpusScheduler, Testcase},
+    inputs::Input,
+    Error,
+};
+
+/// Wraps any [`CorpusScheduler`], and calls the `on_select` hook with the state and the index
+/// of each testcase the wrapped scheduler selects.
+/// Useful for telemetry and adaptive strategies, without forking a scheduler just to observe it.
+pub struct HookedScheduler<CS, F, I, S>
+where
+    CS: CorpusScheduler<I, S>,
+    F: FnMut(&mut S, usize),
+    I: Input,
+{
+    base: CS,
+    on_select: RefCell<F>,
+    phantom: PhantomData<(I, S)>,
+}
+
+impl<CS, F, I, S> fmt::Debug for HookedScheduler<CS, F, I, S>
+where
+    CS: CorpusScheduler<I, S> + fmt::Debug,
+    F: FnMut(&mut S, usize),
+    I: Input,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HookedScheduler")
+            .field("base", &self.base)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<CS, F, I, S> CorpusScheduler<I, S> for HookedScheduler<CS, F, I, S>
+where
+    CS: CorpusScheduler<I, S>,
+    F: FnMut(&mut S, usize),
+    I: Input,
+{
+    fn on_add(&self, state: &mut S, idx: usize) -> Result<(), Error> {
+        self.base.on_add(state, idx)
+    }
+
+    fn on_replace(&self, state: &mut S, idx: usize, testcase: &Testcase<I>) -> Result<(), Error> {
+        self.base.on_replace(state, idx, testcase)
+    }
+
+    fn on_remove(
+        &self,
+        state: &mut S,
+        idx: usize,
+        testcase: &Option<Testcase<I>>,
+    ) -> Result<(), Error> {
+        self.base.on_remove(state, idx, testcase)
+    }
+
+    fn next(&self, state: &mut S) -> Result<usize, Error> {
+        let idx = self.base.next(state)?;
+        (self.on_select.borrow_mut())(state, idx);
+        Ok(idx)
+    }
+}
+
+impl<CS, F, I, S> HookedScheduler<CS, F, I, S>
+where
+    CS: CorpusScheduler<I, S>,
+    F: FnMut(&mut S, usize),
+    I: Input,
+{
+    /// Creates a new [`HookedScheduler`], calling `on_select` for each testcase `base` selects.
+    pub fn new(base: CS, on_select: F) -> Self {
+        Self {
+            base,
+            on_select: RefCell::new(on_select),
+            phantom: PhantomData,
+        }
+    }
+
+    /// The wrapped scheduler
+    #[must_use]
+    pub fn base(&self) -> &CS {
+        &self.base
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::{
+            Corpus, CorpusScheduler, HookedScheduler, InMemoryCorpus, QueueCorpusScheduler,
+            Testcase,
+        },
+        inputs::BytesInput,
+        state::{HasCorpus, StdState},
+    };
+
+    #[test]
+    fn test_hooked_scheduler() {
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        for i in 0..3 {
+            state
+                .corpus_mut()
+                .add(Testcase::new(BytesInput::new(vec![i])))
+                .unwrap();
+        }
+
+        let mut selected = vec![];
+        let mut expected = vec![];
+        {
+            let scheduler = HookedScheduler::new(QueueCorpusScheduler::new(), |_state, idx| {
+                selected.push(idx);
+            });
+            for _ in 0..5 {
+                expected.push(scheduler.next(&mut state).unwrap());
+            }
+        }
+        assert_eq!(selected, expected);
+        assert_eq!(selected, vec![0, 1, 2, 0, 1]);
+    }
+}
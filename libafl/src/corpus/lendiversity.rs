@@ -0,0 +1,291 @@
+//! A corpus scheduler wrapper, preferring testcases of lengths underrepresented in the corpus.
+
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::marker::PhantomData;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::{rands::Rand, HasLen},
+    corpus::{Corpus, CorpusScheduler, Testcase},
+    inputs::Input,
+    state::{HasCorpus, HasMetadata, HasRand},
+    Error,
+};
+
+/// Default probability to pick a testcase by length, instead of asking the base scheduler
+pub const DEFAULT_PREFER_RARE_LEN_PROB: u64 = 20;
+
+/// A state metadata, the length histogram of the corpus, kept up to date by the
+/// [`LengthDiversityScheduler`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LengthHistogramMetadata {
+    /// The length of each testcase, by corpus index
+    pub lens: Vec<usize>,
+    /// The testcases of each length
+    pub by_len: BTreeMap<usize, Vec<usize>>,
+}
+
+crate::impl_serdeany!(LengthHistogramMetadata);
+
+impl LengthHistogramMetadata {
+    /// Adds the testcase at `idx`, right after the last known one
+    fn push(&mut self, idx: usize, len: usize) {
+        debug_assert_eq!(idx, self.lens.len());
+        self.lens.push(len);
+        self.by_len.entry(len).or_default().push(idx);
+    }
+
+    /// Updates the length of the testcase at `idx`
+    fn set(&mut self, idx: usize, len: usize) {
+        self.unlink(idx);
+        self.lens[idx] = len;
+        self.by_len.entry(len).or_default().push(idx);
+    }
+
+    /// Removes the testcase at `idx`, the following testcases move down by one
+    fn remove(&mut self, idx: usize) {
+        self.unlink(idx);
+        self.lens.remove(idx);
+        for entries in self.by_len.values_mut() {
+            for entry in entries.iter_mut().filter(|entry| **entry > idx) {
+                *entry -= 1;
+            }
+        }
+    }
+
+    /// The testcases of the `nth` shortest length
+    fn entries_of_nth_len(&self, nth: usize) -> &[usize] {
+        self.by_len.values().nth(nth).unwrap()
+    }
+
+    /// Removes `idx` from the testcases of its length
+    fn unlink(&mut self, idx: usize) {
+        let len = self.lens[idx];
+        if let Some(entries) = self.by_len.get_mut(&len) {
+            entries.retain(|entry| *entry != idx);
+            if entries.is_empty() {
+                self.by_len.remove(&len);
+            }
+        }
+    }
+}
+
+/// Wraps a `base` [`CorpusScheduler`]. With a given probability, it instead picks a random
+/// length out of all lengths in the corpus, and a random testcase of that length.
+/// Testcases of rare lengths are picked as often as testcases of common lengths, so the
+/// fuzzer keeps exploring size-sensitive code paths if the corpus is dominated by one length.
+/// The lengths are tracked in the [`LengthHistogramMetadata`] of the state, updated as testcases
+/// get added, replaced or removed.
+#[derive(Debug, Clone)]
+pub struct LengthDiversityScheduler<C, CS, I, R, S>
+where
+    CS: CorpusScheduler<I, S>,
+    I: Input + HasLen,
+    S: HasCorpus<C, I> + HasRand<R> + HasMetadata,
+    C: Corpus<I>,
+    R: Rand,
+{
+    base: CS,
+    prefer_rare_prob: u64,
+    phantom: PhantomData<(C, I, R, S)>,
+}
+
+impl<C, CS, I, R, S> CorpusScheduler<I, S> for LengthDiversityScheduler<C, CS, I, R, S>
+where
+    CS: CorpusScheduler<I, S>,
+    I: Input + HasLen,
+    S: HasCorpus<C, I> + HasRand<R> + HasMetadata,
+    C: Corpus<I>,
+    R: Rand,
+{
+    fn on_add(&self, state: &mut S, idx: usize) -> Result<(), Error> {
+        self.base.on_add(state, idx)?;
+        let len = state.corpus().get(idx)?.borrow_mut().cached_len()?;
+        match state.metadata_mut().get_mut::<LengthHistogramMetadata>() {
+            Some(histogram) if idx == histogram.lens.len() => {
+                histogram.push(idx, len);
+                Ok(())
+            }
+            _ => Self::rebuild_histogram(state),
+        }
+    }
+
+    fn on_replace(&self, state: &mut S, idx: usize, testcase: &Testcase<I>) -> Result<(), Error> {
+        self.base.on_replace(state, idx, testcase)?;
+        let len = state.corpus().get(idx)?.borrow_mut().cached_len()?;
+        match state.metadata_mut().get_mut::<LengthHistogramMetadata>() {
+            Some(histogram) if idx < histogram.lens.len() => {
+                histogram.set(idx, len);
+                Ok(())
+            }
+            _ => Self::rebuild_histogram(state),
+        }
+    }
+
+    fn on_remove(
+        &self,
+        state: &mut S,
+        idx: usize,
+        testcase: &Option<Testcase<I>>,
+    ) -> Result<(), Error> {
+        if let Some(histogram) = state.metadata_mut().get_mut::<LengthHistogramMetadata>() {
+            if idx < histogram.lens.len() {
+                histogram.remove(idx);
+            }
+        }
+        self.base.on_remove(state, idx, testcase)
+    }
+
+    /// Gets the next entry, either from the base scheduler or by length
+    fn next(&self, state: &mut S) -> Result<usize, Error> {
+        if state.corpus().count() == 0 || state.rand_mut().below(100) >= self.prefer_rare_prob {
+            return self.base.next(state);
+        }
+
+        // Testcases added without telling the scheduler, e.g. before it was created
+        let in_sync = state
+            .metadata()
+            .get::<LengthHistogramMetadata>()
+            .map_or(false, |histogram| {
+                histogram.lens.len() == state.corpus().count()
+            });
+        if !in_sync {
+            Self::rebuild_histogram(state)?;
+        }
+
+        let lens = Self::histogram(state).by_len.len();
+        let nth = state.rand_mut().below(lens as u64) as usize;
+        let count = Self::histogram(state).entries_of_nth_len(nth).len();
+        let pick = state.rand_mut().below(count as u64) as usize;
+        let id = Self::histogram(state).entries_of_nth_len(nth)[pick];
+
+        *state.corpus_mut().current_mut() = Some(id);
+        Ok(id)
+    }
+}
+
+impl<C, CS, I, R, S> LengthDiversityScheduler<C, CS, I, R, S>
+where
+    CS: CorpusScheduler<I, S>,
+    I: Input + HasLen,
+    S: HasCorpus<C, I> + HasRand<R> + HasMetadata,
+    C: Corpus<I>,
+    R: Rand,
+{
+    /// Creates a new [`LengthDiversityScheduler`] that wraps a `base` [`CorpusScheduler`]
+    /// and picks by length with a default probability of [`DEFAULT_PREFER_RARE_LEN_PROB`].
+    pub fn new(base: CS) -> Self {
+        Self::with_prefer_rare_prob(base, DEFAULT_PREFER_RARE_LEN_PROB)
+    }
+
+    /// Creates a new [`LengthDiversityScheduler`] that wraps a `base` [`CorpusScheduler`]
+    /// and picks by length with a probability of `prefer_rare_prob` percent.
+    pub fn with_prefer_rare_prob(base: CS, prefer_rare_prob: u64) -> Self {
+        Self {
+            base,
+            prefer_rare_prob,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The wrapped scheduler
+    #[must_use]
+    pub fn base(&self) -> &CS {
+        &self.base
+    }
+
+    /// The [`LengthHistogramMetadata`] of the state, which has to be present
+    fn histogram(state: &S) -> &LengthHistogramMetadata {
+        state.metadata().get::<LengthHistogramMetadata>().unwrap()
+    }
+
+    /// Builds the [`LengthHistogramMetadata`] from scratch, out of all testcases in the corpus
+    fn rebuild_histogram(state: &mut S) -> Result<(), Error> {
+        let mut histogram = LengthHistogramMetadata::default();
+        for idx in 0..state.corpus().count() {
+            let len = state.corpus().get(idx)?.borrow_mut().cached_len()?;
+            histogram.push(idx, len);
+        }
+        state.add_metadata(histogram);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::{
+            Corpus, CorpusScheduler, InMemoryCorpus, LengthDiversityScheduler,
+            LengthHistogramMetadata, RandCorpusScheduler, Testcase,
+        },
+        inputs::BytesInput,
+        state::{HasCorpus, HasMetadata, StdState},
+    };
+
+    #[test]
+    fn test_length_diversity_scheduler() {
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        // 95 testcases of length 4, 5 testcases of length 16
+        for i in 0..100 {
+            let len = if i % 20 == 0 { 16 } else { 4 };
+            state
+                .corpus_mut()
+                .add(Testcase::new(BytesInput::new(vec![0; len])))
+                .unwrap();
+        }
+
+        let scheduler =
+            LengthDiversityScheduler::with_prefer_rare_prob(RandCorpusScheduler::new(), 50);
+        let mut rare = 0;
+        for _ in 0..1000 {
+            let idx = scheduler.next(&mut state).unwrap();
+            if idx % 20 == 0 {
+                rare += 1;
+            }
+        }
+        // About 5% if uniform, about 27% with half of the picks by length
+        assert!(rare > 150, "rare lengths picked {} times", rare);
+    }
+
+    #[test]
+    fn test_length_histogram_updates() {
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let scheduler = LengthDiversityScheduler::new(RandCorpusScheduler::new());
+        for len in [4, 16, 4] {
+            let idx = state
+                .corpus_mut()
+                .add(Testcase::new(BytesInput::new(vec![0; len])))
+                .unwrap();
+            scheduler.on_add(&mut state, idx).unwrap();
+        }
+        let histogram = |state: &StdState<_, _, _, _, _>| -> Vec<(usize, Vec<usize>)> {
+            let meta = state.metadata().get::<LengthHistogramMetadata>().unwrap();
+            meta.by_len
+                .iter()
+                .map(|(len, entries)| (*len, entries.clone()))
+                .collect()
+        };
+        assert_eq!(histogram(&state), [(4, vec![0, 2]), (16, vec![1])]);
+
+        let testcase = Testcase::new(BytesInput::new(vec![0; 16]));
+        state.corpus_mut().replace(0, testcase.clone()).unwrap();
+        scheduler.on_replace(&mut state, 0, &testcase).unwrap();
+        assert_eq!(histogram(&state), [(4, vec![2]), (16, vec![1, 0])]);
+
+        // The following testcases move down
+        let removed = state.corpus_mut().remove(1).unwrap();
+        scheduler.on_remove(&mut state, 1, &removed).unwrap();
+        assert_eq!(histogram(&state), [(4, vec![1]), (16, vec![0])]);
+    }
+}
@@ -0,0 +1,210 @@
+//! Offline corpus consolidation, like `afl-cmin`: merges several corpus directories into a
+//! minimal set of inputs covering the same map entries.
+
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use hashbrown::HashSet;
+use num_traits::PrimInt;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    executors::{Executor, HasObservers},
+    fuzzer::ExecutesInput,
+    inputs::Input,
+    observers::{MapObserver, ObserversTuple},
+    Error,
+};
+
+/// Loads all inputs in `in_dirs`, runs each of them, and writes a minimal subset to `out_dir`
+/// that covers every map entry any of the inputs covered.
+///
+/// The coverage of each input is read from the [`MapObserver`] of type `O` named `observer_name`,
+/// in the observers of the `executor`. The subset is selected greedily: the input adding the most
+/// uncovered entries is taken, until nothing is left to cover. Inputs are written to `out_dir`,
+/// created if needed, using [`Input::generate_name`].
+/// Returns the number of inputs written.
+#[allow(clippy::too_many_arguments)]
+pub fn merge_corpora<O, T, E, EM, I, OT, S, Z>(
+    fuzzer: &mut Z,
+    executor: &mut E,
+    state: &mut S,
+    manager: &mut EM,
+    observer_name: &str,
+    in_dirs: &[PathBuf],
+    out_dir: &Path,
+) -> Result<usize, Error>
+where
+    O: MapObserver<T>,
+    T: PrimInt + Default + Copy + Debug,
+    E: Executor<EM, I, S, Z> + HasObservers<I, OT, S>,
+    I: Input,
+    OT: ObserversTuple<I, S>,
+    Z: ExecutesInput<I, OT, S, Z>,
+{
+    let mut paths = vec![];
+    for in_dir in in_dirs {
+        for entry in fs::read_dir(in_dir)? {
+            let path = entry?.path();
+            if fs::metadata(&path).map_or(false, |attr| attr.is_file() && attr.len() > 0) {
+                paths.push(path);
+            }
+        }
+    }
+    paths.sort();
+
+    let mut inputs = vec![];
+    let mut coverages: Vec<HashSet<usize>> = vec![];
+    for path in &paths {
+        let input = I::from_file(path)?;
+        fuzzer.execute_input(state, executor, manager, &input)?;
+        let observer = executor
+            .observers()
+            .match_name::<O>(observer_name)
+            .ok_or_else(|| Error::KeyNotFound(format!("{} not found", observer_name)))?;
+        let initial = observer.initial();
+        coverages.push(
+            (0..observer.usable_count())
+                .filter(|idx| *observer.get(*idx) != initial)
+                .collect(),
+        );
+        inputs.push(input);
+    }
+
+    let mut uncovered: HashSet<usize> = coverages.iter().flatten().copied().collect();
+    fs::create_dir_all(out_dir)?;
+    let mut written = 0;
+    while !uncovered.is_empty() {
+        let (best, _) = coverages
+            .iter()
+            .enumerate()
+            .map(|(idx, coverage)| (idx, coverage.intersection(&uncovered).count()))
+            .rev()
+            .max_by_key(|(_, added)| *added)
+            .unwrap();
+        for entry in &coverages[best] {
+            uncovered.remove(entry);
+        }
+        inputs[best].to_file(out_dir.join(inputs[best].generate_name(written)))?;
+        written += 1;
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use crate::{
+        bolts::{
+            rands::StdRand,
+            tuples::{tuple_list, tuple_list_type},
+        },
+        corpus::{merge_corpora, InMemoryCorpus, QueueCorpusScheduler},
+        events::NopEventManager,
+        executors::{Executor, ExitKind, HasObservers},
+        feedbacks::CrashFeedback,
+        fuzzer::bytes_fuzzer,
+        inputs::{BytesInput, HasBytesVec, Input},
+        observers::{MapObserver, StdMapObserver},
+        state::StdState,
+        Error,
+    };
+
+    type Observers = tuple_list_type!(StdMapObserver<'static, u8>);
+
+    /// Covers map entry `b - b'a'` for each byte `b` of the input
+    #[derive(Debug)]
+    struct LetterExecutor {
+        observers: Observers,
+    }
+
+    impl<EM, S, Z> Executor<EM, BytesInput, S, Z> for LetterExecutor {
+        fn run_target(
+            &mut self,
+            _fuzzer: &mut Z,
+            _state: &mut S,
+            _mgr: &mut EM,
+            input: &BytesInput,
+        ) -> Result<ExitKind, Error> {
+            for byte in input.bytes() {
+                self.observers.0.map_mut().unwrap()[(byte - b'a') as usize] = 1;
+            }
+            Ok(ExitKind::Ok)
+        }
+    }
+
+    impl<S> HasObservers<BytesInput, Observers, S> for LetterExecutor {
+        fn observers(&self) -> &Observers {
+            &self.observers
+        }
+
+        fn observers_mut(&mut self) -> &mut Observers {
+            &mut self.observers
+        }
+    }
+
+    #[test]
+    fn test_merge_corpora() {
+        let base = std::env::temp_dir().join("libafl_test_merge_corpora");
+        let _ = fs::remove_dir_all(&base);
+        let in_dirs: Vec<PathBuf> = (0..3).map(|i| base.join(format!("in{}", i))).collect();
+        for (dir, inputs) in in_dirs
+            .iter()
+            .zip([&["ab", "b"][..], &["cd"], &["abc", "d"]])
+        {
+            fs::create_dir_all(dir).unwrap();
+            for input in inputs {
+                BytesInput::new(input.as_bytes().to_vec())
+                    .to_file(dir.join(input))
+                    .unwrap();
+            }
+        }
+        let out_dir = base.join("out");
+
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let mut fuzzer = bytes_fuzzer::<_, _, _, Observers, _>(
+            QueueCorpusScheduler::new(),
+            CrashFeedback::new(),
+            CrashFeedback::new(),
+        );
+        let mut executor = LetterExecutor {
+            observers: tuple_list!(StdMapObserver::new_owned("letters", vec![0; 4])),
+        };
+        let mut mgr = NopEventManager {};
+
+        // "abc" and one of the inputs covering "d" are enough
+        let written = merge_corpora::<StdMapObserver<u8>, u8, _, _, _, _, _, _>(
+            &mut fuzzer,
+            &mut executor,
+            &mut state,
+            &mut mgr,
+            "letters",
+            &in_dirs,
+            &out_dir,
+        )
+        .unwrap();
+        assert_eq!(written, 2);
+
+        let mut covered: Vec<u8> = vec![];
+        for entry in fs::read_dir(&out_dir).unwrap() {
+            covered.extend(
+                BytesInput::from_file(entry.unwrap().path())
+                    .unwrap()
+                    .bytes(),
+            );
+        }
+        covered.sort_unstable();
+        covered.dedup();
+        assert_eq!(covered, b"abcd");
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+}
@@ -1,11 +1,14 @@
 //! Corpuses contain the testcases, either in mem, on disk, or somewhere else.
 
 pub mod testcase;
-pub use testcase::{PowerScheduleTestcaseMetaData, Testcase};
+pub use testcase::{OriginMetadata, PowerScheduleTestcaseMetaData, Testcase};
 
 pub mod inmemory;
 pub use inmemory::InMemoryCorpus;
 
+pub mod fixed;
+pub use fixed::FixedCorpus;
+
 #[cfg(feature = "std")]
 pub mod ondisk;
 #[cfg(feature = "std")]
@@ -16,6 +19,24 @@ pub mod cached;
 #[cfg(feature = "std")]
 pub use cached::CachedOnDiskCorpus;
 
+#[cfg(feature = "corpus_compression")]
+pub mod compressed;
+#[cfg(feature = "corpus_compression")]
+pub use compressed::CompressedInMemoryCorpus;
+
+#[cfg(feature = "crash_minimization")]
+pub mod crashmin;
+#[cfg(feature = "crash_minimization")]
+pub use crashmin::{minimize_crash, CrashMinimizingCorpus, CrashOracle, MinimizedCrashMetadata};
+
+#[cfg(feature = "std")]
+pub mod merge;
+#[cfg(feature = "std")]
+pub use merge::merge_corpora;
+
+pub mod dedup;
+pub use dedup::CoverageDedupCorpus;
+
 pub mod queue;
 pub use queue::QueueCorpusScheduler;
 
@@ -29,7 +50,24 @@ pub use minimizer::{
 pub mod powersched;
 pub use powersched::PowerQueueCorpusScheduler;
 
-use alloc::borrow::ToOwned;
+pub mod hooked;
+pub use hooked::HookedScheduler;
+
+pub mod lendiversity;
+pub use lendiversity::{
+    LengthDiversityScheduler, LengthHistogramMetadata, DEFAULT_PREFER_RARE_LEN_PROB,
+};
+
+pub mod priority;
+pub use priority::{PriorityCorpusScheduler, PriorityMetadata};
+
+pub mod burst;
+pub use burst::{BurstMetadata, BurstScheduler, DEFAULT_BURST_ROUNDS};
+
+pub mod stable;
+pub use stable::StableCorpusScheduler;
+
+use alloc::{borrow::ToOwned, vec::Vec};
 use core::{cell::RefCell, marker::PhantomData};
 
 use crate::{
@@ -69,6 +107,13 @@ where
 
     /// Current testcase scheduled (mut)
     fn current_mut(&mut self) -> &mut Option<usize>;
+
+    /// Takes the testcases this corpus removed by itself since the last call, for example to stay
+    /// within a capacity, with the index each had, in the order they got removed.
+    /// The fuzzer passes them to [`CorpusScheduler::on_remove`] after each add.
+    fn take_evicted(&mut self) -> Vec<(usize, Testcase<I>)> {
+        vec![]
+    }
 }
 
 /// The scheduler define how the fuzzer requests a testcase from the corpus.
@@ -102,7 +147,9 @@ where
         Ok(())
     }
 
-    /// Gets the next entry
+    /// Gets the next entry.
+    /// The fuzzer counts the selection in [`Testcase::scheduled_count`], not the scheduler, so
+    /// wrapping schedulers don't count it twice.
     fn next(&self, state: &mut S) -> Result<usize, Error>;
 }
 
@@ -1,19 +1,28 @@
 //! The ondisk corpus stores unused testcases to disk.
 
-use alloc::vec::Vec;
+use alloc::{string::String, sync::Arc, vec::Vec};
 use core::{cell::RefCell, time::Duration};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, Serializer};
 use std::{
     fs::OpenOptions,
     path::{Path, PathBuf},
+    sync::{
+        mpsc::{sync_channel, SyncSender},
+        Condvar, Mutex,
+    },
+    thread::{self, JoinHandle},
 };
 
 #[cfg(feature = "std")]
 use std::{fs, fs::File, io::Write};
 
 use crate::{
-    bolts::serdeany::SerdeAnyMap, corpus::Corpus, corpus::Testcase, inputs::Input,
-    state::HasMetadata, Error,
+    bolts::{fs::write_file_atomic, serdeany::SerdeAnyMap},
+    corpus::Corpus,
+    corpus::Testcase,
+    inputs::Input,
+    state::HasMetadata,
+    Error,
 };
 
 /// Options for the the format of the on-disk metadata
@@ -35,6 +44,7 @@ pub struct OnDiskMetadata<'a> {
     metadata: &'a SerdeAnyMap,
     exec_time: &'a Option<Duration>,
     executions: &'a usize,
+    scheduled_count: usize,
 }
 
 /// A corpus able to store testcases to disk, and load them from disk, when they are being used.
@@ -49,6 +59,16 @@ where
     current: Option<usize>,
     dir_path: PathBuf,
     meta_format: Option<OnDiskMetadataFormat>,
+    sync_on_add: bool,
+    /// The maximum number of stored testcases, see [`OnDiskCorpus::new_capped`]
+    cap: Option<usize>,
+    /// The testcases evicted to stay within the `cap`, until taken by [`Corpus::take_evicted`]
+    #[serde(skip)]
+    evicted: Vec<(usize, Testcase<I>)>,
+    /// Serializing the corpus, as the restarting event managers do before exiting, waits for all
+    /// pending writes, so none gets lost when the process exits without dropping the corpus.
+    #[serde(serialize_with = "flush_on_serialize", skip_deserializing)]
+    writer: Option<Arc<AsyncDiskWriter>>,
 }
 
 impl<I> Corpus<I> for OnDiskCorpus<I>
@@ -100,32 +120,52 @@ where
                 ".{}.metadata",
                 filename.file_name().unwrap().to_string_lossy()
             ));
-            let mut tmpfile_name = PathBuf::from(&filename);
-            tmpfile_name.set_file_name(format!(
-                ".{}.tmp",
-                tmpfile_name.file_name().unwrap().to_string_lossy()
-            ));
-
             let ondisk_meta = OnDiskMetadata {
                 metadata: testcase.metadata(),
                 exec_time: testcase.exec_time(),
                 executions: testcase.executions(),
+                scheduled_count: testcase.scheduled_count(),
             };
 
-            let mut tmpfile = File::create(&tmpfile_name)?;
-
             let serialized = match self.meta_format.as_ref().unwrap() {
                 OnDiskMetadataFormat::Postcard => postcard::to_allocvec(&ondisk_meta)?,
                 OnDiskMetadataFormat::Json => serde_json::to_vec(&ondisk_meta)?,
                 OnDiskMetadataFormat::JsonPretty => serde_json::to_vec_pretty(&ondisk_meta)?,
             };
-            tmpfile.write_all(&serialized)?;
-            fs::rename(&tmpfile_name, &filename)?;
+            if let Some(writer) = &self.writer {
+                writer.enqueue(filename, serialized);
+            } else {
+                let mut tmpfile_name = PathBuf::from(&filename);
+                tmpfile_name.set_file_name(format!(
+                    ".{}.tmp",
+                    tmpfile_name.file_name().unwrap().to_string_lossy()
+                ));
+
+                let mut tmpfile = File::create(&tmpfile_name)?;
+                tmpfile.write_all(&serialized)?;
+                fs::rename(&tmpfile_name, &filename)?;
+            }
+        }
+        if let Some(writer) = &self.writer {
+            // The input stays in memory, as it may not be on disk yet when it gets loaded
+            let bytes = testcase.input().as_ref().unwrap().to_file_bytes()?;
+            writer.enqueue(testcase.filename().as_ref().unwrap().into(), bytes);
+        } else {
+            testcase
+                .store_input()
+                .expect("Could not save testcase to disk");
+        }
+        if self.sync_on_add {
+            self.flush()?;
+            self.sync_testcase(&testcase)?;
+            sync_path(&self.dir_path)?;
         }
-        testcase
-            .store_input()
-            .expect("Could not save testcase to disk");
         self.entries.push(RefCell::new(testcase));
+        if let Some(cap) = self.cap {
+            while self.entries.len() > cap {
+                self.evict_oldest()?;
+            }
+        }
         Ok(self.entries.len() - 1)
     }
 
@@ -166,6 +206,12 @@ where
     fn current_mut(&mut self) -> &mut Option<usize> {
         &mut self.current
     }
+
+    /// The testcases evicted to stay within the cap, see [`OnDiskCorpus::new_capped`]
+    #[inline]
+    fn take_evicted(&mut self) -> Vec<(usize, Testcase<I>)> {
+        core::mem::take(&mut self.evicted)
+    }
 }
 
 impl<I> OnDiskCorpus<I>
@@ -185,6 +231,10 @@ where
                 current: None,
                 dir_path,
                 meta_format: None,
+                sync_on_add: false,
+                cap: None,
+                evicted: vec![],
+                writer: None,
             })
         }
         new(dir_path.as_ref().to_path_buf())
@@ -202,6 +252,370 @@ where
             current: None,
             dir_path,
             meta_format,
+            sync_on_add: false,
+            cap: None,
+            evicted: vec![],
+            writer: None,
         })
     }
+
+    /// Creates the [`OnDiskCorpus`], storing at most `cap` testcases, for example for objectives
+    /// of a target that crashes a lot. Once full, adding a testcase removes the oldest one, and
+    /// deletes its files, so the disk usage stays bounded while the most recent finds are kept.
+    /// The indices of all later testcases shift down, the fuzzer tells its scheduler about the
+    /// removal, see [`Corpus::take_evicted`].
+    /// Will error, if [`std::fs::create_dir_all()`] failed for `dir_path`.
+    pub fn new_capped<P>(dir_path: P, cap: usize) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let mut corpus = Self::new(dir_path)?;
+        corpus.cap = Some(cap.max(1));
+        Ok(corpus)
+    }
+
+    /// Creates the [`OnDiskCorpus`], writing testcases and their metadata to disk on a background thread.
+    /// Adding a testcase only enqueues its writes, blocking only if `queue_cap` writes are already pending.
+    /// Call [`OnDiskCorpus::flush`] to wait for all pending writes, which also happens on drop,
+    /// and when the corpus gets serialized, for example in `on_restart` of a crash handler.
+    /// In this mode, the inputs are also kept in memory.
+    /// After deserialization (for example on restart), writes are synchronous again.
+    /// Will error, if [`std::fs::create_dir_all()`] failed for `dir_path`.
+    pub fn new_async<P>(dir_path: P, queue_cap: usize) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let mut corpus = Self::new(dir_path)?;
+        corpus.writer = Some(Arc::new(AsyncDiskWriter::new(queue_cap)));
+        Ok(corpus)
+    }
+
+    /// Waits until all pending background writes of an [`OnDiskCorpus::new_async`] corpus are on disk.
+    /// Returns the first error that happened in the background, if any.
+    /// Without background writer, this does nothing.
+    pub fn flush(&self) -> Result<(), Error> {
+        match &self.writer {
+            Some(writer) => writer.flush(),
+            None => Ok(()),
+        }
+    }
+
+    /// Flushes every added testcase, and its metadata, to disk, using `fsync`.
+    /// Without this, the OS decides when to write them, so testcases added shortly
+    /// before an unclean shutdown may get lost.
+    pub fn sync(&self) -> Result<(), Error> {
+        self.flush()?;
+        for testcase in &self.entries {
+            self.sync_testcase(&testcase.borrow())?;
+        }
+        sync_path(&self.dir_path)
+    }
+
+    /// Syncs each testcase to disk right when it gets added, see [`OnDiskCorpus::sync`].
+    /// This makes sure no testcase is lost on unclean shutdown, at the cost of throughput.
+    #[must_use]
+    pub fn with_sync_on_add(mut self, sync_on_add: bool) -> Self {
+        self.sync_on_add = sync_on_add;
+        self
+    }
+
+    /// Removes the oldest testcase, deleting its files
+    fn evict_oldest(&mut self) -> Result<(), Error> {
+        // Its files may still be pending in the background
+        self.flush()?;
+        let testcase = self.entries.remove(0).into_inner();
+        self.current = match self.current {
+            Some(0) | None => None,
+            Some(current) => Some(current - 1),
+        };
+        if let Some(filename) = testcase.filename() {
+            let filename = PathBuf::from(filename);
+            let name = filename.file_name().unwrap().to_string_lossy();
+            for hidden in [
+                format!(".{}.metadata", name),
+                format!(".{}.lafl_lock", name),
+            ] {
+                let _ = fs::remove_file(filename.with_file_name(hidden));
+            }
+            fs::remove_file(&filename)?;
+        }
+        self.evicted.push((0, testcase));
+        Ok(())
+    }
+
+    fn sync_testcase(&self, testcase: &Testcase<I>) -> Result<(), Error> {
+        if let Some(filename) = testcase.filename() {
+            sync_path(filename)?;
+            if self.meta_format.is_some() {
+                let mut filename = PathBuf::from(filename);
+                filename.set_file_name(format!(
+                    ".{}.metadata",
+                    filename.file_name().unwrap().to_string_lossy()
+                ));
+                sync_path(&filename)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The pending writes of an [`AsyncDiskWriter`], and the first error that happened
+#[derive(Debug, Default)]
+struct AsyncWriteProgress {
+    pending: usize,
+    error: Option<String>,
+}
+
+/// Writes files on a background thread, fed through a bounded queue.
+/// The queue is drained when the writer gets dropped.
+#[derive(Debug)]
+struct AsyncDiskWriter {
+    sender: Option<SyncSender<(PathBuf, Vec<u8>)>>,
+    progress: Arc<(Mutex<AsyncWriteProgress>, Condvar)>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl AsyncDiskWriter {
+    fn new(queue_cap: usize) -> Self {
+        let (sender, receiver) = sync_channel::<(PathBuf, Vec<u8>)>(queue_cap);
+        let progress = Arc::new((Mutex::new(AsyncWriteProgress::default()), Condvar::new()));
+        let thread_progress = progress.clone();
+        let thread = thread::spawn(move || {
+            for (path, bytes) in receiver {
+                let res = write_file_atomic(&path, &bytes);
+                let (lock, cvar) = &*thread_progress;
+                let mut progress = lock.lock().unwrap();
+                progress.pending -= 1;
+                if let Err(err) = res {
+                    progress
+                        .error
+                        .get_or_insert_with(|| format!("Could not write {:?}: {}", path, err));
+                }
+                cvar.notify_all();
+            }
+        });
+        Self {
+            sender: Some(sender),
+            progress,
+            thread: Some(thread),
+        }
+    }
+
+    /// Enqueues a write, blocking while the queue is full
+    fn enqueue(&self, path: PathBuf, bytes: Vec<u8>) {
+        self.progress.0.lock().unwrap().pending += 1;
+        self.sender
+            .as_ref()
+            .unwrap()
+            .send((path, bytes))
+            .expect("The disk writer thread died");
+    }
+
+    /// Waits for all pending writes
+    fn flush(&self) -> Result<(), Error> {
+        let (lock, cvar) = &*self.progress;
+        let mut progress = lock.lock().unwrap();
+        while progress.pending > 0 {
+            progress = cvar.wait(progress).unwrap();
+        }
+        match progress.error.take() {
+            Some(err) => Err(Error::Unknown(err)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for AsyncDiskWriter {
+    fn drop(&mut self) {
+        // Closing the queue ends the thread, once it wrote everything
+        drop(self.sender.take());
+        if let Some(thread) = self.thread.take() {
+            thread.join().expect("The disk writer thread panicked");
+        }
+    }
+}
+
+/// Waits for the pending writes of the [`AsyncDiskWriter`], then serializes it as unit
+fn flush_on_serialize<S>(
+    writer: &Option<Arc<AsyncDiskWriter>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if let Some(writer) = writer {
+        writer.flush().map_err(serde::ser::Error::custom)?;
+    }
+    serializer.serialize_unit()
+}
+
+/// Flushes the file, or directory entries, at `path` to disk.
+/// Directories can't be opened on Windows, where syncing them is a no-op.
+fn sync_path<P: AsRef<Path>>(path: P) -> Result<(), Error> {
+    let path = path.as_ref();
+    if cfg!(windows) && path.is_dir() {
+        return Ok(());
+    }
+    File::open(path)?.sync_all()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+    use std::fs;
+
+    use crate::{
+        bolts::current_time,
+        corpus::{Corpus, OnDiskCorpus, Testcase},
+        inputs::{BytesInput, HasBytesVec, Input},
+    };
+
+    #[test]
+    fn test_ondisk_corpus_sync() {
+        let dir = std::env::temp_dir().join("libafl_test_ondisk_corpus_sync");
+        let _ = fs::remove_dir_all(&dir);
+
+        for sync_on_add in [false, true] {
+            let mut corpus = OnDiskCorpus::<BytesInput>::new(&dir)
+                .unwrap()
+                .with_sync_on_add(sync_on_add);
+            let idx = corpus
+                .add(Testcase::new(BytesInput::new(vec![
+                    sync_on_add.into(),
+                    1,
+                    2,
+                ])))
+                .unwrap();
+            corpus.sync().unwrap();
+
+            let testcase = corpus.get(idx).unwrap().borrow();
+            let input = BytesInput::from_file(testcase.filename().as_ref().unwrap()).unwrap();
+            assert_eq!(input.bytes(), &[sync_on_add.into(), 1, 2]);
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_ondisk_corpus_async() {
+        let dir = std::env::temp_dir().join("libafl_test_ondisk_corpus_async");
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut corpus = OnDiskCorpus::<BytesInput>::new_async(&dir, 4).unwrap();
+        let start = current_time();
+        for i in 0..256_u32 {
+            corpus
+                .add(Testcase::new(BytesInput::new(i.to_le_bytes().to_vec())))
+                .unwrap();
+        }
+        assert!(current_time() < start + Duration::from_secs(5));
+        corpus.flush().unwrap();
+
+        for idx in 0..corpus.count() {
+            let testcase = corpus.get(idx).unwrap().borrow();
+            let input = BytesInput::from_file(testcase.filename().as_ref().unwrap()).unwrap();
+            assert_eq!(input.bytes(), &(idx as u32).to_le_bytes());
+        }
+
+        drop(corpus);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_ondisk_corpus_async_no_flush() {
+        let dir = std::env::temp_dir().join("libafl_test_ondisk_corpus_async_no_flush");
+        let _ = fs::remove_dir_all(&dir);
+
+        // Dropping drains the queue
+        let mut corpus = OnDiskCorpus::<BytesInput>::new_async(&dir, 4).unwrap();
+        for i in 0..64_u32 {
+            corpus
+                .add(Testcase::new(BytesInput::new(i.to_le_bytes().to_vec())))
+                .unwrap();
+        }
+        let filenames: Vec<_> = (0..corpus.count())
+            .map(|idx| {
+                corpus
+                    .get(idx)
+                    .unwrap()
+                    .borrow()
+                    .filename()
+                    .clone()
+                    .unwrap()
+            })
+            .collect();
+        drop(corpus);
+        for (i, filename) in filenames.iter().enumerate() {
+            let input = BytesInput::from_file(filename).unwrap();
+            assert_eq!(input.bytes(), &(i as u32).to_le_bytes());
+        }
+
+        // Serializing, as done on restart before exiting, drains it as well
+        let mut corpus = OnDiskCorpus::<BytesInput>::new_async(&dir, 4).unwrap();
+        let idx = corpus
+            .add(Testcase::new(BytesInput::new(vec![0xff; 4])))
+            .unwrap();
+        let filename = corpus
+            .get(idx)
+            .unwrap()
+            .borrow()
+            .filename()
+            .clone()
+            .unwrap();
+        let serialized = postcard::to_allocvec(&corpus).unwrap();
+        assert_eq!(
+            BytesInput::from_file(&filename).unwrap().bytes(),
+            &[0xff; 4]
+        );
+        core::mem::forget(corpus);
+
+        let restored: OnDiskCorpus<BytesInput> = postcard::from_bytes(&serialized).unwrap();
+        assert_eq!(restored.count(), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_ondisk_corpus_capped() {
+        let dir = std::env::temp_dir().join("libafl_test_ondisk_corpus_capped");
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut corpus = OnDiskCorpus::<BytesInput>::new_capped(&dir, 3).unwrap();
+        let mut filenames = vec![];
+        for i in 0..6_u8 {
+            let idx = corpus.add(Testcase::new(BytesInput::new(vec![i]))).unwrap();
+            let testcase = corpus.get(idx).unwrap().borrow();
+            filenames.push(testcase.filename().clone().unwrap());
+        }
+
+        // Only the 3 most recent objectives are left, on disk and in the corpus
+        assert_eq!(corpus.count(), 3);
+        let files = fs::read_dir(&dir)
+            .unwrap()
+            .filter(|entry| {
+                !entry
+                    .as_ref()
+                    .unwrap()
+                    .file_name()
+                    .to_string_lossy()
+                    .starts_with('.')
+            })
+            .count();
+        assert_eq!(files, 3);
+        for (i, filename) in filenames.iter().enumerate() {
+            assert_eq!(fs::metadata(filename).is_ok(), i >= 3);
+        }
+        let mut oldest = corpus.get(0).unwrap().borrow_mut();
+        assert_eq!(oldest.load_input().unwrap().bytes(), &[3]);
+        drop(oldest);
+
+        // The evicted testcases get reported once, for the scheduler
+        let evicted = corpus.take_evicted();
+        assert_eq!(evicted.len(), 3);
+        assert!(evicted.iter().all(|(idx, _)| *idx == 0));
+        assert!(corpus.take_evicted().is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
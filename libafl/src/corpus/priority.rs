@@ -0,0 +1,141 @@
+//! A corpus scheduler picking testcases proportionally to a priority set by hand, to steer fuzzing.
+
+use alloc::{borrow::ToOwned, vec::Vec};
+use core::marker::PhantomData;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::rands::Rand,
+    corpus::{Corpus, CorpusScheduler},
+    inputs::Input,
+    state::{HasCorpus, HasMetadata, HasRand},
+    Error,
+};
+
+/// A testcase metadata, the priority of the testcase for the [`PriorityCorpusScheduler`].
+/// Testcases without it have priority `1`, testcases with priority `0` are never picked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PriorityMetadata {
+    /// The priority, the weight of the testcase
+    pub priority: u32,
+}
+
+crate::impl_serdeany!(PriorityMetadata);
+
+impl PriorityMetadata {
+    /// Creates a new [`PriorityMetadata`]
+    #[must_use]
+    pub fn new(priority: u32) -> Self {
+        Self { priority }
+    }
+}
+
+/// Picks a random testcase, with a probability proportional to its [`PriorityMetadata`].
+/// Set the metadata on testcases suspected to be close to a bug, to fuzz them more often.
+#[derive(Debug, Clone)]
+pub struct PriorityCorpusScheduler<C, I, R, S>
+where
+    S: HasCorpus<C, I> + HasRand<R>,
+    C: Corpus<I>,
+    I: Input,
+    R: Rand,
+{
+    phantom: PhantomData<(C, I, R, S)>,
+}
+
+impl<C, I, R, S> CorpusScheduler<I, S> for PriorityCorpusScheduler<C, I, R, S>
+where
+    S: HasCorpus<C, I> + HasRand<R>,
+    C: Corpus<I>,
+    I: Input,
+    R: Rand,
+{
+    /// Gets the next entry, weighted by priority
+    fn next(&self, state: &mut S) -> Result<usize, Error> {
+        let weights = (0..state.corpus().count())
+            .map(|idx| {
+                Ok(state
+                    .corpus()
+                    .get(idx)?
+                    .borrow()
+                    .metadata()
+                    .get::<PriorityMetadata>()
+                    .map_or(1, |meta| u64::from(meta.priority)))
+            })
+            .collect::<Result<Vec<u64>, Error>>()?;
+        let id = state
+            .rand_mut()
+            .choose_weighted(&weights)
+            .ok_or_else(|| Error::Empty("No entries with a priority in corpus".to_owned()))?;
+
+        *state.corpus_mut().current_mut() = Some(id);
+        Ok(id)
+    }
+}
+
+impl<C, I, R, S> PriorityCorpusScheduler<C, I, R, S>
+where
+    S: HasCorpus<C, I> + HasRand<R>,
+    C: Corpus<I>,
+    I: Input,
+    R: Rand,
+{
+    /// Creates a new [`PriorityCorpusScheduler`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<C, I, R, S> Default for PriorityCorpusScheduler<C, I, R, S>
+where
+    S: HasCorpus<C, I> + HasRand<R>,
+    C: Corpus<I>,
+    I: Input,
+    R: Rand,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::{
+            Corpus, CorpusScheduler, InMemoryCorpus, PriorityCorpusScheduler, PriorityMetadata,
+            Testcase,
+        },
+        inputs::BytesInput,
+        state::{HasCorpus, HasMetadata, StdState},
+    };
+
+    #[test]
+    fn test_priority_corpus_scheduler() {
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let mut boosted = Testcase::new(BytesInput::new(vec![0]));
+        boosted.add_metadata(PriorityMetadata::new(10));
+        let mut disabled = Testcase::new(BytesInput::new(vec![2]));
+        disabled.add_metadata(PriorityMetadata::new(0));
+        for testcase in [boosted, Testcase::new(BytesInput::new(vec![1])), disabled] {
+            state.corpus_mut().add(testcase).unwrap();
+        }
+
+        let scheduler = PriorityCorpusScheduler::new();
+        let mut picks = [0; 3];
+        for _ in 0..11_000 {
+            picks[scheduler.next(&mut state).unwrap()] += 1;
+        }
+        // About 10000 and 1000
+        assert!(picks[0] > 9 * picks[1] && picks[0] < 11 * picks[1]);
+        assert_eq!(picks[2], 0);
+    }
+}
@@ -0,0 +1,88 @@
+//! A corpus scheduler wrapper skipping the testcases marked as unstable by the recalibration.
+
+use core::marker::PhantomData;
+
+use crate::{
+    corpus::{Corpus, CorpusScheduler, Testcase},
+    inputs::Input,
+    stages::recalibrate::UnstableTestcaseMetadata,
+    state::{HasCorpus, HasMetadata},
+    Error,
+};
+
+/// Wraps any [`CorpusScheduler`], and skips the testcases with an [`UnstableTestcaseMetadata`],
+/// set by a [`crate::stages::RecalibrationStage`] with a minimum stability.
+/// Unstable testcases stay in the corpus, so the indices of the wrapped scheduler stay valid.
+/// If the wrapped scheduler only returns unstable testcases, the last one is used anyway.
+#[derive(Debug, Clone)]
+pub struct StableCorpusScheduler<C, CS, I, S>
+where
+    C: Corpus<I>,
+    CS: CorpusScheduler<I, S>,
+    I: Input,
+    S: HasCorpus<C, I>,
+{
+    base: CS,
+    phantom: PhantomData<(C, I, S)>,
+}
+
+impl<C, CS, I, S> CorpusScheduler<I, S> for StableCorpusScheduler<C, CS, I, S>
+where
+    C: Corpus<I>,
+    CS: CorpusScheduler<I, S>,
+    I: Input,
+    S: HasCorpus<C, I>,
+{
+    fn on_add(&self, state: &mut S, idx: usize) -> Result<(), Error> {
+        self.base.on_add(state, idx)
+    }
+
+    fn on_replace(&self, state: &mut S, idx: usize, testcase: &Testcase<I>) -> Result<(), Error> {
+        self.base.on_replace(state, idx, testcase)
+    }
+
+    fn on_remove(
+        &self,
+        state: &mut S,
+        idx: usize,
+        testcase: &Option<Testcase<I>>,
+    ) -> Result<(), Error> {
+        self.base.on_remove(state, idx, testcase)
+    }
+
+    fn next(&self, state: &mut S) -> Result<usize, Error> {
+        // Give up after as many tries as there are testcases, so this always terminates
+        let mut idx = self.base.next(state)?;
+        for _ in 1..state.corpus().count() {
+            let unstable = state
+                .corpus()
+                .get(idx)?
+                .borrow()
+                .metadata()
+                .get::<UnstableTestcaseMetadata>()
+                .is_some();
+            if !unstable {
+                break;
+            }
+            idx = self.base.next(state)?;
+        }
+        Ok(idx)
+    }
+}
+
+impl<C, CS, I, S> StableCorpusScheduler<C, CS, I, S>
+where
+    C: Corpus<I>,
+    CS: CorpusScheduler<I, S>,
+    I: Input,
+    S: HasCorpus<C, I>,
+{
+    /// Creates a new [`StableCorpusScheduler`], wrapping `base`
+    #[must_use]
+    pub fn new(base: CS) -> Self {
+        Self {
+            base,
+            phantom: PhantomData,
+        }
+    }
+}
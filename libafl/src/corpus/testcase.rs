@@ -2,11 +2,12 @@
 //! It will contain a respective input, and metadata.
 
 use alloc::string::String;
-use core::{convert::Into, default::Default, option::Option, time::Duration};
+use core::{convert::Into, default::Default, fmt, option::Option, time::Duration};
 use serde::{Deserialize, Serialize};
 
 use crate::{
     bolts::{serdeany::SerdeAnyMap, HasLen},
+    corpus::Corpus,
     inputs::Input,
     state::HasMetadata,
     Error,
@@ -31,6 +32,8 @@ where
     cached_len: Option<usize>,
     /// Number of executions done at discovery time
     executions: usize,
+    /// Number of times the scheduler selected this testcase
+    scheduled_count: usize,
 }
 
 impl<I> HasMetadata for Testcase<I>
@@ -151,6 +154,19 @@ where
         &mut self.executions
     }
 
+    /// Get the number of times this testcase was selected by the scheduler.
+    /// It is serialized along with the testcase (and with its on-disk metadata), so it persists across restarts.
+    #[inline]
+    pub fn scheduled_count(&self) -> usize {
+        self.scheduled_count
+    }
+
+    /// Set the number of times this testcase was selected by the scheduler
+    #[inline]
+    pub fn set_scheduled_count(&mut self, scheduled_count: usize) {
+        self.scheduled_count = scheduled_count;
+    }
+
     /// Create a new Testcase instace given an input
     #[inline]
     pub fn new<T>(input: T) -> Self
@@ -164,6 +180,7 @@ where
             exec_time: None,
             cached_len: None,
             executions: 0,
+            scheduled_count: 0,
         }
     }
 
@@ -177,6 +194,7 @@ where
             exec_time: None,
             cached_len: None,
             executions: 0,
+            scheduled_count: 0,
         }
     }
 
@@ -190,6 +208,7 @@ where
             exec_time: None,
             cached_len: None,
             executions,
+            scheduled_count: 0,
         }
     }
 
@@ -204,6 +223,7 @@ where
             exec_time: None,
             cached_len: None,
             executions: 0,
+            scheduled_count: 0,
         }
     }
 }
@@ -330,3 +350,40 @@ impl PowerScheduleTestcaseMetaData {
 }
 
 crate::impl_serdeany!(PowerScheduleTestcaseMetaData);
+
+/// Where a corpus entry came from, set when it gets added to the corpus
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OriginMetadata {
+    /// Loaded from disk, for example from the initial seeds
+    Imported,
+    /// Produced by a [`crate::generators::Generator`]
+    Generated,
+    /// Produced by mutating another corpus entry
+    Mutated,
+}
+
+crate::impl_serdeany!(OriginMetadata);
+
+impl OriginMetadata {
+    /// Tags the corpus entry at `idx` with this origin, if an entry was added
+    pub fn tag<C, I>(self, corpus: &C, idx: Option<usize>) -> Result<(), Error>
+    where
+        C: Corpus<I>,
+        I: Input,
+    {
+        if let Some(idx) = idx {
+            corpus.get(idx)?.borrow_mut().add_metadata(self);
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for OriginMetadata {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OriginMetadata::Imported => write!(f, "imported"),
+            OriginMetadata::Generated => write!(f, "generated"),
+            OriginMetadata::Mutated => write!(f, "mutated"),
+        }
+    }
+}
@@ -10,29 +10,42 @@ use crate::bolts::{
     llmp::{LLMP_FLAG_COMPRESSED, LLMP_FLAG_INITIALIZED},
 };
 #[cfg(feature = "std")]
-use crate::bolts::{llmp::LlmpConnection, shmem::StdShMemProvider, staterestore::StateRestorer};
+use crate::bolts::{
+    current_time, llmp::LlmpConnection, shmem::StdShMemProvider, staterestore::StateRestorer,
+};
+#[cfg(feature = "std")]
+use crate::events::client_seed;
 use crate::{
     bolts::{
         llmp::{self, Flags, LlmpClient, LlmpClientDescription, Tag},
+        rands::Rand,
         shmem::ShMemProvider,
     },
     events::{
         BrokerEventResult, Event, EventConfig, EventFirer, EventManager, EventManagerId,
-        EventProcessor, EventRestarter, HasEventManagerId, ProgressReporter,
+        EventProcessor, EventRestarter, HasEventManagerId, LogSeverity, ProgressReporter,
     },
     executors::{Executor, HasObservers},
     fuzzer::{EvaluatorObservers, ExecutionProcessor},
     inputs::Input,
     monitors::Monitor,
     observers::ObserversTuple,
+    state::HasRand,
     Error,
 };
-use alloc::string::ToString;
 #[cfg(feature = "std")]
-use core::sync::atomic::{compiler_fence, Ordering};
-use core::{marker::PhantomData, time::Duration};
+use alloc::collections::VecDeque;
+use alloc::{string::ToString, vec::Vec};
+use core::{cell::RefCell, marker::PhantomData, time::Duration};
+#[cfg(feature = "std")]
+use core::{
+    fmt::Display,
+    ops::ControlFlow,
+    sync::atomic::{compiler_fence, Ordering},
+};
 #[cfg(feature = "std")]
 use core_affinity::CoreId;
+use hashbrown::{HashMap, HashSet};
 use serde::de::DeserializeOwned;
 #[cfg(feature = "std")]
 use serde::Serialize;
@@ -48,6 +61,9 @@ const _LLMP_TAG_EVENT_TO_BROKER: Tag = 0x2B80438;
 /// Handle in both
 ///
 const LLMP_TAG_EVENT_TO_BOTH: Tag = 0x2B0741;
+/// Sent by the broker to tell a client it got rejected, see [`LlmpEventBroker::set_fingerprint`].
+/// The payload is the id of the client, which exits on receiving it.
+const LLMP_TAG_CLIENT_REJECTED: Tag = 0x2E7EC7ED;
 const _LLMP_TAG_RESTART: Tag = 0x8357A87;
 const _LLMP_TAG_NO_RESTART: Tag = 0x57A7EE71;
 
@@ -55,6 +71,134 @@ const _LLMP_TAG_NO_RESTART: Tag = 0x57A7EE71;
 #[cfg(feature = "llmp_compression")]
 const COMPRESS_THRESHOLD: usize = 1024;
 
+/// Which [`Event::NewTestcase`] events the [`LlmpEventBroker`] forwards to the other clients.
+/// Sharing fewer testcases keeps the broker from saturating on large fleets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CorpusSharingPolicy {
+    /// Forward all new testcases, the default
+    All,
+    /// Forward only the testcases growing the corpus of their client beyond the biggest corpus
+    /// size any client reported so far. This is not aware of coverage: the broker cannot decode
+    /// the observers of its clients, so it only shares the finds of the clients leading in corpus
+    /// size, and drops those of clients catching up, even if they cover something new.
+    BeyondLargestCorpus,
+    /// Forward one in `n` new testcases of each client, starting with the first one
+    Sample(u64),
+}
+
+impl Default for CorpusSharingPolicy {
+    fn default() -> Self {
+        Self::All
+    }
+}
+
+/// Applies the [`CorpusSharingPolicy`] of a broker to the new testcases of its clients
+#[derive(Debug, Clone, Default)]
+struct CorpusSharing {
+    policy: CorpusSharingPolicy,
+    /// The number of new testcases received from each client
+    received: HashMap<u32, u64>,
+    /// The biggest corpus size any client reported
+    max_corpus_size: usize,
+}
+
+impl CorpusSharing {
+    /// Returns `true` if a new testcase of this client should be forwarded
+    fn should_forward(&mut self, client_id: u32, corpus_size: usize) -> bool {
+        let received = self.received.entry(client_id).or_default();
+        *received += 1;
+        let beyond_largest = corpus_size > self.max_corpus_size;
+        self.max_corpus_size = self.max_corpus_size.max(corpus_size);
+
+        match self.policy {
+            CorpusSharingPolicy::All => true,
+            CorpusSharingPolicy::BeyondLargestCorpus => beyond_largest,
+            CorpusSharingPolicy::Sample(n) => (*received - 1) % n.max(1) == 0,
+        }
+    }
+}
+
+/// Refuses the clients of a broker built from another binary, see [`EventConfig::Fingerprinted`]
+#[derive(Debug, Clone, Default)]
+struct ClientAdmission {
+    /// The schema fingerprint all clients need to have, if any
+    fingerprint: Option<u64>,
+    /// The clients admitted so far
+    admitted: HashSet<u32>,
+    /// The clients refused so far, all their events get dropped
+    rejected: HashSet<u32>,
+    /// The clients refused since the last [`ClientAdmission::take_newly_rejected`]
+    newly_rejected: Vec<u32>,
+}
+
+impl ClientAdmission {
+    /// Returns `true` if this client got refused before
+    fn is_rejected(&self, client_id: u32) -> bool {
+        self.rejected.contains(&client_id)
+    }
+
+    /// Refuses this client from now on
+    fn reject(&mut self, client_id: u32) {
+        if self.rejected.insert(client_id) {
+            self.newly_rejected.push(client_id);
+        }
+    }
+
+    /// The clients refused since the last call, to tell them to exit
+    fn take_newly_rejected(&mut self) -> Vec<u32> {
+        core::mem::take(&mut self.newly_rejected)
+    }
+
+    /// Checks the first event of a client, its [`Event::Handshake`], or the [`Event::NewTestcase`]
+    /// of older clients. Clients sending anything else first get refused.
+    fn check<I>(&mut self, client_id: u32, event: &Event<I>) -> Result<(), Error>
+    where
+        I: Input,
+    {
+        if self.fingerprint.is_none() || self.admitted.contains(&client_id) {
+            return Ok(());
+        }
+        match event {
+            Event::Handshake {
+                client_config,
+                phantom: _,
+            }
+            | Event::NewTestcase { client_config, .. } => self.admit(client_id, client_config),
+            _ => {
+                self.reject(client_id);
+                Err(Error::IllegalState(format!(
+                    "Rejected client {}: it sent a {} event before identifying itself",
+                    client_id,
+                    event.name()
+                )))
+            }
+        }
+    }
+
+    /// Checks the config of a client against the fingerprint of the broker, and refuses the
+    /// client if they don't match
+    fn admit(&mut self, client_id: u32, config: &EventConfig) -> Result<(), Error> {
+        let expected = match self.fingerprint {
+            Some(expected) => expected,
+            None => return Ok(()),
+        };
+        match config.fingerprint() {
+            Some(fingerprint) if fingerprint == expected => {
+                self.admitted.insert(client_id);
+                Ok(())
+            }
+            fingerprint => {
+                self.reject(client_id);
+                Err(Error::IllegalState(format!(
+                    "Rejected client {}: its schema fingerprint {:?} does not match the fingerprint {:#x} of the broker. \
+                    Was it built from another version of the fuzzer, or with another map size?",
+                    client_id, fingerprint, expected
+                )))
+            }
+        }
+    }
+}
+
 /// An LLMP-backed event manager for scalable multi-processed fuzzing
 #[derive(Debug)]
 pub struct LlmpEventBroker<I, MT, SP>
@@ -68,6 +212,8 @@ where
     llmp: llmp::LlmpBroker<SP>,
     #[cfg(feature = "llmp_compression")]
     compressor: GzipCompressor,
+    sharing: CorpusSharing,
+    admission: ClientAdmission,
     phantom: PhantomData<I>,
 }
 
@@ -84,6 +230,8 @@ where
             llmp,
             #[cfg(feature = "llmp_compression")]
             compressor: GzipCompressor::new(COMPRESS_THRESHOLD),
+            sharing: CorpusSharing::default(),
+            admission: ClientAdmission::default(),
             phantom: PhantomData,
         })
     }
@@ -97,10 +245,36 @@ where
             llmp: llmp::LlmpBroker::create_attach_to_tcp(shmem_provider, port)?,
             #[cfg(feature = "llmp_compression")]
             compressor: GzipCompressor::new(COMPRESS_THRESHOLD),
+            sharing: CorpusSharing::default(),
+            admission: ClientAdmission::default(),
             phantom: PhantomData,
         })
     }
 
+    /// The policy deciding which new testcases get forwarded to the other clients
+    #[must_use]
+    pub fn corpus_sharing_policy(&self) -> CorpusSharingPolicy {
+        self.sharing.policy
+    }
+
+    /// Sets the policy deciding which new testcases get forwarded to the other clients
+    pub fn set_corpus_sharing_policy(&mut self, policy: CorpusSharingPolicy) {
+        self.sharing.policy = policy;
+    }
+
+    /// The schema fingerprint clients need to have to get admitted, if any
+    #[must_use]
+    pub fn fingerprint(&self) -> Option<u64> {
+        self.admission.fingerprint
+    }
+
+    /// Sets the schema fingerprint clients need to have, see [`EventConfig::Fingerprinted`].
+    /// The events of clients with another fingerprint get dropped, and they are told to exit,
+    /// before they handle the events of the other clients.
+    pub fn set_fingerprint(&mut self, fingerprint: Option<u64>) {
+        self.admission.fingerprint = fingerprint;
+    }
+
     /// Connect to an llmp broker on the givien address
     #[cfg(feature = "std")]
     pub fn connect_b2b<A>(&mut self, addr: A) -> Result<(), Error>
@@ -113,9 +287,13 @@ where
     /// Run forever in the broker
     pub fn broker_loop(&mut self) -> Result<(), Error> {
         let monitor = &mut self.monitor;
+        let sharing = &mut self.sharing;
+        let admission = &mut self.admission;
         #[cfg(feature = "llmp_compression")]
         let compressor = &self.compressor;
-        self.llmp.loop_forever(
+        // Filled while handling the messages, sent once they are handled
+        let rejected = RefCell::new(vec![]);
+        self.llmp.loop_forever_with(
             &mut |client_id: u32, tag: Tag, _flags: Flags, msg: &[u8]| {
                 if tag == LLMP_TAG_EVENT_TO_BOTH {
                     #[cfg(not(feature = "llmp_compression"))]
@@ -130,7 +308,12 @@ where
                         msg
                     };
                     let event: Event<I> = postcard::from_bytes(event_bytes)?;
-                    match Self::handle_in_broker(monitor, client_id, &event)? {
+                    let result =
+                        Self::handle_in_broker(monitor, sharing, admission, client_id, &event)?;
+                    rejected
+                        .borrow_mut()
+                        .append(&mut admission.take_newly_rejected());
+                    match result {
                         BrokerEventResult::Forward => Ok(llmp::LlmpMsgHookResult::ForwardToClients),
                         BrokerEventResult::Handled => Ok(llmp::LlmpMsgHookResult::Handled),
                     }
@@ -138,6 +321,12 @@ where
                     Ok(llmp::LlmpMsgHookResult::ForwardToClients)
                 }
             },
+            &mut |llmp| {
+                for client_id in rejected.borrow_mut().drain(..) {
+                    llmp.send_buf(LLMP_TAG_CLIENT_REJECTED, &client_id.to_le_bytes())?;
+                }
+                Ok(())
+            },
             Some(Duration::from_millis(5)),
         );
 
@@ -145,12 +334,22 @@ where
     }
 
     /// Handle arriving events in the broker
-    #[allow(clippy::unnecessary_wraps)]
+    #[allow(clippy::unnecessary_wraps, clippy::too_many_lines)]
     fn handle_in_broker(
         monitor: &mut MT,
+        sharing: &mut CorpusSharing,
+        admission: &mut ClientAdmission,
         client_id: u32,
         event: &Event<I>,
     ) -> Result<BrokerEventResult, Error> {
+        if admission.is_rejected(client_id) {
+            return Ok(BrokerEventResult::Handled);
+        }
+        if let Err(err) = admission.check(client_id, event) {
+            monitor.client_stats_mut_for(client_id);
+            monitor.display(err.to_string(), client_id);
+            return Ok(BrokerEventResult::Handled);
+        }
         match &event {
             Event::NewTestcase {
                 input: _,
@@ -165,7 +364,11 @@ where
                 client.update_corpus_size(*corpus_size as u64);
                 client.update_executions(*executions as u64, *time);
                 monitor.display(event.name().to_string(), client_id);
-                Ok(BrokerEventResult::Forward)
+                if sharing.should_forward(client_id, *corpus_size) {
+                    Ok(BrokerEventResult::Forward)
+                } else {
+                    Ok(BrokerEventResult::Handled)
+                }
             }
             Event::UpdateExecStats {
                 time,
@@ -237,7 +440,23 @@ where
                 #[cfg(feature = "std")]
                 println!("[LOG {}]: {}", severity_level, message);
                 Ok(BrokerEventResult::Handled)
-            } //_ => Ok(BrokerEventResult::Forward),
+            }
+            Event::CoverageSnapshot {
+                map,
+                epoch,
+                delta,
+                phantom: _,
+            } => {
+                let client = monitor.client_stats_mut_for(client_id);
+                client.update_coverage(map, *epoch, *delta);
+                monitor.display(event.name().to_string(), client_id);
+                Ok(BrokerEventResult::Handled)
+            }
+            // Already checked above
+            Event::Handshake {
+                client_config: _,
+                phantom: _,
+            } => Ok(BrokerEventResult::Handled), //_ => Ok(BrokerEventResult::Forward),
         }
     }
 }
@@ -256,6 +475,12 @@ where
     #[cfg(feature = "llmp_compression")]
     compressor: GzipCompressor,
     configuration: EventConfig,
+    /// [`Event::Log`] events less severe than this are dropped
+    min_log_severity: LogSeverity,
+    /// Whether the [`Event::Handshake`] was sent, before the first other event
+    handshake_sent: bool,
+    /// Whether the broker rejected this client, see [`LlmpEventBroker::set_fingerprint`]
+    rejected: bool,
     phantom: PhantomData<(I, OT, S)>,
 }
 
@@ -284,6 +509,9 @@ where
             #[cfg(feature = "llmp_compression")]
             compressor: GzipCompressor::new(COMPRESS_THRESHOLD),
             configuration,
+            min_log_severity: LogSeverity::Debug,
+            handshake_sent: false,
+            rejected: false,
             phantom: PhantomData,
         })
     }
@@ -302,6 +530,9 @@ where
             #[cfg(feature = "llmp_compression")]
             compressor: GzipCompressor::new(COMPRESS_THRESHOLD),
             configuration,
+            min_log_severity: LogSeverity::Debug,
+            handshake_sent: false,
+            rejected: false,
             phantom: PhantomData,
         })
     }
@@ -318,6 +549,9 @@ where
             #[cfg(feature = "llmp_compression")]
             compressor: GzipCompressor::new(COMPRESS_THRESHOLD),
             configuration,
+            min_log_severity: LogSeverity::Debug,
+            handshake_sent: false,
+            rejected: false,
             phantom: PhantomData,
         })
     }
@@ -338,16 +572,103 @@ where
             #[cfg(feature = "llmp_compression")]
             compressor: GzipCompressor::new(COMPRESS_THRESHOLD),
             configuration,
+            min_log_severity: LogSeverity::Debug,
+            handshake_sent: false,
+            rejected: false,
             phantom: PhantomData,
         })
     }
 
+    /// The least severity of the [`Event::Log`] events this manager fires, all by default
+    #[must_use]
+    pub fn min_log_severity(&self) -> LogSeverity {
+        self.min_log_severity
+    }
+
+    /// Drops the [`Event::Log`] events less severe than `min_log_severity` from now on,
+    /// instead of sending them to the broker
+    pub fn set_min_log_severity(&mut self, min_log_severity: LogSeverity) {
+        self.min_log_severity = min_log_severity;
+    }
+
+    /// Sends an event to the broker, compressed if it is big
+    #[cfg(feature = "llmp_compression")]
+    fn send(&mut self, event: &Event<I>) -> Result<(), Error> {
+        let serialized = postcard::to_allocvec(event)?;
+        let flags: Flags = LLMP_FLAG_INITIALIZED;
+
+        match self.compressor.compress(&serialized)? {
+            Some(comp_buf) => {
+                self.llmp.send_buf_with_flags(
+                    LLMP_TAG_EVENT_TO_BOTH,
+                    flags | LLMP_FLAG_COMPRESSED,
+                    &comp_buf,
+                )?;
+            }
+            None => {
+                self.llmp.send_buf(LLMP_TAG_EVENT_TO_BOTH, &serialized)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends an event to the broker
+    #[cfg(not(feature = "llmp_compression"))]
+    fn send(&mut self, event: &Event<I>) -> Result<(), Error> {
+        let serialized = postcard::to_allocvec(event)?;
+        self.llmp.send_buf(LLMP_TAG_EVENT_TO_BOTH, &serialized)?;
+        Ok(())
+    }
+
     /// Write the config for a client [`EventManager`] to env vars, a new client can reattach using [`LlmpEventManager::existing_client_from_env()`].
     #[cfg(feature = "std")]
     pub fn to_env(&self, env_name: &str) {
         self.llmp.to_env(env_name).unwrap();
     }
 
+    /// Receives the events of the other clients. Once the broker rejected this client, this only
+    /// errors, without deserializing any more events, see [`LlmpEventBroker::set_fingerprint`].
+    fn receive_events(&mut self) -> Result<Vec<(u32, Event<I>)>, Error> {
+        let mut events = vec![];
+        let self_id = self.llmp.sender.id;
+        while !self.rejected {
+            let (client_id, tag, _flags, msg) = match self.llmp.recv_buf_with_flags()? {
+                Some(received) => received,
+                None => return Ok(events),
+            };
+            assert!(
+                tag != _LLMP_TAG_EVENT_TO_BROKER,
+                "EVENT_TO_BROKER parcel should not have arrived in the client!"
+            );
+
+            if tag == LLMP_TAG_CLIENT_REJECTED {
+                self.rejected = msg == self_id.to_le_bytes();
+                continue;
+            }
+            if client_id == self_id {
+                continue;
+            }
+            #[cfg(not(feature = "llmp_compression"))]
+            let event_bytes = msg;
+            #[cfg(feature = "llmp_compression")]
+            let compressed;
+            #[cfg(feature = "llmp_compression")]
+            let event_bytes = if _flags & LLMP_FLAG_COMPRESSED == LLMP_FLAG_COMPRESSED {
+                compressed = self.compressor.decompress(msg)?;
+                &compressed
+            } else {
+                msg
+            };
+            let event: Event<I> = postcard::from_bytes(event_bytes)?;
+            events.push((client_id, event));
+        }
+        Err(Error::IllegalState(format!(
+            "The broker rejected client {}, its schema fingerprint does not match the one of the broker. \
+            Was it built from another version of the fuzzer, or with another map size?",
+            self_id
+        )))
+    }
+
     // Handle arriving events in the client
     #[allow(clippy::unused_self)]
     fn handle_in_client<E, Z>(
@@ -408,31 +729,18 @@ where
     SP: ShMemProvider,
     //CE: CustomEvent<I>,
 {
-    #[cfg(feature = "llmp_compression")]
     fn fire<S2>(&mut self, _state: &mut S2, event: Event<I>) -> Result<(), Error> {
-        let serialized = postcard::to_allocvec(&event)?;
-        let flags: Flags = LLMP_FLAG_INITIALIZED;
-
-        match self.compressor.compress(&serialized)? {
-            Some(comp_buf) => {
-                self.llmp.send_buf_with_flags(
-                    LLMP_TAG_EVENT_TO_BOTH,
-                    flags | LLMP_FLAG_COMPRESSED,
-                    &comp_buf,
-                )?;
-            }
-            None => {
-                self.llmp.send_buf(LLMP_TAG_EVENT_TO_BOTH, &serialized)?;
-            }
+        if event.is_log_below(self.min_log_severity) {
+            return Ok(());
         }
-        Ok(())
-    }
-
-    #[cfg(not(feature = "llmp_compression"))]
-    fn fire<S2>(&mut self, _state: &mut S2, event: Event<I>) -> Result<(), Error> {
-        let serialized = postcard::to_allocvec(&event)?;
-        self.llmp.send_buf(LLMP_TAG_EVENT_TO_BOTH, &serialized)?;
-        Ok(())
+        if !self.handshake_sent {
+            self.send(&Event::Handshake {
+                client_config: self.configuration,
+                phantom: PhantomData,
+            })?;
+            self.handshake_sent = true;
+        }
+        self.send(&event)
     }
 
     fn configuration(&self) -> EventConfig {
@@ -465,31 +773,7 @@ where
 {
     fn process(&mut self, fuzzer: &mut Z, state: &mut S, executor: &mut E) -> Result<usize, Error> {
         // TODO: Get around local event copy by moving handle_in_client
-        let mut events = vec![];
-        let self_id = self.llmp.sender.id;
-        while let Some((client_id, tag, _flags, msg)) = self.llmp.recv_buf_with_flags()? {
-            assert!(
-                tag != _LLMP_TAG_EVENT_TO_BROKER,
-                "EVENT_TO_BROKER parcel should not have arrived in the client!"
-            );
-
-            if client_id == self_id {
-                continue;
-            }
-            #[cfg(not(feature = "llmp_compression"))]
-            let event_bytes = msg;
-            #[cfg(feature = "llmp_compression")]
-            let compressed;
-            #[cfg(feature = "llmp_compression")]
-            let event_bytes = if _flags & LLMP_FLAG_COMPRESSED == LLMP_FLAG_COMPRESSED {
-                compressed = self.compressor.decompress(msg)?;
-                &compressed
-            } else {
-                msg
-            };
-            let event: Event<I> = postcard::from_bytes(event_bytes)?;
-            events.push((client_id, event));
-        }
+        let mut events = self.receive_events()?;
         let count = events.len();
         events.drain(..).try_for_each(|(client_id, event)| {
             self.handle_in_client(fuzzer, executor, state, client_id, event)
@@ -544,6 +828,8 @@ where
     llmp_mgr: LlmpEventManager<I, OT, S, SP>,
     /// The staterestorer to serialize the state for the next runner
     staterestorer: StateRestorer<SP>,
+    /// The deterministic RNG seed of this client, if the run has a base seed
+    client_seed: Option<u64>,
 }
 
 #[cfg(feature = "std")]
@@ -591,6 +877,8 @@ where
     }
 
     /// Reset the single page (we reuse it over and over from pos 0), then send the current state to the next runner.
+    /// The state includes the feedback states, such as the [`crate::feedbacks::MapFeedbackState`],
+    /// so the next runner starts with the coverage known so far.
     fn on_restart(&mut self, state: &mut S) -> Result<(), Error> {
         // First, reset the page to 0 so the next iteration can read read from the beginning of this page
         self.staterestorer.reset();
@@ -659,9 +947,36 @@ where
         Self {
             llmp_mgr,
             staterestorer,
+            client_seed: None,
         }
     }
 
+    /// The deterministic RNG seed of this client, derived from the base seed of the
+    /// [`RestartingMgr`] with [`crate::events::client_seed`], if one was set.
+    /// Use it instead of [`crate::bolts::current_nanos`] to make the whole run reproducible.
+    #[must_use]
+    pub fn client_seed(&self) -> Option<u64> {
+        self.client_seed
+    }
+
+    /// Seeds the rand of a newly created `state` with the [`Self::client_seed`], if one was set.
+    /// Call it on the state created on the first run, restored states keep their rand going.
+    pub fn seed_rand<R, S2>(&self, state: &mut S2)
+    where
+        R: Rand,
+        S2: HasRand<R>,
+    {
+        if let Some(seed) = self.client_seed {
+            state.rand_mut().set_seed(seed);
+        }
+    }
+
+    /// Drops the [`Event::Log`] events less severe than `min_log_severity` from now on,
+    /// see [`LlmpEventManager::set_min_log_severity`]
+    pub fn set_min_log_severity(&mut self, min_log_severity: LogSeverity) {
+        self.llmp_mgr.set_min_log_severity(min_log_severity);
+    }
+
     /// Get the staterestorer
     pub fn staterestorer(&self) -> &StateRestorer<SP> {
         &self.staterestorer
@@ -720,6 +1035,109 @@ where
         .launch()
 }
 
+/// Sets up a restarting fuzzer like [`setup_restarting_mgr_std`], but gives up with an error
+/// instead of spawning the next client, once clients restart more than `max_restarts_per_minute`
+/// times within a minute. A client crashing this often is most likely broken during startup.
+#[cfg(feature = "std")]
+#[allow(clippy::type_complexity)]
+pub fn setup_restarting_mgr_std_with_restart_budget<I, MT, OT, S>(
+    monitor: MT,
+    broker_port: u16,
+    configuration: EventConfig,
+    max_restarts_per_minute: usize,
+) -> Result<
+    (
+        Option<S>,
+        LlmpRestartingEventManager<I, OT, S, StdShMemProvider>,
+    ),
+    Error,
+>
+where
+    I: Input,
+    S: DeserializeOwned,
+    MT: Monitor + Clone,
+    OT: ObserversTuple<I, S> + DeserializeOwned,
+    S: DeserializeOwned,
+{
+    RestartingMgr::builder()
+        .shmem_provider(StdShMemProvider::new()?)
+        .monitor(Some(monitor))
+        .broker_port(broker_port)
+        .configuration(configuration)
+        .max_restarts_per_minute(Some(max_restarts_per_minute))
+        .build()
+        .launch()
+}
+
+/// Keeps track of recent client restarts, to detect a client stuck in a crash loop.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+struct RestartBudget {
+    max_restarts: usize,
+    window: Duration,
+    restarts: VecDeque<Duration>,
+}
+
+#[cfg(feature = "std")]
+impl RestartBudget {
+    /// Allow at most `max_restarts` restarts within any minute.
+    fn per_minute(max_restarts: usize) -> Self {
+        Self {
+            max_restarts,
+            window: Duration::from_secs(60),
+            restarts: VecDeque::with_capacity(max_restarts + 1),
+        }
+    }
+
+    /// Records a restart at time `now`.
+    /// Returns `false` if this restart exceeds the budget.
+    fn try_restart(&mut self, now: Duration) -> bool {
+        while let Some(oldest) = self.restarts.front() {
+            if now.saturating_sub(*oldest) < self.window {
+                break;
+            }
+            self.restarts.pop_front();
+        }
+        self.restarts.push_back(now);
+        self.restarts.len() <= self.max_restarts
+    }
+}
+
+/// The client->parent loop of the restarter: spawns the next client each time the last one exits.
+/// `spawn` returns [`ControlFlow::Break`] in the newly spawned client, and the exit status of the
+/// client in the restarter. Returns `Ok` in the client, or an error once the clients restart more
+/// often than the `restart_budget` allows.
+#[cfg(feature = "std")]
+fn respawn_clients<CS, F>(
+    mut restart_budget: Option<RestartBudget>,
+    mut spawn: F,
+) -> Result<(), Error>
+where
+    CS: Display,
+    F: FnMut() -> Result<ControlFlow<(), CS>, Error>,
+{
+    let mut ctr: u64 = 0;
+    loop {
+        dbg!("Spawning next client (id {})", ctr);
+
+        let child_status = match spawn()? {
+            ControlFlow::Break(()) => return Ok(()),
+            ControlFlow::Continue(child_status) => child_status,
+        };
+
+        if let Some(restart_budget) = &mut restart_budget {
+            if !restart_budget.try_restart(current_time()) {
+                return Err(Error::IllegalState(format!(
+                    "Fuzzer-respawner: The client restarted more than {} times within a minute, the harness is likely broken. (Child exited with: {})",
+                    restart_budget.max_restarts, child_status
+                )));
+            }
+        }
+
+        ctr = ctr.wrapping_add(1);
+    }
+}
+
 /// Provides a `builder` which can be used to build a [`RestartingMgr`], which is a combination of a
 /// `restarter` and `runner`, that can be used on systems both with and without `fork` support. The
 /// `restarter` will start a new process each time the child crashes or times out.
@@ -752,6 +1170,21 @@ where
     /// The type of manager to build
     #[builder(default = ManagerKind::Any)]
     kind: ManagerKind,
+    /// The maximum number of client restarts within a minute before the restarter gives up.
+    /// A client crashing this often is most likely broken during startup, not on an input.
+    #[builder(default = None)]
+    max_restarts_per_minute: Option<usize>,
+    /// The base seed of the whole run. If set, each client gets a deterministic seed derived from
+    /// it and its cpu core (or, if not bound to a core, its client id), see [`LlmpRestartingEventManager::client_seed`].
+    #[builder(default = None)]
+    base_seed: Option<u64>,
+    /// The clients drop [`Event::Log`] events less severe than this, instead of sending them
+    /// to the broker. All are sent by default.
+    #[builder(default = LogSeverity::Debug)]
+    min_log_severity: LogSeverity,
+    /// The policy of the broker deciding which new testcases get forwarded to the other clients
+    #[builder(default = CorpusSharingPolicy::All)]
+    corpus_sharing_policy: CorpusSharingPolicy,
     #[builder(setter(skip), default = PhantomData)]
     phantom_data: PhantomData<(I, OT, S)>,
 }
@@ -774,7 +1207,11 @@ where
         let (staterestorer, new_shmem_provider, core_id) = if std::env::var(_ENV_FUZZER_SENDER)
             .is_err()
         {
+            let corpus_sharing_policy = self.corpus_sharing_policy;
+            let fingerprint = self.configuration.fingerprint();
             let broker_things = |mut broker: LlmpEventBroker<I, MT, SP>, remote_broker_addr| {
+                broker.set_corpus_sharing_policy(corpus_sharing_policy);
+                broker.set_fingerprint(fingerprint);
                 if let Some(remote_broker_addr) = remote_broker_addr {
                     println!("B2b: Connecting to {:?}", &remote_broker_addr);
                     broker.connect_b2b(remote_broker_addr)?;
@@ -848,23 +1285,22 @@ where
             // Store the information to a map.
             staterestorer.write_to_env(_ENV_FUZZER_SENDER)?;
 
-            let mut ctr: u64 = 0;
+            let restart_budget = self.max_restarts_per_minute.map(RestartBudget::per_minute);
+            let shmem_provider = &mut self.shmem_provider;
             // Client->parent loop
-            loop {
-                dbg!("Spawning next client (id {})", ctr);
-
+            respawn_clients(restart_budget, || {
                 // On Unix, we fork
                 #[cfg(all(unix, feature = "fork"))]
                 let child_status = {
-                    self.shmem_provider.pre_fork()?;
+                    shmem_provider.pre_fork()?;
                     match unsafe { fork() }? {
                         ForkResult::Parent(handle) => {
-                            self.shmem_provider.post_fork(false)?;
+                            shmem_provider.post_fork(false)?;
                             handle.status()
                         }
                         ForkResult::Child => {
-                            self.shmem_provider.post_fork(true)?;
-                            break (staterestorer, self.shmem_provider.clone(), core_id);
+                            shmem_provider.post_fork(true)?;
+                            return Ok(ControlFlow::Break(()));
                         }
                     }
                 };
@@ -890,8 +1326,10 @@ where
                     panic!("Fuzzer-respawner: Storing state in crashed fuzzer instance did not work, no point to spawn the next client! This can happen if the child calls `exit()`, in that case make sure it uses `abort()`, if it got killed unrecoverable (OOM), or if there is a bug in the fuzzer itself. (Child exited with: {})", child_status);
                 }
 
-                ctr = ctr.wrapping_add(1);
-            }
+                Ok(ControlFlow::Continue(child_status))
+            })?;
+
+            (staterestorer, self.shmem_provider.clone(), core_id)
         } else {
             // We are the newly started fuzzing instance (i.e. on Windows), first, connect to our own restore map.
             // We get here *only on Windows*, if we were started by a restarting fuzzer.
@@ -933,6 +1371,18 @@ where
         };
         // We reset the staterestorer, the next staterestorer and receiver (after crash) will reuse the page from the initial message.
         mgr.staterestorer.reset();
+        mgr.set_min_log_severity(self.min_log_severity);
+
+        if let Some(base_seed) = self.base_seed {
+            let client_id = core_id.map_or(mgr.llmp_mgr.mgr_id().id, |core_id| core_id.id) as u64;
+            let seed = client_seed(base_seed, client_id);
+            mgr.client_seed = Some(seed);
+            mgr.log(
+                &mut (),
+                LogSeverity::Info,
+                format!("Client {} using seed {}", client_id, seed),
+            )?;
+        }
 
         /* TODO: Not sure if this is needed
         // We commit an empty NO_RESTART message to this buf, against infinite loops,
@@ -949,19 +1399,32 @@ where
 mod tests {
     use serial_test::serial;
 
+    use core::{marker::PhantomData, ops::ControlFlow, time::Duration};
+
     use crate::{
         bolts::{
-            llmp::{LlmpClient, LlmpSharedMap},
+            current_time,
+            llmp::{LlmpClient, LlmpSender, LlmpSharedMap},
             rands::StdRand,
-            shmem::{ShMemProvider, StdShMemProvider},
+            shmem::{ShMem, ShMemProvider, StdShMemProvider},
             staterestore::StateRestorer,
             tuples::tuple_list,
         },
         corpus::{Corpus, InMemoryCorpus, RandCorpusScheduler, Testcase},
-        events::{llmp::_ENV_FUZZER_SENDER, LlmpEventManager},
+        events::{
+            llmp::{
+                respawn_clients, ClientAdmission, CorpusSharing, RestartBudget, _ENV_FUZZER_SENDER,
+                LLMP_TAG_CLIENT_REJECTED, LLMP_TAG_EVENT_TO_BOTH,
+            },
+            schema_fingerprint, BrokerEventResult, CorpusSharingPolicy, Event, EventConfig,
+            EventFirer, LlmpEventBroker, LlmpEventManager, LogSeverity, NopEventManager,
+        },
         executors::{ExitKind, InProcessExecutor},
+        feedbacks::{Feedback, MapFeedbackState, MaxMapFeedback},
         inputs::BytesInput,
+        monitors::NopMonitor,
         mutators::BitFlipMutator,
+        observers::{MapObserver, StdMapObserver},
         stages::StdMutationalStage,
         state::StdState,
         Fuzzer, StdFuzzer,
@@ -1052,4 +1515,304 @@ mod tests {
                 .unwrap();
         }
     }
+
+    #[test]
+    #[serial]
+    fn test_mgr_min_log_severity() {
+        let shmem_provider = StdShMemProvider::new().unwrap();
+        let mut llmp_client = LlmpClient::new(
+            shmem_provider.clone(),
+            LlmpSharedMap::new(0, shmem_provider.clone().new_map(1024).unwrap()),
+        )
+        .unwrap();
+        unsafe {
+            llmp_client.mark_safe_to_unmap();
+        }
+        let mut llmp_mgr =
+            LlmpEventManager::<BytesInput, (), (), _>::new(llmp_client, "fuzzer".into()).unwrap();
+        llmp_mgr.set_min_log_severity(LogSeverity::Info);
+
+        // The debug log is dropped, nothing gets sent
+        llmp_mgr
+            .log(&mut (), LogSeverity::Debug, "debug".into())
+            .unwrap();
+        assert!(llmp_mgr.llmp.sender.last_msg_sent.is_null());
+
+        llmp_mgr
+            .log(&mut (), LogSeverity::Info, "info".into())
+            .unwrap();
+        assert!(!llmp_mgr.llmp.sender.last_msg_sent.is_null());
+    }
+
+    #[test]
+    #[serial]
+    fn test_mgr_restore_coverage() {
+        let map_state = MapFeedbackState::<u8>::new("edges", 4);
+        let observer = StdMapObserver::new_owned("edges", vec![0_u8; 4]);
+        let mut feedback = MaxMapFeedback::new(&map_state, &observer);
+        let mut observers = tuple_list!(observer);
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            tuple_list!(map_state),
+        );
+        let mut mgr = NopEventManager {};
+        let input = BytesInput::new(vec![]);
+
+        // Discover edge 1 before the restart
+        *observers.0.get_mut(1) = 1;
+        assert!(feedback
+            .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+
+        // Restart, as the restarting event managers do in `on_restart`
+        let mut shmem_provider = StdShMemProvider::new().unwrap();
+        let mut staterestorer =
+            StateRestorer::<StdShMemProvider>::new(shmem_provider.new_map(1024 * 1024).unwrap());
+        staterestorer.reset();
+        staterestorer.save(&state).unwrap();
+        drop(state);
+        let mut restored: StdState<InMemoryCorpus<BytesInput>, _, BytesInput, StdRand, _> =
+            staterestorer.restore().unwrap().unwrap();
+
+        // The known edge does not trigger interestingness again, a new one does
+        assert!(!feedback
+            .is_interesting(&mut restored, &mut mgr, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+        *observers.0.get_mut(2) = 1;
+        assert!(feedback
+            .is_interesting(&mut restored, &mut mgr, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+    }
+
+    #[test]
+    fn test_corpus_sharing_sample() {
+        let mut monitor = NopMonitor::new();
+        let mut sharing = CorpusSharing {
+            policy: CorpusSharingPolicy::Sample(4),
+            ..CorpusSharing::default()
+        };
+
+        let mut forwarded = [0; 2];
+        for corpus_size in 0..100 {
+            for (client_id, forwarded) in forwarded.iter_mut().enumerate() {
+                let event = Event::NewTestcase {
+                    input: BytesInput::new(vec![0]),
+                    observers_buf: None,
+                    exit_kind: ExitKind::Ok,
+                    corpus_size,
+                    client_config: EventConfig::AlwaysUnique,
+                    time: current_time(),
+                    executions: 0,
+                };
+                let result =
+                    LlmpEventBroker::<BytesInput, NopMonitor, StdShMemProvider>::handle_in_broker(
+                        &mut monitor,
+                        &mut sharing,
+                        &mut ClientAdmission::default(),
+                        client_id as u32,
+                        &event,
+                    )
+                    .unwrap();
+                if let BrokerEventResult::Forward = result {
+                    *forwarded += 1;
+                }
+            }
+        }
+        // One in four testcases of each client is rebroadcast
+        assert_eq!(forwarded, [25, 25]);
+    }
+
+    #[test]
+    fn test_fingerprint_mismatch_rejected() {
+        let mut monitor = NopMonitor::new();
+        let mut sharing = CorpusSharing::default();
+        let fingerprint = schema_fingerprint(65536);
+        assert_ne!(fingerprint, schema_fingerprint(1024));
+        let mut admission = ClientAdmission {
+            fingerprint: Some(fingerprint),
+            ..ClientAdmission::default()
+        };
+
+        let mut handle = |client_id, client_config| {
+            let event = Event::NewTestcase {
+                input: BytesInput::new(vec![0]),
+                observers_buf: None,
+                exit_kind: ExitKind::Ok,
+                corpus_size: 1,
+                client_config,
+                time: current_time(),
+                executions: 0,
+            };
+            LlmpEventBroker::<BytesInput, NopMonitor, StdShMemProvider>::handle_in_broker(
+                &mut monitor,
+                &mut sharing,
+                &mut admission,
+                client_id,
+                &event,
+            )
+            .unwrap()
+        };
+
+        // A client of the same build joins
+        let same_build = EventConfig::from_name_with_fingerprint("default", fingerprint);
+        assert!(matches!(handle(0, same_build), BrokerEventResult::Forward));
+
+        // A client built with another map size gets refused, and stays refused
+        let other_build =
+            EventConfig::from_name_with_fingerprint("default", schema_fingerprint(1024));
+        assert!(!other_build.match_with(&same_build));
+        assert!(matches!(handle(1, other_build), BrokerEventResult::Handled));
+        assert!(matches!(handle(1, same_build), BrokerEventResult::Handled));
+
+        // As does a client without a fingerprint
+        assert!(matches!(
+            handle(2, EventConfig::from_name("default")),
+            BrokerEventResult::Handled
+        ));
+        assert!(matches!(handle(0, same_build), BrokerEventResult::Forward));
+        assert!(admission.is_rejected(1) && admission.is_rejected(2));
+        assert!(!admission.is_rejected(0));
+
+        // Clients are checked on their handshake, before their first testcase
+        let stats = Event::UpdateExecStats {
+            time: current_time(),
+            stability: None,
+            executions: 0,
+            phantom: PhantomData,
+        };
+        for (client_id, client_config) in [(3, same_build), (4, other_build)] {
+            let handshake = Event::Handshake {
+                client_config,
+                phantom: PhantomData,
+            };
+            for event in [&handshake, &stats] {
+                LlmpEventBroker::<BytesInput, NopMonitor, StdShMemProvider>::handle_in_broker(
+                    &mut monitor,
+                    &mut sharing,
+                    &mut admission,
+                    client_id,
+                    event,
+                )
+                .unwrap();
+            }
+        }
+        assert!(!admission.is_rejected(3));
+        assert!(admission.is_rejected(4));
+
+        // Clients sending anything else first get refused
+        LlmpEventBroker::<BytesInput, NopMonitor, StdShMemProvider>::handle_in_broker(
+            &mut monitor,
+            &mut sharing,
+            &mut admission,
+            5,
+            &stats,
+        )
+        .unwrap();
+        assert!(admission.is_rejected(5));
+
+        // Each refused client gets told once
+        assert_eq!(admission.take_newly_rejected(), vec![1, 2, 4, 5]);
+        assert!(admission.take_newly_rejected().is_empty());
+    }
+
+    #[test]
+    #[serial]
+    fn test_rejected_client_receives_nothing() {
+        let mut shmem_provider = StdShMemProvider::new().unwrap();
+        // Stands in for the broadcast map of the broker
+        let mut broker_out = LlmpSender::new(shmem_provider.clone(), 7, false).unwrap();
+        let broker_map = LlmpSharedMap::existing(
+            shmem_provider
+                .from_description(broker_out.out_maps[0].shmem.description())
+                .unwrap(),
+        );
+        let mut llmp_client = LlmpClient::new(shmem_provider.clone(), broker_map).unwrap();
+        unsafe {
+            llmp_client.mark_safe_to_unmap();
+        }
+        let self_id = llmp_client.sender.id;
+        let mut llmp_mgr =
+            LlmpEventManager::<BytesInput, (), (), _>::new(llmp_client, "fuzzer".into()).unwrap();
+
+        let testcase = postcard::to_allocvec(&Event::NewTestcase {
+            input: BytesInput::new(vec![0]),
+            observers_buf: None,
+            exit_kind: ExitKind::Ok,
+            corpus_size: 1,
+            client_config: EventConfig::AlwaysUnique,
+            time: current_time(),
+            executions: 0,
+        })
+        .unwrap();
+
+        // The rejection of another client does not matter here
+        broker_out
+            .send_buf(LLMP_TAG_EVENT_TO_BOTH, &testcase)
+            .unwrap();
+        broker_out
+            .send_buf(LLMP_TAG_CLIENT_REJECTED, &(self_id + 1).to_le_bytes())
+            .unwrap();
+        assert_eq!(llmp_mgr.receive_events().unwrap().len(), 1);
+
+        // Once rejected, the client gets no more events
+        broker_out
+            .send_buf(LLMP_TAG_CLIENT_REJECTED, &self_id.to_le_bytes())
+            .unwrap();
+        broker_out
+            .send_buf(LLMP_TAG_EVENT_TO_BOTH, &testcase)
+            .unwrap();
+        assert!(llmp_mgr.receive_events().is_err());
+        broker_out
+            .send_buf(LLMP_TAG_EVENT_TO_BOTH, &testcase)
+            .unwrap();
+        assert!(llmp_mgr.receive_events().is_err());
+    }
+
+    #[test]
+    fn test_restart_budget() {
+        let mut budget = RestartBudget::per_minute(3);
+
+        // A client exiting right after startup, over and over again
+        let mut now = Duration::from_secs(1000);
+        for _ in 0..3 {
+            assert!(budget.try_restart(now));
+            now += Duration::from_millis(10);
+        }
+        assert!(!budget.try_restart(now));
+
+        // Occasional restarts are fine
+        let mut budget = RestartBudget::per_minute(3);
+        for _ in 0..10 {
+            assert!(budget.try_restart(now));
+            now += Duration::from_secs(30);
+        }
+    }
+
+    #[test]
+    fn test_respawn_clients_restart_budget() {
+        // Clients exiting right away, over and over again
+        let mut spawned = 0;
+        let res = respawn_clients(Some(RestartBudget::per_minute(3)), || {
+            spawned += 1;
+            Ok(ControlFlow::Continue(1))
+        });
+        assert!(res.is_err());
+        // The first client and three restarts, the fourth restart exceeds the budget
+        assert_eq!(spawned, 4);
+
+        // Without a budget, the restarter keeps going until it is the client
+        let mut spawned = 0;
+        respawn_clients(None, || {
+            spawned += 1;
+            if spawned == 10 {
+                Ok(ControlFlow::Break(()))
+            } else {
+                Ok(ControlFlow::Continue(1))
+            }
+        })
+        .unwrap();
+        assert_eq!(spawned, 10);
+    }
 }
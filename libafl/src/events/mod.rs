@@ -14,7 +14,7 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
-    bolts::current_time,
+    bolts::{current_time, rands::splitmix64, serdeany::RegistryBuilder},
     executors::ExitKind,
     inputs::Input,
     monitors::UserStats,
@@ -36,8 +36,8 @@ use crate::monitors::ClientPerfMonitor;
 #[cfg(feature = "introspection")]
 use alloc::boxed::Box;
 
-/// The log event severity
-#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+/// The log event severity, ordered from [`LogSeverity::Debug`] to [`LogSeverity::Error`]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum LogSeverity {
     /// Debug severity
     Debug,
@@ -85,16 +85,64 @@ pub enum EventConfig {
         /// The build-time [`Uuid`]
         id: Uuid,
     },
+    /// Create a fuzzer config from a name hash and a schema fingerprint, see
+    /// [`EventConfig::from_name_with_fingerprint`]
+    Fingerprinted {
+        /// The name hash
+        name_hash: u64,
+        /// The schema fingerprint, usually from [`schema_fingerprint`]
+        fingerprint: u64,
+    },
+}
+
+/// A fingerprint of the data layout of this build: the names of all registered serdeany types,
+/// and the size of the coverage map.
+/// Builds with different fingerprints can not deserialize each other's events.
+#[must_use]
+pub fn schema_fingerprint(map_size: usize) -> u64 {
+    let mut type_names = RegistryBuilder::registered_type_names();
+    type_names.sort_unstable();
+    let mut hasher = AHasher::new_with_keys(0, 0);
+    for type_name in type_names {
+        hasher.write(type_name.as_bytes());
+        hasher.write_u8(0);
+    }
+    hasher.write_u64(map_size as u64);
+    hasher.finish()
 }
 
 impl EventConfig {
     /// Create a new [`EventConfig`] from a name hash
     #[must_use]
     pub fn from_name(name: &str) -> Self {
+        EventConfig::FromName {
+            name_hash: Self::hash_name(name),
+        }
+    }
+
+    fn hash_name(name: &str) -> u64 {
         let mut hasher = AHasher::new_with_keys(0, 0);
         hasher.write(name.as_bytes());
-        EventConfig::FromName {
-            name_hash: hasher.finish(),
+        hasher.finish()
+    }
+
+    /// Create a new [`EventConfig`] from a name hash and a schema fingerprint.
+    /// The broker refuses clients with the same name but another fingerprint, as they come
+    /// from an incompatible build.
+    #[must_use]
+    pub fn from_name_with_fingerprint(name: &str, fingerprint: u64) -> Self {
+        EventConfig::Fingerprinted {
+            name_hash: Self::hash_name(name),
+            fingerprint,
+        }
+    }
+
+    /// The schema fingerprint of this config, if any
+    #[must_use]
+    pub fn fingerprint(&self) -> Option<u64> {
+        match self {
+            EventConfig::Fingerprinted { fingerprint, .. } => Some(*fingerprint),
+            _ => None,
         }
     }
 
@@ -110,20 +158,23 @@ impl EventConfig {
     /// Match if the currenti [`EventConfig`] matches another given config
     #[must_use]
     pub fn match_with(&self, other: &EventConfig) -> bool {
-        match self {
-            EventConfig::AlwaysUnique => false,
-            EventConfig::FromName { name_hash: a } => match other {
-                #[cfg(not(feature = "std"))]
-                EventConfig::AlwaysUnique => false,
-                EventConfig::FromName { name_hash: b } => (a == b),
-                #[cfg(feature = "std")]
-                EventConfig::AlwaysUnique | EventConfig::BuildID { id: _ } => false,
-            },
+        match (self, other) {
+            (EventConfig::FromName { name_hash: a }, EventConfig::FromName { name_hash: b }) => {
+                a == b
+            }
             #[cfg(feature = "std")]
-            EventConfig::BuildID { id: a } => match other {
-                EventConfig::AlwaysUnique | EventConfig::FromName { name_hash: _ } => false,
-                EventConfig::BuildID { id: b } => (a == b),
-            },
+            (EventConfig::BuildID { id: a }, EventConfig::BuildID { id: b }) => a == b,
+            (
+                EventConfig::Fingerprinted {
+                    name_hash: a,
+                    fingerprint: fa,
+                },
+                EventConfig::Fingerprinted {
+                    name_hash: b,
+                    fingerprint: fb,
+                },
+            ) => a == b && fa == fb,
+            _ => false,
         }
     }
 }
@@ -232,6 +283,27 @@ where
         /// `PhantomData`
         phantom: PhantomData<I>,
     },
+    /// A snapshot of the accumulated coverage of a client, for live coverage views.
+    /// See [`crate::monitors::ClientStats::update_coverage`] for the encoding of `map`.
+    CoverageSnapshot {
+        /// One byte per map entry, `1` if covered, or, if `delta` is set, the little-endian
+        /// `u32` indices of the entries covered since the previous snapshot of this client
+        map: Vec<u8>,
+        /// The number of snapshots this client sent before
+        epoch: u64,
+        /// Whether `map` only holds the newly covered entries
+        delta: bool,
+        /// [`PhantomData`]
+        phantom: PhantomData<I>,
+    },
+    /// The first event of a client, identifying it to the broker before any other event,
+    /// see [`EventConfig::Fingerprinted`]
+    Handshake {
+        /// The config of the client
+        client_config: EventConfig,
+        /// [`PhantomData`]
+        phantom: PhantomData<I>,
+    },
     /*/// A custom type
     Custom {
         // TODO: Allow custom events
@@ -243,6 +315,13 @@ impl<I> Event<I>
 where
     I: Input,
 {
+    /// Returns `true` if this is an [`Event::Log`] less severe than `min_severity`.
+    /// The event managers drop these instead of firing them.
+    #[must_use]
+    pub fn is_log_below(&self, min_severity: LogSeverity) -> bool {
+        matches!(self, Event::Log { severity_level, .. } if *severity_level < min_severity)
+    }
+
     fn name(&self) -> &str {
         match self {
             Event::NewTestcase {
@@ -279,6 +358,16 @@ where
                 message: _,
                 phantom: _,
             } => "Log",
+            Event::CoverageSnapshot {
+                map: _,
+                epoch: _,
+                delta: _,
+                phantom: _,
+            } => "CoverageSnapshot",
+            Event::Handshake {
+                client_config: _,
+                phantom: _,
+            } => "Handshake",
             /*Event::Custom {
                 sender_id: _, /*custom_event} => custom_event.name()*/
             } => "todo",*/
@@ -423,6 +512,14 @@ pub trait EventProcessor<E, I, S, Z> {
         Ok(postcard::from_bytes(observers_buf)?)
     }
 }
+/// Derives a deterministic RNG seed for a client from a fleet-wide `base_seed` and the `client_id`.
+/// Reusing the same `base_seed` reproduces the seeds of all clients of a multi-client run.
+#[must_use]
+pub fn client_seed(base_seed: u64, client_id: u64) -> u64 {
+    // Both mixing steps are bijections, so the clients of one fleet never share a seed
+    splitmix64(base_seed ^ splitmix64(client_id))
+}
+
 /// The id of this [`EventManager`].
 /// For multi processed [`EventManager`]s,
 /// each connected client sholud have a unique ids.
@@ -491,7 +588,7 @@ mod tests {
             current_time,
             tuples::{tuple_list, Named},
         },
-        events::{Event, EventConfig},
+        events::{client_seed, Event, EventConfig},
         executors::ExitKind,
         inputs::bytes::BytesInput,
         observers::StdMapObserver,
@@ -536,4 +633,21 @@ mod tests {
             _ => panic!("mistmatch"),
         };
     }
+
+    #[test]
+    fn test_client_seed() {
+        let fleet = |base_seed| {
+            (0..8)
+                .map(|client_id| client_seed(base_seed, client_id))
+                .collect::<Vec<_>>()
+        };
+        let seeds = fleet(1337);
+        assert_eq!(seeds, fleet(1337));
+        assert_ne!(seeds, fleet(1338));
+
+        let mut unique = seeds.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        assert_eq!(unique.len(), seeds.len());
+    }
 }
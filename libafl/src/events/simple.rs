@@ -3,7 +3,7 @@
 use crate::{
     events::{
         BrokerEventResult, Event, EventFirer, EventManager, EventManagerId, EventProcessor,
-        EventRestarter, HasEventManagerId,
+        EventRestarter, HasEventManagerId, LogSeverity,
     },
     inputs::Input,
     monitors::Monitor,
@@ -48,6 +48,8 @@ where
     monitor: MT,
     /// The events that happened since the last handle_in_broker
     events: Vec<Event<I>>,
+    /// [`Event::Log`] events less severe than this are dropped
+    min_log_severity: LogSeverity,
 }
 
 impl<I, MT> EventFirer<I> for SimpleEventManager<I, MT>
@@ -56,6 +58,9 @@ where
     MT: Monitor, //CE: CustomEvent<I, OT>,
 {
     fn fire<S>(&mut self, _state: &mut S, event: Event<I>) -> Result<(), Error> {
+        if event.is_log_below(self.min_log_severity) {
+            return Ok(());
+        }
         match Self::handle_in_broker(&mut self.monitor, &event)? {
             BrokerEventResult::Forward => self.events.push(event),
             BrokerEventResult::Handled => (),
@@ -125,9 +130,21 @@ where
         Self {
             monitor,
             events: vec![],
+            min_log_severity: LogSeverity::Debug,
         }
     }
 
+    /// The least severity of the [`Event::Log`] events this manager fires, all by default
+    #[must_use]
+    pub fn min_log_severity(&self) -> LogSeverity {
+        self.min_log_severity
+    }
+
+    /// Drops the [`Event::Log`] events less severe than `min_log_severity` from now on
+    pub fn set_min_log_severity(&mut self, min_log_severity: LogSeverity) {
+        self.min_log_severity = min_log_severity;
+    }
+
     // Handle arriving events in the broker
     #[allow(clippy::unnecessary_wraps)]
     fn handle_in_broker(monitor: &mut MT, event: &Event<I>) -> Result<BrokerEventResult, Error> {
@@ -212,7 +229,24 @@ where
                 #[cfg(feature = "std")]
                 println!("[LOG {}]: {}", severity_level, message);
                 Ok(BrokerEventResult::Handled)
-            } //_ => Ok(BrokerEventResult::Forward),
+            }
+            Event::CoverageSnapshot {
+                map,
+                epoch,
+                delta,
+                phantom: _,
+            } => {
+                monitor
+                    .client_stats_mut_for(0)
+                    .update_coverage(map, *epoch, *delta);
+                monitor.display(event.name().to_string(), 0);
+                Ok(BrokerEventResult::Handled)
+            }
+            // There is only one client, nothing to check
+            Event::Handshake {
+                client_config: _,
+                phantom: _,
+            } => Ok(BrokerEventResult::Handled), //_ => Ok(BrokerEventResult::Forward),
         }
     }
 
@@ -274,6 +308,7 @@ where
     MT: Monitor, //CE: CustomEvent<I, OT>,
 {
     /// Reset the single page (we reuse it over and over from pos 0), then send the current state to the next runner.
+    /// The state includes the feedback states, so the next runner starts with the coverage known so far.
     fn on_restart(&mut self, state: &mut S) -> Result<(), Error> {
         // First, reset the page to 0 so the next iteration can read read from the beginning of this page
         self.staterestorer.reset();
@@ -355,6 +390,12 @@ where
         }
     }
 
+    /// Drops the [`Event::Log`] events less severe than `min_log_severity` from now on,
+    /// see [`SimpleEventManager::set_min_log_severity`]
+    pub fn set_min_log_severity(&mut self, min_log_severity: LogSeverity) {
+        self.simple_event_mgr.set_min_log_severity(min_log_severity);
+    }
+
     /// Launch the simple restarting manager.
     /// This [`EventManager`] is simple and single threaded,
     /// but can still used shared maps to recover from crashes and timeouts.
@@ -11,7 +11,7 @@ use std::process::Child;
 use crate::{executors::HasObservers, inputs::Input, observers::ObserversTuple, Error};
 
 #[cfg(all(feature = "std", unix))]
-use crate::executors::{Executor, ExitKind};
+use crate::executors::{normalize_crash, CrashKind, Executor, ExitKind, RawCrash};
 
 #[cfg(all(feature = "std", unix))]
 use std::time::Duration;
@@ -22,15 +22,21 @@ pub struct CommandExecutor<EM, I, OT: Debug, S, T: Debug, Z> {
     inner: T,
     /// [`crate::observers::Observer`]s for this executor
     observers: OT,
+    /// The [`CrashKind`] of the last run, if it crashed
+    #[cfg(all(feature = "std", unix))]
+    last_crash_kind: Option<CrashKind>,
     phantom: PhantomData<(EM, I, S, Z)>,
 }
 
 impl<EM, I, OT: Debug, S, T: Debug, Z> Debug for CommandExecutor<EM, I, OT, S, T, Z> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        f.debug_struct("CommandExecutor")
+        let mut debug = f.debug_struct("CommandExecutor");
+        debug
             .field("inner", &self.inner)
-            .field("observers", &self.observers)
-            .finish()
+            .field("observers", &self.observers);
+        #[cfg(all(feature = "std", unix))]
+        debug.field("last_crash_kind", &self.last_crash_kind);
+        debug.finish()
     }
 }
 
@@ -39,6 +45,13 @@ impl<EM, I, OT: Debug, S, T: Debug, Z> CommandExecutor<EM, I, OT, S, T, Z> {
     pub fn inner(&mut self) -> &mut T {
         &mut self.inner
     }
+
+    /// The [`CrashKind`] of the last run, or `None` if it did not crash
+    #[cfg(all(feature = "std", unix))]
+    #[must_use]
+    pub fn last_crash_kind(&self) -> Option<CrashKind> {
+        self.last_crash_kind
+    }
 }
 
 // this only works on unix because of the reliance on checking the process signal for detecting OOM
@@ -56,19 +69,35 @@ where
         _mgr: &mut EM,
         input: &I,
     ) -> Result<ExitKind, Error> {
-        use std::os::unix::prelude::ExitStatusExt;
+        use std::{io::Read, os::unix::prelude::ExitStatusExt, thread};
         use wait_timeout::ChildExt;
 
+        self.last_crash_kind = None;
         let mut child = self.inner.spawn_child(_fuzzer, _state, _mgr, input)?;
 
-        match child
+        // Drain `stderr` on a separate thread, so a chatty child can't block on a full pipe.
+        let stderr_reader = if self.observers.observes_stderr() {
+            child.stderr.take().map(|mut stderr| {
+                thread::spawn(move || {
+                    let mut buf = vec![];
+                    stderr.read_to_end(&mut buf).map(|_| buf)
+                })
+            })
+        } else {
+            None
+        };
+
+        let exit_kind = match child
             .wait_timeout(Duration::from_secs(5))
             .expect("waiting on child failed")
             .map(|status| status.signal())
         {
             // for reference: https://www.man7.org/linux/man-pages/man7/signal.7.html
             Some(Some(9)) => Ok(ExitKind::Oom),
-            Some(Some(_)) => Ok(ExitKind::Crash),
+            Some(Some(signal)) => {
+                self.last_crash_kind = Some(normalize_crash(RawCrash::Signal(signal)));
+                Ok(ExitKind::Crash)
+            }
             Some(None) => Ok(ExitKind::Ok),
             None => {
                 // if this fails, there is not much we can do. let's hope it failed because the process finished
@@ -78,7 +107,16 @@ where
                 drop(child.wait());
                 Ok(ExitKind::Timeout)
             }
+        };
+
+        if let Some(stderr_reader) = stderr_reader {
+            let stderr = stderr_reader
+                .join()
+                .map_err(|_| Error::Unknown("The stderr reader thread panicked".into()))??;
+            self.observers.observe_stderr(&stderr);
         }
+
+        exit_kind
     }
 }
 
@@ -102,6 +140,7 @@ where
 }
 
 /// A `CommandConfigurator` takes care of creating and spawning a [`std::process::Command`] for the [`CommandExecutor`].
+/// If the spawned [`Child`] has a piped `stderr`, its output is handed to observers like the [`crate::observers::StdErrObserver`].
 /// # Example
 /// ```
 /// use std::{io::Write, process::{Stdio, Command, Child}};
@@ -153,6 +192,7 @@ pub trait CommandConfigurator<EM, I: Input, S, Z>: Sized + Debug {
         CommandExecutor {
             inner: self,
             observers,
+            last_crash_kind: None,
             phantom: PhantomData,
         }
     }
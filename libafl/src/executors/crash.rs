@@ -0,0 +1,211 @@
+//! A platform-independent taxonomy of crashes, [`CrashKind`], and [`normalize_crash`] to map
+//! the raw signals, exception codes, and exit codes executors see onto it.
+
+use core::fmt;
+use serde::{Deserialize, Serialize};
+
+/// `STATUS_ACCESS_VIOLATION`
+const STATUS_ACCESS_VIOLATION: u32 = 0xC000_0005;
+/// `STATUS_IN_PAGE_ERROR`
+const STATUS_IN_PAGE_ERROR: u32 = 0xC000_0006;
+/// `STATUS_STACK_OVERFLOW`
+const STATUS_STACK_OVERFLOW: u32 = 0xC000_00FD;
+/// `STATUS_GUARD_PAGE_VIOLATION`
+const STATUS_GUARD_PAGE_VIOLATION: u32 = 0x8000_0001;
+/// `STATUS_ARRAY_BOUNDS_EXCEEDED`
+const STATUS_ARRAY_BOUNDS_EXCEEDED: u32 = 0xC000_008C;
+/// `STATUS_ILLEGAL_INSTRUCTION`
+const STATUS_ILLEGAL_INSTRUCTION: u32 = 0xC000_001D;
+/// `STATUS_PRIVILEGED_INSTRUCTION`
+const STATUS_PRIVILEGED_INSTRUCTION: u32 = 0xC000_0096;
+/// `STATUS_FLOAT_DENORMAL_OPERAND`, the first of the `STATUS_FLOAT_*` codes
+const STATUS_FLOAT_DENORMAL_OPERAND: u32 = 0xC000_008D;
+/// `STATUS_INTEGER_OVERFLOW`, the last of the `STATUS_FLOAT_*` and `STATUS_INTEGER_*` codes
+const STATUS_INTEGER_OVERFLOW: u32 = 0xC000_0095;
+/// `STATUS_FLOAT_MULTIPLE_FAULTS`
+const STATUS_FLOAT_MULTIPLE_FAULTS: u32 = 0xC000_02B4;
+/// `STATUS_FLOAT_MULTIPLE_TRAPS`
+const STATUS_FLOAT_MULTIPLE_TRAPS: u32 = 0xC000_02B5;
+/// `STATUS_DATATYPE_MISALIGNMENT`
+const STATUS_DATATYPE_MISALIGNMENT: u32 = 0x8000_0002;
+/// `STATUS_STACK_BUFFER_OVERRUN`, raised by `__fastfail` and `abort` in recent CRTs
+const STATUS_STACK_BUFFER_OVERRUN: u32 = 0xC000_0409;
+/// `STATUS_HEAP_CORRUPTION`
+const STATUS_HEAP_CORRUPTION: u32 = 0xC000_0374;
+/// `STATUS_ASSERTION_FAILURE`
+const STATUS_ASSERTION_FAILURE: u32 = 0xC000_0420;
+/// The exit code of the Windows CRT `abort`
+const WINDOWS_ABORT_EXIT_CODE: i32 = 3;
+
+#[cfg(unix)]
+mod signals {
+    pub const SIGSEGV: i32 = libc::SIGSEGV;
+    pub const SIGABRT: i32 = libc::SIGABRT;
+    pub const SIGILL: i32 = libc::SIGILL;
+    pub const SIGFPE: i32 = libc::SIGFPE;
+    pub const SIGBUS: i32 = libc::SIGBUS;
+    pub const SIGTRAP: i32 = libc::SIGTRAP;
+    pub const SIGSYS: i32 = libc::SIGSYS;
+}
+
+/// The signal numbers of the Windows CRT, which has no bus error, trap, or bad syscall signals
+#[cfg(not(unix))]
+mod signals {
+    pub const SIGSEGV: i32 = 11;
+    pub const SIGABRT: i32 = 22;
+    pub const SIGILL: i32 = 4;
+    pub const SIGFPE: i32 = 8;
+    pub const SIGBUS: i32 = -1;
+    pub const SIGTRAP: i32 = -2;
+    pub const SIGSYS: i32 = -3;
+    pub const SIGABRT_COMPAT: i32 = 6;
+}
+
+/// The kind of a crash, the same on every platform
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CrashKind {
+    /// An invalid memory access, `SIGSEGV` or an access violation
+    Segv,
+    /// The target aborted itself, for example on a failed assertion or a sanitizer report
+    Abort,
+    /// An illegal or privileged instruction, or a trap
+    Illegal,
+    /// An arithmetic error, such as a division by zero
+    FpError,
+    /// A misaligned or otherwise invalid physical memory access
+    BusError,
+    /// Any other crash
+    Unknown,
+}
+
+crate::impl_serdeany!(CrashKind);
+
+impl fmt::Display for CrashKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            CrashKind::Segv => "segv",
+            CrashKind::Abort => "abort",
+            CrashKind::Illegal => "illegal instruction",
+            CrashKind::FpError => "floating point error",
+            CrashKind::BusError => "bus error",
+            CrashKind::Unknown => "unknown crash",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A crash, as an executor observed it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RawCrash {
+    /// The target was killed by this signal
+    Signal(i32),
+    /// The target raised this Windows exception, an `NTSTATUS` code
+    ExceptionCode(u32),
+    /// The target exited with this code, for targets reporting crashes through the exit code,
+    /// such as shells (`128` + the signal) or Windows processes (the exception code)
+    ExitCode(i32),
+}
+
+/// Maps a raw signal, exception code, or exit code to its [`CrashKind`].
+/// Sanitizers abort, or exit with a custom code, after their report: run the target with
+/// `abort_on_error=1` to get [`CrashKind::Abort`] for them.
+#[must_use]
+pub fn normalize_crash(raw: RawCrash) -> CrashKind {
+    match raw {
+        RawCrash::Signal(signal) => normalize_signal(signal),
+        RawCrash::ExceptionCode(code) => normalize_exception_code(code),
+        RawCrash::ExitCode(code) => normalize_exit_code(code),
+    }
+}
+
+fn normalize_signal(signal: i32) -> CrashKind {
+    match signal {
+        signals::SIGSEGV => CrashKind::Segv,
+        signals::SIGABRT => CrashKind::Abort,
+        #[cfg(not(unix))]
+        signals::SIGABRT_COMPAT => CrashKind::Abort,
+        signals::SIGILL | signals::SIGTRAP | signals::SIGSYS => CrashKind::Illegal,
+        signals::SIGFPE => CrashKind::FpError,
+        signals::SIGBUS => CrashKind::BusError,
+        _ => CrashKind::Unknown,
+    }
+}
+
+fn normalize_exception_code(code: u32) -> CrashKind {
+    match code {
+        STATUS_ACCESS_VIOLATION
+        | STATUS_IN_PAGE_ERROR
+        | STATUS_STACK_OVERFLOW
+        | STATUS_GUARD_PAGE_VIOLATION
+        | STATUS_ARRAY_BOUNDS_EXCEEDED => CrashKind::Segv,
+        STATUS_ILLEGAL_INSTRUCTION | STATUS_PRIVILEGED_INSTRUCTION => CrashKind::Illegal,
+        STATUS_FLOAT_DENORMAL_OPERAND..=STATUS_INTEGER_OVERFLOW
+        | STATUS_FLOAT_MULTIPLE_FAULTS
+        | STATUS_FLOAT_MULTIPLE_TRAPS => CrashKind::FpError,
+        STATUS_DATATYPE_MISALIGNMENT => CrashKind::BusError,
+        STATUS_STACK_BUFFER_OVERRUN | STATUS_HEAP_CORRUPTION | STATUS_ASSERTION_FAILURE => {
+            CrashKind::Abort
+        }
+        _ => CrashKind::Unknown,
+    }
+}
+
+fn normalize_exit_code(code: i32) -> CrashKind {
+    // Windows processes exit with the code of the unhandled exception
+    #[allow(clippy::cast_sign_loss)]
+    let status = code as u32;
+    if status & 0xC000_0000 != 0 {
+        return normalize_exception_code(status);
+    }
+    match code {
+        WINDOWS_ABORT_EXIT_CODE if cfg!(windows) => CrashKind::Abort,
+        129..=192 => normalize_signal(code - 128),
+        _ => CrashKind::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::executors::{normalize_crash, CrashKind, RawCrash};
+
+    #[cfg(unix)]
+    #[test]
+    fn test_normalize_signals() {
+        let cases = [
+            (libc::SIGSEGV, CrashKind::Segv),
+            (libc::SIGABRT, CrashKind::Abort),
+            (libc::SIGILL, CrashKind::Illegal),
+            (libc::SIGTRAP, CrashKind::Illegal),
+            (libc::SIGFPE, CrashKind::FpError),
+            (libc::SIGBUS, CrashKind::BusError),
+            (libc::SIGTERM, CrashKind::Unknown),
+        ];
+        for (signal, kind) in cases {
+            assert_eq!(normalize_crash(RawCrash::Signal(signal)), kind);
+            // The shell reports a signal as `128` + the signal
+            assert_eq!(normalize_crash(RawCrash::ExitCode(128 + signal)), kind);
+        }
+        assert_eq!(normalize_crash(RawCrash::ExitCode(1)), CrashKind::Unknown);
+    }
+
+    #[test]
+    fn test_normalize_exception_codes() {
+        let cases = [
+            (0xC000_0005, CrashKind::Segv),
+            (0xC000_00FD, CrashKind::Segv),
+            (0xC000_001D, CrashKind::Illegal),
+            (0xC000_0094, CrashKind::FpError),
+            (0xC000_008E, CrashKind::FpError),
+            (0x8000_0002, CrashKind::BusError),
+            (0xC000_0409, CrashKind::Abort),
+            (0xC000_0135, CrashKind::Unknown),
+        ];
+        for (code, kind) in cases {
+            assert_eq!(normalize_crash(RawCrash::ExceptionCode(code)), kind);
+        }
+        // An exit code holding an exception code
+        #[allow(clippy::cast_possible_wrap)]
+        let code = 0xC000_0005_u32 as i32;
+        assert_eq!(normalize_crash(RawCrash::ExitCode(code)), CrashKind::Segv);
+    }
+}
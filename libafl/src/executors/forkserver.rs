@@ -20,7 +20,7 @@ use crate::{
         os::{dup2, pipes::Pipe},
         shmem::{ShMem, ShMemProvider, StdShMem, StdShMemProvider},
     },
-    executors::{Executor, ExitKind, HasObservers},
+    executors::{normalize_crash, CrashKind, Executor, ExitKind, HasObservers, RawCrash},
     inputs::{HasTargetBytes, Input},
     observers::ObserversTuple,
     Error,
@@ -281,6 +281,18 @@ impl Forkserver {
         self.status
     }
 
+    /// The [`CrashKind`] of the last run, from the status, or `None` if it was not killed by a signal
+    #[must_use]
+    pub fn crash_kind(&self) -> Option<CrashKind> {
+        if libc::WIFSIGNALED(self.status) {
+            Some(normalize_crash(RawCrash::Signal(libc::WTERMSIG(
+                self.status,
+            ))))
+        } else {
+            None
+        }
+    }
+
     /// Sets the status
     pub fn set_status(&mut self, status: i32) {
         self.status = status;
@@ -779,14 +791,15 @@ where
 #[cfg(test)]
 mod tests {
     use serial_test::serial;
+    use std::{env, fs, os::unix::fs::PermissionsExt, process};
 
     use crate::{
         bolts::{
             shmem::{ShMem, ShMemProvider, StdShMemProvider},
             tuples::tuple_list,
         },
-        executors::ForkserverExecutor,
-        inputs::NopInput,
+        executors::{Executor, ExitKind, ForkserverExecutor},
+        inputs::{BytesInput, NopInput},
         observers::{ConstMapObserver, HitcountsMapObserver},
         Error,
     };
@@ -823,4 +836,51 @@ mod tests {
         };
         assert!(result);
     }
+
+    /// A forkserver stub, speaking the AFL forkserver protocol on fds 198 and 199.
+    /// Instead of forking, it reports a `SIGABRT` crash for inputs containing `crash`.
+    const FORKSERVER_STUB: &str = r#"#!/bin/bash
+le32() {
+    printf "$(printf '\\%03o\\%03o\\%03o\\%03o' $(($1 & 255)) $(($1 >> 8 & 255)) $(($1 >> 16 & 255)) $(($1 >> 24 & 255)))"
+}
+le32 0 >&199
+while [ "$(head -c 4 <&198 | wc -c)" -eq 4 ]; do
+    le32 $$ >&199
+    if grep -q crash "$1"; then
+        le32 6 >&199
+    else
+        le32 0 >&199
+    fi
+done
+"#;
+
+    #[test]
+    #[serial]
+    fn test_forkserver_stub() {
+        let stub = env::temp_dir().join(format!("libafl_forkserver_stub_{}", process::id()));
+        fs::write(&stub, FORKSERVER_STUB).unwrap();
+        fs::set_permissions(&stub, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let mut executor = ForkserverExecutor::<BytesInput, _, ()>::new(
+            stub.to_str().unwrap().to_string(),
+            &[String::from("@@")],
+            false,
+            tuple_list!(),
+        )
+        .unwrap();
+
+        for (input, expected) in [
+            (&b"hello"[..], ExitKind::Ok),
+            (&b"crash"[..], ExitKind::Crash),
+            (&b"world"[..], ExitKind::Ok),
+        ] {
+            let exit_kind = executor
+                .run_target(&mut (), &mut (), &mut (), &BytesInput::new(input.to_vec()))
+                .unwrap();
+            assert_eq!(exit_kind, expected);
+        }
+
+        drop(executor);
+        fs::remove_file(&stub).unwrap();
+    }
 }
@@ -18,12 +18,18 @@ use core::{
 
 #[cfg(all(feature = "std", unix))]
 use nix::{
-    sys::wait::{waitpid, WaitStatus},
+    sys::{
+        signal::Signal,
+        wait::{waitpid, WaitStatus},
+    },
     unistd::{fork, ForkResult},
 };
 
 #[cfg(all(feature = "std", unix))]
-use crate::bolts::shmem::ShMemProvider;
+use crate::{
+    bolts::shmem::ShMemProvider,
+    executors::{normalize_crash, CrashKind, RawCrash},
+};
 
 #[cfg(unix)]
 use crate::bolts::os::unix_signals::setup_signal_handler;
@@ -45,6 +51,49 @@ use crate::{
     Error,
 };
 
+#[cfg(feature = "std")]
+std::thread_local! {
+    /// Set while a harness of an [`InProcessExecutor`] runs on this thread
+    static HARNESS_RUNNING: core::cell::Cell<bool> = core::cell::Cell::new(false);
+}
+
+/// Set while a harness of an [`InProcessExecutor`] runs
+#[cfg(not(feature = "std"))]
+static HARNESS_RUNNING: core::sync::atomic::AtomicBool = core::sync::atomic::AtomicBool::new(false);
+
+/// Marks a running harness execution, until dropped, even if the harness panics
+#[derive(Debug)]
+struct HarnessGuard {}
+
+impl HarnessGuard {
+    /// Marks the start of a harness execution.
+    /// Fails if a harness is already running, as the nested execution would overwrite the
+    /// [`GLOBAL_STATE`] and the observers of the outer one.
+    fn enter() -> Result<Self, Error> {
+        #[cfg(feature = "std")]
+        let running = HARNESS_RUNNING.with(|running| running.replace(true));
+        #[cfg(not(feature = "std"))]
+        let running = HARNESS_RUNNING.swap(true, core::sync::atomic::Ordering::SeqCst);
+        if running {
+            Err(Error::IllegalState(
+                "The in-process harness got re-entered while it was running".into(),
+            ))
+        } else {
+            Ok(Self {})
+        }
+    }
+}
+
+impl Drop for HarnessGuard {
+    /// Marks the end of a harness execution
+    fn drop(&mut self) {
+        #[cfg(feature = "std")]
+        HARNESS_RUNNING.with(|running| running.set(false));
+        #[cfg(not(feature = "std"))]
+        HARNESS_RUNNING.store(false, core::sync::atomic::Ordering::SeqCst);
+    }
+}
+
 /// The inmem executor simply calls a target function, then returns afterwards.
 #[allow(dead_code)]
 pub struct InProcessExecutor<'a, H, I, OT, S>
@@ -90,6 +139,7 @@ where
         mgr: &mut EM,
         input: &I,
     ) -> Result<ExitKind, Error> {
+        let _guard = HarnessGuard::enter()?;
         self.handlers
             .pre_run_target(self, fuzzer, state, mgr, input);
         let ret = (self.harness_fn)(input);
@@ -433,8 +483,9 @@ mod unix_signal_handler {
         events::{Event, EventFirer, EventRestarter},
         executors::{
             inprocess::{InProcessExecutorHandlerData, GLOBAL_STATE},
+            normalize_crash,
             timeout::unix_remove_timeout,
-            ExitKind, HasObservers,
+            ExitKind, HasObservers, RawCrash,
         },
         feedbacks::Feedback,
         fuzzer::HasObjective,
@@ -592,8 +643,9 @@ mod unix_signal_handler {
         let _context = &mut *(((_context as *mut _ as *mut libc::c_void as usize) + 128)
             as *mut libc::c_void as *mut ucontext_t);
 
+        let crash_kind = normalize_crash(RawCrash::Signal(signal as i32));
         #[cfg(feature = "std")]
-        eprintln!("Crashed with {}", signal);
+        eprintln!("Crashed with {} ({})", signal, crash_kind);
         if data.current_input_ptr.is_null() {
             #[cfg(feature = "std")]
             {
@@ -658,6 +710,7 @@ mod unix_signal_handler {
                 let new_input = input.clone();
                 let mut new_testcase = Testcase::new(new_input);
                 new_testcase.add_metadata(ExitKind::Crash);
+                new_testcase.add_metadata(crash_kind);
                 fuzzer
                     .objective_mut()
                     .append_metadata(state, &mut new_testcase)
@@ -705,8 +758,9 @@ mod windows_exception_handler {
         events::{Event, EventFirer, EventRestarter},
         executors::{
             inprocess::{InProcessExecutorHandlerData, GLOBAL_STATE},
+            normalize_crash,
             timeout::windows_delete_timer_queue,
-            ExitKind, HasObservers,
+            ExitKind, HasObservers, RawCrash,
         },
         feedbacks::Feedback,
         fuzzer::HasObjective,
@@ -893,8 +947,10 @@ mod windows_exception_handler {
         )
         .unwrap();
 
+        #[allow(clippy::cast_sign_loss)]
+        let crash_kind = normalize_crash(RawCrash::ExceptionCode(code as i32 as u32));
         #[cfg(feature = "std")]
-        eprintln!("Crashed with {}", code);
+        eprintln!("Crashed with {} ({})", code, crash_kind);
         if data.current_input_ptr.is_null() {
             #[cfg(feature = "std")]
             {
@@ -947,6 +1003,7 @@ mod windows_exception_handler {
                 let new_input = input.clone();
                 let mut new_testcase = Testcase::new(new_input);
                 new_testcase.add_metadata(ExitKind::Crash);
+                new_testcase.add_metadata(crash_kind);
                 fuzzer
                     .objective_mut()
                     .append_metadata(state, &mut new_testcase)
@@ -998,7 +1055,20 @@ where
     }
 }
 
+/// The exit code a forked child uses to report [`ExitKind::Oom`] to the [`InProcessForkExecutor`]
+#[cfg(all(feature = "std", unix))]
+pub const FORK_EXIT_CODE_OOM: i32 = 0x4f;
+/// The exit code a forked child uses to report [`ExitKind::Timeout`] to the [`InProcessForkExecutor`]
+#[cfg(all(feature = "std", unix))]
+pub const FORK_EXIT_CODE_TIMEOUT: i32 = 0x54;
+
 /// [`InProcessForkExecutor`] is an executor that forks the current process before each execution.
+/// The harness runs in the child, so changes it makes to global state don't leak into later runs.
+/// Observers need to live in shared memory for the parent to see the results of a run.
+/// The [`ExitKind`] returned by the harness, or the signal that killed the child, is reported as result.
+/// The [`CrashKind`] of a crashed child is available from [`InProcessForkExecutor::last_crash_kind`].
+/// [`ExitKind::Custom`] codes are passed on as exit code of the child, so `0`, [`FORK_EXIT_CODE_OOM`]
+/// and [`FORK_EXIT_CODE_TIMEOUT`] can't be used as custom codes with this executor.
 #[cfg(all(feature = "std", unix))]
 pub struct InProcessForkExecutor<'a, H, I, OT, S, SP>
 where
@@ -1010,6 +1080,8 @@ where
     harness_fn: &'a mut H,
     shmem_provider: SP,
     observers: OT,
+    /// The [`CrashKind`] of the last run, if it crashed
+    last_crash_kind: Option<CrashKind>,
     phantom: PhantomData<(I, S)>,
 }
 
@@ -1025,6 +1097,7 @@ where
         f.debug_struct("InProcessForkExecutor")
             .field("observers", &self.observers)
             .field("shmem_provider", &self.shmem_provider)
+            .field("last_crash_kind", &self.last_crash_kind)
             .finish()
     }
 }
@@ -1047,6 +1120,7 @@ where
         _mgr: &mut EM,
         input: &I,
     ) -> Result<ExitKind, Error> {
+        self.last_crash_kind = None;
         unsafe {
             self.shmem_provider.pre_fork()?;
             match fork() {
@@ -1054,9 +1128,14 @@ where
                     // Child
                     self.shmem_provider.post_fork(true)?;
 
-                    (self.harness_fn)(input);
-
-                    std::process::exit(0);
+                    // Report the `ExitKind` of the harness to the parent
+                    match (self.harness_fn)(input) {
+                        ExitKind::Ok => std::process::exit(0),
+                        ExitKind::Crash => std::process::abort(),
+                        ExitKind::Oom => std::process::exit(FORK_EXIT_CODE_OOM),
+                        ExitKind::Timeout => std::process::exit(FORK_EXIT_CODE_TIMEOUT),
+                        ExitKind::Custom(code) => std::process::exit(code.into()),
+                    }
 
                     Ok(ExitKind::Ok)
                 }
@@ -1066,7 +1145,17 @@ where
 
                     let res = waitpid(child, None)?;
                     match res {
-                        WaitStatus::Signaled(_, _, _) => Ok(ExitKind::Crash),
+                        WaitStatus::Signaled(_, Signal::SIGKILL, _)
+                        | WaitStatus::Exited(_, FORK_EXIT_CODE_OOM) => Ok(ExitKind::Oom),
+                        WaitStatus::Signaled(_, signal, _) => {
+                            self.last_crash_kind =
+                                Some(normalize_crash(RawCrash::Signal(signal as i32)));
+                            Ok(ExitKind::Crash)
+                        }
+                        WaitStatus::Exited(_, FORK_EXIT_CODE_TIMEOUT) => Ok(ExitKind::Timeout),
+                        WaitStatus::Exited(_, code) if code != 0 => {
+                            Ok(u8::try_from(code).map_or(ExitKind::Ok, ExitKind::Custom))
+                        }
                         _ => Ok(ExitKind::Ok),
                     }
                 }
@@ -1104,6 +1193,7 @@ where
             harness_fn,
             shmem_provider,
             observers,
+            last_crash_kind: None,
             phantom: PhantomData,
         })
     }
@@ -1119,6 +1209,12 @@ where
     pub fn harness_mut(&mut self) -> &mut H {
         self.harness_fn
     }
+
+    /// The [`CrashKind`] of the last run, or `None` if it did not crash
+    #[must_use]
+    pub fn last_crash_kind(&self) -> Option<CrashKind> {
+        self.last_crash_kind
+    }
 }
 
 #[cfg(all(feature = "std", unix))]
@@ -1144,15 +1240,24 @@ where
 mod tests {
     use core::marker::PhantomData;
 
+    #[cfg(feature = "std")]
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::InMemoryCorpus,
+        events::NopEventManager,
+        feedbacks::{CustomExitFeedback, Feedback},
+        state::StdState,
+    };
     #[cfg(all(feature = "std", feature = "fork", unix))]
     use crate::{
         bolts::shmem::{ShMemProvider, StdShMemProvider},
-        executors::InProcessForkExecutor,
+        executors::{CrashKind, InProcessForkExecutor},
     };
     use crate::{
         bolts::tuples::tuple_list,
         executors::{inprocess::InProcessHandlers, Executor, ExitKind, InProcessExecutor},
         inputs::NopInput,
+        Error,
     };
 
     #[test]
@@ -1171,6 +1276,96 @@ mod tests {
             .is_ok());
     }
 
+    #[test]
+    fn test_inmem_exec_reentrant() {
+        // The harness runs a nested executor, as a callback into the fuzzer would
+        let mut nested = None;
+        let mut harness = |input: &NopInput| {
+            let mut inner_harness = |_buf: &NopInput| ExitKind::Ok;
+            let mut inner_executor = InProcessExecutor::<_, NopInput, (), ()> {
+                harness_fn: &mut inner_harness,
+                observers: tuple_list!(),
+                handlers: InProcessHandlers::nop(),
+                phantom: PhantomData,
+            };
+            nested = Some(inner_executor.run_target(&mut (), &mut (), &mut (), input));
+            ExitKind::Ok
+        };
+
+        {
+            let mut in_process_executor = InProcessExecutor::<_, NopInput, (), ()> {
+                harness_fn: &mut harness,
+                observers: tuple_list!(),
+                handlers: InProcessHandlers::nop(),
+                phantom: PhantomData,
+            };
+            assert!(in_process_executor
+                .run_target(&mut (), &mut (), &mut (), &NopInput {})
+                .is_ok());
+        }
+        assert!(matches!(nested, Some(Err(Error::IllegalState(_)))));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_inmem_exec_after_panic() {
+        let panic = core::cell::Cell::new(true);
+        let mut harness = |_buf: &NopInput| {
+            assert!(!panic.get(), "harness panicked");
+            ExitKind::Ok
+        };
+        let mut in_process_executor = InProcessExecutor::<_, NopInput, (), ()> {
+            harness_fn: &mut harness,
+            observers: tuple_list!(),
+            handlers: InProcessHandlers::nop(),
+            phantom: PhantomData,
+        };
+        let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            in_process_executor.run_target(&mut (), &mut (), &mut (), &NopInput {})
+        }));
+        assert!(res.is_err());
+
+        // The panic did not leave the harness marked as running
+        panic.set(false);
+        assert!(in_process_executor
+            .run_target(&mut (), &mut (), &mut (), &NopInput {})
+            .is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_inmem_exec_custom_exit_kind() {
+        let mut harness = |_buf: &NopInput| ExitKind::Custom(7);
+
+        let mut in_process_executor = InProcessExecutor::<_, NopInput, (), _> {
+            harness_fn: &mut harness,
+            observers: tuple_list!(),
+            handlers: InProcessHandlers::nop(),
+            phantom: PhantomData,
+        };
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<NopInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let mut mgr = NopEventManager {};
+        let input = NopInput {};
+        let exit_kind = in_process_executor
+            .run_target(&mut (), &mut state, &mut mgr, &input)
+            .unwrap();
+        assert_eq!(exit_kind, ExitKind::Custom(7));
+
+        for (code, expected) in [(7, true), (3, false)] {
+            assert_eq!(
+                CustomExitFeedback::new(code)
+                    .is_interesting(&mut state, &mut mgr, &input, &(), &exit_kind)
+                    .unwrap(),
+                expected
+            );
+        }
+    }
+
     #[test]
     #[cfg(all(feature = "std", feature = "fork", unix))]
     fn test_inprocessfork_exec() {
@@ -1181,6 +1376,7 @@ mod tests {
             harness_fn: &mut harness,
             shmem_provider: provider,
             observers: tuple_list!(),
+            last_crash_kind: None,
             phantom: PhantomData,
         };
         let input = NopInput {};
@@ -1188,4 +1384,65 @@ mod tests {
             .run_target(&mut (), &mut (), &mut (), &input)
             .is_ok());
     }
+
+    #[test]
+    #[cfg(all(feature = "std", feature = "fork", unix))]
+    fn test_inprocessfork_isolation() {
+        let provider = StdShMemProvider::new().unwrap();
+
+        // Each run happens in a fresh child, so the global never gets seen as dirty
+        let mut global_state = 0_usize;
+        let mut harness = |_buf: &NopInput| {
+            global_state += 1;
+            if global_state == 1 {
+                ExitKind::Ok
+            } else {
+                ExitKind::Crash
+            }
+        };
+        let mut in_process_fork_executor = InProcessForkExecutor::<_, NopInput, (), (), _> {
+            harness_fn: &mut harness,
+            shmem_provider: provider,
+            observers: tuple_list!(),
+            last_crash_kind: None,
+            phantom: PhantomData,
+        };
+        let input = NopInput {};
+        for _ in 0..3 {
+            assert_eq!(
+                in_process_fork_executor
+                    .run_target(&mut (), &mut (), &mut (), &input)
+                    .unwrap(),
+                ExitKind::Ok
+            );
+        }
+        assert_eq!(global_state, 0);
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", feature = "fork", unix))]
+    fn test_inprocessfork_crash_kind() {
+        let provider = StdShMemProvider::new().unwrap();
+
+        let mut harness = |_buf: &NopInput| ExitKind::Crash;
+        let mut in_process_fork_executor = InProcessForkExecutor::<_, NopInput, (), (), _> {
+            harness_fn: &mut harness,
+            shmem_provider: provider,
+            observers: tuple_list!(),
+            last_crash_kind: None,
+            phantom: PhantomData,
+        };
+        let input = NopInput {};
+        assert_eq!(
+            in_process_fork_executor
+                .run_target(&mut (), &mut (), &mut (), &input)
+                .unwrap(),
+            ExitKind::Crash
+        );
+        // The child reports a crash of the harness by aborting
+        assert_eq!(
+            in_process_fork_executor.last_crash_kind(),
+            Some(CrashKind::Abort)
+        );
+    }
 }
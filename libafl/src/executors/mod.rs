@@ -26,22 +26,28 @@ pub use shadow::ShadowExecutor;
 pub mod with_observers;
 pub use with_observers::WithObservers;
 
+pub mod crash;
+pub use crash::{normalize_crash, CrashKind, RawCrash};
+
 #[cfg(all(feature = "std", unix))]
 pub mod command;
 #[cfg(all(feature = "std", unix))]
 pub use command::CommandExecutor;
 
-use crate::{
-    inputs::{HasTargetBytes, Input},
-    observers::ObserversTuple,
-    Error,
-};
+#[cfg(feature = "std")]
+pub mod triage;
+#[cfg(feature = "std")]
+pub use triage::{triage_directory, triage_directory_with, TriageGroup, TriageReport};
+
+#[cfg(test)]
+use crate::inputs::HasTargetBytes;
+use crate::{inputs::Input, observers::ObserversTuple, Error};
 
 use core::fmt::Debug;
 use serde::{Deserialize, Serialize};
 
 /// How an execution finished.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum ExitKind {
     /// The run exited normally.
     Ok,
@@ -51,8 +57,8 @@ pub enum ExitKind {
     Oom,
     /// The run timed out
     Timeout,
-    // The run resulted in a custom `ExitKind`.
-    // Custom(Box<dyn SerdeAny>),
+    /// The run resulted in a harness-defined outcome, e.g. a failed validation.
+    Custom(u8),
 }
 
 crate::impl_serdeany!(ExitKind);
@@ -96,11 +102,24 @@ where
     }
 }
 
-/// A simple executor that does nothing.
+/// A simple executor that does nothing, without observers.
 /// If intput len is 0, `run_target` will return Err
+#[cfg(test)]
 #[derive(Debug)]
-struct NopExecutor {}
+pub(crate) struct NopExecutor {
+    observers: (),
+}
 
+#[cfg(test)]
+impl NopExecutor {
+    /// Creates a new [`NopExecutor`]
+    #[must_use]
+    pub(crate) fn new() -> Self {
+        Self { observers: () }
+    }
+}
+
+#[cfg(test)]
 impl<EM, I, S, Z> Executor<EM, I, S, Z> for NopExecutor
 where
     I: Input + HasTargetBytes,
@@ -120,6 +139,20 @@ where
     }
 }
 
+#[cfg(test)]
+impl<I, S> HasObservers<I, (), S> for NopExecutor
+where
+    I: Input,
+{
+    fn observers(&self) -> &() {
+        &self.observers
+    }
+
+    fn observers_mut(&mut self) -> &mut () {
+        &mut self.observers
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::{Executor, NopExecutor};
@@ -129,7 +162,7 @@ mod test {
     fn nop_executor() {
         let empty_input = BytesInput::new(vec![]);
         let nonempty_input = BytesInput::new(vec![1u8]);
-        let mut executor = NopExecutor {};
+        let mut executor = NopExecutor::new();
         assert!(executor
             .run_target(&mut (), &mut (), &mut (), &empty_input)
             .is_err());
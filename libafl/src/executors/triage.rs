@@ -0,0 +1,353 @@
+//! Replays a directory of crashing inputs from an earlier campaign, and groups them by crash signature.
+
+use ahash::AHasher;
+use core::{
+    fmt,
+    hash::{Hash, Hasher},
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    executors::{Executor, ExitKind, HasObservers},
+    inputs::Input,
+    observers::ObserversTuple,
+    Error,
+};
+
+/// All inputs of a [`TriageReport`] that crashed with the same signature
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TriageGroup {
+    /// The signature shared by all inputs of this group
+    pub signature: u64,
+    /// The [`ExitKind`] of the first input of this group
+    pub exit_kind: ExitKind,
+    /// The inputs of this group, in the order they were replayed
+    pub inputs: Vec<PathBuf>,
+}
+
+/// The result of [`triage_directory`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TriageReport {
+    /// The crash groups, in the order they were first seen
+    pub groups: Vec<TriageGroup>,
+    /// Inputs that didn't crash (anymore) when replayed
+    pub not_reproduced: Vec<PathBuf>,
+}
+
+impl TriageReport {
+    /// The group with the given signature, if any input crashed this way
+    #[must_use]
+    pub fn group(&self, signature: u64) -> Option<&TriageGroup> {
+        self.groups
+            .iter()
+            .find(|group| group.signature == signature)
+    }
+}
+
+/// A grouped summary, one line per group
+impl fmt::Display for TriageReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} crash groups, {} inputs not reproduced",
+            self.groups.len(),
+            self.not_reproduced.len()
+        )?;
+        for group in &self.groups {
+            write!(
+                f,
+                "\n{:016x} {:?}: {} inputs, e.g. {:?}",
+                group.signature,
+                group.exit_kind,
+                group.inputs.len(),
+                group.inputs[0]
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A crash signature of only the [`ExitKind`] of the run.
+/// Returns `None` for runs that finished with [`ExitKind::Ok`].
+#[must_use]
+pub fn exit_kind_signature<OT>(exit_kind: &ExitKind, _observers: &OT) -> Option<u64> {
+    if *exit_kind == ExitKind::Ok {
+        return None;
+    }
+    let mut hasher = AHasher::new_with_keys(0, 0);
+    exit_kind.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+/// The default crash signature: the [`ExitKind`] of the run, combined with the crash hash of the
+/// observers, such as a backtrace hash, see [`ObserversTuple::crash_hash_all`].
+/// Falls back to [`exit_kind_signature`] if no observer knows where the run crashed.
+/// Returns `None` for runs that finished with [`ExitKind::Ok`].
+#[must_use]
+pub fn crash_signature<I, OT, S>(exit_kind: &ExitKind, observers: &OT) -> Option<u64>
+where
+    OT: ObserversTuple<I, S>,
+{
+    let signature = exit_kind_signature(exit_kind, observers)?;
+    match observers.crash_hash_all() {
+        Some(crash_hash) => {
+            let mut hasher = AHasher::new_with_keys(0, 0);
+            hasher.write_u64(signature);
+            hasher.write_u64(crash_hash);
+            Some(hasher.finish())
+        }
+        None => Some(signature),
+    }
+}
+
+/// Replays all files in `dir` through the `executor`, without fuzzing, and groups crashes by
+/// [`crash_signature`]. See [`triage_directory_with`] for custom signatures.
+pub fn triage_directory<E, EM, I, OT, S, Z>(
+    fuzzer: &mut Z,
+    executor: &mut E,
+    state: &mut S,
+    mgr: &mut EM,
+    dir: &Path,
+) -> Result<TriageReport, Error>
+where
+    E: Executor<EM, I, S, Z> + HasObservers<I, OT, S>,
+    I: Input,
+    OT: ObserversTuple<I, S>,
+{
+    triage_directory_with(
+        fuzzer,
+        executor,
+        state,
+        mgr,
+        dir,
+        crash_signature::<I, OT, S>,
+    )
+}
+
+/// Replays all files in `dir` through the `executor`, without fuzzing, and groups crashes by the
+/// signature the `classifier` computes from the [`ExitKind`] and the observers of each run,
+/// for example a backtrace hash. The `classifier` returns `None` for runs that didn't crash.
+/// Files are replayed in name order, so the report is deterministic.
+pub fn triage_directory_with<E, EM, F, I, OT, S, Z>(
+    fuzzer: &mut Z,
+    executor: &mut E,
+    state: &mut S,
+    mgr: &mut EM,
+    dir: &Path,
+    mut classifier: F,
+) -> Result<TriageReport, Error>
+where
+    E: Executor<EM, I, S, Z> + HasObservers<I, OT, S>,
+    F: FnMut(&ExitKind, &OT) -> Option<u64>,
+    I: Input,
+    OT: ObserversTuple<I, S>,
+{
+    let mut paths = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let attr = fs::metadata(&path)?;
+        if attr.is_file() && attr.len() > 0 {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    let mut report = TriageReport::default();
+    for path in paths {
+        let input = I::from_file(&path)?;
+
+        executor.observers_mut().pre_exec_all(state, &input)?;
+        let exit_kind = executor.run_target(fuzzer, state, mgr, &input)?;
+        executor.observers_mut().post_exec_all(state, &input)?;
+
+        match classifier(&exit_kind, executor.observers()) {
+            None => report.not_reproduced.push(path),
+            Some(signature) => {
+                match report
+                    .groups
+                    .iter_mut()
+                    .find(|group| group.signature == signature)
+                {
+                    Some(group) => group.inputs.push(path),
+                    None => report.groups.push(TriageGroup {
+                        signature,
+                        exit_kind,
+                        inputs: vec![path],
+                    }),
+                }
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use core::fmt::Debug;
+    use std::fs;
+
+    use crate::{
+        bolts::tuples::{tuple_list, tuple_list_type, MatchName},
+        executors::{
+            triage::{exit_kind_signature, triage_directory, triage_directory_with},
+            Executor, ExitKind, HasObservers,
+        },
+        inputs::{BytesInput, HasTargetBytes},
+        observers::{MapObserver, ObserversTuple, StdErrObserver, StdMapObserver},
+        Error,
+    };
+
+    static mut SITE: [u8; 4] = [0; 4];
+
+    /// Inputs starting with `A` crash at one site, inputs starting with `B` at another
+    #[derive(Debug)]
+    struct SiteExecutor<OT> {
+        observers: OT,
+    }
+
+    impl<EM, OT: Debug, S, Z> Executor<EM, BytesInput, S, Z> for SiteExecutor<OT> {
+        fn run_target(
+            &mut self,
+            _fuzzer: &mut Z,
+            _state: &mut S,
+            _mgr: &mut EM,
+            input: &BytesInput,
+        ) -> Result<ExitKind, Error> {
+            unsafe {
+                SITE = [0; 4];
+                match input.target_bytes().as_slice()[0] {
+                    b'A' => SITE[1] = 1,
+                    b'B' => SITE[2] = 1,
+                    _ => return Ok(ExitKind::Ok),
+                }
+            }
+            Ok(ExitKind::Crash)
+        }
+    }
+
+    impl<OT: ObserversTuple<BytesInput, S>, S> HasObservers<BytesInput, OT, S> for SiteExecutor<OT> {
+        fn observers(&self) -> &OT {
+            &self.observers
+        }
+
+        fn observers_mut(&mut self) -> &mut OT {
+            &mut self.observers
+        }
+    }
+
+    #[test]
+    fn test_triage_directory() {
+        let dir = std::env::temp_dir().join("libafl_test_triage_directory");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        for (name, content) in [("a", &b"A1"[..]), ("b", b"A2"), ("c", b"B"), ("d", b"ok")] {
+            fs::write(dir.join(name), content).unwrap();
+        }
+
+        let mut executor = SiteExecutor {
+            observers: tuple_list!(StdMapObserver::new("site", unsafe { &mut SITE })),
+        };
+        let report = triage_directory_with(
+            &mut (),
+            &mut executor,
+            &mut (),
+            &mut (),
+            &dir,
+            |exit_kind, observers| {
+                exit_kind_signature(exit_kind, observers)?;
+                observers
+                    .match_name::<StdMapObserver<u8>>("site")
+                    .map(MapObserver::hash)
+            },
+        )
+        .unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(report.groups.len(), 2);
+        assert_eq!(report.groups[0].inputs, vec![dir.join("a"), dir.join("b")]);
+        assert_eq!(report.groups[1].inputs, vec![dir.join("c")]);
+        assert_eq!(report.not_reproduced, vec![dir.join("d")]);
+
+        let summary = report.to_string();
+        let lines: Vec<&str> = summary.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "2 crash groups, 1 inputs not reproduced");
+        assert!(lines[1].ends_with(&format!("Crash: 2 inputs, e.g. {:?}", dir.join("a"))));
+    }
+
+    /// An ASAN report, crashing at `location`, called from `main`
+    fn asan_report(address: usize, location: &str) -> String {
+        format!(
+            "==1==ERROR: AddressSanitizer: SEGV\n    #0 0x{:x} in {}\n    #1 0x4f0000 in main /src/main.c:3:1\n",
+            address, location
+        )
+    }
+
+    /// Crashes on every input, printing an ASAN report with a stack depending on the first byte
+    #[derive(Debug)]
+    struct AsanExecutor {
+        observers: tuple_list_type!(StdErrObserver),
+    }
+
+    impl<EM, S, Z> Executor<EM, BytesInput, S, Z> for AsanExecutor {
+        fn run_target(
+            &mut self,
+            _fuzzer: &mut Z,
+            _state: &mut S,
+            _mgr: &mut EM,
+            input: &BytesInput,
+        ) -> Result<ExitKind, Error> {
+            let bytes = input.target_bytes();
+            let stderr = match bytes.as_slice()[0] {
+                // The address differs between runs, like under ASLR
+                b'A' => asan_report(0x4f1a00 + bytes.as_slice().len(), "parse_a /src/a.c:10:5"),
+                b'B' => asan_report(0x4f1b00, "parse_b /src/b.c:7:2"),
+                _ => "Aborted\n".into(),
+            };
+            ObserversTuple::<BytesInput, S>::observe_stderr(&mut self.observers, stderr.as_bytes());
+            Ok(ExitKind::Crash)
+        }
+    }
+
+    impl<S> HasObservers<BytesInput, tuple_list_type!(StdErrObserver), S> for AsanExecutor {
+        fn observers(&self) -> &tuple_list_type!(StdErrObserver) {
+            &self.observers
+        }
+
+        fn observers_mut(&mut self) -> &mut tuple_list_type!(StdErrObserver) {
+            &mut self.observers
+        }
+    }
+
+    #[test]
+    fn test_triage_directory_backtrace() {
+        let dir = std::env::temp_dir().join("libafl_test_triage_directory_backtrace");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        for (name, content) in [("a", &b"A1"[..]), ("b", b"A22"), ("c", b"B"), ("d", b"C")] {
+            fs::write(dir.join(name), content).unwrap();
+        }
+
+        // All inputs crash, but the ASAN stacks tell them apart, addresses aside
+        let mut executor = AsanExecutor {
+            observers: tuple_list!(StdErrObserver::new("stderr")),
+        };
+        let report = triage_directory(&mut (), &mut executor, &mut (), &mut (), &dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(report.groups.len(), 3);
+        assert_eq!(report.groups[0].inputs, vec![dir.join("a"), dir.join("b")]);
+        assert_eq!(report.groups[1].inputs, vec![dir.join("c")]);
+        // Without a sanitizer report, the signature falls back to the exit kind
+        assert_eq!(report.groups[2].inputs, vec![dir.join("d")]);
+        assert_eq!(
+            Some(report.groups[2].signature),
+            exit_kind_signature(&ExitKind::Crash, &())
+        );
+        assert!(report.not_reproduced.is_empty());
+    }
+}
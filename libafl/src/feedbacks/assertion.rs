@@ -0,0 +1,176 @@
+//! The [`AssertionMapFeedback`] reports inputs firing an assertion no input fired before,
+//! to find violated logic assertions that don't crash the target.
+//!
+//! The harness records fired assertions in a map, observed by a [`MapObserver`]: each assertion
+//! gets a fixed index into the map, for example hashed from its file and line, and the panic hook
+//! (or a custom `assert!` macro) sets the entry at that index to a non-zero value, then lets the
+//! harness return normally. The map has to be reset to zero before each run, as map observers do.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt::Debug, marker::PhantomData};
+use num_traits::PrimInt;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, FeedbackStatesTuple, MapFeedbackState},
+    inputs::Input,
+    observers::{MapObserver, ObserversTuple},
+    state::{HasClientPerfMonitor, HasFeedbackStates, HasMetadata},
+    Error,
+};
+
+/// A testcase metadata holding the indexes of the assertions it fired first
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AssertionMetadata {
+    /// The indexes of the new assertions
+    pub list: Vec<usize>,
+}
+
+crate::impl_serdeany!(AssertionMetadata);
+
+/// An [`AssertionMapFeedback`] reports an input as interesting if it fired an assertion, that is,
+/// set an entry of the assertion map, that no input fired before. Use it as objective, next to
+/// the crash and timeout feedbacks. The fired assertions are kept in a [`MapFeedbackState`], and
+/// the objectives get an [`AssertionMetadata`].
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AssertionMapFeedback<FT, O, S, T> {
+    name: String,
+    observer_name: String,
+    /// The assertions fired first by the last input
+    new_assertions: Vec<usize>,
+    phantom: PhantomData<(FT, O, S, T)>,
+}
+
+impl<FT, I, O, S, T> Feedback<I, S> for AssertionMapFeedback<FT, O, S, T>
+where
+    T: PrimInt + Default + Copy + 'static + Serialize + serde::de::DeserializeOwned + Debug,
+    O: MapObserver<T>,
+    I: Input,
+    S: HasFeedbackStates<FT> + HasClientPerfMonitor + Debug,
+    FT: FeedbackStatesTuple,
+{
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        _manager: &mut EM,
+        _input: &I,
+        observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<I>,
+        OT: ObserversTuple<I, S>,
+    {
+        let observer = observers.match_name::<O>(&self.observer_name).unwrap();
+        let map_state = state
+            .feedback_states_mut()
+            .match_name_mut::<MapFeedbackState<T>>(&self.name)
+            .unwrap();
+
+        self.new_assertions.clear();
+        let initial = observer.initial();
+        let len = observer.usable_count().min(map_state.history_map.len());
+        for idx in 0..len {
+            if *observer.get(idx) != initial && map_state.history_map[idx] == T::min_value() {
+                map_state.history_map[idx] = T::one();
+                self.new_assertions.push(idx);
+            }
+        }
+        Ok(!self.new_assertions.is_empty())
+    }
+
+    fn append_metadata(&mut self, _state: &mut S, testcase: &mut Testcase<I>) -> Result<(), Error> {
+        testcase.add_metadata(AssertionMetadata {
+            list: core::mem::take(&mut self.new_assertions),
+        });
+        Ok(())
+    }
+
+    fn discard_metadata(&mut self, _state: &mut S, _input: &I) -> Result<(), Error> {
+        self.new_assertions.clear();
+        Ok(())
+    }
+}
+
+impl<FT, O, S, T> Named for AssertionMapFeedback<FT, O, S, T> {
+    #[inline]
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
+impl<FT, O, S, T> AssertionMapFeedback<FT, O, S, T>
+where
+    T: PrimInt + Default + Copy + 'static + Serialize + serde::de::DeserializeOwned + Debug,
+    O: MapObserver<T>,
+{
+    /// Creates a new [`AssertionMapFeedback`], for the given state and assertion map observer
+    #[must_use]
+    pub fn new(feedback_state: &MapFeedbackState<T>, observer: &O) -> Self {
+        Self {
+            name: feedback_state.name().to_string(),
+            observer_name: observer.name().to_string(),
+            new_assertions: vec![],
+            phantom: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::{rands::StdRand, tuples::tuple_list},
+        corpus::{InMemoryCorpus, Testcase},
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{AssertionMapFeedback, AssertionMetadata, Feedback, MapFeedbackState},
+        inputs::BytesInput,
+        observers::{MapObserver, StdMapObserver},
+        state::{HasMetadata, StdState},
+    };
+
+    #[test]
+    fn test_assertion_map_feedback() {
+        let observer = StdMapObserver::new_owned("assertions", vec![0_u8; 8]);
+        let map_state = MapFeedbackState::with_observer(&observer);
+        let mut feedback = AssertionMapFeedback::new(&map_state, &observer);
+        let mut observers = tuple_list!(observer);
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            tuple_list!(map_state),
+        );
+        let mut mgr = NopEventManager {};
+        let input = BytesInput::new(vec![]);
+
+        // No assertion fired
+        assert!(!feedback
+            .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+
+        // A new assertion is an objective, even if the run did not crash
+        *observers.0.get_mut(3) = 1;
+        assert!(feedback
+            .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+        let mut testcase = Testcase::<BytesInput>::new(input.clone());
+        feedback.append_metadata(&mut state, &mut testcase).unwrap();
+        assert_eq!(
+            testcase.metadata().get::<AssertionMetadata>().unwrap().list,
+            [3]
+        );
+
+        // Firing it again is not
+        assert!(!feedback
+            .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+    }
+}
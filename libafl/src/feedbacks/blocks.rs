@@ -0,0 +1,165 @@
+//! The [`BlockCountFeedback`] reports inputs executing more basic blocks than any input before,
+//! to find compute-heavy paths and potential denial of service, even if edge coverage plateaus.
+
+use alloc::string::{String, ToString};
+use core::{fmt::Debug, marker::PhantomData};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, FeedbackState, FeedbackStatesTuple},
+    inputs::Input,
+    observers::{BlockCountObserver, ObserversTuple},
+    state::{HasClientPerfMonitor, HasFeedbackStates},
+    Error,
+};
+
+/// The state of [`BlockCountFeedback`]: the highest block count seen so far
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BlockCountFeedbackState {
+    /// The highest number of blocks executed by a single run so far
+    pub max_count: u64,
+    /// Name identifier of this instance
+    pub name: String,
+}
+
+impl FeedbackState for BlockCountFeedbackState {
+    fn reset(&mut self) -> Result<(), Error> {
+        self.max_count = 0;
+        Ok(())
+    }
+}
+
+impl Named for BlockCountFeedbackState {
+    #[inline]
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
+impl BlockCountFeedbackState {
+    /// Creates a new [`BlockCountFeedbackState`]
+    #[must_use]
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            max_count: 0,
+            name: name.to_string(),
+        }
+    }
+
+    /// Creates a new [`BlockCountFeedbackState`] named after the given observer
+    #[must_use]
+    pub fn with_observer(observer: &BlockCountObserver) -> Self {
+        Self {
+            max_count: 0,
+            name: observer.name().to_string(),
+        }
+    }
+}
+
+/// A [`BlockCountFeedback`] reports an input as interesting, if the [`BlockCountObserver`] counted
+/// more executed basic blocks than in any run before.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BlockCountFeedback<FT, S> {
+    name: String,
+    observer_name: String,
+    phantom: PhantomData<(FT, S)>,
+}
+
+impl<FT, I, S> Feedback<I, S> for BlockCountFeedback<FT, S>
+where
+    I: Input,
+    S: HasFeedbackStates<FT> + HasClientPerfMonitor + Debug,
+    FT: FeedbackStatesTuple,
+{
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        _manager: &mut EM,
+        _input: &I,
+        observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<I>,
+        OT: ObserversTuple<I, S>,
+    {
+        let count = observers
+            .match_name::<BlockCountObserver>(&self.observer_name)
+            .unwrap()
+            .count();
+        let block_state = state
+            .feedback_states_mut()
+            .match_name_mut::<BlockCountFeedbackState>(&self.name)
+            .unwrap();
+
+        if count > block_state.max_count {
+            block_state.max_count = count;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+}
+
+impl<FT, S> Named for BlockCountFeedback<FT, S> {
+    #[inline]
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
+impl<FT, S> BlockCountFeedback<FT, S> {
+    /// Creates a new [`BlockCountFeedback`], for the given state and observer
+    #[must_use]
+    pub fn new(feedback_state: &BlockCountFeedbackState, observer: &BlockCountObserver) -> Self {
+        Self {
+            name: feedback_state.name().to_string(),
+            observer_name: observer.name().to_string(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::{rands::StdRand, tuples::tuple_list},
+        corpus::InMemoryCorpus,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{BlockCountFeedback, BlockCountFeedbackState, Feedback},
+        inputs::BytesInput,
+        observers::BlockCountObserver,
+        state::StdState,
+    };
+
+    #[test]
+    fn test_block_count_feedback() {
+        let observer = BlockCountObserver::new_owned("blocks");
+        let block_state = BlockCountFeedbackState::with_observer(&observer);
+        let mut feedback = BlockCountFeedback::new(&block_state, &observer);
+        let mut observers = tuple_list!(observer);
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            tuple_list!(block_state),
+        );
+        let mut mgr = NopEventManager {};
+        let input = BytesInput::new(vec![]);
+
+        // Only counts above the previous maximum are interesting
+        for (count, interesting) in [(100, true), (50, false), (100, false), (101, true)] {
+            *observers.0.count_mut() = count;
+            assert_eq!(
+                feedback
+                    .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                    .unwrap(),
+                interesting
+            );
+        }
+    }
+}
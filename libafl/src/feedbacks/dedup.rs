@@ -0,0 +1,521 @@
+//! The [`DedupFeedback`] skips inputs whose content has already been added to the corpus.
+//! Seen inputs are tracked by content hash in a [`DedupFeedbackState`], either exactly in a
+//! [`HashSeenSet`], or in the memory-bounded, probabilistic [`BloomSeenSet`].
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt::Debug, marker::PhantomData};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::{
+    bolts::{hash::HashKind, tuples::Named},
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, FeedbackState, FeedbackStatesTuple},
+    inputs::{HasTargetBytes, Input},
+    observers::ObserversTuple,
+    state::{HasClientPerfMonitor, HasFeedbackStates},
+    Error,
+};
+
+/// The default capacity of a [`BloomSeenSet`]
+pub const DEFAULT_BLOOM_CAPACITY: usize = 1 << 20;
+/// The default false positive rate of a [`BloomSeenSet`]
+pub const DEFAULT_BLOOM_FP_RATE: f64 = 0.001;
+
+/// A set of content hashes of inputs seen so far
+pub trait SeenSet: Debug {
+    /// Returns true if the given hash has (probably) been inserted before
+    fn contains(&self, hash: u64) -> bool;
+
+    /// Inserts the given hash, returns true if it was not contained before
+    fn insert(&mut self, hash: u64) -> bool;
+
+    /// Forgets all inserted hashes
+    fn clear(&mut self);
+}
+
+/// An exact [`SeenSet`], growing with each new input
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct HashSeenSet {
+    hashes: HashSet<u64>,
+}
+
+impl SeenSet for HashSeenSet {
+    #[inline]
+    fn contains(&self, hash: u64) -> bool {
+        self.hashes.contains(&hash)
+    }
+
+    #[inline]
+    fn insert(&mut self, hash: u64) -> bool {
+        self.hashes.insert(hash)
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        self.hashes.clear();
+    }
+}
+
+impl HashSeenSet {
+    /// Creates a new, empty [`HashSeenSet`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The amount of hashes in this set
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    /// Returns true if no hash has been inserted yet
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+}
+
+/// A bloom filter based [`SeenSet`], using a fixed amount of memory.
+///
+/// A bloom filter never forgets an inserted hash, so there are no false negatives.
+/// It may however claim to contain a hash it has never seen, with a probability of roughly
+/// the configured false positive rate, as long as no more than `capacity` hashes got inserted.
+/// Used for dedup, such a false positive means a novel input is wrongly skipped.
+/// Beyond `capacity`, the false positive rate keeps growing, so size the filter for the campaign.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BloomSeenSet {
+    bits: Vec<u64>,
+    num_bits: u64,
+    num_hashes: u64,
+    capacity: usize,
+    fp_rate: f64,
+}
+
+impl SeenSet for BloomSeenSet {
+    fn contains(&self, hash: u64) -> bool {
+        self.bit_indexes(hash)
+            .all(|idx| self.bits[(idx / 64) as usize] & (1 << (idx % 64)) != 0)
+    }
+
+    fn insert(&mut self, hash: u64) -> bool {
+        let mut new = false;
+        for idx in self.bit_indexes(hash) {
+            let word = &mut self.bits[(idx / 64) as usize];
+            let bit = 1 << (idx % 64);
+            new |= *word & bit == 0;
+            *word |= bit;
+        }
+        new
+    }
+
+    fn clear(&mut self) {
+        self.bits.iter_mut().for_each(|word| *word = 0);
+    }
+}
+
+impl BloomSeenSet {
+    /// Creates a new [`BloomSeenSet`] for up to `capacity` hashes,
+    /// sized to keep the false positive rate below `fp_rate` until then.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss, clippy::cast_sign_loss)]
+    pub fn new(capacity: usize, fp_rate: f64) -> Self {
+        assert!(
+            fp_rate > 0.0 && fp_rate < 1.0,
+            "The false positive rate needs to be in (0, 1)"
+        );
+        let capacity = capacity.max(1);
+        let ln2 = core::f64::consts::LN_2;
+        // The optimal filter size and hash count for the requested false positive rate
+        let num_bits = ((-(capacity as f64) * fp_rate.ln() / (ln2 * ln2)).ceil() as u64).max(64);
+        let num_hashes = ((num_bits as f64 / capacity as f64 * ln2).round() as u64).max(1);
+        Self {
+            bits: vec![0; ((num_bits + 63) / 64) as usize],
+            num_bits,
+            num_hashes,
+            capacity,
+            fp_rate,
+        }
+    }
+
+    /// The amount of hashes this filter is sized for
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The false positive rate this filter guarantees up to its capacity
+    #[must_use]
+    pub fn fp_rate(&self) -> f64 {
+        self.fp_rate
+    }
+
+    /// The memory used by the filter, in bytes
+    #[must_use]
+    pub fn size_in_bytes(&self) -> usize {
+        self.bits.len() * 8
+    }
+
+    /// The bits to set for a hash, using double hashing on the two halves of the hash
+    fn bit_indexes(&self, hash: u64) -> impl Iterator<Item = u64> {
+        let num_bits = self.num_bits;
+        let h1 = hash & 0xffff_ffff;
+        let h2 = (hash >> 32) | 1;
+        (0..self.num_hashes).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % num_bits)
+    }
+}
+
+impl Default for BloomSeenSet {
+    fn default() -> Self {
+        Self::new(DEFAULT_BLOOM_CAPACITY, DEFAULT_BLOOM_FP_RATE)
+    }
+}
+
+/// Maps the target bytes of an input to their canonical form, before [`DedupFeedback`] hashes them
+pub type CanonicalizeFn = fn(&[u8]) -> Vec<u8>;
+
+/// A canonicalization for [`DedupFeedback::with_canonicalization`], ignoring trailing zero bytes
+#[must_use]
+pub fn strip_trailing_zeros(bytes: &[u8]) -> Vec<u8> {
+    let len = bytes
+        .iter()
+        .rposition(|byte| *byte != 0)
+        .map_or(0, |idx| idx + 1);
+    bytes[..len].to_vec()
+}
+
+/// The state of a [`DedupFeedback`]: the [`SeenSet`] of the inputs added to the corpus so far.
+/// Kept in the fuzzer state, so it survives restarts.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(bound = "SS: Serialize + DeserializeOwned")]
+pub struct DedupFeedbackState<SS>
+where
+    SS: SeenSet,
+{
+    /// The content hashes of the inputs added to the corpus
+    pub seen: SS,
+    /// Name identifier of this instance
+    pub name: String,
+}
+
+impl<SS> FeedbackState for DedupFeedbackState<SS>
+where
+    SS: SeenSet + Serialize + DeserializeOwned,
+{
+    /// Forgets all seen inputs
+    fn reset(&mut self) -> Result<(), Error> {
+        self.seen.clear();
+        Ok(())
+    }
+}
+
+impl<SS> Named for DedupFeedbackState<SS>
+where
+    SS: SeenSet,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
+impl DedupFeedbackState<HashSeenSet> {
+    /// Creates a new [`DedupFeedbackState`], tracking the exact hashes of all seen inputs
+    #[must_use]
+    pub fn new(name: &'static str) -> Self {
+        Self::with_seen_set(name, HashSeenSet::new())
+    }
+}
+
+impl DedupFeedbackState<BloomSeenSet> {
+    /// Creates a new [`DedupFeedbackState`], tracking seen inputs in a [`BloomSeenSet`].
+    /// See [`BloomSeenSet`] for the tradeoffs of `capacity` and `fp_rate`.
+    #[must_use]
+    pub fn with_bloom_filter(name: &'static str, capacity: usize, fp_rate: f64) -> Self {
+        Self::with_seen_set(name, BloomSeenSet::new(capacity, fp_rate))
+    }
+}
+
+impl<SS> DedupFeedbackState<SS>
+where
+    SS: SeenSet,
+{
+    /// Creates a new [`DedupFeedbackState`] with the given [`SeenSet`] backend
+    #[must_use]
+    pub fn with_seen_set(name: &'static str, seen: SS) -> Self {
+        Self {
+            seen,
+            name: name.to_string(),
+        }
+    }
+
+    /// The [`SeenSet`] backend
+    #[must_use]
+    pub fn seen_set(&self) -> &SS {
+        &self.seen
+    }
+}
+
+/// A [`DedupFeedback`] reports an input as interesting, if its content has not been added to the
+/// corpus before. Combine it with other feedbacks using AND, to filter duplicate inputs.
+/// The seen inputs are kept in its [`DedupFeedbackState`].
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DedupFeedback<FT, S, SS>
+where
+    S: HasFeedbackStates<FT>,
+    FT: FeedbackStatesTuple,
+    SS: SeenSet,
+{
+    /// Name identifier of this instance, and of its [`DedupFeedbackState`]
+    name: String,
+    last_hash: Option<u64>,
+    /// A function pointer can't be serialized, it has to be set again after deserialization
+    #[serde(skip)]
+    canonicalize: Option<CanonicalizeFn>,
+    /// If inputs get canonicalized, a missing `canonicalize` is an error then
+    canonicalized: bool,
+    hash_kind: HashKind,
+    phantom: PhantomData<(FT, S, SS)>,
+}
+
+impl<FT, I, S, SS> Feedback<I, S> for DedupFeedback<FT, S, SS>
+where
+    I: Input + HasTargetBytes,
+    S: HasFeedbackStates<FT> + HasClientPerfMonitor + Debug,
+    FT: FeedbackStatesTuple,
+    SS: SeenSet + Serialize + DeserializeOwned,
+{
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        _manager: &mut EM,
+        input: &I,
+        _observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<I>,
+        OT: ObserversTuple<I, S>,
+    {
+        let hash = self.fingerprint(input.target_bytes().as_slice())?;
+        self.last_hash = Some(hash);
+        let dedup_state = state
+            .feedback_states()
+            .match_name::<DedupFeedbackState<SS>>(&self.name)
+            .ok_or_else(|| Error::KeyNotFound(format!("{} not found", self.name)))?;
+        Ok(!dedup_state.seen.contains(hash))
+    }
+
+    /// Remember the content of the new corpus item
+    #[inline]
+    fn append_metadata(&mut self, state: &mut S, _testcase: &mut Testcase<I>) -> Result<(), Error> {
+        if let Some(hash) = self.last_hash.take() {
+            let dedup_state = state
+                .feedback_states_mut()
+                .match_name_mut::<DedupFeedbackState<SS>>(&self.name)
+                .ok_or_else(|| Error::KeyNotFound(format!("{} not found", self.name)))?;
+            dedup_state.seen.insert(hash);
+        }
+        Ok(())
+    }
+
+    /// Discard the hash in case that the testcase is not added to the corpus
+    #[inline]
+    fn discard_metadata(&mut self, _state: &mut S, _input: &I) -> Result<(), Error> {
+        self.last_hash = None;
+        Ok(())
+    }
+}
+
+impl<FT, S, SS> Named for DedupFeedback<FT, S, SS>
+where
+    S: HasFeedbackStates<FT>,
+    FT: FeedbackStatesTuple,
+    SS: SeenSet,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<FT, S, SS> DedupFeedback<FT, S, SS>
+where
+    S: HasFeedbackStates<FT>,
+    FT: FeedbackStatesTuple,
+    SS: SeenSet,
+{
+    /// Creates a new [`DedupFeedback`], tracking the seen inputs in the given `feedback_state`
+    #[must_use]
+    pub fn new(feedback_state: &DedupFeedbackState<SS>) -> Self {
+        Self {
+            name: feedback_state.name().to_string(),
+            last_hash: None,
+            canonicalize: None,
+            canonicalized: false,
+            hash_kind: HashKind::default(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Canonicalizes the target bytes of each input with `canonicalize` before hashing them,
+    /// so that inputs with the same canonical form count as duplicates,
+    /// for example [`strip_trailing_zeros`] for inputs that only differ in their padding.
+    #[must_use]
+    pub fn with_canonicalization(mut self, canonicalize: CanonicalizeFn) -> Self {
+        self.canonicalize = Some(canonicalize);
+        self.canonicalized = true;
+        self
+    }
+
+    /// Fingerprints the inputs with the given [`HashKind`], XXH3 by default
+    #[must_use]
+    pub fn with_hash_kind(mut self, hash_kind: HashKind) -> Self {
+        self.hash_kind = hash_kind;
+        self
+    }
+
+    /// The dedup fingerprint of the given target bytes, after canonicalization.
+    /// Errors, if this feedback got deserialized without setting its canonicalization again,
+    /// as the fingerprints would no longer match the seen ones.
+    pub fn fingerprint(&self, bytes: &[u8]) -> Result<u64, Error> {
+        match self.canonicalize {
+            Some(canonicalize) => Ok(self.hash_kind.hash_bytes(&canonicalize(bytes))),
+            None if self.canonicalized => Err(Error::IllegalState(format!(
+                "{} lost its canonicalization on deserialization, set it again with with_canonicalization",
+                self.name
+            ))),
+            None => Ok(self.hash_kind.hash_bytes(bytes)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use xxhash_rust::xxh3::xxh3_64;
+
+    use crate::{
+        bolts::{
+            rands::StdRand,
+            tuples::{tuple_list, tuple_list_type, MatchName},
+        },
+        corpus::{InMemoryCorpus, Testcase},
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{
+            strip_trailing_zeros, BloomSeenSet, DedupFeedback, DedupFeedbackState, Feedback,
+            HashSeenSet, SeenSet,
+        },
+        inputs::BytesInput,
+        state::{HasFeedbackStates, StdState},
+    };
+
+    #[test]
+    #[allow(clippy::cast_precision_loss)]
+    fn test_bloom_seen_set() {
+        const CAPACITY: u64 = 10_000;
+        const FP_RATE: f64 = 0.01;
+
+        let mut bloom = BloomSeenSet::new(CAPACITY as usize, FP_RATE);
+        for i in 0..CAPACITY {
+            bloom.insert(xxh3_64(&i.to_le_bytes()));
+        }
+
+        // No false negatives
+        assert!((0..CAPACITY).all(|i| bloom.contains(xxh3_64(&i.to_le_bytes()))));
+
+        // Leave some slack for the random variation of the measured rate
+        let false_positives = (CAPACITY..CAPACITY * 11)
+            .filter(|i| bloom.contains(xxh3_64(&i.to_le_bytes())))
+            .count();
+        let measured_rate = false_positives as f64 / (CAPACITY * 10) as f64;
+        assert!(measured_rate < FP_RATE * 1.2, "fp rate {}", measured_rate);
+    }
+
+    #[test]
+    fn test_dedup_canonicalization() {
+        type DedupState = StdState<
+            InMemoryCorpus<BytesInput>,
+            tuple_list_type!(DedupFeedbackState<HashSeenSet>),
+            BytesInput,
+            StdRand,
+            InMemoryCorpus<BytesInput>,
+        >;
+
+        let feedback_state = DedupFeedbackState::new("dedup");
+        let mut feedback =
+            DedupFeedback::new(&feedback_state).with_canonicalization(strip_trailing_zeros);
+        let mut state: DedupState = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            tuple_list!(feedback_state),
+        );
+        let mut mgr = NopEventManager {};
+
+        let padded = BytesInput::new(b"data\0\0\0".to_vec());
+        let unpadded = BytesInput::new(b"data".to_vec());
+        assert_eq!(
+            feedback.fingerprint(b"data\0\0\0").unwrap(),
+            feedback.fingerprint(b"data").unwrap()
+        );
+
+        let mut kept = 0;
+        for input in [padded, unpadded.clone()] {
+            if feedback
+                .is_interesting(&mut state, &mut mgr, &input, &(), &ExitKind::Ok)
+                .unwrap()
+            {
+                feedback
+                    .append_metadata(&mut state, &mut Testcase::<BytesInput>::new(input))
+                    .unwrap();
+                kept += 1;
+            } else {
+                feedback.discard_metadata(&mut state, &input).unwrap();
+            }
+        }
+        assert_eq!(kept, 1);
+        let seen_len = |state: &DedupState| {
+            state
+                .feedback_states()
+                .match_name::<DedupFeedbackState<HashSeenSet>>("dedup")
+                .unwrap()
+                .seen_set()
+                .len()
+        };
+        assert_eq!(seen_len(&state), 1);
+
+        // The seen inputs are part of the state, so they survive a restart
+        let state_serialized = postcard::to_allocvec(&state).unwrap();
+        let mut state: DedupState = postcard::from_bytes(&state_serialized).unwrap();
+        assert_eq!(seen_len(&state), 1);
+        let mut feedback = DedupFeedback::new(
+            state
+                .feedback_states()
+                .match_name::<DedupFeedbackState<HashSeenSet>>("dedup")
+                .unwrap(),
+        )
+        .with_canonicalization(strip_trailing_zeros);
+        assert!(!feedback
+            .is_interesting(&mut state, &mut mgr, &unpadded, &(), &ExitKind::Ok)
+            .unwrap());
+
+        // A deserialized feedback does not silently fingerprint without its canonicalization
+        let feedback_serialized = postcard::to_allocvec(&feedback).unwrap();
+        let mut feedback: DedupFeedback<_, DedupState, HashSeenSet> =
+            postcard::from_bytes(&feedback_serialized).unwrap();
+        assert!(feedback
+            .is_interesting(&mut state, &mut mgr, &unpadded, &(), &ExitKind::Ok)
+            .is_err());
+        let mut feedback = feedback.with_canonicalization(strip_trailing_zeros);
+        assert!(!feedback
+            .is_interesting(&mut state, &mut mgr, &unpadded, &(), &ExitKind::Ok)
+            .unwrap());
+    }
+}
@@ -0,0 +1,302 @@
+//! AFLGo-style directed fuzzing: the [`DistanceFeedback`] prefers inputs that get closer to a target,
+//! according to a precomputed distance-to-target for each edge of the map.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt::Debug, marker::PhantomData};
+use num_traits::PrimInt;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "std")]
+use std::{fs, path::Path};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, FeedbackState, FeedbackStatesTuple},
+    inputs::Input,
+    observers::{MapObserver, ObserversTuple},
+    state::{HasClientPerfMonitor, HasFeedbackStates, HasMetadata},
+    Error,
+};
+
+/// The aggregate distance to the target of an input, added to the testcase by the [`DistanceFeedback`]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DistanceMetadata {
+    /// The mean distance to the target of the edges this input covered
+    pub distance: f64,
+}
+
+crate::impl_serdeany!(DistanceMetadata);
+
+/// The state of [`DistanceFeedback`]: the distance map, and the minimum distance reached so far
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DistanceFeedbackState {
+    /// The distance to the target of each edge, `None` for edges that can't reach the target
+    pub distances: Vec<Option<f64>>,
+    /// The minimum aggregate distance of all inputs so far
+    pub min_distance: Option<f64>,
+    /// Name identifier of this instance
+    pub name: String,
+}
+
+impl FeedbackState for DistanceFeedbackState {
+    fn reset(&mut self) -> Result<(), Error> {
+        self.min_distance = None;
+        Ok(())
+    }
+}
+
+impl Named for DistanceFeedbackState {
+    #[inline]
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
+impl DistanceFeedbackState {
+    /// Creates a new [`DistanceFeedbackState`] for the given per-edge distances to the target
+    #[must_use]
+    pub fn new(name: &'static str, distances: Vec<Option<f64>>) -> Self {
+        Self {
+            distances,
+            min_distance: None,
+            name: name.to_string(),
+        }
+    }
+
+    /// Loads the distance map for a map of `map_size` edges from a file.
+    /// Each line holds an edge index and its distance to the target, separated by whitespace.
+    /// Edges without a line can't reach the target. Empty lines and lines starting with `#` are skipped.
+    #[cfg(feature = "std")]
+    pub fn from_file<P>(name: &'static str, path: P, map_size: usize) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let mut distances = vec![None; map_size];
+        for line in fs::read_to_string(path)?.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split_whitespace();
+            let (idx, distance) = match (fields.next(), fields.next(), fields.next()) {
+                (Some(idx), Some(distance), None) => {
+                    (idx.parse::<usize>(), distance.parse::<f64>())
+                }
+                _ => {
+                    return Err(Error::IllegalArgument(format!(
+                        "Invalid distance map line: {}",
+                        line
+                    )))
+                }
+            };
+            match (idx, distance) {
+                (Ok(idx), Ok(distance)) if idx < map_size && distance >= 0.0 => {
+                    distances[idx] = Some(distance);
+                }
+                _ => {
+                    return Err(Error::IllegalArgument(format!(
+                        "Invalid distance map line: {}",
+                        line
+                    )))
+                }
+            }
+        }
+        Ok(Self::new(name, distances))
+    }
+}
+
+/// A [`DistanceFeedback`] reports an input as interesting, if its aggregate distance to the target,
+/// the mean distance of all covered edges that can reach the target, is below the minimum so far.
+/// This is the core of directed greybox fuzzing, as done by `AFLGo`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DistanceFeedback<FT, O, S, T> {
+    name: String,
+    observer_name: String,
+    last_distance: Option<f64>,
+    phantom: PhantomData<(FT, O, S, T)>,
+}
+
+impl<FT, I, O, S, T> Feedback<I, S> for DistanceFeedback<FT, O, S, T>
+where
+    FT: FeedbackStatesTuple,
+    I: Input,
+    O: MapObserver<T>,
+    S: HasFeedbackStates<FT> + HasClientPerfMonitor + Debug,
+    T: PrimInt + Default + Copy + Debug,
+{
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        _manager: &mut EM,
+        _input: &I,
+        observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<I>,
+        OT: ObserversTuple<I, S>,
+    {
+        let observer = observers.match_name::<O>(&self.observer_name).unwrap();
+        let distance_state = state
+            .feedback_states_mut()
+            .match_name_mut::<DistanceFeedbackState>(&self.name)
+            .unwrap();
+
+        let initial = observer.initial();
+        let size = observer.usable_count().min(distance_state.distances.len());
+        let mut sum = 0.0;
+        let mut count: u32 = 0;
+        for i in 0..size {
+            if *observer.get(i) != initial {
+                if let Some(distance) = distance_state.distances[i] {
+                    sum += distance;
+                    count += 1;
+                }
+            }
+        }
+        if count == 0 {
+            self.last_distance = None;
+            return Ok(false);
+        }
+
+        let distance = sum / f64::from(count);
+        self.last_distance = Some(distance);
+        match distance_state.min_distance {
+            Some(min) if min <= distance => Ok(false),
+            _ => {
+                distance_state.min_distance = Some(distance);
+                Ok(true)
+            }
+        }
+    }
+
+    fn append_metadata(&mut self, _state: &mut S, testcase: &mut Testcase<I>) -> Result<(), Error> {
+        if let Some(distance) = self.last_distance.take() {
+            testcase.add_metadata(DistanceMetadata { distance });
+        }
+        Ok(())
+    }
+
+    fn discard_metadata(&mut self, _state: &mut S, _input: &I) -> Result<(), Error> {
+        self.last_distance = None;
+        Ok(())
+    }
+}
+
+impl<FT, O, S, T> Named for DistanceFeedback<FT, O, S, T> {
+    #[inline]
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
+impl<FT, O, S, T> DistanceFeedback<FT, O, S, T>
+where
+    O: MapObserver<T>,
+    T: PrimInt + Default + Copy + Debug,
+{
+    /// Creates a new [`DistanceFeedback`], for the given state and map observer
+    #[must_use]
+    pub fn new(feedback_state: &DistanceFeedbackState, map_observer: &O) -> Self {
+        Self {
+            name: feedback_state.name().to_string(),
+            observer_name: map_observer.name().to_string(),
+            last_distance: None,
+            phantom: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::{
+            rands::StdRand,
+            tuples::{tuple_list, tuple_list_type},
+        },
+        corpus::{InMemoryCorpus, Testcase},
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{DistanceFeedback, DistanceFeedbackState, DistanceMetadata, Feedback},
+        inputs::BytesInput,
+        observers::{MapObserver, StdMapObserver},
+        state::{HasMetadata, StdState},
+    };
+
+    #[test]
+    fn test_distance_feedback() {
+        // Edge 3 is the target, edge 0 can't reach it
+        let distance_state =
+            DistanceFeedbackState::new("distance", vec![None, Some(3.0), Some(1.0), Some(0.0)]);
+        let observer = StdMapObserver::new_owned("edges", vec![0_u8; 4]);
+        let mut feedback = DistanceFeedback::new(&distance_state, &observer);
+        let mut observers = tuple_list!(observer);
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            tuple_list!(distance_state),
+        );
+        let mut mgr = NopEventManager {};
+        let input = BytesInput::new(vec![]);
+
+        let cover = |observers: &mut tuple_list_type!(StdMapObserver<u8>), edges: &[usize]| {
+            observers.0.reset_map().unwrap();
+            for edge in edges {
+                *observers.0.get_mut(*edge) = 1;
+            }
+        };
+
+        // Mean distance 3, the first distance is always interesting
+        cover(&mut observers, &[0, 1]);
+        assert!(feedback
+            .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+        feedback.discard_metadata(&mut state, &input).unwrap();
+
+        // Mean distance 3 again, then 2
+        assert!(!feedback
+            .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+        cover(&mut observers, &[0, 1, 2]);
+        assert!(feedback
+            .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+        let mut testcase = Testcase::<BytesInput>::new(input.clone());
+        feedback.append_metadata(&mut state, &mut testcase).unwrap();
+        let meta = testcase.metadata().get::<DistanceMetadata>().unwrap();
+        assert!((meta.distance - 2.0).abs() < f64::EPSILON);
+
+        // Farther away is not interesting, closer is
+        cover(&mut observers, &[1]);
+        assert!(!feedback
+            .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+        cover(&mut observers, &[2, 3]);
+        assert!(feedback
+            .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_distance_map_from_file() {
+        let path = std::env::temp_dir().join("libafl_test_distance_map");
+        std::fs::write(&path, "# edge distance\n1 3.0\n2 1\n\n3 0.0\n").unwrap();
+        let distance_state = DistanceFeedbackState::from_file("distance", &path, 4).unwrap();
+        assert_eq!(
+            distance_state.distances,
+            vec![None, Some(3.0), Some(1.0), Some(0.0)]
+        );
+
+        std::fs::write(&path, "4 1.0\n").unwrap();
+        assert!(DistanceFeedbackState::from_file("distance", &path, 4).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
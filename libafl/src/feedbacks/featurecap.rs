@@ -0,0 +1,253 @@
+//! The [`PerFeatureCapFeedback`] limits how many corpus entries each coverage feature may bring in,
+//! so that a single edge, hit by thousands of slightly different inputs, does not bloat the corpus.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt::Debug, marker::PhantomData};
+use num_traits::PrimInt;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, FeedbackState, FeedbackStatesTuple, MapFeedbackState},
+    inputs::Input,
+    observers::{MapObserver, ObserversTuple},
+    state::{HasClientPerfMonitor, HasFeedbackStates},
+    Error,
+};
+
+/// The state of [`PerFeatureCapFeedback`]: the amount of inputs admitted for each map entry
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PerFeatureCapFeedbackState {
+    /// The amount of admitted inputs that were new on each map entry
+    pub admitted: Vec<usize>,
+    /// Name identifier of this instance
+    pub name: String,
+}
+
+impl FeedbackState for PerFeatureCapFeedbackState {
+    fn reset(&mut self) -> Result<(), Error> {
+        self.admitted.iter_mut().for_each(|x| *x = 0);
+        Ok(())
+    }
+}
+
+impl Named for PerFeatureCapFeedbackState {
+    #[inline]
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
+impl PerFeatureCapFeedbackState {
+    /// Creates a new [`PerFeatureCapFeedbackState`] for a map of `map_size` entries
+    #[must_use]
+    pub fn new(name: &'static str, map_size: usize) -> Self {
+        Self {
+            admitted: vec![0; map_size],
+            name: name.to_string(),
+        }
+    }
+}
+
+/// Wraps a maximizing map feedback, such as [`crate::feedbacks::MaxMapFeedback`], and admits at
+/// most `cap` inputs per map entry they are new on, its "features".
+/// An input the inner feedback deems interesting is rejected, if all its features reached the cap.
+/// Inputs the inner feedback deems interesting without any new map entry are not limited.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PerFeatureCapFeedback<A, FT, O, S, T> {
+    inner: A,
+    name: String,
+    map_state_name: String,
+    observer_name: String,
+    cap: usize,
+    /// The map entries the last input was new on
+    features: Vec<usize>,
+    phantom: PhantomData<(FT, O, S, T)>,
+}
+
+impl<A, FT, I, O, S, T> Feedback<I, S> for PerFeatureCapFeedback<A, FT, O, S, T>
+where
+    A: Feedback<I, S>,
+    FT: FeedbackStatesTuple,
+    I: Input,
+    O: MapObserver<T>,
+    S: HasFeedbackStates<FT> + HasClientPerfMonitor + Debug,
+    T: PrimInt + Default + Copy + 'static + Serialize + serde::de::DeserializeOwned + Debug,
+{
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        manager: &mut EM,
+        input: &I,
+        observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<I>,
+        OT: ObserversTuple<I, S>,
+    {
+        // The features have to be taken before the inner feedback updates the history
+        let observer = observers.match_name::<O>(&self.observer_name).unwrap();
+        let map_state = state
+            .feedback_states()
+            .match_name::<MapFeedbackState<T>>(&self.map_state_name)
+            .unwrap();
+        let size = observer.usable_count().min(map_state.history_map.len());
+        self.features.clear();
+        for i in 0..size {
+            if *observer.get(i) > map_state.history_map[i] {
+                self.features.push(i);
+            }
+        }
+
+        if !self
+            .inner
+            .is_interesting(state, manager, input, observers, exit_kind)?
+        {
+            return Ok(false);
+        }
+        if self.features.is_empty() {
+            return Ok(true);
+        }
+
+        let cap_state = state
+            .feedback_states()
+            .match_name::<PerFeatureCapFeedbackState>(&self.name)
+            .unwrap();
+        Ok(self
+            .features
+            .iter()
+            .any(|i| cap_state.admitted.get(*i).map_or(true, |n| *n < self.cap)))
+    }
+
+    fn append_metadata(&mut self, state: &mut S, testcase: &mut Testcase<I>) -> Result<(), Error> {
+        let cap_state = state
+            .feedback_states_mut()
+            .match_name_mut::<PerFeatureCapFeedbackState>(&self.name)
+            .unwrap();
+        for i in self.features.drain(..) {
+            if let Some(admitted) = cap_state.admitted.get_mut(i) {
+                *admitted += 1;
+            }
+        }
+        self.inner.append_metadata(state, testcase)
+    }
+
+    fn discard_metadata(&mut self, state: &mut S, input: &I) -> Result<(), Error> {
+        self.features.clear();
+        self.inner.discard_metadata(state, input)
+    }
+}
+
+impl<A, FT, O, S, T> Named for PerFeatureCapFeedback<A, FT, O, S, T> {
+    #[inline]
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
+impl<A, FT, O, S, T> PerFeatureCapFeedback<A, FT, O, S, T>
+where
+    O: MapObserver<T>,
+    T: PrimInt + Default + Copy + 'static + Serialize + serde::de::DeserializeOwned + Debug,
+{
+    /// Creates a new [`PerFeatureCapFeedback`], admitting at most `cap` inputs per new entry of
+    /// `map_observer`. `map_state` has to be the state of the `inner` map feedback.
+    #[must_use]
+    pub fn new(
+        inner: A,
+        feedback_state: &PerFeatureCapFeedbackState,
+        map_state: &MapFeedbackState<T>,
+        map_observer: &O,
+        cap: usize,
+    ) -> Self {
+        Self {
+            inner,
+            name: feedback_state.name().to_string(),
+            map_state_name: map_state.name().to_string(),
+            observer_name: map_observer.name().to_string(),
+            cap,
+            features: vec![],
+            phantom: PhantomData,
+        }
+    }
+
+    /// The maximum amount of inputs admitted per feature
+    #[must_use]
+    pub fn cap(&self) -> usize {
+        self.cap
+    }
+
+    /// The wrapped feedback
+    #[must_use]
+    pub fn inner(&self) -> &A {
+        &self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::{rands::StdRand, tuples::tuple_list},
+        corpus::{InMemoryCorpus, Testcase},
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{
+            Feedback, MapFeedbackState, MaxMapFeedback, PerFeatureCapFeedback,
+            PerFeatureCapFeedbackState,
+        },
+        inputs::BytesInput,
+        observers::{MapObserver, StdMapObserver},
+        state::StdState,
+    };
+
+    #[test]
+    fn test_per_feature_cap_feedback() {
+        let map_state = MapFeedbackState::<u8>::new("edges", 4);
+        let cap_state = PerFeatureCapFeedbackState::new("edges_cap", 4);
+        let observer = StdMapObserver::new_owned("edges", vec![0_u8; 4]);
+        let mut feedback = PerFeatureCapFeedback::new(
+            MaxMapFeedback::new(&map_state, &observer),
+            &cap_state,
+            &map_state,
+            &observer,
+            2,
+        );
+        let mut observers = tuple_list!(observer);
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            tuple_list!(map_state, cap_state),
+        );
+        let mut mgr = NopEventManager {};
+        let input = BytesInput::new(vec![]);
+
+        // Each input is new on edge 0 only, with a higher hitcount, the third one is over the cap
+        for hits in 1..=3 {
+            *observers.0.get_mut(0) = hits;
+            let interesting = feedback
+                .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                .unwrap();
+            if interesting {
+                let mut testcase = Testcase::<BytesInput>::new(input.clone());
+                feedback.append_metadata(&mut state, &mut testcase).unwrap();
+            } else {
+                feedback.discard_metadata(&mut state, &input).unwrap();
+            }
+            assert_eq!(interesting, hits <= 2);
+        }
+
+        // A new edge is still admitted
+        *observers.0.get_mut(1) = 1;
+        assert!(feedback
+            .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+    }
+}
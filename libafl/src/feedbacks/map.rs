@@ -272,6 +272,8 @@ impl<T> FeedbackState for MapFeedbackState<T>
 where
     T: PrimInt + Default + Copy + 'static + Serialize + serde::de::DeserializeOwned + Debug,
 {
+    /// Zeroes the history map, so every entry counts as new coverage again,
+    /// and previously known inputs re-register as interesting
     fn reset(&mut self) -> Result<(), Error> {
         self.history_map
             .iter_mut()
@@ -346,6 +348,8 @@ where
     name: String,
     /// Name identifier of the observer
     observer_name: String,
+    /// Fail if the observer map is not exactly as large as the history map
+    strict_size: bool,
     /// Phantom Data of Reducer
     phantom: PhantomData<(FT, I, N, S, R, O, T)>,
 }
@@ -383,7 +387,10 @@ where
             .match_name_mut::<MapFeedbackState<T>>(&self.name)
             .unwrap();
 
-        assert!(size <= map_state.history_map.len(), "The size of the associated map observer cannot exceed the size of the history map of the feedback. If you are running multiple instances of slightly different fuzzers (e.g. one with ASan and another without) synchronized using LLMP please check the `configuration` field of the LLMP manager.");
+        let expected = map_state.history_map.len();
+        if size > expected || (self.strict_size && size != expected) {
+            return Err(Error::IllegalState(format!("The size of the map observer {} ({}) does not match the size of the history map of the feedback {} ({}). If you are running multiple instances of slightly different fuzzers (e.g. one with ASan and another without) synchronized using LLMP please check the `configuration` field of the LLMP manager.", self.observer_name, size, self.name, expected)));
+        }
 
         assert!(size <= observer.len());
 
@@ -498,6 +505,7 @@ where
             novelties: None,
             name: feedback_state.name().to_string(),
             observer_name: map_observer.name().to_string(),
+            strict_size: false,
             phantom: PhantomData,
         }
     }
@@ -515,6 +523,7 @@ where
             novelties: if track_novelties { Some(vec![]) } else { None },
             name: feedback_state.name().to_string(),
             observer_name: map_observer.name().to_string(),
+            strict_size: false,
             phantom: PhantomData,
         }
     }
@@ -527,6 +536,7 @@ where
             novelties: None,
             name: name.to_string(),
             observer_name: observer_name.to_string(),
+            strict_size: false,
             phantom: PhantomData,
         }
     }
@@ -544,6 +554,173 @@ where
             novelties: if track_novelties { Some(vec![]) } else { None },
             observer_name: observer_name.to_string(),
             name: name.to_string(),
+            strict_size: false,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Requires the map observer to be exactly as large as the history map, to catch maps that
+    /// were resized since the state was created, e.g. by running a different binary.
+    /// A mismatch is reported as [`Error::IllegalState`]. By default, only larger maps are an error.
+    #[must_use]
+    pub fn with_strict_size_check(mut self, strict_size: bool) -> Self {
+        self.strict_size = strict_size;
+        self
+    }
+}
+
+/// A [`CombinedMapFeedback`] that strives to maximize the contents of all its maps.
+pub type CombinedMaxMapFeedback<FT, I, O, S, T> =
+    CombinedMapFeedback<FT, I, DifferentIsNovel, O, MaxReducer, S, T>;
+
+/// A feedback over multiple independent maps of the same kind, e.g. edge coverage and a
+/// function-level map, each with its own [`MapFeedbackState`].
+/// An input is interesting if any of the maps gained coverage, and the history of every map
+/// is updated on each run, unlike chaining [`MapFeedback`]s with a short-circuiting OR.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CombinedMapFeedback<FT, I, N, O, R, S, T>
+where
+    T: PrimInt + Default + Copy + 'static + Serialize + serde::de::DeserializeOwned + Debug,
+    R: Reducer<T>,
+    O: MapObserver<T>,
+    N: IsNovel<T>,
+    S: HasFeedbackStates<FT>,
+    FT: FeedbackStatesTuple,
+{
+    /// Name identifier of this instance
+    name: String,
+    /// Pairs of observer name and feedback state name
+    maps: Vec<(String, String)>,
+    /// Phantom Data of Reducer
+    phantom: PhantomData<(FT, I, N, S, R, O, T)>,
+}
+
+impl<FT, I, N, O, R, S, T> Feedback<I, S> for CombinedMapFeedback<FT, I, N, O, R, S, T>
+where
+    T: PrimInt + Default + Copy + 'static + Serialize + serde::de::DeserializeOwned + Debug,
+    R: Reducer<T>,
+    O: MapObserver<T>,
+    N: IsNovel<T>,
+    I: Input,
+    S: HasFeedbackStates<FT> + HasClientPerfMonitor + Debug,
+    FT: FeedbackStatesTuple,
+{
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        manager: &mut EM,
+        _input: &I,
+        observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<I>,
+        OT: ObserversTuple<I, S>,
+    {
+        let mut interesting = false;
+        for (observer_name, state_name) in &self.maps {
+            let observer = observers
+                .match_name::<O>(observer_name)
+                .ok_or_else(|| Error::KeyNotFound(format!("{} not found", observer_name)))?;
+            let size = observer.usable_count();
+            let initial = observer.initial();
+
+            let map_state = state
+                .feedback_states_mut()
+                .match_name_mut::<MapFeedbackState<T>>(state_name)
+                .ok_or_else(|| Error::KeyNotFound(format!("{} not found", state_name)))?;
+
+            let expected = map_state.history_map.len();
+            if size > expected {
+                return Err(Error::IllegalState(format!("The size of the map observer {} ({}) exceeds the size of the history map of the feedback state {} ({}).", observer_name, size, state_name, expected)));
+            }
+
+            let mut map_interesting = false;
+            for i in 0..size {
+                let history = map_state.history_map[i];
+                let item = *observer.get(i);
+
+                let reduced = R::reduce(history, item);
+                if N::is_novel(history, reduced) {
+                    map_state.history_map[i] = reduced;
+                    map_interesting = true;
+                }
+            }
+
+            if map_interesting {
+                let filled = map_state.history_map[..size]
+                    .iter()
+                    .filter(|&&x| x != initial)
+                    .count();
+                manager.fire(
+                    state,
+                    Event::UpdateUserStats {
+                        name: state_name.clone(),
+                        value: UserStats::Ratio(filled as u64, size as u64),
+                        phantom: PhantomData,
+                    },
+                )?;
+                interesting = true;
+            }
+        }
+
+        Ok(interesting)
+    }
+}
+
+impl<FT, I, N, O, R, S, T> Named for CombinedMapFeedback<FT, I, N, O, R, S, T>
+where
+    T: PrimInt + Default + Copy + 'static + Serialize + serde::de::DeserializeOwned + Debug,
+    R: Reducer<T>,
+    N: IsNovel<T>,
+    O: MapObserver<T>,
+    S: HasFeedbackStates<FT>,
+    FT: FeedbackStatesTuple,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
+impl<FT, I, N, O, R, S, T> CombinedMapFeedback<FT, I, N, O, R, S, T>
+where
+    T: PrimInt + Default + Copy + 'static + Serialize + serde::de::DeserializeOwned + Debug,
+    R: Reducer<T>,
+    N: IsNovel<T>,
+    O: MapObserver<T>,
+    S: HasFeedbackStates<FT>,
+    FT: FeedbackStatesTuple,
+{
+    /// Create new `CombinedMapFeedback` named `name`, for the given pairs of map observer and feedback state
+    #[must_use]
+    pub fn new(name: &'static str, maps: &[(&O, &MapFeedbackState<T>)]) -> Self {
+        Self {
+            name: name.to_string(),
+            maps: maps
+                .iter()
+                .map(|(observer, feedback_state)| {
+                    (
+                        observer.name().to_string(),
+                        feedback_state.name().to_string(),
+                    )
+                })
+                .collect(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Create new `CombinedMapFeedback` named `name`, for the given pairs of observer name and feedback state name
+    #[must_use]
+    pub fn with_names(name: &'static str, maps: &[(&'static str, &'static str)]) -> Self {
+        Self {
+            name: name.to_string(),
+            maps: maps
+                .iter()
+                .map(|(observer_name, state_name)| {
+                    (observer_name.to_string(), state_name.to_string())
+                })
+                .collect(),
             phantom: PhantomData,
         }
     }
@@ -642,9 +819,277 @@ where
     }
 }
 
+/// The state of a [`TargetEdgesFeedback`]: the target edges, and which of them an input of the
+/// corpus hit so far. Kept in the fuzzer state, so it survives restarts.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TargetEdgesFeedbackState {
+    /// The target edge indexes
+    pub targets: Vec<usize>,
+    /// For each target, if an input of the corpus hit it
+    pub reached: Vec<bool>,
+    /// Name identifier of this instance
+    pub name: String,
+}
+
+impl FeedbackState for TargetEdgesFeedbackState {
+    /// Forgets which targets were reached
+    fn reset(&mut self) -> Result<(), Error> {
+        self.reached.iter_mut().for_each(|reached| *reached = false);
+        Ok(())
+    }
+}
+
+impl Named for TargetEdgesFeedbackState {
+    #[inline]
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
+impl TargetEdgesFeedbackState {
+    /// Creates a new [`TargetEdgesFeedbackState`] for the given target edge indexes
+    #[must_use]
+    pub fn new(name: &'static str, targets: Vec<usize>) -> Self {
+        Self {
+            reached: vec![false; targets.len()],
+            targets,
+            name: name.to_string(),
+        }
+    }
+
+    /// The target edge indexes no input of the corpus hit so far
+    #[must_use]
+    pub fn unreached(&self) -> Vec<usize> {
+        self.targets
+            .iter()
+            .zip(&self.reached)
+            .filter(|(_, reached)| !**reached)
+            .map(|(target, _)| *target)
+            .collect()
+    }
+}
+
+/// A [`TargetEdgesFeedback`] reports an input as interesting, if it newly hits any of a set of
+/// target edges, whether or not it increases the overall coverage. A basic form of directed fuzzing,
+/// towards the edges of a known-suspicious function. A target counts as reached once an input
+/// hitting it is added to the corpus, see [`TargetEdgesFeedbackState`].
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TargetEdgesFeedback<FT, O, S, T>
+where
+    S: HasFeedbackStates<FT>,
+    FT: FeedbackStatesTuple,
+{
+    /// Name identifier of this instance, and of its [`TargetEdgesFeedbackState`]
+    name: String,
+    observer_name: String,
+    /// The indexes into the targets hit by the last input, for the first time
+    newly_hit: Vec<usize>,
+    phantom: PhantomData<(FT, O, S, T)>,
+}
+
+impl<FT, O, S, T> TargetEdgesFeedback<FT, O, S, T>
+where
+    O: MapObserver<T>,
+    T: PrimInt + Default + Copy + Debug,
+    S: HasFeedbackStates<FT>,
+    FT: FeedbackStatesTuple,
+{
+    /// Creates a new [`TargetEdgesFeedback`], rewarding inputs that hit any of the target edges
+    /// of the `feedback_state` in the `map_observer` for the first time.
+    #[must_use]
+    pub fn new(feedback_state: &TargetEdgesFeedbackState, map_observer: &O) -> Self {
+        Self {
+            name: feedback_state.name().to_string(),
+            observer_name: map_observer.name().to_string(),
+            newly_hit: vec![],
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<FT, I, O, S, T> Feedback<I, S> for TargetEdgesFeedback<FT, O, S, T>
+where
+    I: Input,
+    O: MapObserver<T>,
+    S: HasFeedbackStates<FT> + HasClientPerfMonitor + Debug,
+    T: PrimInt + Default + Copy + Debug,
+    FT: FeedbackStatesTuple,
+{
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        _manager: &mut EM,
+        _input: &I,
+        observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<I>,
+        OT: ObserversTuple<I, S>,
+    {
+        let observer = observers
+            .match_name::<O>(&self.observer_name)
+            .ok_or_else(|| Error::KeyNotFound(format!("{} not found", self.observer_name)))?;
+        let size = observer.usable_count();
+        let initial = observer.initial();
+        let target_state = state
+            .feedback_states()
+            .match_name::<TargetEdgesFeedbackState>(&self.name)
+            .ok_or_else(|| Error::KeyNotFound(format!("{} not found", self.name)))?;
+        self.newly_hit.clear();
+        for (i, target) in target_state.targets.iter().enumerate() {
+            if !target_state.reached[i] && *target < size && *observer.get(*target) != initial {
+                self.newly_hit.push(i);
+            }
+        }
+        Ok(!self.newly_hit.is_empty())
+    }
+
+    fn append_metadata(&mut self, state: &mut S, _testcase: &mut Testcase<I>) -> Result<(), Error> {
+        let target_state = state
+            .feedback_states_mut()
+            .match_name_mut::<TargetEdgesFeedbackState>(&self.name)
+            .ok_or_else(|| Error::KeyNotFound(format!("{} not found", self.name)))?;
+        for i in self.newly_hit.drain(..) {
+            target_state.reached[i] = true;
+        }
+        Ok(())
+    }
+
+    fn discard_metadata(&mut self, _state: &mut S, _input: &I) -> Result<(), Error> {
+        self.newly_hit.clear();
+        Ok(())
+    }
+}
+
+impl<FT, O, S, T> Named for TargetEdgesFeedback<FT, O, S, T>
+where
+    S: HasFeedbackStates<FT>,
+    FT: FeedbackStatesTuple,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::feedbacks::{AllIsNovel, IsNovel, NextPow2IsNovel};
+    use crate::{
+        bolts::{
+            rands::StdRand,
+            tuples::{tuple_list, tuple_list_type, MatchName, Named},
+        },
+        corpus::{InMemoryCorpus, Testcase},
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{
+            AllIsNovel, CombinedMaxMapFeedback, Feedback, IsNovel, MapFeedbackState,
+            MaxMapFeedback, NextPow2IsNovel, TargetEdgesFeedback, TargetEdgesFeedbackState,
+        },
+        inputs::BytesInput,
+        observers::{MapObserver, StdMapObserver},
+        state::{HasFeedbackStates, StdState},
+        Error,
+    };
+
+    #[test]
+    fn test_map_feedback_state_reset() {
+        let edges_state = MapFeedbackState::<u8>::new("edges", 4);
+        let mut observer = StdMapObserver::new_owned("edges", vec![0_u8; 4]);
+        *observer.get_mut(1) = 1;
+        let mut feedback = MaxMapFeedback::new(&edges_state, &observer);
+        let observers = tuple_list!(observer);
+
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            tuple_list!(edges_state),
+        );
+        let mut mgr = NopEventManager {};
+        let input = BytesInput::new(vec![]);
+
+        assert!(feedback
+            .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+        assert!(!feedback
+            .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+
+        // After a reset, the same edge is new coverage again
+        state.reset_feedback_states().unwrap();
+        assert!(feedback
+            .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+        state
+            .reset_feedback_state::<MapFeedbackState<u8>>("edges")
+            .unwrap();
+        assert!(feedback
+            .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+        assert!(state
+            .reset_feedback_state::<MapFeedbackState<u8>>("funcs")
+            .is_err());
+    }
+
+    #[test]
+    fn test_combined_map_feedback() {
+        let edges_state = MapFeedbackState::<u8>::new("edges", 4);
+        let funcs_state = MapFeedbackState::<u8>::new("funcs", 4);
+        let edges_observer = StdMapObserver::new_owned("edges", vec![0_u8; 4]);
+        let funcs_observer = StdMapObserver::new_owned("funcs", vec![0_u8; 4]);
+        let mut feedback = CombinedMaxMapFeedback::new(
+            "combined",
+            &[
+                (&edges_observer, &edges_state),
+                (&funcs_observer, &funcs_state),
+            ],
+        );
+        let mut observers = tuple_list!(edges_observer, funcs_observer);
+
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            tuple_list!(edges_state, funcs_state),
+        );
+        let mut mgr = NopEventManager {};
+        let input = BytesInput::new(vec![]);
+
+        // Nothing covered yet
+        assert!(!feedback
+            .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+
+        // New coverage in only the second map
+        *observers
+            .match_name_mut::<StdMapObserver<u8>>("funcs")
+            .unwrap()
+            .get_mut(2) = 1;
+        assert!(feedback
+            .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+        assert!(!feedback
+            .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+        assert_eq!(feedback.name(), "combined");
+
+        // A map grown past its history is an error, not a panic
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            tuple_list!(
+                MapFeedbackState::<u8>::new("edges", 4),
+                MapFeedbackState::<u8>::new("funcs", 2)
+            ),
+        );
+        assert!(matches!(
+            feedback.is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok),
+            Err(Error::IllegalState(_))
+        ));
+    }
 
     #[test]
     fn test_map_is_novel() {
@@ -665,4 +1110,105 @@ mod tests {
         assert!(NextPow2IsNovel::is_novel(254_u8, 255));
         assert!(!NextPow2IsNovel::is_novel(255_u8, 255));
     }
+
+    #[test]
+    fn test_target_edges_feedback() {
+        type TargetState = StdState<
+            InMemoryCorpus<BytesInput>,
+            tuple_list_type!(TargetEdgesFeedbackState),
+            BytesInput,
+            StdRand,
+            InMemoryCorpus<BytesInput>,
+        >;
+
+        let observer = StdMapObserver::new_owned("edges", vec![0_u8; 8]);
+        let feedback_state = TargetEdgesFeedbackState::new("targets", vec![2, 5]);
+        let mut feedback = TargetEdgesFeedback::new(&feedback_state, &observer);
+        let mut observers = tuple_list!(observer);
+        let mut state: TargetState = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            tuple_list!(feedback_state),
+        );
+        let mut mgr = NopEventManager {};
+        let input = BytesInput::new(vec![]);
+
+        let hit = |observers: &mut tuple_list_type!(StdMapObserver<u8>), edge: usize| {
+            observers.0.reset_map().unwrap();
+            *observers.0.get_mut(edge) = 1;
+        };
+        let unreached = |state: &TargetState| {
+            state
+                .feedback_states()
+                .match_name::<TargetEdgesFeedbackState>("targets")
+                .unwrap()
+                .unreached()
+        };
+
+        // An untracked edge is not interesting
+        hit(&mut observers, 3);
+        assert!(!feedback
+            .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+
+        // A target edge is, until an input hitting it got added
+        hit(&mut observers, 5);
+        assert!(feedback
+            .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+        feedback
+            .append_metadata(&mut state, &mut Testcase::<BytesInput>::new(input.clone()))
+            .unwrap();
+        assert_eq!(unreached(&state), vec![2]);
+        assert!(!feedback
+            .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+        feedback.discard_metadata(&mut state, &input).unwrap();
+        assert_eq!(unreached(&state), vec![2]);
+
+        // The reached targets are part of the state, so they survive a restart
+        let state_serialized = postcard::to_allocvec(&state).unwrap();
+        let mut state: TargetState = postcard::from_bytes(&state_serialized).unwrap();
+        assert_eq!(unreached(&state), vec![2]);
+        let mut feedback = TargetEdgesFeedback::new(
+            state
+                .feedback_states()
+                .match_name::<TargetEdgesFeedbackState>("targets")
+                .unwrap(),
+            &observers.0,
+        );
+        assert!(!feedback
+            .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+    }
+
+    #[test]
+    fn test_map_size_mismatch() {
+        // The history map was sized for a larger binary
+        let map_state = MapFeedbackState::<u8>::new("edges", 8);
+        let observer = StdMapObserver::new_owned("edges", vec![1_u8; 4]);
+        let mut lenient = MaxMapFeedback::new(&map_state, &observer);
+        let mut strict = MaxMapFeedback::new(&map_state, &observer).with_strict_size_check(true);
+        let observers = tuple_list!(observer);
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            tuple_list!(map_state),
+        );
+        let mut mgr = NopEventManager {};
+        let input = BytesInput::new(vec![]);
+
+        assert!(lenient
+            .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+        match strict.is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok) {
+            Err(Error::IllegalState(msg)) => {
+                assert!(msg.contains("(4)"));
+                assert!(msg.contains("(8)"));
+            }
+            res => panic!("Expected a size mismatch error, got {:?}", res),
+        }
+    }
 }
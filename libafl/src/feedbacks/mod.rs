@@ -4,11 +4,36 @@
 pub mod map;
 pub use map::*;
 
+pub mod distance;
+pub use distance::*;
+
+pub mod featurecap;
+pub use featurecap::*;
+
+pub mod value;
+pub use value::*;
+
+pub mod blocks;
+pub use blocks::*;
+
+pub mod assertion;
+pub use assertion::*;
+
 #[cfg(feature = "std")]
 pub mod concolic;
 #[cfg(feature = "std")]
 pub use concolic::ConcolicFeedback;
 
+#[cfg(feature = "std")]
+pub mod dedup;
+#[cfg(feature = "std")]
+pub use dedup::*;
+
+#[cfg(feature = "std")]
+pub mod stdio;
+#[cfg(feature = "std")]
+pub use stdio::OutputPatternFeedback;
+
 #[cfg(feature = "nautilus")]
 pub mod nautilus;
 #[cfg(feature = "nautilus")]
@@ -23,8 +48,10 @@ use crate::{
     events::EventFirer,
     executors::ExitKind,
     inputs::Input,
-    observers::{ObserversTuple, TimeObserver},
-    state::HasClientPerfMonitor,
+    observers::{
+        ObserversTuple, RunSpeed, RunSpeedMetadata, TimeObserver, TimeoutClassifierObserver,
+    },
+    state::{HasClientPerfMonitor, HasMetadata},
     Error,
 };
 
@@ -108,7 +135,7 @@ where
 /// [`FeedbackState`] is the data associated with a [`Feedback`] that must persist as part
 /// of the fuzzer State
 pub trait FeedbackState: Named + Serialize + serde::de::DeserializeOwned + Debug {
-    /// Reset the internal state
+    /// Reset the internal state, forgetting what the feedback has seen so far
     fn reset(&mut self) -> Result<(), Error> {
         Ok(())
     }
@@ -771,6 +798,59 @@ impl Default for CrashFeedback {
     }
 }
 
+/// A [`CustomExitFeedback`] reports as interesting if the harness returned the configured
+/// [`ExitKind::Custom`] code, to route harness-defined outcomes to their own corpus or objective.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CustomExitFeedback {
+    code: u8,
+    name: String,
+}
+
+impl<I, S> Feedback<I, S> for CustomExitFeedback
+where
+    I: Input,
+    S: HasClientPerfMonitor,
+{
+    fn is_interesting<EM, OT>(
+        &mut self,
+        _state: &mut S,
+        _manager: &mut EM,
+        _input: &I,
+        _observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<I>,
+        OT: ObserversTuple<I, S>,
+    {
+        Ok(*exit_kind == ExitKind::Custom(self.code))
+    }
+}
+
+impl Named for CustomExitFeedback {
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl CustomExitFeedback {
+    /// Creates a new [`CustomExitFeedback`], matching [`ExitKind::Custom`] with the given `code`
+    #[must_use]
+    pub fn new(code: u8) -> Self {
+        Self {
+            code,
+            name: format!("CustomExitFeedback({})", code),
+        }
+    }
+
+    /// The [`ExitKind::Custom`] code this feedback matches
+    #[must_use]
+    pub fn code(&self) -> u8 {
+        self.code
+    }
+}
+
 /// A [`TimeoutFeedback`] reduces the timeout value of a run.
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct TimeoutFeedback {}
@@ -895,3 +975,85 @@ impl TimeFeedback {
         }
     }
 }
+
+/// Nop feedback that annotates the [`RunSpeed`] of the run in the new testcase, as [`RunSpeedMetadata`].
+/// The testcase is never interesting for this feedback (use with an OR).
+/// Schedulers can use the metadata to deprioritize slow inputs, without discarding them.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RunSpeedFeedback {
+    speed: Option<RunSpeed>,
+    name: String,
+}
+
+impl<I, S> Feedback<I, S> for RunSpeedFeedback
+where
+    I: Input,
+    S: HasClientPerfMonitor,
+{
+    fn is_interesting<EM, OT>(
+        &mut self,
+        _state: &mut S,
+        _manager: &mut EM,
+        _input: &I,
+        observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<I>,
+        OT: ObserversTuple<I, S>,
+    {
+        let observer = observers
+            .match_name::<TimeoutClassifierObserver>(self.name())
+            .ok_or_else(|| Error::KeyNotFound(format!("{} not found", self.name())))?;
+        // A run killed by the executor never finished, no matter what the observer measured
+        self.speed = if *exit_kind == ExitKind::Timeout {
+            Some(RunSpeed::Hang)
+        } else {
+            observer.last_speed()
+        };
+        Ok(false)
+    }
+
+    /// Append to the testcase the generated metadata in case of a new corpus item
+    #[inline]
+    fn append_metadata(&mut self, _state: &mut S, testcase: &mut Testcase<I>) -> Result<(), Error> {
+        if let Some(speed) = self.speed.take() {
+            testcase.add_metadata(RunSpeedMetadata { speed });
+        }
+        Ok(())
+    }
+
+    /// Discard the stored metadata in case that the testcase is not added to the corpus
+    #[inline]
+    fn discard_metadata(&mut self, _state: &mut S, _input: &I) -> Result<(), Error> {
+        self.speed = None;
+        Ok(())
+    }
+}
+
+impl Named for RunSpeedFeedback {
+    #[inline]
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
+impl RunSpeedFeedback {
+    /// Creates a new [`RunSpeedFeedback`], reading the [`TimeoutClassifierObserver`] with the given `name`.
+    #[must_use]
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            speed: None,
+            name: name.to_string(),
+        }
+    }
+
+    /// Creates a new [`RunSpeedFeedback`] for the given [`TimeoutClassifierObserver`].
+    #[must_use]
+    pub fn new_with_observer(observer: &TimeoutClassifierObserver) -> Self {
+        Self {
+            speed: None,
+            name: observer.name().to_string(),
+        }
+    }
+}
@@ -0,0 +1,188 @@
+//! The [`OutputPatternFeedback`] flags runs whose `stderr` output matches known bug patterns,
+//! such as sanitizer reports or panic messages of targets that exit cleanly.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+#[cfg(feature = "regex")]
+use regex::bytes::Regex;
+
+use crate::{
+    bolts::tuples::Named,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::Feedback,
+    inputs::Input,
+    observers::{ObserversTuple, StdErrObserver},
+    state::HasClientPerfMonitor,
+    Error,
+};
+
+/// A [`OutputPatternFeedback`] reports as interesting if the `stderr` output, captured by the
+/// [`StdErrObserver`] with the given name, contains one of the configured patterns.
+/// Use it as objective to catch bugs that don't crash the process.
+#[derive(Clone, Debug)]
+pub struct OutputPatternFeedback {
+    name: String,
+    observer_name: String,
+    patterns: Vec<Vec<u8>>,
+    #[cfg(feature = "regex")]
+    regexes: Vec<Regex>,
+}
+
+impl<I, S> Feedback<I, S> for OutputPatternFeedback
+where
+    I: Input,
+    S: HasClientPerfMonitor,
+{
+    fn is_interesting<EM, OT>(
+        &mut self,
+        _state: &mut S,
+        _manager: &mut EM,
+        _input: &I,
+        observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<I>,
+        OT: ObserversTuple<I, S>,
+    {
+        let observer = observers
+            .match_name::<StdErrObserver>(&self.observer_name)
+            .ok_or_else(|| Error::KeyNotFound(format!("{} not found", self.observer_name)))?;
+
+        Ok(observer
+            .stderr()
+            .map_or(false, |stderr| self.matches(stderr)))
+    }
+}
+
+impl Named for OutputPatternFeedback {
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl OutputPatternFeedback {
+    /// Creates a new [`OutputPatternFeedback`], matching the output of the given [`StdErrObserver`]
+    /// against a set of byte substrings, e.g. `AddressSanitizer` or `panicked at`.
+    /// Empty patterns are ignored.
+    #[must_use]
+    pub fn new<P>(observer: &StdErrObserver, patterns: &[P]) -> Self
+    where
+        P: AsRef<[u8]>,
+    {
+        Self {
+            name: format!("OutputPatternFeedback({})", observer.name()),
+            observer_name: observer.name().to_string(),
+            patterns: patterns
+                .iter()
+                .map(|p| p.as_ref().to_vec())
+                .filter(|p| !p.is_empty())
+                .collect(),
+            #[cfg(feature = "regex")]
+            regexes: vec![],
+        }
+    }
+
+    /// Additionally reports the run as interesting if the output matches the given [`Regex`].
+    #[cfg(feature = "regex")]
+    #[must_use]
+    pub fn with_regex(mut self, regex: Regex) -> Self {
+        self.regexes.push(regex);
+        self
+    }
+
+    /// Returns true if the given output matches any of the configured patterns.
+    #[must_use]
+    pub fn matches(&self, output: &[u8]) -> bool {
+        let found = self.patterns.iter().any(|pattern| {
+            output
+                .windows(pattern.len())
+                .any(|window| window == pattern.as_slice())
+        });
+        #[cfg(feature = "regex")]
+        let found = found || self.regexes.iter().any(|regex| regex.is_match(output));
+        found
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::process::{Child, Command, Stdio};
+
+    use crate::{
+        bolts::{rands::StdRand, tuples::tuple_list},
+        corpus::InMemoryCorpus,
+        events::NopEventManager,
+        executors::{command::CommandConfigurator, Executor, HasObservers},
+        feedbacks::{Feedback, OutputPatternFeedback},
+        inputs::{BytesInput, Input},
+        observers::StdErrObserver,
+        state::StdState,
+        Error,
+    };
+
+    #[derive(Debug)]
+    struct PanicPrinter;
+
+    impl<EM, I: Input, S, Z> CommandConfigurator<EM, I, S, Z> for PanicPrinter {
+        fn spawn_child(
+            &mut self,
+            _fuzzer: &mut Z,
+            _state: &mut S,
+            _mgr: &mut EM,
+            _input: &I,
+        ) -> Result<Child, Error> {
+            Ok(Command::new("sh")
+                .arg("-c")
+                .arg("echo 'thread main panicked' >&2")
+                .stdout(Stdio::null())
+                .stderr(Stdio::piped())
+                .spawn()?)
+        }
+    }
+
+    #[test]
+    fn test_output_pattern_feedback() {
+        let observer = StdErrObserver::new("stderr");
+        let mut panic_feedback = OutputPatternFeedback::new(&observer, &["panicked"]);
+        let mut asan_feedback = OutputPatternFeedback::new(&observer, &["AddressSanitizer"]);
+        let mut executor = PanicPrinter.into_executor(tuple_list!(observer));
+
+        let mut mgr = NopEventManager {};
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let input = BytesInput::new(vec![]);
+
+        let exit_kind = executor
+            .run_target(&mut (), &mut state, &mut mgr, &input)
+            .unwrap();
+
+        assert!(panic_feedback
+            .is_interesting(
+                &mut state,
+                &mut mgr,
+                &input,
+                executor.observers(),
+                &exit_kind
+            )
+            .unwrap());
+        assert!(!asan_feedback
+            .is_interesting(
+                &mut state,
+                &mut mgr,
+                &input,
+                executor.observers(),
+                &exit_kind
+            )
+            .unwrap());
+    }
+}
@@ -0,0 +1,172 @@
+//! Value profiling: the [`ValueProfileFeedback`] reports inputs that made the target compute
+//! a value never seen before at one of its points of interest, even without new coverage.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt::Debug, marker::PhantomData};
+use hashbrown::HashSet;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, FeedbackState, FeedbackStatesTuple},
+    inputs::Input,
+    observers::{ObserversTuple, ValueProfileObserver},
+    state::{HasClientPerfMonitor, HasFeedbackStates},
+    Error,
+};
+
+/// The state of [`ValueProfileFeedback`]: the values seen so far, per slot
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ValueProfileFeedbackState {
+    /// The values seen so far, for each slot of the [`ValueProfileObserver`]
+    pub seen: Vec<HashSet<u64>>,
+    /// Name identifier of this instance
+    pub name: String,
+}
+
+impl FeedbackState for ValueProfileFeedbackState {
+    fn reset(&mut self) -> Result<(), Error> {
+        self.seen.iter_mut().for_each(HashSet::clear);
+        Ok(())
+    }
+}
+
+impl Named for ValueProfileFeedbackState {
+    #[inline]
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
+impl ValueProfileFeedbackState {
+    /// Creates a new [`ValueProfileFeedbackState`] for `slots` value slots
+    #[must_use]
+    pub fn new(name: &'static str, slots: usize) -> Self {
+        Self {
+            seen: vec![HashSet::new(); slots],
+            name: name.to_string(),
+        }
+    }
+
+    /// Creates a new [`ValueProfileFeedbackState`] for the slots of the given observer
+    #[must_use]
+    pub fn with_observer(observer: &ValueProfileObserver) -> Self {
+        Self {
+            seen: vec![HashSet::new(); observer.values().len()],
+            name: observer.name().to_string(),
+        }
+    }
+}
+
+/// A [`ValueProfileFeedback`] reports an input as interesting, if it produced a value not seen
+/// before in any slot of a [`ValueProfileObserver`]. Unwritten slots, holding `0`, are ignored.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ValueProfileFeedback<FT, S> {
+    name: String,
+    observer_name: String,
+    phantom: PhantomData<(FT, S)>,
+}
+
+impl<FT, I, S> Feedback<I, S> for ValueProfileFeedback<FT, S>
+where
+    I: Input,
+    S: HasFeedbackStates<FT> + HasClientPerfMonitor + Debug,
+    FT: FeedbackStatesTuple,
+{
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        _manager: &mut EM,
+        _input: &I,
+        observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<I>,
+        OT: ObserversTuple<I, S>,
+    {
+        let observer = observers
+            .match_name::<ValueProfileObserver>(&self.observer_name)
+            .unwrap();
+        let value_state = state
+            .feedback_states_mut()
+            .match_name_mut::<ValueProfileFeedbackState>(&self.name)
+            .unwrap();
+
+        let mut interesting = false;
+        for (seen, value) in value_state.seen.iter_mut().zip(observer.values()) {
+            if *value != 0 && seen.insert(*value) {
+                interesting = true;
+            }
+        }
+        Ok(interesting)
+    }
+}
+
+impl<FT, S> Named for ValueProfileFeedback<FT, S> {
+    #[inline]
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
+impl<FT, S> ValueProfileFeedback<FT, S> {
+    /// Creates a new [`ValueProfileFeedback`], for the given state and observer
+    #[must_use]
+    pub fn new(
+        feedback_state: &ValueProfileFeedbackState,
+        observer: &ValueProfileObserver,
+    ) -> Self {
+        Self {
+            name: feedback_state.name().to_string(),
+            observer_name: observer.name().to_string(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::{rands::StdRand, tuples::tuple_list},
+        corpus::InMemoryCorpus,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{Feedback, ValueProfileFeedback, ValueProfileFeedbackState},
+        inputs::BytesInput,
+        observers::ValueProfileObserver,
+        state::StdState,
+    };
+
+    #[test]
+    fn test_value_profile_feedback() {
+        let observer = ValueProfileObserver::new_owned("values", vec![0; 2]);
+        let value_state = ValueProfileFeedbackState::with_observer(&observer);
+        let mut feedback = ValueProfileFeedback::new(&value_state, &observer);
+        let mut observers = tuple_list!(observer);
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            tuple_list!(value_state),
+        );
+        let mut mgr = NopEventManager {};
+        let input = BytesInput::new(vec![]);
+
+        // A new value in slot 1 is interesting, a repeat is not, the same value in slot 0 is
+        for (values, interesting) in [([0, 42], true), ([0, 42], false), ([42, 42], true)] {
+            observers.0.values_mut().copy_from_slice(&values);
+            assert_eq!(
+                feedback
+                    .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                    .unwrap(),
+                interesting
+            );
+        }
+    }
+}
@@ -3,27 +3,108 @@
 use crate::{
     bolts::current_time,
     corpus::{Corpus, CorpusScheduler, Testcase},
-    events::{Event, EventConfig, EventFirer, EventManager, ProgressReporter},
+    events::{Event, EventConfig, EventFirer, EventManager, EventRestarter, ProgressReporter},
     executors::{Executor, ExitKind, HasObservers},
-    feedbacks::Feedback,
+    feedbacks::{Feedback, FeedbackStatesTuple},
     inputs::Input,
     mark_feature_time,
     observers::ObserversTuple,
     stages::StagesTuple,
     start_timer,
-    state::{HasClientPerfMonitor, HasCorpus, HasExecutions, HasSolutions},
+    state::{HasClientPerfMonitor, HasCorpus, HasExecutions, HasFeedbackStates, HasSolutions},
     Error,
 };
 
 #[cfg(feature = "introspection")]
 use crate::monitors::PerfFeature;
+#[cfg(test)]
+use crate::{corpus::InMemoryCorpus, inputs::BytesInput};
+
+use alloc::{string::ToString, vec::Vec};
+use core::{
+    fmt::{self, Debug},
+    marker::PhantomData,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "std")]
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
-use alloc::string::ToString;
-use core::{marker::PhantomData, time::Duration};
+#[cfg(all(unix, feature = "std"))]
+use crate::bolts::os::unix_signals::{
+    setup_signal_handler, signal_handler_installed, ucontext_t, Handler, Signal,
+};
+#[cfg(all(unix, feature = "std"))]
+use libc::siginfo_t;
 
 /// Send a monitor update all 15 (or more) seconds
 const STATS_TIMEOUT_DEFAULT: Duration = Duration::from_secs(15);
 
+/// Set once a shutdown got requested, checked by the fuzz loops after each iteration
+static SHUTDOWN_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Requests [`Fuzzer::fuzz_loop`] and [`Fuzzer::fuzz_loop_for`] to return, once the current
+/// iteration is done. The loop clears the request, see [`Fuzzer::fuzz_loop_until_shutdown`]
+/// to also store the state.
+pub fn request_shutdown() {
+    SHUTDOWN_REQUESTED.store(true, Ordering::SeqCst);
+}
+
+/// Whether a shutdown got requested, see [`request_shutdown`]
+#[must_use]
+pub fn shutdown_requested() -> bool {
+    SHUTDOWN_REQUESTED.load(Ordering::SeqCst)
+}
+
+/// Clears a pending shutdown request, returning whether there was one
+fn take_shutdown_request() -> bool {
+    SHUTDOWN_REQUESTED.swap(false, Ordering::SeqCst)
+}
+
+/// Requests a shutdown on the first `SIGINT`, and exits right away on the second one
+#[cfg(all(unix, feature = "std"))]
+#[derive(Debug)]
+struct ShutdownSignalHandler;
+
+#[cfg(all(unix, feature = "std"))]
+impl Handler for ShutdownSignalHandler {
+    fn handle(&mut self, _signal: Signal, _info: siginfo_t, _context: &mut ucontext_t) {
+        if SHUTDOWN_REQUESTED.swap(true, Ordering::SeqCst) {
+            unsafe {
+                libc::_exit(130);
+            }
+        }
+    }
+
+    fn signals(&self) -> Vec<Signal> {
+        vec![Signal::SigInterrupt]
+    }
+}
+
+#[cfg(all(unix, feature = "std"))]
+static mut SHUTDOWN_SIGNAL_HANDLER: ShutdownSignalHandler = ShutdownSignalHandler;
+
+/// Installs a `SIGINT` handler for a graceful shutdown on Ctrl-C: the first signal makes the fuzz
+/// loops return `Ok` after the current iteration, see [`request_shutdown`], a second one exits
+/// the process immediately.
+/// Nothing installs it by default. Call it in the fuzzing client, once the event manager is set up,
+/// so neither the broker nor the process respawning the client gets it.
+/// Errors, if another `SIGINT` handler is installed already, such as the one of the LLMP broker.
+#[cfg(all(unix, feature = "std"))]
+pub fn install_shutdown_handler() -> Result<(), Error> {
+    if signal_handler_installed(Signal::SigInterrupt) {
+        return Err(Error::IllegalState(
+            "Another SIGINT handler, for example of the broker, is installed already".to_string(),
+        ));
+    }
+    unsafe { setup_signal_handler(&mut SHUTDOWN_SIGNAL_HANDLER) }
+}
+
 /// Holds a scheduler
 pub trait HasCorpusScheduler<CS, I, S>
 where
@@ -134,7 +215,8 @@ pub trait Evaluator<E, EM, I, S> {
 
     /// Runs the input and triggers observers and feedback.
     /// Adds an input, to the corpus even if it's not considered `interesting` by the `feedback`.
-    /// Returns the `index` of the new testcase in the corpus.
+    /// Returns the `index` of the new testcase in the corpus, or of the testcase it duplicates, if
+    /// the corpus dropped it, see [`crate::Error::DuplicateTestcase`].
     /// Usually, you want to use [`Evaluator::evaluate_input`], unless you know what you are doing.
     fn add_input(
         &mut self,
@@ -167,6 +249,8 @@ where
     ) -> Result<usize, Error>;
 
     /// Fuzz forever (or until stopped)
+    /// Returns the index of the last fuzzed corpus item, once a shutdown got requested,
+    /// see [`request_shutdown`].
     fn fuzz_loop(
         &mut self,
         stages: &mut ST,
@@ -177,17 +261,41 @@ where
         let mut last = current_time();
         let monitor_timeout = STATS_TIMEOUT_DEFAULT;
         loop {
-            self.fuzz_one(stages, executor, state, manager)?;
+            let idx = self.fuzz_one(stages, executor, state, manager)?;
             last = manager.maybe_report_progress(state, last, monitor_timeout)?;
+            if take_shutdown_request() {
+                return Ok(idx);
+            }
         }
     }
 
+    /// Fuzz like [`Fuzzer::fuzz_loop`], until a shutdown got requested, see [`request_shutdown`].
+    /// Then stores the state, including the solutions, using [`EventRestarter::on_restart`],
+    /// so a restarting event manager picks it up in the next run.
+    /// Event managers without restarts, such as [`crate::events::SimpleEventManager`], store
+    /// nothing, the state stays with the caller.
+    fn fuzz_loop_until_shutdown(
+        &mut self,
+        stages: &mut ST,
+        executor: &mut E,
+        state: &mut S,
+        manager: &mut EM,
+    ) -> Result<usize, Error>
+    where
+        EM: EventRestarter<S>,
+    {
+        let idx = self.fuzz_loop(stages, executor, state, manager)?;
+        manager.on_restart(state)?;
+        Ok(idx)
+    }
+
     /// Fuzz for n iterations
     /// Returns the index of the last fuzzed corpus item
     ///
     /// If you use this fn in a restarting scenario to only run for `n` iterations,
     /// before exiting, make sure you call `event_mgr.on_restart(&mut state)?;`.
     /// This way, the state will be available in the next, respawned, iteration.
+    /// If a shutdown got requested, see [`request_shutdown`], the loop returns early.
     fn fuzz_loop_for(
         &mut self,
         stages: &mut ST,
@@ -209,6 +317,9 @@ where
         for _ in 0..iters {
             ret = self.fuzz_one(stages, executor, state, manager)?;
             last = manager.maybe_report_progress(state, last, monitor_timeout)?;
+            if take_shutdown_request() {
+                break;
+            }
         }
 
         // If we would assume the fuzzer loop will always exit after this, we could do this here:
@@ -231,6 +342,94 @@ pub enum ExecuteInputResult {
     Solution,
 }
 
+/// The observers and [`ExitKind`] of a run, as returned by [`StdFuzzer::probe_input`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObserverSnapshot {
+    exit_kind: ExitKind,
+    observers_buf: Vec<u8>,
+}
+
+impl ObserverSnapshot {
+    /// How the probed run finished
+    #[must_use]
+    pub fn exit_kind(&self) -> ExitKind {
+        self.exit_kind
+    }
+
+    /// The serialized observers of the probed run
+    #[must_use]
+    pub fn observers_buf(&self) -> &[u8] {
+        &self.observers_buf
+    }
+
+    /// Deserializes an owned copy of the observers of the probed run
+    pub fn observers<OT>(&self) -> Result<OT, Error>
+    where
+        OT: serde::de::DeserializeOwned,
+    {
+        Ok(postcard::from_bytes(&self.observers_buf)?)
+    }
+}
+
+/// A configuration problem found by [`StdFuzzer::dry_run`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DryRunDiagnostic {
+    /// There are no seeds to run
+    NoSeeds,
+    /// No seed covered any map entry: the target is not instrumented, or the observer reads the wrong map
+    EmptyCoverage,
+    /// The feedback accepted none of the seeds, so the corpus would stay empty
+    NoSeedAccepted,
+    /// Every seed crashed, or was reported by the objective: the harness fails on any input
+    AllSeedsCrash,
+    /// Every seed timed out
+    AllSeedsTimeout,
+}
+
+impl fmt::Display for DryRunDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DryRunDiagnostic::NoSeeds => write!(f, "No seeds to run"),
+            DryRunDiagnostic::EmptyCoverage => write!(
+                f,
+                "No seed covered any map entry, is the target instrumented and the right map observed?"
+            ),
+            DryRunDiagnostic::NoSeedAccepted => write!(f, "The feedback accepted none of the seeds"),
+            DryRunDiagnostic::AllSeedsCrash => {
+                write!(f, "Every seed crashed, or was reported by the objective")
+            }
+            DryRunDiagnostic::AllSeedsTimeout => write!(f, "Every seed timed out"),
+        }
+    }
+}
+
+/// The outcome of running each seed once, as returned by [`StdFuzzer::dry_run`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DryRunReport {
+    /// The number of seeds run
+    pub seeds: usize,
+    /// The number of seeds covering at least one map entry
+    pub covering: usize,
+    /// The number of seeds the feedback would add to the corpus
+    pub accepted: usize,
+    /// The number of seeds the objective reported
+    pub solutions: usize,
+    /// The number of seeds that crashed
+    pub crashes: usize,
+    /// The number of seeds that timed out
+    pub timeouts: usize,
+    /// The configuration problems found
+    pub diagnostics: Vec<DryRunDiagnostic>,
+}
+
+impl DryRunReport {
+    /// `true` if no configuration problems were found
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+}
+
 /// Your default fuzzer instance, for everyday use.
 #[derive(Debug)]
 pub struct StdFuzzer<C, CS, F, I, OF, OT, S, SC>
@@ -244,6 +443,9 @@ where
     scheduler: CS,
     feedback: F,
     objective: OF,
+    /// The directories [`StdFuzzer::dry_run`] loads the seeds from
+    #[cfg(feature = "std")]
+    seed_dirs: Vec<PathBuf>,
     phantom: PhantomData<(C, I, OT, S, SC)>,
 }
 
@@ -367,7 +569,15 @@ where
                 // Add the input to the main corpus
                 let mut testcase = Testcase::with_executions(input.clone(), *state.executions());
                 self.feedback_mut().append_metadata(state, &mut testcase)?;
-                let idx = state.corpus_mut().add(testcase)?;
+                let idx = match state.corpus_mut().add(testcase) {
+                    Ok(idx) => idx,
+                    // The corpus dropped it, nothing new to schedule or share
+                    Err(Error::DuplicateTestcase(_)) => {
+                        return Ok((ExecuteInputResult::None, None))
+                    }
+                    Err(err) => return Err(err),
+                };
+                self.notify_evicted(state)?;
                 self.scheduler_mut().on_add(state, idx)?;
 
                 if send_events {
@@ -400,6 +610,8 @@ where
                 let mut testcase = Testcase::with_executions(input, *state.executions());
                 self.objective_mut().append_metadata(state, &mut testcase)?;
                 state.solutions_mut().add(testcase)?;
+                // There is no scheduler for the solutions to tell about evicted ones
+                state.solutions_mut().take_evicted();
 
                 if send_events {
                     manager.fire(
@@ -493,7 +705,13 @@ where
         // Add the input to the main corpus
         let mut testcase = Testcase::with_executions(input.clone(), *state.executions());
         self.feedback_mut().append_metadata(state, &mut testcase)?;
-        let idx = state.corpus_mut().add(testcase)?;
+        let idx = match state.corpus_mut().add(testcase) {
+            Ok(idx) => idx,
+            // The corpus dropped it, nothing new to schedule or share
+            Err(Error::DuplicateTestcase(idx)) => return Ok(idx),
+            Err(err) => return Err(err),
+        };
+        self.notify_evicted(state)?;
         self.scheduler_mut().on_add(state, idx)?;
 
         let observers_buf = if manager.configuration() == EventConfig::AlwaysUnique {
@@ -520,11 +738,12 @@ where
 impl<C, CS, E, EM, F, I, OF, OT, S, ST, SC> Fuzzer<E, EM, I, S, ST>
     for StdFuzzer<C, CS, F, I, OF, OT, S, SC>
 where
+    C: Corpus<I>,
     CS: CorpusScheduler<I, S>,
     EM: EventManager<E, I, S, Self>,
     F: Feedback<I, S>,
     I: Input,
-    S: HasClientPerfMonitor + HasExecutions,
+    S: HasClientPerfMonitor + HasExecutions + HasCorpus<C, I>,
     OF: Feedback<I, S>,
     ST: StagesTuple<E, EM, S, Self>,
 {
@@ -541,6 +760,12 @@ where
 
         // Get the next index from the scheduler
         let idx = self.scheduler.next(state)?;
+        {
+            // Counted here, once, even if wrapping schedulers ask their base for the next index
+            let mut testcase = state.corpus().get(idx)?.borrow_mut();
+            let scheduled_count = testcase.scheduled_count() + 1;
+            testcase.set_scheduled_count(scheduled_count);
+        }
 
         // Mark the elapsed time for the scheduler
         #[cfg(feature = "introspection")]
@@ -582,10 +807,20 @@ where
             scheduler,
             feedback,
             objective,
+            #[cfg(feature = "std")]
+            seed_dirs: vec![],
             phantom: PhantomData,
         }
     }
 
+    /// Sets the directories [`StdFuzzer::dry_run`] loads the seeds from, recursively
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn with_seed_dirs(mut self, seed_dirs: Vec<PathBuf>) -> Self {
+        self.seed_dirs = seed_dirs;
+        self
+    }
+
     /// Runs the input and triggers observers and feedback
     pub fn execute_input<E, EM>(
         &mut self,
@@ -616,6 +851,171 @@ where
     }
 }
 
+impl<C, CS, F, I, OF, OT, S, SC> StdFuzzer<C, CS, F, I, OF, OT, S, SC>
+where
+    C: Corpus<I>,
+    CS: CorpusScheduler<I, S>,
+    F: Feedback<I, S>,
+    I: Input,
+    OF: Feedback<I, S>,
+    S: HasCorpus<C, I> + HasClientPerfMonitor,
+{
+    /// Tells the scheduler about the testcases the corpus evicted, see [`Corpus::take_evicted`]
+    fn notify_evicted(&mut self, state: &mut S) -> Result<(), Error> {
+        for (idx, testcase) in state.corpus_mut().take_evicted() {
+            self.scheduler.on_remove(state, idx, &Some(testcase))?;
+        }
+        Ok(())
+    }
+}
+
+impl<C, CS, F, I, OF, OT, S, SC> StdFuzzer<C, CS, F, I, OF, OT, S, SC>
+where
+    CS: CorpusScheduler<I, S>,
+    F: Feedback<I, S>,
+    I: Input,
+    OF: Feedback<I, S>,
+    OT: ObserversTuple<I, S> + serde::Serialize + serde::de::DeserializeOwned,
+    S: HasExecutions + HasClientPerfMonitor,
+{
+    /// Runs the input, and asks the objective and the feedback whether it is interesting,
+    /// without adding it to any corpus or firing events.
+    /// The feedback states are restored afterwards, so the verdict of later runs is unaffected.
+    /// Only the executions count of the state changes.
+    pub fn probe_input<E, EM, FT>(
+        &mut self,
+        state: &mut S,
+        executor: &mut E,
+        manager: &mut EM,
+        input: &I,
+    ) -> Result<(ExecuteInputResult, ObserverSnapshot), Error>
+    where
+        E: Executor<EM, I, S, Self> + HasObservers<I, OT, S>,
+        EM: EventFirer<I>,
+        FT: FeedbackStatesTuple,
+        S: HasFeedbackStates<FT>,
+    {
+        let feedback_states = postcard::to_allocvec(state.feedback_states())?;
+
+        let exit_kind = self.execute_input(state, executor, manager, input)?;
+        let observers = executor.observers();
+
+        let res = if self
+            .objective_mut()
+            .is_interesting(state, manager, input, observers, &exit_kind)?
+        {
+            ExecuteInputResult::Solution
+        } else if self
+            .feedback_mut()
+            .is_interesting(state, manager, input, observers, &exit_kind)?
+        {
+            ExecuteInputResult::Corpus
+        } else {
+            ExecuteInputResult::None
+        };
+        self.feedback_mut().discard_metadata(state, input)?;
+        self.objective_mut().discard_metadata(state, input)?;
+
+        *state.feedback_states_mut() = postcard::from_bytes(&feedback_states)?;
+
+        let snapshot = ObserverSnapshot {
+            exit_kind,
+            observers_buf: postcard::to_allocvec(observers)?,
+        };
+        Ok((res, snapshot))
+    }
+
+    /// Validates the fuzzing pipeline before a campaign: loads the seeds from the seed
+    /// directories, see [`StdFuzzer::with_seed_dirs`], runs each once, using
+    /// [`StdFuzzer::probe_input`], without mutating or adding anything, and reports
+    /// configuration problems as [`DryRunDiagnostic`]s.
+    ///
+    /// The coverage is read from the coverage map observers, see [`ObserversTuple::coverage_all`].
+    /// Each seed is judged against the current feedback states, so run this before loading the
+    /// initial inputs.
+    #[cfg(feature = "std")]
+    pub fn dry_run<E, EM, FT>(
+        &mut self,
+        state: &mut S,
+        executor: &mut E,
+        manager: &mut EM,
+    ) -> Result<DryRunReport, Error>
+    where
+        E: Executor<EM, I, S, Self> + HasObservers<I, OT, S>,
+        EM: EventFirer<I>,
+        FT: FeedbackStatesTuple,
+        S: HasFeedbackStates<FT>,
+    {
+        let mut seeds = vec![];
+        for seed_dir in &self.seed_dirs {
+            load_seeds(seed_dir, &mut seeds)?;
+        }
+
+        let mut report = DryRunReport {
+            seeds: seeds.len(),
+            ..DryRunReport::default()
+        };
+        let mut failing = 0;
+        for seed in &seeds {
+            let (res, snapshot) = self.probe_input(state, executor, manager, seed)?;
+            if res == ExecuteInputResult::Solution || snapshot.exit_kind() == ExitKind::Crash {
+                failing += 1;
+            }
+            let coverage = executor.observers().coverage_all().ok_or_else(|| {
+                Error::KeyNotFound("No coverage map observer to read the coverage from".into())
+            })?;
+            if coverage > 0 {
+                report.covering += 1;
+            }
+            match res {
+                ExecuteInputResult::Corpus => report.accepted += 1,
+                ExecuteInputResult::Solution => report.solutions += 1,
+                ExecuteInputResult::None => (),
+            }
+            match snapshot.exit_kind() {
+                ExitKind::Crash => report.crashes += 1,
+                ExitKind::Timeout => report.timeouts += 1,
+                _ => (),
+            }
+        }
+
+        if seeds.is_empty() {
+            report.diagnostics.push(DryRunDiagnostic::NoSeeds);
+            return Ok(report);
+        }
+        if report.covering == 0 {
+            report.diagnostics.push(DryRunDiagnostic::EmptyCoverage);
+        }
+        if report.accepted == 0 {
+            report.diagnostics.push(DryRunDiagnostic::NoSeedAccepted);
+        }
+        if report.timeouts == report.seeds {
+            report.diagnostics.push(DryRunDiagnostic::AllSeedsTimeout);
+        } else if failing == report.seeds {
+            report.diagnostics.push(DryRunDiagnostic::AllSeedsCrash);
+        }
+        Ok(report)
+    }
+}
+
+/// Loads the non-empty files in `dir` and its subdirectories as inputs
+#[cfg(feature = "std")]
+fn load_seeds<I>(dir: &Path, seeds: &mut Vec<I>) -> Result<(), Error>
+where
+    I: Input,
+{
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let attr = fs::metadata(&path)?;
+        if attr.is_file() && attr.len() > 0 {
+            seeds.push(I::from_file(&path)?);
+        } else if attr.is_dir() {
+            load_seeds(&path, seeds)?;
+        }
+    }
+    Ok(())
+}
+
 /// Structs with this trait will execute an [`Input`]
 pub trait ExecutesInput<I, OT, S, Z>
 where
@@ -674,3 +1074,289 @@ where
         Ok(exit_kind)
     }
 }
+
+/// A [`StdFuzzer`] for the tests, fuzzing [`BytesInput`]s kept in [`InMemoryCorpus`]es
+#[cfg(test)]
+pub(crate) fn bytes_fuzzer<CS, F, OF, OT, S>(
+    scheduler: CS,
+    feedback: F,
+    objective: OF,
+) -> StdFuzzer<InMemoryCorpus<BytesInput>, CS, F, BytesInput, OF, OT, S, InMemoryCorpus<BytesInput>>
+where
+    CS: CorpusScheduler<BytesInput, S>,
+    F: Feedback<BytesInput, S>,
+    OF: Feedback<BytesInput, S>,
+    S: HasExecutions + HasClientPerfMonitor,
+{
+    StdFuzzer::new(scheduler, feedback, objective)
+}
+
+#[cfg(test)]
+mod tests {
+    use core::fmt::Debug;
+
+    use crate::{
+        bolts::{
+            rands::StdRand,
+            tuples::{tuple_list, tuple_list_type},
+        },
+        corpus::{
+            BurstScheduler, Corpus, CorpusScheduler, InMemoryCorpus, QueueCorpusScheduler, Testcase,
+        },
+        events::NopEventManager,
+        executors::{Executor, ExitKind, HasObservers},
+        feedbacks::{CrashFeedback, MapFeedbackState, MaxMapFeedback},
+        fuzzer::{
+            bytes_fuzzer, request_shutdown, shutdown_requested, DryRunDiagnostic,
+            ExecuteInputResult, HasCorpusScheduler,
+        },
+        inputs::{BytesInput, HasTargetBytes},
+        observers::{MapObserver, ObserversTuple, StdMapObserver},
+        state::{HasClientPerfMonitor, HasCorpus, HasExecutions, HasSolutions, StdState},
+        Error, Fuzzer,
+    };
+
+    /// Sets one map entry per distinct first byte, and crashes on `!`
+    #[derive(Debug)]
+    struct MapExecutor {
+        observers: tuple_list_type!(StdMapObserver<'static, u8>),
+    }
+
+    impl<EM, S, Z> Executor<EM, BytesInput, S, Z> for MapExecutor {
+        fn run_target(
+            &mut self,
+            _fuzzer: &mut Z,
+            _state: &mut S,
+            _mgr: &mut EM,
+            input: &BytesInput,
+        ) -> Result<ExitKind, Error> {
+            let first = input.target_bytes().as_slice()[0];
+            *self.observers.0.get_mut(usize::from(first) % 4) = 1;
+            if first == b'!' {
+                Ok(ExitKind::Crash)
+            } else {
+                Ok(ExitKind::Ok)
+            }
+        }
+    }
+
+    impl<S> HasObservers<BytesInput, tuple_list_type!(StdMapObserver<'static, u8>), S> for MapExecutor {
+        fn observers(&self) -> &tuple_list_type!(StdMapObserver<'static, u8>) {
+            &self.observers
+        }
+
+        fn observers_mut(&mut self) -> &mut tuple_list_type!(StdMapObserver<'static, u8>) {
+            &mut self.observers
+        }
+    }
+
+    #[test]
+    fn test_probe_input() {
+        let observer = StdMapObserver::new_owned("map", vec![0_u8; 4]);
+        let feedback_state = MapFeedbackState::with_observer(&observer);
+        let feedback = MaxMapFeedback::new(&feedback_state, &observer);
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            tuple_list!(feedback_state),
+        );
+        let mut fuzzer = bytes_fuzzer(QueueCorpusScheduler::new(), feedback, CrashFeedback::new());
+        let mut executor = MapExecutor {
+            observers: tuple_list!(observer),
+        };
+        let mut mgr = NopEventManager {};
+
+        // Probing the same new input twice gives the same verdict, as the feedback state is restored
+        for _ in 0..2 {
+            let (res, snapshot) = fuzzer
+                .probe_input(
+                    &mut state,
+                    &mut executor,
+                    &mut mgr,
+                    &BytesInput::new(vec![b'a']),
+                )
+                .unwrap();
+            assert_eq!(res, ExecuteInputResult::Corpus);
+            assert_eq!(snapshot.exit_kind(), ExitKind::Ok);
+            let observers: tuple_list_type!(StdMapObserver<u8>) = snapshot.observers().unwrap();
+            assert_eq!(observers.0.count_bytes(), 1);
+        }
+
+        let (res, snapshot) = fuzzer
+            .probe_input(
+                &mut state,
+                &mut executor,
+                &mut mgr,
+                &BytesInput::new(vec![b'!']),
+            )
+            .unwrap();
+        assert_eq!(res, ExecuteInputResult::Solution);
+        assert_eq!(snapshot.exit_kind(), ExitKind::Crash);
+
+        assert_eq!(state.corpus().count(), 0);
+        assert_eq!(state.solutions().count(), 0);
+    }
+
+    /// Runs nothing, so the target never covers anything
+    #[derive(Debug)]
+    struct UninstrumentedExecutor<OT> {
+        observers: OT,
+    }
+
+    impl<EM, OT: Debug, S, Z> Executor<EM, BytesInput, S, Z> for UninstrumentedExecutor<OT> {
+        fn run_target(
+            &mut self,
+            _fuzzer: &mut Z,
+            _state: &mut S,
+            _mgr: &mut EM,
+            _input: &BytesInput,
+        ) -> Result<ExitKind, Error> {
+            Ok(ExitKind::Ok)
+        }
+    }
+
+    impl<OT: ObserversTuple<BytesInput, S>, S> HasObservers<BytesInput, OT, S>
+        for UninstrumentedExecutor<OT>
+    {
+        fn observers(&self) -> &OT {
+            &self.observers
+        }
+
+        fn observers_mut(&mut self) -> &mut OT {
+            &mut self.observers
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_dry_run_empty_coverage() {
+        let observer = StdMapObserver::new_owned("map", vec![0_u8; 4]);
+        let feedback_state = MapFeedbackState::with_observer(&observer);
+        let feedback = MaxMapFeedback::new(&feedback_state, &observer);
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            tuple_list!(feedback_state),
+        );
+        let seed_dir = std::env::temp_dir().join("libafl_test_dry_run_empty_coverage");
+        let _ = std::fs::remove_dir_all(&seed_dir);
+        std::fs::create_dir_all(&seed_dir).unwrap();
+        std::fs::write(seed_dir.join("a"), b"a").unwrap();
+        std::fs::write(seed_dir.join("b"), b"b").unwrap();
+        let mut fuzzer = bytes_fuzzer(QueueCorpusScheduler::new(), feedback, CrashFeedback::new())
+            .with_seed_dirs(vec![seed_dir.clone()]);
+        let mut executor = UninstrumentedExecutor {
+            observers: tuple_list!(observer),
+        };
+        let mut mgr = NopEventManager {};
+
+        // The seeds run fine, but cover nothing, so nothing is accepted either
+        let report = fuzzer.dry_run(&mut state, &mut executor, &mut mgr).unwrap();
+        std::fs::remove_dir_all(&seed_dir).unwrap();
+        assert_eq!(report.seeds, 2);
+        assert_eq!(report.covering, 0);
+        assert_eq!(
+            report.diagnostics,
+            [
+                DryRunDiagnostic::EmptyCoverage,
+                DryRunDiagnostic::NoSeedAccepted
+            ]
+        );
+        assert!(!report.is_ok());
+        assert_eq!(state.corpus().count(), 0);
+    }
+
+    #[test]
+    fn test_scheduled_count() {
+        let mut state = StdState::new(
+            StdRand::with_seed(4),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let idx = state
+            .corpus_mut()
+            .add(Testcase::new(BytesInput::new(vec![0_u8; 4])))
+            .unwrap();
+        let scheduler = BurstScheduler::with_burst_rounds(QueueCorpusScheduler::new(), 1);
+        let mut fuzzer = bytes_fuzzer::<_, _, _, tuple_list_type!(StdMapObserver<u8>), _>(
+            scheduler,
+            CrashFeedback::new(),
+            CrashFeedback::new(),
+        );
+        let mut executor = MapExecutor {
+            observers: tuple_list!(StdMapObserver::new_owned("map", vec![0_u8; 4])),
+        };
+        let mut mgr = NopEventManager {};
+
+        // With a single testcase, each iteration selects it, the wrapping scheduler counts nothing
+        fuzzer.scheduler().on_add(&mut state, idx).unwrap();
+        for _ in 0..2 {
+            assert_eq!(
+                fuzzer
+                    .fuzz_one(&mut (), &mut executor, &mut state, &mut mgr)
+                    .unwrap(),
+                idx
+            );
+        }
+        assert_eq!(
+            state.corpus().get(idx).unwrap().borrow().scheduled_count(),
+            2
+        );
+    }
+
+    /// Counts its iterations, and requests a shutdown in the third one
+    #[derive(Debug)]
+    struct InterruptedFuzzer {
+        iterations: usize,
+    }
+
+    impl<S> Fuzzer<(), NopEventManager, BytesInput, S, ()> for InterruptedFuzzer
+    where
+        S: HasExecutions + HasClientPerfMonitor,
+    {
+        fn fuzz_one(
+            &mut self,
+            _stages: &mut (),
+            _executor: &mut (),
+            _state: &mut S,
+            _manager: &mut NopEventManager,
+        ) -> Result<usize, Error> {
+            self.iterations += 1;
+            if self.iterations == 3 {
+                request_shutdown();
+            }
+            Ok(self.iterations)
+        }
+    }
+
+    #[test]
+    fn test_fuzz_loop_shutdown() {
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let mut fuzzer = InterruptedFuzzer { iterations: 0 };
+
+        // The loop finishes the interrupted iteration, then returns
+        let ret = fuzzer
+            .fuzz_loop(&mut (), &mut (), &mut state, &mut NopEventManager {})
+            .unwrap();
+        assert_eq!(ret, 3);
+        assert_eq!(fuzzer.iterations, 3);
+        // The loop consumed the request
+        assert!(!shutdown_requested());
+
+        // Storing the state on shutdown is up to the event manager
+        let mut fuzzer = InterruptedFuzzer { iterations: 0 };
+        let ret = fuzzer
+            .fuzz_loop_until_shutdown(&mut (), &mut (), &mut state, &mut NopEventManager {})
+            .unwrap();
+        assert_eq!(ret, 3);
+        assert!(!shutdown_requested());
+    }
+}
@@ -0,0 +1,160 @@
+//! The [`FieldCombinationGenerator`] generates structured seeds from lists of valid values per field.
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use crate::{bolts::rands::Rand, generators::Generator, inputs::BytesInput, state::HasRand, Error};
+
+/// Generates inputs by picking one value per field, and joining the fields with separators.
+/// With a few valid values per field, for example methods, paths, and versions of a request line,
+/// it generates every valid combination of them.
+#[derive(Clone, Debug)]
+pub struct FieldCombinationGenerator<R, S>
+where
+    R: Rand,
+    S: HasRand<R>,
+{
+    /// The valid values of each field
+    fields: Vec<Vec<Vec<u8>>>,
+    /// The separator after each field but the last
+    separators: Vec<Vec<u8>>,
+    phantom: PhantomData<(R, S)>,
+}
+
+impl<R, S> Generator<BytesInput, S> for FieldCombinationGenerator<R, S>
+where
+    R: Rand,
+    S: HasRand<R>,
+{
+    fn generate(&mut self, state: &mut S) -> Result<BytesInput, Error> {
+        let mut bytes = vec![];
+        for (idx, values) in self.fields.iter().enumerate() {
+            bytes.extend_from_slice(state.rand_mut().choose(values));
+            if let Some(separator) = self.separators.get(idx) {
+                bytes.extend_from_slice(separator);
+            }
+        }
+        Ok(BytesInput::new(bytes))
+    }
+
+    /// Generates the combination of the first value of each field
+    fn generate_dummy(&self, _state: &mut S) -> BytesInput {
+        let mut bytes = vec![];
+        for (idx, values) in self.fields.iter().enumerate() {
+            bytes.extend_from_slice(&values[0]);
+            if let Some(separator) = self.separators.get(idx) {
+                bytes.extend_from_slice(separator);
+            }
+        }
+        BytesInput::new(bytes)
+    }
+}
+
+impl<R, S> FieldCombinationGenerator<R, S>
+where
+    R: Rand,
+    S: HasRand<R>,
+{
+    /// Creates a new [`FieldCombinationGenerator`] for the valid values of each field,
+    /// joining the fields with `separator`.
+    /// Fails if there are no fields, or a field has no values.
+    pub fn new(fields: Vec<Vec<Vec<u8>>>, separator: &[u8]) -> Result<Self, Error> {
+        let separators = vec![separator.to_vec(); fields.len().saturating_sub(1)];
+        Self::with_separators(fields, separators)
+    }
+
+    /// Creates a new [`FieldCombinationGenerator`] for the valid values of each field,
+    /// with `separators[i]` following field `i`.
+    /// Fails if there are no fields, a field has no values, or the number of separators is not
+    /// the number of fields minus one.
+    pub fn with_separators(
+        fields: Vec<Vec<Vec<u8>>>,
+        separators: Vec<Vec<u8>>,
+    ) -> Result<Self, Error> {
+        if fields.is_empty() || fields.iter().any(Vec::is_empty) {
+            return Err(Error::IllegalArgument(
+                "Every field needs at least one value".into(),
+            ));
+        }
+        if separators.len() + 1 != fields.len() {
+            return Err(Error::IllegalArgument(format!(
+                "Expected {} separators for {} fields, got {}",
+                fields.len() - 1,
+                fields.len(),
+                separators.len()
+            )));
+        }
+        Ok(Self {
+            fields,
+            separators,
+            phantom: PhantomData,
+        })
+    }
+
+    /// The number of distinct combinations this generator can generate, at most
+    #[must_use]
+    pub fn combinations(&self) -> usize {
+        self.fields
+            .iter()
+            .fold(1_usize, |count, values| count.saturating_mul(values.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hashbrown::HashSet;
+
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::InMemoryCorpus,
+        generators::{FieldCombinationGenerator, Generator},
+        inputs::{BytesInput, HasBytesVec},
+        state::StdState,
+    };
+
+    #[test]
+    fn test_field_combination_generator() {
+        type State = StdState<
+            InMemoryCorpus<BytesInput>,
+            (),
+            BytesInput,
+            StdRand,
+            InMemoryCorpus<BytesInput>,
+        >;
+
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let mut generator = FieldCombinationGenerator::new(
+            vec![
+                vec![b"GET".to_vec(), b"POST".to_vec()],
+                vec![b"/".to_vec(), b"/index".to_vec()],
+            ],
+            b" ",
+        )
+        .unwrap();
+        assert_eq!(generator.combinations(), 4);
+
+        let mut generated = HashSet::new();
+        for _ in 0..64 {
+            let input = generator.generate(&mut state).unwrap();
+            generated.insert(input.bytes().to_vec());
+        }
+        let expected: HashSet<Vec<u8>> = [
+            &b"GET /"[..],
+            &b"GET /index"[..],
+            &b"POST /"[..],
+            &b"POST /index"[..],
+        ]
+        .iter()
+        .map(|combination| combination.to_vec())
+        .collect();
+        assert_eq!(generated, expected);
+
+        // A field without values
+        assert!(FieldCombinationGenerator::<StdRand, State>::new(vec![vec![]], b" ").is_err());
+    }
+}
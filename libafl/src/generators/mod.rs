@@ -13,6 +13,9 @@ use crate::{
 pub mod gramatron;
 pub use gramatron::*;
 
+pub mod field;
+pub use field::FieldCombinationGenerator;
+
 #[cfg(feature = "nautilus")]
 pub mod nautilus;
 #[cfg(feature = "nautilus")]
@@ -4,25 +4,50 @@
 use ahash::AHasher;
 use alloc::{borrow::ToOwned, rc::Rc, string::String, vec::Vec};
 use core::hash::Hasher;
-use core::{cell::RefCell, convert::From};
+use core::{
+    cell::RefCell,
+    cmp::{max, min},
+    convert::From,
+    ops::Range,
+};
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "std")]
 use std::{fs::File, io::Read, path::Path};
 
 #[cfg(feature = "std")]
-use crate::{bolts::fs::write_file_atomic, Error};
+use crate::bolts::fs::write_file_atomic;
 use crate::{
     bolts::{ownedref::OwnedSlice, HasLen},
     inputs::{HasBytesVec, HasTargetBytes, Input},
+    Error,
 };
 
 /// A bytes input is the basic input
-#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct BytesInput {
     /// The raw input bytes
     bytes: Vec<u8>,
+    /// The range of bytes changed by mutations, see [`HasBytesVec::dirty_range`]
+    #[serde(skip)]
+    dirty: Option<Range<usize>>,
+}
+
+/// A clone starts out without any changed bytes
+impl Clone for BytesInput {
+    fn clone(&self) -> Self {
+        Self::new(self.bytes.clone())
+    }
 }
 
+/// Only the bytes are compared, not which of them changed
+impl PartialEq for BytesInput {
+    fn eq(&self, other: &Self) -> bool {
+        self.bytes == other.bytes
+    }
+}
+
+impl Eq for BytesInput {}
+
 impl Input for BytesInput {
     #[cfg(feature = "std")]
     /// Write this input to the file
@@ -33,6 +58,11 @@ impl Input for BytesInput {
         write_file_atomic(path, &self.bytes)
     }
 
+    /// The contents [`Input::to_file`] writes to the file
+    fn to_file_bytes(&self) -> Result<Vec<u8>, Error> {
+        Ok(self.bytes.clone())
+    }
+
     /// Load the contents of this input from a file
     #[cfg(feature = "std")]
     fn from_file<P>(path: P) -> Result<Self, Error>
@@ -51,6 +81,11 @@ impl Input for BytesInput {
         hasher.write(self.bytes());
         format!("{:016x}", hasher.finish())
     }
+
+    #[inline]
+    fn truncate(&mut self, max_size: usize) {
+        self.bytes.truncate(max_size);
+    }
 }
 
 /// Rc Ref-cell from Input
@@ -70,6 +105,19 @@ impl HasBytesVec for BytesInput {
     fn bytes_mut(&mut self) -> &mut Vec<u8> {
         &mut self.bytes
     }
+
+    #[inline]
+    fn dirty_range(&self) -> Option<Range<usize>> {
+        self.dirty.clone()
+    }
+
+    #[inline]
+    fn mark_dirty(&mut self, range: Range<usize>) {
+        self.dirty = Some(match self.dirty.take() {
+            Some(dirty) => min(dirty.start, range.start)..max(dirty.end, range.end),
+            None => range,
+        });
+    }
 }
 
 impl HasTargetBytes for BytesInput {
@@ -102,7 +150,12 @@ impl BytesInput {
     /// Creates a new bytes input using the given bytes
     #[must_use]
     pub fn new(bytes: Vec<u8>) -> Self {
-        Self { bytes }
+        Self { bytes, dirty: None }
+    }
+
+    /// Forgets which bytes changed, for example after the harness processed this input.
+    pub fn clear_dirty_range(&mut self) {
+        self.dirty = None;
     }
 }
 
@@ -0,0 +1,111 @@
+//! The [`FixedSizeInput`] hands a fixed-size buffer to the target, while mutations keep
+//! working on a variable-length [`BytesInput`].
+
+use alloc::{string::String, vec::Vec};
+use core::ops::Range;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::{ownedref::OwnedSlice, HasLen},
+    inputs::{BytesInput, HasBytesVec, HasTargetBytes, Input},
+};
+
+/// Wraps a [`BytesInput`], and always returns exactly `size` bytes from [`HasTargetBytes::target_bytes`],
+/// zero-padding shorter inputs and truncating longer ones.
+/// For harnesses that expect fixed-length records or frames.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FixedSizeInput {
+    input: BytesInput,
+    size: usize,
+}
+
+impl Input for FixedSizeInput {
+    /// Generate a name for this input
+    fn generate_name(&self, idx: usize) -> String {
+        self.input.generate_name(idx)
+    }
+
+    #[inline]
+    fn truncate(&mut self, max_size: usize) {
+        self.input.truncate(max_size);
+    }
+}
+
+impl HasBytesVec for FixedSizeInput {
+    #[inline]
+    fn bytes(&self) -> &[u8] {
+        self.input.bytes()
+    }
+
+    #[inline]
+    fn bytes_mut(&mut self) -> &mut Vec<u8> {
+        self.input.bytes_mut()
+    }
+
+    #[inline]
+    fn dirty_range(&self) -> Option<Range<usize>> {
+        self.input.dirty_range()
+    }
+
+    #[inline]
+    fn mark_dirty(&mut self, range: Range<usize>) {
+        self.input.mark_dirty(range);
+    }
+}
+
+impl HasTargetBytes for FixedSizeInput {
+    fn target_bytes(&self) -> OwnedSlice<u8> {
+        let bytes = self.input.bytes();
+        if bytes.len() >= self.size {
+            OwnedSlice::Ref(&bytes[..self.size])
+        } else {
+            let mut padded = bytes.to_vec();
+            padded.resize(self.size, 0);
+            OwnedSlice::Owned(padded)
+        }
+    }
+}
+
+/// The length of the underlying, variable-length input
+impl HasLen for FixedSizeInput {
+    #[inline]
+    fn len(&self) -> usize {
+        self.input.len()
+    }
+}
+
+impl FixedSizeInput {
+    /// Creates a new [`FixedSizeInput`], handing exactly `size` bytes of `input` to the target
+    #[must_use]
+    pub fn new(input: BytesInput, size: usize) -> Self {
+        Self { input, size }
+    }
+
+    /// The number of bytes the target gets
+    #[must_use]
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// The wrapped, variable-length input
+    #[must_use]
+    pub fn inner(&self) -> &BytesInput {
+        &self.input
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::inputs::{BytesInput, FixedSizeInput, HasBytesVec, HasTargetBytes};
+
+    #[test]
+    fn test_fixed_size_input() {
+        let mut input = FixedSizeInput::new(BytesInput::new(vec![1, 2, 3]), 8);
+        assert_eq!(input.target_bytes().as_slice(), [1, 2, 3, 0, 0, 0, 0, 0]);
+
+        // Mutations see the variable-length buffer, the target the first 8 bytes
+        input.bytes_mut().extend_from_slice(&[4, 5, 6, 7, 8, 9]);
+        assert_eq!(input.bytes().len(), 9);
+        assert_eq!(input.target_bytes().as_slice(), [1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+}
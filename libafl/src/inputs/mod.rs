@@ -3,6 +3,9 @@
 pub mod bytes;
 pub use bytes::BytesInput;
 
+pub mod fixed;
+pub use fixed::FixedSizeInput;
+
 pub mod encoded;
 pub use encoded::*;
 
@@ -14,11 +17,16 @@ pub mod nautilus;
 #[cfg(feature = "nautilus")]
 pub use nautilus::*;
 
+#[cfg(feature = "structured_input")]
+pub mod structured;
+#[cfg(feature = "structured_input")]
+pub use structured::StructuredInput;
+
 use alloc::{
     string::{String, ToString},
     vec::Vec,
 };
-use core::{clone::Clone, fmt::Debug};
+use core::{clone::Clone, fmt::Debug, ops::Range};
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "std")]
 use std::{fs::File, io::Read, path::Path};
@@ -35,7 +43,12 @@ pub trait Input: Clone + Serialize + serde::de::DeserializeOwned + Debug {
     where
         P: AsRef<Path>,
     {
-        write_file_atomic(path, &postcard::to_allocvec(self)?)
+        write_file_atomic(path, &self.to_file_bytes()?)
+    }
+
+    /// The contents [`Input::to_file`] writes to the file
+    fn to_file_bytes(&self) -> Result<Vec<u8>, Error> {
+        Ok(postcard::to_allocvec(self)?)
     }
 
     #[cfg(not(feature = "std"))]
@@ -64,6 +77,11 @@ pub trait Input: Clone + Serialize + serde::de::DeserializeOwned + Debug {
 
     /// Generate a name for this input
     fn generate_name(&self, idx: usize) -> String;
+
+    /// Truncates this input to at most `max_size` bytes, for inputs that have a byte size.
+    /// The mutational stages call this before each execution, see [`crate::state::HasMaxSize`].
+    /// The default does nothing.
+    fn truncate(&mut self, _max_size: usize) {}
 }
 
 /// An input for tests, mainly. There is no real use much else.
@@ -94,4 +112,14 @@ pub trait HasBytesVec {
     fn bytes(&self) -> &[u8];
     /// The internal bytes map (as mutable borrow)
     fn bytes_mut(&mut self) -> &mut Vec<u8>;
+
+    /// The range of bytes changed by mutations since this input got created or cloned,
+    /// if this input keeps track of it. The range may reach past the end of an input that shrank.
+    fn dirty_range(&self) -> Option<Range<usize>> {
+        None
+    }
+
+    /// Records that the bytes in `range` changed. Called by mutators.
+    /// Inputs not keeping track of changed bytes ignore this.
+    fn mark_dirty(&mut self, _range: Range<usize>) {}
 }
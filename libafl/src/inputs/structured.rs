@@ -0,0 +1,245 @@
+//! The [`StructuredInput`] fuzzes a typed value, generated from a byte buffer with [`arbitrary`],
+//! like in cargo-fuzz, so the byte mutators can be used for structure-aware fuzzing.
+
+use ahash::AHasher;
+use alloc::{borrow::ToOwned, string::String, vec::Vec};
+use arbitrary::{Arbitrary, Unstructured};
+use core::{
+    fmt::{self, Debug},
+    hash::Hasher,
+    marker::PhantomData,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+#[cfg(feature = "std")]
+use std::{fs::File, io::Read, path::Path};
+
+#[cfg(feature = "std")]
+use crate::bolts::fs::write_file_atomic;
+use crate::{
+    bolts::{ownedref::OwnedSlice, HasLen},
+    inputs::{HasBytesVec, HasTargetBytes, Input},
+    Error,
+};
+
+/// An input holding the raw bytes a value of type `T` is generated from, with
+/// [`Arbitrary::arbitrary_take_rest`]. The mutators change the bytes, so every mutation yields
+/// a valid `T`. The target gets the value serialized with `postcard`, see [`Self::target_bytes`].
+/// Files hold the raw bytes, so cargo-fuzz corpora can be used as seeds.
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct StructuredInput<T>
+where
+    T: for<'a> Arbitrary<'a> + Serialize + DeserializeOwned,
+{
+    /// The raw bytes, to generate the value from
+    bytes: Vec<u8>,
+    phantom: PhantomData<T>,
+}
+
+impl<T> Clone for StructuredInput<T>
+where
+    T: for<'a> Arbitrary<'a> + Serialize + DeserializeOwned,
+{
+    fn clone(&self) -> Self {
+        Self::new(self.bytes.clone())
+    }
+}
+
+impl<T> Debug for StructuredInput<T>
+where
+    T: for<'a> Arbitrary<'a> + Serialize + DeserializeOwned,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StructuredInput")
+            .field("bytes", &self.bytes)
+            .finish()
+    }
+}
+
+impl<T> Input for StructuredInput<T>
+where
+    T: for<'a> Arbitrary<'a> + Serialize + DeserializeOwned,
+{
+    #[cfg(feature = "std")]
+    /// Write the raw bytes of this input to the file
+    fn to_file<P>(&self, path: P) -> Result<(), Error>
+    where
+        P: AsRef<Path>,
+    {
+        write_file_atomic(path, &self.bytes)
+    }
+
+    /// The contents [`Input::to_file`] writes to the file
+    fn to_file_bytes(&self) -> Result<Vec<u8>, Error> {
+        Ok(self.bytes.clone())
+    }
+
+    /// Load the raw bytes of this input from a file
+    #[cfg(feature = "std")]
+    fn from_file<P>(path: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let mut file = File::open(path)?;
+        let mut bytes: Vec<u8> = vec![];
+        file.read_to_end(&mut bytes)?;
+        Ok(Self::new(bytes))
+    }
+
+    /// Generate a name for this input
+    fn generate_name(&self, _idx: usize) -> String {
+        let mut hasher = AHasher::new_with_keys(0, 0);
+        hasher.write(&self.bytes);
+        format!("{:016x}", hasher.finish())
+    }
+
+    #[inline]
+    fn truncate(&mut self, max_size: usize) {
+        self.bytes.truncate(max_size);
+    }
+}
+
+impl<T> HasBytesVec for StructuredInput<T>
+where
+    T: for<'a> Arbitrary<'a> + Serialize + DeserializeOwned,
+{
+    #[inline]
+    fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    #[inline]
+    fn bytes_mut(&mut self) -> &mut Vec<u8> {
+        &mut self.bytes
+    }
+}
+
+impl<T> HasTargetBytes for StructuredInput<T>
+where
+    T: for<'a> Arbitrary<'a> + Serialize + DeserializeOwned,
+{
+    /// The value, serialized with `postcard`.
+    /// Empty if the bytes don't generate a value, see [`StructuredInput::value`].
+    fn target_bytes(&self) -> OwnedSlice<u8> {
+        OwnedSlice::Owned(
+            self.value()
+                .and_then(|value| Ok(postcard::to_allocvec(&value)?))
+                .unwrap_or_default(),
+        )
+    }
+}
+
+impl<T> HasLen for StructuredInput<T>
+where
+    T: for<'a> Arbitrary<'a> + Serialize + DeserializeOwned,
+{
+    #[inline]
+    fn len(&self) -> usize {
+        self.bytes.len()
+    }
+}
+
+impl<T> From<Vec<u8>> for StructuredInput<T>
+where
+    T: for<'a> Arbitrary<'a> + Serialize + DeserializeOwned,
+{
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::new(bytes)
+    }
+}
+
+impl<T> From<&[u8]> for StructuredInput<T>
+where
+    T: for<'a> Arbitrary<'a> + Serialize + DeserializeOwned,
+{
+    fn from(bytes: &[u8]) -> Self {
+        Self::new(bytes.to_owned())
+    }
+}
+
+impl<T> StructuredInput<T>
+where
+    T: for<'a> Arbitrary<'a> + Serialize + DeserializeOwned,
+{
+    /// Creates a new [`StructuredInput`], generating its value from the given bytes
+    #[must_use]
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self {
+            bytes,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Generates the value from the bytes, as the harness of cargo-fuzz does.
+    ///
+    /// # Errors
+    /// Fails if `T` rejects the bytes, for example if it requires a format they don't follow
+    pub fn value(&self) -> Result<T, Error> {
+        T::arbitrary_take_rest(Unstructured::new(&self.bytes))
+            .map_err(|err| Error::IllegalArgument(format!("Cannot generate the value: {}", err)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arbitrary::{Arbitrary, Unstructured};
+    use serde::{Deserialize, Serialize};
+
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::{Corpus, InMemoryCorpus, Testcase},
+        inputs::{HasTargetBytes, StructuredInput},
+        mutators::{havoc_mutations, Mutator, StdScheduledMutator},
+        state::{HasCorpus, StdState},
+    };
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Packet {
+        kind: u8,
+        flags: bool,
+        payload: Vec<u16>,
+    }
+
+    impl<'a> Arbitrary<'a> for Packet {
+        fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+            Ok(Self {
+                kind: u.arbitrary()?,
+                flags: u.arbitrary()?,
+                payload: u.arbitrary()?,
+            })
+        }
+    }
+
+    fn assert_round_trip(input: &StructuredInput<Packet>) {
+        let value = input.value().unwrap();
+        let target_bytes = input.target_bytes();
+        let parsed: Packet = postcard::from_bytes(target_bytes.as_slice()).unwrap();
+        assert_eq!(parsed, value);
+    }
+
+    #[test]
+    fn test_structured_input() {
+        let input = StructuredInput::<Packet>::new(vec![7, 1, 1, 0, 2, 0, 3, 0]);
+        assert_eq!(input.value().unwrap().kind, 7);
+        assert_round_trip(&input);
+
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<StructuredInput<Packet>>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        state
+            .corpus_mut()
+            .add(Testcase::new(StructuredInput::new(vec![1, 0, 42, 0])))
+            .unwrap();
+
+        // Any mutated bytes still generate a valid packet
+        let mut mutator = StdScheduledMutator::new(havoc_mutations());
+        let mut mutated = input;
+        for _ in 0..256 {
+            mutator.mutate(&mut state, &mut mutated, 0).unwrap();
+            assert_round_trip(&mutated);
+        }
+    }
+}
@@ -112,7 +112,7 @@ pub enum Error {
     /// Serialization error
     Serialize(String),
     /// Compression error
-    #[cfg(feature = "llmp_compression")]
+    #[cfg(any(feature = "llmp_compression", feature = "corpus_compression"))]
     Compression,
     /// File related error
     #[cfg(feature = "std")]
@@ -135,6 +135,10 @@ pub enum Error {
     Forkserver(String),
     /// MOpt related Error
     MOpt(String),
+    /// A serialized [`bolts::serdeany::SerdeAny`] type is not registered in this build
+    UnknownSerdeType(String),
+    /// The testcase was not added to the corpus, the testcase at this index already covers it
+    DuplicateTestcase(usize),
     /// Shutting down, not really an error.
     ShuttingDown,
     /// Something else happened
@@ -145,7 +149,7 @@ impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Self::Serialize(s) => write!(f, "Error in Serialization: `{0}`", &s),
-            #[cfg(feature = "llmp_compression")]
+            #[cfg(any(feature = "llmp_compression", feature = "corpus_compression"))]
             Self::Compression => write!(f, "Error in decompression"),
             #[cfg(feature = "std")]
             Self::File(err) => write!(f, "File IO failed: {:?}", &err),
@@ -160,6 +164,14 @@ impl fmt::Display for Error {
             Self::IllegalArgument(s) => write!(f, "Illegal argument: {0}", &s),
             Self::Forkserver(s) => write!(f, "Forkserver : {0}", &s),
             Self::MOpt(s) => write!(f, "MOpt: {0}", &s),
+            Self::UnknownSerdeType(s) => write!(
+                f,
+                "Unknown serde type `{0}`, make sure it is registered in the current build",
+                &s
+            ),
+            Self::DuplicateTestcase(idx) => {
+                write!(f, "Duplicate of the testcase at index {0}, not added", idx)
+            }
             Self::ShuttingDown => write!(f, "Shutting down!"),
             Self::Unknown(s) => write!(f, "Unknown error: {0}", &s),
         }
@@ -177,6 +189,9 @@ impl From<postcard::Error> for Error {
 #[cfg(feature = "std")]
 impl From<serde_json::Error> for Error {
     fn from(err: serde_json::Error) -> Self {
+        if let Some(type_name) = bolts::serdeany::unknown_serde_type(&err.to_string()) {
+            return Self::UnknownSerdeType(type_name.into());
+        }
         Self::Serialize(format!("{:?}", err))
     }
 }
@@ -62,6 +62,11 @@ pub struct ClientStats {
     pub user_monitor: HashMap<String, UserStats>,
     /// Stability, and if we ever received a stability value
     pub stability: Option<f32>,
+    /// The coverage of this client, one byte per map entry, `1` if covered,
+    /// as of the last [`crate::events::Event::CoverageSnapshot`]
+    pub coverage: Vec<u8>,
+    /// The epoch of the last coverage snapshot
+    pub coverage_epoch: u64,
     /// Client performance statistics
     #[cfg(feature = "introspection")]
     pub introspection_monitor: ClientPerfMonitor,
@@ -81,6 +86,26 @@ impl ClientStats {
         self.executions = executions;
     }
 
+    /// We got a new coverage snapshot for this client, see [`crate::events::Event::CoverageSnapshot`].
+    /// A full snapshot replaces the coverage, one byte per entry, `1` if covered.
+    /// A `delta` snapshot holds the little-endian `u32` indices of newly covered entries,
+    /// added to the coverage of the previous snapshots.
+    pub fn update_coverage(&mut self, map: &[u8], epoch: u64, delta: bool) {
+        if delta {
+            for idx in map.chunks_exact(4) {
+                let idx = u32::from_le_bytes([idx[0], idx[1], idx[2], idx[3]]) as usize;
+                if idx >= self.coverage.len() {
+                    self.coverage.resize(idx + 1, 0);
+                }
+                self.coverage[idx] = 1;
+            }
+        } else {
+            self.coverage.clear();
+            self.coverage.extend_from_slice(map);
+        }
+        self.coverage_epoch = epoch;
+    }
+
     /// We got a new information about corpus size for this client, insert them.
     pub fn update_corpus_size(&mut self, corpus_size: u64) {
         self.corpus_size = corpus_size;
@@ -408,6 +433,12 @@ pub struct ClientPerfMonitor {
     /// Clock cycles spent in the the various features of each stage
     stages: Vec<[u64; PerfFeature::Count as usize]>,
 
+    /// Name and clock cycles spent in each stage, in the order the stages finish
+    stage_cycles: Vec<(String, u64)>,
+
+    /// Position of the next stage to finish in the current fuzz loop iteration
+    curr_timed_stage: usize,
+
     /// Clock cycles spent in each feedback mechanism of the fuzzer.
     feedbacks: HashMap<String, u64>,
 
@@ -517,6 +548,8 @@ impl ClientPerfMonitor {
             curr_stage: 0,
             stages: vec![],
             stages_used: vec![],
+            stage_cycles: vec![],
+            curr_timed_stage: 0,
             feedbacks: HashMap::new(),
             timer_start: None,
         }
@@ -540,6 +573,7 @@ impl ClientPerfMonitor {
         self.update_scheduler(monitor.scheduler);
         self.update_manager(monitor.manager);
         self.update_stages(&monitor.stages);
+        self.update_stage_cycles(&monitor.stage_cycles);
         self.update_feedbacks(&monitor.feedbacks);
     }
 
@@ -629,6 +663,36 @@ impl ClientPerfMonitor {
     #[inline]
     pub fn reset_stage_index(&mut self) {
         self.curr_stage = 0;
+        self.curr_timed_stage = 0;
+    }
+
+    /// Adds the clock cycles `time` a whole stage named `name` took to the next stage position
+    /// of the current fuzz loop iteration. Called by the [`crate::stages::StagesTuple`].
+    pub fn mark_stage_cycles(&mut self, name: &str, time: u64) {
+        if self.curr_timed_stage >= self.stage_cycles.len() {
+            self.stage_cycles
+                .resize(self.curr_timed_stage + 1, (String::new(), 0));
+        }
+        let (stage_name, cycles) = &mut self.stage_cycles[self.curr_timed_stage];
+        if stage_name != name {
+            *stage_name = name.into();
+        }
+        *cycles = cycles.saturating_add(time);
+        self.curr_timed_stage += 1;
+    }
+
+    /// Update the clock cycles spent in each stage
+    pub fn update_stage_cycles(&mut self, stage_cycles: &[(String, u64)]) {
+        if self.stage_cycles.len() < stage_cycles.len() {
+            self.stage_cycles
+                .resize(stage_cycles.len(), (String::new(), 0));
+        }
+        for ((stage_name, cycles), (name, time)) in self.stage_cycles.iter_mut().zip(stage_cycles) {
+            if stage_name != name {
+                *stage_name = name.clone();
+            }
+            *cycles = cycles.saturating_add(*time);
+        }
     }
 
     /// Update the time spent in the feedback
@@ -723,6 +787,12 @@ impl ClientPerfMonitor {
     pub fn feedbacks(&self) -> &HashMap<String, u64> {
         &self.feedbacks
     }
+
+    /// The name and clock cycles spent in each stage, in the order the stages finish
+    #[must_use]
+    pub fn stage_cycles(&self) -> &[(String, u64)] {
+        &self.stage_cycles
+    }
 }
 
 #[cfg(feature = "introspection")]
@@ -774,6 +844,17 @@ impl core::fmt::Display for ClientPerfMonitor {
             }
         }
 
+        // The share of each whole stage in the time spent in all stages.
+        // Stages overlap with the features above, so they are not taken from the other percent.
+        let stages_total: u64 = self.stage_cycles.iter().map(|(_, cycles)| cycles).sum();
+        if stages_total > 0 {
+            writeln!(f, "  Stage time:")?;
+            for (stage_name, cycles) in &self.stage_cycles {
+                let stage_percent = *cycles as f64 / stages_total as f64;
+                writeln!(f, "    {:6.4}: {}", stage_percent, stage_name)?;
+            }
+        }
+
         writeln!(f, "  Feedbacks:")?;
 
         for (feedback_name, feedback_time) in self.feedbacks() {
@@ -1,6 +1,6 @@
 //! Monitor to disply both cumulative and per-client monitor
 
-use alloc::{string::String, vec::Vec};
+use alloc::{collections::BTreeSet, string::String, vec::Vec};
 use core::time::Duration;
 
 #[cfg(feature = "introspection")]
@@ -20,6 +20,23 @@ where
     print_fn: F,
     start_time: Duration,
     client_stats: Vec<ClientStats>,
+    table_mode: bool,
+    /// The ids of all clients that reported so far, for the table
+    reported: BTreeSet<u32>,
+    /// Whether each table overwrites the previous one in place, with terminal escape sequences
+    overwrite: bool,
+    /// The number of lines of the last printed table and its global line, to overwrite them
+    table_lines: usize,
+}
+
+/// Returns `true` if stdout is a terminal, to use escape sequences only there
+fn stdout_is_tty() -> bool {
+    #[cfg(unix)]
+    unsafe {
+        libc::isatty(libc::STDOUT_FILENO) == 1
+    }
+    #[cfg(not(unix))]
+    false
 }
 
 impl<F> Monitor for MultiMonitor<F>
@@ -59,33 +76,45 @@ where
             self.total_execs(),
             self.execs_per_sec()
         );
-        (self.print_fn)(global_fmt);
-
-        let client = self.client_stats_mut_for(sender_id);
-        let cur_time = current_time();
-        let exec_sec = client.execs_per_sec(cur_time);
+        if self.table_mode {
+            self.reported.insert(sender_id);
+            self.client_stats_mut_for(sender_id);
+            let table = self.render_table(&global_fmt);
+            (self.print_fn)(table);
+        } else {
+            (self.print_fn)(global_fmt);
+            let client = self.client_stats_mut_for(sender_id);
+            let cur_time = current_time();
+            let exec_sec = client.execs_per_sec(cur_time);
 
-        let pad = " ".repeat(head.len());
-        let mut fmt = format!(
-            " {}   (CLIENT) corpus: {}, objectives: {}, executions: {}, exec/sec: {}",
-            pad, client.corpus_size, client.objective_size, client.executions, exec_sec
-        );
-        for (key, val) in &client.user_monitor {
-            fmt += &format!(", {}: {}", key, val);
+            let pad = " ".repeat(head.len());
+            let mut fmt = format!(
+                " {}   (CLIENT) corpus: {}, objectives: {}, executions: {}, exec/sec: {}",
+                pad, client.corpus_size, client.objective_size, client.executions, exec_sec
+            );
+            for (key, val) in &client.user_monitor {
+                fmt += &format!(", {}: {}", key, val);
+            }
+            (self.print_fn)(fmt);
         }
-        (self.print_fn)(fmt);
 
         // Only print perf monitor if the feature is enabled
         #[cfg(feature = "introspection")]
         {
             // Print the client performance monitor. Skip the Client 0 which is the broker
+            let mut perf_lines = 0;
             for (i, client) in self.client_stats.iter().skip(1).enumerate() {
                 let fmt = format!("Client {:03}:\n{}", i + 1, client.introspection_monitor);
+                perf_lines += fmt.split('\n').count();
                 (self.print_fn)(fmt);
             }
 
             // Separate the spacing just a bit
             (self.print_fn)("\n".to_string());
+            // The table gets overwritten together with these lines
+            if self.table_mode {
+                self.table_lines += perf_lines + 2;
+            }
         }
     }
 }
@@ -100,6 +129,10 @@ where
             print_fn,
             start_time: current_time(),
             client_stats: vec![],
+            table_mode: false,
+            reported: BTreeSet::new(),
+            overwrite: false,
+            table_lines: 0,
         }
     }
 
@@ -109,6 +142,112 @@ where
             print_fn,
             start_time,
             client_stats: vec![],
+            table_mode: false,
+            reported: BTreeSet::new(),
+            overwrite: false,
+            table_lines: 0,
         }
     }
+
+    /// Renders the clients as a fixed-layout table instead, one line per client, ordered by client id.
+    /// If stdout is a terminal, each table overwrites the previous one in place, so long-run logs
+    /// stay readable. Otherwise, the tables are printed one after the other.
+    #[must_use]
+    pub fn table_mode(mut self) -> Self {
+        self.table_mode = true;
+        self.overwrite = stdout_is_tty();
+        self
+    }
+
+    /// Sets whether each table overwrites the previous one in place, for output that does not go
+    /// to stdout. Only has an effect in [`Self::table_mode`].
+    #[must_use]
+    pub fn overwrite_in_place(mut self, overwrite: bool) -> Self {
+        self.overwrite = overwrite;
+        self
+    }
+
+    /// Renders the `global` line, then the table of all clients that reported so far, ordered by
+    /// client id. If overwriting in place, starts with the escape sequence moving the cursor back
+    /// over the previous global line and table, if any.
+    fn render_table(&mut self, global: &str) -> String {
+        let cur_time = current_time();
+        let mut lines = Vec::with_capacity(self.reported.len());
+        for id in &self.reported {
+            let client = &mut self.client_stats[*id as usize];
+            let exec_sec = client.execs_per_sec(cur_time);
+            let mut line = format!(
+                "  #{:<4} corpus: {:>8}, objectives: {:>6}, executions: {:>12}, exec/sec: {:>8}",
+                id, client.corpus_size, client.objective_size, client.executions, exec_sec
+            );
+            // Sorted, so that the layout doesn't change between updates
+            let mut user_monitor: Vec<_> = client.user_monitor.iter().collect();
+            user_monitor.sort_by(|a, b| a.0.cmp(b.0));
+            for (key, val) in user_monitor {
+                line += &format!(", {}: {}", key, val);
+            }
+            lines.push(line);
+        }
+
+        let mut table = if self.overwrite && self.table_lines > 0 {
+            // Move up over the last global line and table, and clear them
+            format!("\x1b[{}A\x1b[J", self.table_lines)
+        } else {
+            String::new()
+        };
+        table += global;
+        for line in &lines {
+            table.push('\n');
+            table += line;
+        }
+        self.table_lines = lines.len() + 1;
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{rc::Rc, string::String, vec::Vec};
+    use core::cell::RefCell;
+
+    use crate::monitors::{Monitor, MultiMonitor};
+
+    #[test]
+    fn test_multi_monitor_table_mode() {
+        let printed = Rc::new(RefCell::new(Vec::<String>::new()));
+        let printed_clone = printed.clone();
+        let mut monitor = MultiMonitor::new(move |s| printed_clone.borrow_mut().push(s))
+            .table_mode()
+            .overwrite_in_place(true);
+
+        for (id, corpus_size) in [(3, 30), (1, 10), (2, 20), (1, 11)] {
+            monitor
+                .client_stats_mut_for(id)
+                .update_corpus_size(corpus_size);
+            monitor.display("Testcase".into(), id);
+        }
+
+        let printed = printed.borrow();
+        // The global line and the table, at once
+        let tables: Vec<&String> = printed.iter().filter(|s| s.contains("(GLOBAL)")).collect();
+        assert_eq!(tables.len(), 4);
+        let table = tables.last().unwrap();
+        // The previous global line and table, with the same three clients, get overwritten
+        assert!(table.starts_with("\x1b["));
+        #[cfg(not(feature = "introspection"))]
+        assert!(table.starts_with("\x1b[4A\x1b[J"));
+        assert!(table.lines().next().unwrap().contains("(GLOBAL)"));
+        let ids: Vec<&str> = table
+            .lines()
+            .skip(1)
+            .map(|line| {
+                line.trim_start_matches(|c| c != '#')
+                    .split(' ')
+                    .next()
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(ids, vec!["#1", "#2", "#3"]);
+        assert!(table.lines().nth(1).unwrap().contains("corpus:       11"));
+    }
 }
@@ -0,0 +1,188 @@
+//! Sets integer fields known to be constrained, for example lengths and sizes, to boundary values.
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::{rands::Rand, tuples::Named},
+    inputs::{HasBytesVec, Input},
+    mutators::{MutationResult, Mutator},
+    state::{HasMetadata, HasRand},
+    Error,
+};
+
+/// An integer field of the current input, that the target checks against other values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConstrainedField {
+    /// The offset of the field in the input
+    pub offset: usize,
+    /// The width of the field in bytes: 1, 2, 4 or 8
+    pub width: usize,
+    /// `true` if the field is stored big endian
+    pub big_endian: bool,
+}
+
+/// A state metadata listing the [`ConstrainedField`]s of the current input, as found by
+/// colorization or other input-to-state analyses, for the [`IntBoundaryMutator`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConstrainedFieldsMetadata {
+    /// The constrained fields
+    pub list: Vec<ConstrainedField>,
+}
+
+crate::impl_serdeany!(ConstrainedFieldsMetadata);
+
+impl ConstrainedFieldsMetadata {
+    /// Creates a new, empty [`ConstrainedFieldsMetadata`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self { list: vec![] }
+    }
+
+    /// Records a field of `width` bytes at `offset`. Widths other than 1, 2, 4 and 8 are ignored.
+    pub fn add(&mut self, offset: usize, width: usize, big_endian: bool) {
+        if matches!(width, 1 | 2 | 4 | 8) {
+            self.list.push(ConstrainedField {
+                offset,
+                width,
+                big_endian,
+            });
+        }
+    }
+}
+
+/// The boundary values of an unsigned integer of `width` bytes: 0, 1, MAX, MAX - 1, and the
+/// signed MAX and MIN, as unsigned
+fn boundary_values(width: usize) -> [u64; 6] {
+    let max = u64::MAX >> (64 - 8 * width);
+    let signed_max = max >> 1;
+    [0, 1, max, max - 1, signed_max, signed_max + 1]
+}
+
+/// Sets one of the [`ConstrainedField`]s in the [`ConstrainedFieldsMetadata`] of the state to a
+/// boundary value: `0`, `1`, `MAX`, `MAX - 1`, or the signed extremes, to find integer
+/// overflows in size and length checks. Skips if no recorded field fits in the input.
+#[derive(Debug, Default)]
+pub struct IntBoundaryMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    R: Rand,
+    S: HasRand<R> + HasMetadata,
+{
+    phantom: PhantomData<(I, R, S)>,
+}
+
+impl<I, R, S> Mutator<I, S> for IntBoundaryMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    R: Rand,
+    S: HasRand<R> + HasMetadata,
+{
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut I,
+        _stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        let size = input.bytes().len();
+        let fields: Vec<ConstrainedField> =
+            match state.metadata().get::<ConstrainedFieldsMetadata>() {
+                Some(meta) => meta
+                    .list
+                    .iter()
+                    .filter(|field| field.offset + field.width <= size)
+                    .copied()
+                    .collect(),
+                None => return Ok(MutationResult::Skipped),
+            };
+        if fields.is_empty() {
+            return Ok(MutationResult::Skipped);
+        }
+
+        let field = *state.rand_mut().choose(&fields);
+        let val = *state.rand_mut().choose(&boundary_values(field.width));
+        let range = field.offset..field.offset + field.width;
+        if field.big_endian {
+            input.bytes_mut()[range.clone()].copy_from_slice(&val.to_be_bytes()[8 - field.width..]);
+        } else {
+            input.bytes_mut()[range.clone()].copy_from_slice(&val.to_le_bytes()[..field.width]);
+        }
+        input.mark_dirty(range);
+        Ok(MutationResult::Mutated)
+    }
+}
+
+impl<I, R, S> Named for IntBoundaryMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    R: Rand,
+    S: HasRand<R> + HasMetadata,
+{
+    fn name(&self) -> &str {
+        "IntBoundaryMutator"
+    }
+}
+
+impl<I, R, S> IntBoundaryMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    R: Rand,
+    S: HasRand<R> + HasMetadata,
+{
+    /// Creates a new [`IntBoundaryMutator`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            phantom: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::InMemoryCorpus,
+        inputs::{BytesInput, HasBytesVec},
+        mutators::{ConstrainedFieldsMetadata, IntBoundaryMutator, MutationResult, Mutator},
+        state::{HasMetadata, StdState},
+    };
+
+    #[test]
+    fn test_int_boundary_mutator() {
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let mut mutator = IntBoundaryMutator::new();
+        let mut input = BytesInput::new(vec![0x11; 8]);
+        assert_eq!(
+            mutator.mutate(&mut state, &mut input, 0).unwrap(),
+            MutationResult::Skipped
+        );
+
+        // A 4 byte length field at offset 2, and one that does not fit in the input
+        let mut meta = ConstrainedFieldsMetadata::new();
+        meta.add(2, 4, false);
+        meta.add(6, 4, false);
+        state.add_metadata(meta);
+
+        let mut set_max = false;
+        for _ in 0..64 {
+            let mut input = BytesInput::new(vec![0x11; 8]);
+            assert_eq!(
+                mutator.mutate(&mut state, &mut input, 0).unwrap(),
+                MutationResult::Mutated
+            );
+            assert_eq!(input.bytes()[..2], [0x11; 2]);
+            assert_eq!(input.bytes()[6..], [0x11; 2]);
+            let val = u32::from_le_bytes(input.bytes()[2..6].try_into().unwrap());
+            assert!([0, 1, u32::MAX, u32::MAX - 1, 0x7fff_ffff, 0x8000_0000].contains(&val));
+            set_max |= val == u32::MAX;
+        }
+        assert!(set_max);
+    }
+}
@@ -0,0 +1,267 @@
+//! The [`EntropyBoundaryMutator`] mutates the input where its byte entropy changes sharply,
+//! such as between a header and a compressed payload, a heuristic for format boundaries.
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use crate::{
+    bolts::{rands::Rand, tuples::Named},
+    inputs::{HasBytesVec, Input},
+    mutators::{MutationResult, Mutator},
+    state::{HasMaxSize, HasRand},
+    Error,
+};
+
+/// The default number of bytes on each side of a position the entropy is computed over
+pub const DEFAULT_ENTROPY_WINDOW: usize = 16;
+
+/// The minimum difference, in bits per byte, between the entropy before and after a position,
+/// for it to count as a boundary
+pub const ENTROPY_BOUNDARY_THRESHOLD: f64 = 1.0;
+
+/// The maximum number of bytes the [`EntropyBoundaryMutator`] inserts at once
+pub const ENTROPY_BOUNDARY_MAX_INSERT: u64 = 4;
+
+/// `c * log2(c)`, the contribution of a byte seen `c` times to the entropy of a window
+fn c_log_c(count: u32) -> f64 {
+    if count == 0 {
+        0.0
+    } else {
+        f64::from(count) * libm::log2(f64::from(count))
+    }
+}
+
+/// The byte counts of a window, and the sum of their [`c_log_c`], to update the entropy in O(1)
+struct EntropyWindow {
+    counts: [u32; 256],
+    sum: f64,
+    len: usize,
+}
+
+impl EntropyWindow {
+    fn new(bytes: &[u8]) -> Self {
+        let mut window = Self {
+            counts: [0; 256],
+            sum: 0.0,
+            len: bytes.len(),
+        };
+        for byte in bytes {
+            window.counts[usize::from(*byte)] += 1;
+        }
+        window.sum = window.counts.iter().map(|count| c_log_c(*count)).sum();
+        window
+    }
+
+    /// Replaces one occurrence of `old` in the window with `new`
+    fn replace(&mut self, old: u8, new: u8) {
+        for (byte, add) in [(old, false), (new, true)] {
+            let count = &mut self.counts[usize::from(byte)];
+            self.sum -= c_log_c(*count);
+            if add {
+                *count += 1;
+            } else {
+                *count -= 1;
+            }
+            self.sum += c_log_c(*count);
+        }
+    }
+
+    /// The Shannon entropy of the window, in bits per byte
+    #[allow(clippy::cast_precision_loss)]
+    fn entropy(&self) -> f64 {
+        let len = self.len as f64;
+        libm::log2(len) - self.sum / len
+    }
+}
+
+/// The positions in `bytes` where the entropy of the `window` bytes before differs from the
+/// entropy of the `window` bytes after by at least [`ENTROPY_BOUNDARY_THRESHOLD`].
+/// A sharp change shows up as a run of consecutive positions around it.
+#[must_use]
+pub fn entropy_boundaries(bytes: &[u8], window: usize) -> Vec<usize> {
+    let mut boundaries = vec![];
+    if window == 0 || bytes.len() < 2 * window {
+        return boundaries;
+    }
+    let mut before = EntropyWindow::new(&bytes[..window]);
+    let mut after = EntropyWindow::new(&bytes[window..2 * window]);
+    let mut pos = window;
+    loop {
+        if (before.entropy() - after.entropy()).abs() >= ENTROPY_BOUNDARY_THRESHOLD {
+            boundaries.push(pos);
+        }
+        if pos + window == bytes.len() {
+            break;
+        }
+        // Slide both windows by one byte
+        before.replace(bytes[pos - window], bytes[pos]);
+        after.replace(bytes[pos], bytes[pos + window]);
+        pos += 1;
+    }
+    boundaries
+}
+
+/// Picks one of the [`entropy_boundaries`] of the input, and inserts up to
+/// [`ENTROPY_BOUNDARY_MAX_INSERT`] random bytes there, or sets the byte right at it, to hit
+/// format boundaries without a grammar. Inserts only within [`HasMaxSize::max_size`].
+/// Skips inputs without boundaries.
+#[derive(Debug)]
+pub struct EntropyBoundaryMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    window: usize,
+    phantom: PhantomData<(I, R, S)>,
+}
+
+impl<I, R, S> Mutator<I, S> for EntropyBoundaryMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut I,
+        _stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        let boundaries = entropy_boundaries(input.bytes(), self.window);
+        if boundaries.is_empty() {
+            return Ok(MutationResult::Skipped);
+        }
+        let pos = *state.rand_mut().choose(&boundaries);
+        let size = input.bytes().len();
+
+        let room = state.max_size().saturating_sub(size) as u64;
+        if room > 0 && state.rand_mut().below(2) == 0 {
+            let len = state
+                .rand_mut()
+                .between(1, ENTROPY_BOUNDARY_MAX_INSERT.min(room)) as usize;
+            let bytes: Vec<u8> = (0..len)
+                .map(|_| state.rand_mut().below(256) as u8)
+                .collect();
+            input.bytes_mut().splice(pos..pos, bytes);
+            input.mark_dirty(pos..size + len);
+        } else {
+            // Boundaries always have `window` bytes after them
+            let byte = &mut input.bytes_mut()[pos];
+            *byte ^= 1 + state.rand_mut().below(255) as u8;
+            input.mark_dirty(pos..pos + 1);
+        }
+        Ok(MutationResult::Mutated)
+    }
+}
+
+impl<I, R, S> Named for EntropyBoundaryMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    fn name(&self) -> &str {
+        "EntropyBoundaryMutator"
+    }
+}
+
+impl<I, R, S> EntropyBoundaryMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    /// Creates a new [`EntropyBoundaryMutator`], computing the entropy over
+    /// [`DEFAULT_ENTROPY_WINDOW`] bytes
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_window(DEFAULT_ENTROPY_WINDOW)
+    }
+
+    /// Creates a new [`EntropyBoundaryMutator`], computing the entropy over the `window` bytes
+    /// on each side of a position
+    ///
+    /// # Panics
+    /// Panics if `window` is smaller than 2, as a single byte has no entropy
+    #[must_use]
+    pub fn with_window(window: usize) -> Self {
+        assert!(window >= 2, "The entropy window must be at least 2 bytes");
+        Self {
+            window,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The number of bytes on each side of a position the entropy is computed over
+    #[must_use]
+    pub fn window(&self) -> usize {
+        self.window
+    }
+}
+
+impl<I, R, S> Default for EntropyBoundaryMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::InMemoryCorpus,
+        inputs::{BytesInput, HasBytesVec},
+        mutators::{entropy_boundaries, EntropyBoundaryMutator, MutationResult, Mutator},
+        state::{HasMaxSize, StdState},
+    };
+
+    #[test]
+    fn test_entropy_boundary_mutator() {
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        state.set_max_size(256);
+
+        // A low-entropy header, followed by a high-entropy payload
+        let mut original = vec![b'A'; 64];
+        original.extend((0..64_u8).map(|i| i.wrapping_mul(37) ^ 0x5a));
+        let near_boundary = |pos: usize| (49..80).contains(&pos);
+        let boundaries = entropy_boundaries(&original, 16);
+        assert!(!boundaries.is_empty());
+        assert!(boundaries.contains(&64));
+        assert!(boundaries.iter().all(|pos| near_boundary(*pos)));
+
+        let mut mutator = EntropyBoundaryMutator::with_window(16);
+        for _ in 0..64 {
+            let mut input = BytesInput::new(original.clone());
+            assert_eq!(
+                mutator.mutate(&mut state, &mut input, 0).unwrap(),
+                MutationResult::Mutated
+            );
+            // The first change is close to the boundary, not in the uniform parts
+            let changed = input
+                .bytes()
+                .iter()
+                .zip(&original)
+                .position(|(a, b)| a != b)
+                .unwrap_or(original.len());
+            assert!(near_boundary(changed), "changed at {}", changed);
+        }
+
+        // Uniform inputs have no boundaries
+        let mut input = BytesInput::new(vec![b'A'; 128]);
+        assert_eq!(
+            mutator.mutate(&mut state, &mut input, 0).unwrap(),
+            MutationResult::Skipped
+        );
+    }
+}
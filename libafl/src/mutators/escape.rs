@@ -0,0 +1,332 @@
+//! The [`EscapeToggleMutator`] escapes or unescapes a part of a text input, to reach the
+//! encoding and decoding code of text protocols.
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::{rands::Rand, tuples::Named},
+    inputs::{HasBytesVec, Input},
+    mutators::{MutationResult, Mutator},
+    state::{HasMaxSize, HasRand},
+    Error,
+};
+
+/// The maximum length of the part of the input the [`EscapeToggleMutator`] escapes
+pub const ESCAPE_TOGGLE_MAX_LEN: u64 = 16;
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+/// An escaping scheme
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EscapeScheme {
+    /// Percent-encoding, as in URLs: `%XX`
+    Url,
+    /// HTML character references: `&amp;`, `&lt;`, `&gt;`, `&quot;`, `&#39;`, and `&#NN;`
+    Html,
+    /// Backslash escapes, as in C strings: `\n`, `\t`, `\r`, `\0`, `\\`, `\"`, `\'`, and `\xNN`
+    Backslash,
+}
+
+impl EscapeScheme {
+    /// Appends the escaped form of `byte` to `out`. Every byte gets escaped, not only the ones
+    /// that have to be, so escaping always changes the input.
+    pub fn escape_byte(self, byte: u8, out: &mut Vec<u8>) {
+        match self {
+            EscapeScheme::Url => out.extend_from_slice(&[
+                b'%',
+                HEX_DIGITS[usize::from(byte >> 4)],
+                HEX_DIGITS[usize::from(byte & 0xf)],
+            ]),
+            EscapeScheme::Html => match byte {
+                b'&' => out.extend_from_slice(b"&amp;"),
+                b'<' => out.extend_from_slice(b"&lt;"),
+                b'>' => out.extend_from_slice(b"&gt;"),
+                b'"' => out.extend_from_slice(b"&quot;"),
+                _ => {
+                    out.extend_from_slice(b"&#");
+                    if byte >= 100 {
+                        out.push(b'0' + byte / 100);
+                    }
+                    if byte >= 10 {
+                        out.push(b'0' + byte / 10 % 10);
+                    }
+                    out.extend_from_slice(&[b'0' + byte % 10, b';']);
+                }
+            },
+            EscapeScheme::Backslash => match byte {
+                b'\n' => out.extend_from_slice(b"\\n"),
+                b'\t' => out.extend_from_slice(b"\\t"),
+                b'\r' => out.extend_from_slice(b"\\r"),
+                0 => out.extend_from_slice(b"\\0"),
+                b'\\' | b'"' | b'\'' => out.extend_from_slice(&[b'\\', byte]),
+                _ => out.extend_from_slice(&[
+                    b'\\',
+                    b'x',
+                    HEX_DIGITS[usize::from(byte >> 4)],
+                    HEX_DIGITS[usize::from(byte & 0xf)],
+                ]),
+            },
+        }
+    }
+
+    /// Escapes all of `bytes`
+    #[must_use]
+    pub fn escape(self, bytes: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(bytes.len() * 3);
+        for byte in bytes {
+            self.escape_byte(*byte, &mut out);
+        }
+        out
+    }
+
+    /// Decodes the escape sequences in `bytes`, and keeps everything else as is
+    #[must_use]
+    pub fn unescape(self, bytes: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(bytes.len());
+        let mut idx = 0;
+        while idx < bytes.len() {
+            if let Some((byte, len)) = self.unescape_at(&bytes[idx..]) {
+                out.push(byte);
+                idx += len;
+            } else {
+                out.push(bytes[idx]);
+                idx += 1;
+            }
+        }
+        out
+    }
+
+    /// Decodes the escape sequence at the start of `bytes`, returning the byte and its length
+    fn unescape_at(self, bytes: &[u8]) -> Option<(u8, usize)> {
+        match self {
+            EscapeScheme::Url => match bytes {
+                [b'%', high, low, ..] => Some((hex_value(*high)? << 4 | hex_value(*low)?, 3)),
+                _ => None,
+            },
+            EscapeScheme::Html => {
+                for (entity, byte) in [
+                    (&b"&amp;"[..], b'&'),
+                    (b"&lt;", b'<'),
+                    (b"&gt;", b'>'),
+                    (b"&quot;", b'"'),
+                    (b"&apos;", b'\''),
+                ] {
+                    if bytes.starts_with(entity) {
+                        return Some((byte, entity.len()));
+                    }
+                }
+                let end = bytes.iter().take(7).position(|b| *b == b';')?;
+                let (digits, radix) = match bytes.get(..end)? {
+                    [b'&', b'#', b'x' | b'X', digits @ ..] => (digits, 16),
+                    [b'&', b'#', digits @ ..] => (digits, 10),
+                    _ => return None,
+                };
+                if digits.is_empty() {
+                    return None;
+                }
+                let mut value: u32 = 0;
+                for digit in digits {
+                    let digit = char::from(*digit).to_digit(radix)?;
+                    value = value * radix + digit;
+                }
+                Some((u8::try_from(value).ok()?, end + 1))
+            }
+            EscapeScheme::Backslash => match bytes {
+                [b'\\', b'x', high, low, ..] => {
+                    Some((hex_value(*high)? << 4 | hex_value(*low)?, 4))
+                }
+                [b'\\', escaped, ..] => {
+                    let byte = match escaped {
+                        b'n' => b'\n',
+                        b't' => b'\t',
+                        b'r' => b'\r',
+                        b'0' => 0,
+                        b'\\' | b'"' | b'\'' => *escaped,
+                        _ => return None,
+                    };
+                    Some((byte, 2))
+                }
+                _ => None,
+            },
+        }
+    }
+}
+
+/// The value of a hex digit
+fn hex_value(digit: u8) -> Option<u8> {
+    char::from(digit).to_digit(16).map(|value| value as u8)
+}
+
+/// Picks a part of the input, and unescapes it, or, if that changes nothing, escapes it,
+/// according to the configured [`EscapeScheme`]. Escaping grows the input, so only as much of
+/// the part as fits in [`HasMaxSize::max_size`] gets escaped.
+#[derive(Debug)]
+pub struct EscapeToggleMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    scheme: EscapeScheme,
+    phantom: PhantomData<(I, R, S)>,
+}
+
+impl<I, R, S> Mutator<I, S> for EscapeToggleMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut I,
+        _stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        let size = input.bytes().len();
+        if size == 0 {
+            return Ok(MutationResult::Skipped);
+        }
+        let start = state.rand_mut().below(size as u64) as usize;
+        let max_len = ESCAPE_TOGGLE_MAX_LEN.min((size - start) as u64);
+        let end = start + state.rand_mut().between(1, max_len) as usize;
+
+        let part = &input.bytes()[start..end];
+        let unescaped = self.scheme.unescape(part);
+        let replacement = if unescaped == part {
+            // Escape as many bytes as fit
+            let room = state.max_size().saturating_sub(size);
+            let mut escaped = vec![];
+            let mut escaped_len = 0;
+            for byte in part {
+                let prev_len = escaped.len();
+                self.scheme.escape_byte(*byte, &mut escaped);
+                if escaped.len() - escaped_len - 1 > room {
+                    escaped.truncate(prev_len);
+                    break;
+                }
+                escaped_len += 1;
+            }
+            if escaped_len == 0 {
+                return Ok(MutationResult::Skipped);
+            }
+            escaped.extend_from_slice(&part[escaped_len..]);
+            escaped
+        } else {
+            unescaped
+        };
+
+        let new_size = size - (end - start) + replacement.len();
+        input.bytes_mut().splice(start..end, replacement);
+        input.mark_dirty(start..size.max(new_size));
+        Ok(MutationResult::Mutated)
+    }
+}
+
+impl<I, R, S> Named for EscapeToggleMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    fn name(&self) -> &str {
+        "EscapeToggleMutator"
+    }
+}
+
+impl<I, R, S> EscapeToggleMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    /// Creates a new [`EscapeToggleMutator`], for the given [`EscapeScheme`]
+    #[must_use]
+    pub fn new(scheme: EscapeScheme) -> Self {
+        Self {
+            scheme,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The [`EscapeScheme`] of this mutator
+    #[must_use]
+    pub fn scheme(&self) -> EscapeScheme {
+        self.scheme
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::InMemoryCorpus,
+        inputs::{BytesInput, HasBytesVec},
+        mutators::{EscapeScheme, EscapeToggleMutator, MutationResult, Mutator},
+        state::{HasMaxSize, StdState},
+    };
+
+    #[test]
+    fn test_escape_schemes() {
+        for scheme in [
+            EscapeScheme::Url,
+            EscapeScheme::Html,
+            EscapeScheme::Backslash,
+        ] {
+            let bytes: Vec<u8> = (0..=255).collect();
+            let escaped = scheme.escape(&bytes);
+            assert_ne!(escaped, bytes);
+            assert_eq!(scheme.unescape(&escaped), bytes);
+        }
+        assert_eq!(EscapeScheme::Url.escape(b"a b"), b"%61%20%62");
+        assert_eq!(
+            EscapeScheme::Html.unescape(b"&lt;&#x41;&#66;&bad;"),
+            b"<AB&bad;"
+        );
+        assert_eq!(EscapeScheme::Backslash.unescape(b"\\x41\\n\\q"), b"A\n\\q");
+    }
+
+    #[test]
+    fn test_escape_toggle_mutator() {
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        state.set_max_size(64);
+        let original = b"GET /index.html?q=a b".to_vec();
+        let mut mutator = EscapeToggleMutator::new(EscapeScheme::Url);
+
+        for _ in 0..32 {
+            let mut input = BytesInput::new(original.clone());
+            assert_eq!(
+                mutator.mutate(&mut state, &mut input, 0).unwrap(),
+                MutationResult::Mutated
+            );
+            // A part of the input got URL-encoded, decoding it gives back the original
+            assert!(input.bytes().contains(&b'%'));
+            assert!(input.bytes().len() > original.len());
+            assert!(input.bytes().len() <= 64);
+            assert_eq!(EscapeScheme::Url.unescape(input.bytes()), original);
+        }
+
+        // Escaped parts get decoded again
+        let decoded = (0..32).any(|_| {
+            let mut input = BytesInput::new(b"%41%42%43%44".to_vec());
+            mutator.mutate(&mut state, &mut input, 0).unwrap();
+            input.bytes().len() < 12
+        });
+        assert!(decoded);
+
+        // Only the room left within `max_size` gets used
+        state.set_max_size(original.len() + 5);
+        for _ in 0..32 {
+            let mut input = BytesInput::new(original.clone());
+            mutator.mutate(&mut state, &mut input, 0).unwrap();
+            assert!(input.bytes().len() <= original.len() + 5);
+        }
+    }
+}
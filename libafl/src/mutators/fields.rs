@@ -0,0 +1,165 @@
+//! The [`FieldReorderMutator`] shuffles the fields of delimiter-separated inputs,
+//! such as CSV lines or `KEY=VAL` lists.
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use crate::{
+    bolts::{rands::Rand, tuples::Named},
+    inputs::{HasBytesVec, Input},
+    mutators::{MutationResult, Mutator},
+    state::HasRand,
+    Error,
+};
+
+/// Splits the input on a delimiter, randomly permutes the fields, and joins them again.
+/// The length, the delimiters, and the contents of each field stay the same, only their order
+/// changes, to reach parser paths depending on it without a grammar.
+/// Skips inputs with fewer than two different fields.
+#[derive(Debug)]
+pub struct FieldReorderMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R>,
+    R: Rand,
+{
+    delim: Vec<u8>,
+    phantom: PhantomData<(I, R, S)>,
+}
+
+impl<I, R, S> Mutator<I, S> for FieldReorderMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R>,
+    R: Rand,
+{
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut I,
+        _stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        let mut fields = split_fields(input.bytes(), &self.delim);
+        if fields.len() < 2 || fields.iter().all(|field| *field == fields[0]) {
+            return Ok(MutationResult::Skipped);
+        }
+
+        // Fisher-Yates
+        let original = fields.clone();
+        for i in (1..fields.len()).rev() {
+            let j = state.rand_mut().below(i as u64 + 1) as usize;
+            fields.swap(i, j);
+        }
+        if fields == original {
+            // A rotation always changes the order, as the fields are not all the same
+            fields.rotate_left(1);
+        }
+
+        let size = input.bytes().len();
+        let mut bytes = Vec::with_capacity(size);
+        for (idx, field) in fields.iter().enumerate() {
+            if idx > 0 {
+                bytes.extend_from_slice(&self.delim);
+            }
+            bytes.extend_from_slice(field);
+        }
+        *input.bytes_mut() = bytes;
+        input.mark_dirty(0..size);
+
+        Ok(MutationResult::Mutated)
+    }
+}
+
+/// Splits `bytes` on each occurrence of `delim`
+fn split_fields(bytes: &[u8], delim: &[u8]) -> Vec<Vec<u8>> {
+    let mut fields = vec![];
+    let mut start = 0;
+    let mut idx = 0;
+    while idx + delim.len() <= bytes.len() {
+        if bytes[idx..].starts_with(delim) {
+            fields.push(bytes[start..idx].to_vec());
+            idx += delim.len();
+            start = idx;
+        } else {
+            idx += 1;
+        }
+    }
+    fields.push(bytes[start..].to_vec());
+    fields
+}
+
+impl<I, R, S> Named for FieldReorderMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R>,
+    R: Rand,
+{
+    fn name(&self) -> &str {
+        "FieldReorderMutator"
+    }
+}
+
+impl<I, R, S> FieldReorderMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R>,
+    R: Rand,
+{
+    /// Creates a new [`FieldReorderMutator`], for fields separated by `delim`
+    ///
+    /// # Panics
+    /// Panics if `delim` is empty
+    #[must_use]
+    pub fn new(delim: &[u8]) -> Self {
+        assert!(!delim.is_empty(), "The delimiter must not be empty");
+        Self {
+            delim: delim.to_vec(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::InMemoryCorpus,
+        inputs::{BytesInput, HasBytesVec},
+        mutators::{FieldReorderMutator, MutationResult, Mutator},
+        state::StdState,
+    };
+
+    #[test]
+    fn test_field_reorder_mutator() {
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let mut mutator = FieldReorderMutator::new(b"&");
+        let original = b"a=1&bb=22&ccc=333".to_vec();
+
+        for _ in 0..16 {
+            let mut input = BytesInput::new(original.clone());
+            assert_eq!(
+                mutator.mutate(&mut state, &mut input, 0).unwrap(),
+                MutationResult::Mutated
+            );
+            assert_ne!(input.bytes(), &original[..]);
+            assert_eq!(input.bytes().len(), original.len());
+
+            // The same fields, in another order
+            let mut fields: Vec<&[u8]> = input.bytes().split(|b| *b == b'&').collect();
+            fields.sort_unstable();
+            assert_eq!(fields, [&b"a=1"[..], b"bb=22", b"ccc=333"]);
+        }
+
+        // A single field
+        let mut input = BytesInput::new(b"a=1".to_vec());
+        assert_eq!(
+            mutator.mutate(&mut state, &mut input, 0).unwrap(),
+            MutationResult::Skipped
+        );
+    }
+}
@@ -0,0 +1,268 @@
+//! Byte-frequency-guided mutations, keeping the set bytes in the distribution of the corpus.
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::{rands::Rand, tuples::Named},
+    corpus::Corpus,
+    inputs::{HasBytesVec, Input},
+    mutators::{MutationResult, Mutator},
+    state::{HasCorpus, HasMetadata, HasRand},
+    Error,
+};
+
+/// A state metadata holding how often each byte value occurs, used by the [`FrequencyByteMutator`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ByteFrequencyMetadata {
+    counts: Vec<u64>,
+}
+
+crate::impl_serdeany!(ByteFrequencyMetadata);
+
+impl Default for ByteFrequencyMetadata {
+    fn default() -> Self {
+        Self {
+            counts: vec![0; 256],
+        }
+    }
+}
+
+impl ByteFrequencyMetadata {
+    /// Creates a new [`ByteFrequencyMetadata`] from the count of each byte value
+    #[must_use]
+    pub fn new(counts: [u64; 256]) -> Self {
+        Self {
+            counts: counts.to_vec(),
+        }
+    }
+
+    /// Creates a new [`ByteFrequencyMetadata`] from the bytes of all inputs in the `corpus`
+    pub fn from_corpus<C, I>(corpus: &C) -> Result<Self, Error>
+    where
+        C: Corpus<I>,
+        I: Input + HasBytesVec,
+    {
+        let mut ret = Self::default();
+        for idx in 0..corpus.count() {
+            ret.add_bytes(corpus.get(idx)?.borrow_mut().load_input()?.bytes());
+        }
+        Ok(ret)
+    }
+
+    /// Counts the given bytes into the table
+    pub fn add_bytes(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.counts[*byte as usize] += 1;
+        }
+    }
+
+    /// The count of each byte value
+    #[must_use]
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    /// The sum of all counts
+    #[must_use]
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// The byte value at position `pos` of the counts laid out one after the other, `None` if
+    /// `pos` is not below [`ByteFrequencyMetadata::total()`].
+    /// For a uniformly random `pos`, each byte value is picked proportionally to its count.
+    #[must_use]
+    pub fn byte_at(&self, mut pos: u64) -> Option<u8> {
+        for (byte, count) in self.counts.iter().enumerate() {
+            if pos < *count {
+                return Some(byte as u8);
+            }
+            pos -= count;
+        }
+        None
+    }
+}
+
+/// Sets a random byte to a value sampled from the [`ByteFrequencyMetadata`] of the state,
+/// instead of uniformly, to keep mutated inputs in the distribution of the format.
+/// It skips if the state has no [`ByteFrequencyMetadata`], or an empty one.
+/// Unless disabled, the bytes of each new corpus entry are counted into the table in
+/// [`Mutator::post_exec`], so the table follows the corpus as it grows.
+#[derive(Debug)]
+pub struct FrequencyByteMutator<C, I, R, S>
+where
+    C: Corpus<I>,
+    I: Input + HasBytesVec,
+    R: Rand,
+    S: HasRand<R> + HasCorpus<C, I> + HasMetadata,
+{
+    update: bool,
+    phantom: PhantomData<(C, I, R, S)>,
+}
+
+impl<C, I, R, S> Mutator<I, S> for FrequencyByteMutator<C, I, R, S>
+where
+    C: Corpus<I>,
+    I: Input + HasBytesVec,
+    R: Rand,
+    S: HasRand<R> + HasCorpus<C, I> + HasMetadata,
+{
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut I,
+        _stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        let size = input.bytes().len();
+        if size == 0 {
+            return Ok(MutationResult::Skipped);
+        }
+        let idx = state.rand_mut().below(size as u64) as usize;
+
+        let total = state
+            .metadata()
+            .get::<ByteFrequencyMetadata>()
+            .map_or(0, ByteFrequencyMetadata::total);
+        if total == 0 {
+            return Ok(MutationResult::Skipped);
+        }
+        let pos = state.rand_mut().below(total);
+        let byte = state
+            .metadata()
+            .get::<ByteFrequencyMetadata>()
+            .and_then(|meta| meta.byte_at(pos))
+            .unwrap();
+
+        input.bytes_mut()[idx] = byte;
+        input.mark_dirty(idx..idx + 1);
+        Ok(MutationResult::Mutated)
+    }
+
+    fn post_exec(
+        &mut self,
+        state: &mut S,
+        _stage_idx: i32,
+        corpus_idx: Option<usize>,
+    ) -> Result<(), Error> {
+        if let (true, Some(idx)) = (self.update, corpus_idx) {
+            let bytes = state
+                .corpus()
+                .get(idx)?
+                .borrow_mut()
+                .load_input()?
+                .bytes()
+                .to_vec();
+            if state.metadata().get::<ByteFrequencyMetadata>().is_none() {
+                state.add_metadata(ByteFrequencyMetadata::default());
+            }
+            state
+                .metadata_mut()
+                .get_mut::<ByteFrequencyMetadata>()
+                .unwrap()
+                .add_bytes(&bytes);
+        }
+        Ok(())
+    }
+}
+
+impl<C, I, R, S> Named for FrequencyByteMutator<C, I, R, S>
+where
+    C: Corpus<I>,
+    I: Input + HasBytesVec,
+    R: Rand,
+    S: HasRand<R> + HasCorpus<C, I> + HasMetadata,
+{
+    fn name(&self) -> &str {
+        "FrequencyByteMutator"
+    }
+}
+
+impl<C, I, R, S> FrequencyByteMutator<C, I, R, S>
+where
+    C: Corpus<I>,
+    I: Input + HasBytesVec,
+    R: Rand,
+    S: HasRand<R> + HasCorpus<C, I> + HasMetadata,
+{
+    /// Creates a new [`FrequencyByteMutator`], updating the table with each new corpus entry
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            update: true,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Sets whether new corpus entries are counted into the table, for example to keep a
+    /// provided table fixed
+    #[must_use]
+    pub fn with_update(mut self, update: bool) -> Self {
+        self.update = update;
+        self
+    }
+}
+
+impl<C, I, R, S> Default for FrequencyByteMutator<C, I, R, S>
+where
+    C: Corpus<I>,
+    I: Input + HasBytesVec,
+    R: Rand,
+    S: HasRand<R> + HasCorpus<C, I> + HasMetadata,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::{Corpus, InMemoryCorpus, Testcase},
+        inputs::{BytesInput, HasBytesVec},
+        mutators::{ByteFrequencyMetadata, FrequencyByteMutator, MutationResult, Mutator},
+        state::{HasCorpus, HasMetadata, StdState},
+    };
+
+    #[test]
+    fn test_frequency_byte_mutator() {
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let mut mutator = FrequencyByteMutator::new();
+        let mut input = BytesInput::new(vec![0]);
+        assert_eq!(
+            mutator.mutate(&mut state, &mut input, 0).unwrap(),
+            MutationResult::Skipped
+        );
+
+        // 'A' is three times as frequent as 'B'
+        let mut counts = [0; 256];
+        counts[b'A' as usize] = 3;
+        counts[b'B' as usize] = 1;
+        state.add_metadata(ByteFrequencyMetadata::new(counts));
+
+        let mut set = [0_u32; 256];
+        for _ in 0..4000 {
+            mutator.mutate(&mut state, &mut input, 0).unwrap();
+            set[input.bytes()[0] as usize] += 1;
+        }
+        assert_eq!(set[b'A' as usize] + set[b'B' as usize], 4000);
+        assert!((2800..3200).contains(&set[b'A' as usize]));
+
+        // New corpus entries are counted into the table
+        let idx = state
+            .corpus_mut()
+            .add(Testcase::new(BytesInput::new(b"CC".to_vec())))
+            .unwrap();
+        mutator.post_exec(&mut state, 0, Some(idx)).unwrap();
+        let meta = state.metadata().get::<ByteFrequencyMetadata>().unwrap();
+        assert_eq!(meta.counts()[b'C' as usize], 2);
+        assert_eq!(meta.total(), 6);
+    }
+}
@@ -0,0 +1,218 @@
+//! Discovers format magics shared by the corpus inputs, and replaces them with variants.
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use hashbrown::{HashMap, HashSet};
+
+use crate::{
+    bolts::{rands::Rand, tuples::Named},
+    corpus::Corpus,
+    inputs::{HasBytesVec, Input},
+    mutators::{MutationResult, Mutator},
+    state::{HasCorpus, HasRand},
+    Error,
+};
+
+/// The length of the magic values the [`CorpusMagicMutator`] looks for
+pub const CORPUS_MAGIC_LEN: usize = 4;
+
+/// The maximum number of magic values the [`CorpusMagicMutator`] keeps
+pub const CORPUS_MAGIC_MAX_COUNT: usize = 16;
+
+/// Finds the sequences of [`CORPUS_MAGIC_LEN`] bytes contained in at least half of the inputs of
+/// the `corpus`, and in at least two, most frequent first. Runs of a single byte, like zero
+/// padding, are not considered magic.
+pub fn find_corpus_magics<C, I>(corpus: &C) -> Result<Vec<[u8; CORPUS_MAGIC_LEN]>, Error>
+where
+    C: Corpus<I>,
+    I: Input + HasBytesVec,
+{
+    let mut counts: HashMap<[u8; CORPUS_MAGIC_LEN], usize> = HashMap::new();
+    for idx in 0..corpus.count() {
+        let mut testcase = corpus.get(idx)?.borrow_mut();
+        let seen: HashSet<[u8; CORPUS_MAGIC_LEN]> = testcase
+            .load_input()?
+            .bytes()
+            .windows(CORPUS_MAGIC_LEN)
+            .filter(|window| window.iter().any(|b| *b != window[0]))
+            .map(|window| window.try_into().unwrap())
+            .collect();
+        for magic in seen {
+            *counts.entry(magic).or_default() += 1;
+        }
+    }
+
+    let min_count = ((corpus.count() + 1) / 2).max(2);
+    let mut magics: Vec<_> = counts
+        .into_iter()
+        .filter(|(_, count)| *count >= min_count)
+        .collect();
+    magics.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+    magics.truncate(CORPUS_MAGIC_MAX_COUNT);
+    Ok(magics.into_iter().map(|(magic, _)| magic).collect())
+}
+
+/// Replaces an occurrence of a magic value in the input with a variant of it: a bit flipped,
+/// a byte set at random, the value incremented or decremented, or its bytes swapped.
+/// The magic values are found with [`find_corpus_magics`], once, the first time this mutator
+/// runs, so the initial corpus should be loaded by then.
+/// Skips if the input contains none of them.
+#[derive(Debug, Default)]
+pub struct CorpusMagicMutator<C, I, R, S>
+where
+    C: Corpus<I>,
+    I: Input + HasBytesVec,
+    R: Rand,
+    S: HasRand<R> + HasCorpus<C, I>,
+{
+    magics: Option<Vec<[u8; CORPUS_MAGIC_LEN]>>,
+    phantom: PhantomData<(C, I, R, S)>,
+}
+
+impl<C, I, R, S> Mutator<I, S> for CorpusMagicMutator<C, I, R, S>
+where
+    C: Corpus<I>,
+    I: Input + HasBytesVec,
+    R: Rand,
+    S: HasRand<R> + HasCorpus<C, I>,
+{
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut I,
+        _stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        if self.magics.is_none() {
+            self.magics = Some(find_corpus_magics(state.corpus())?);
+        }
+        let magics = self.magics.as_ref().unwrap();
+
+        let occurrences: Vec<(usize, [u8; CORPUS_MAGIC_LEN])> = input
+            .bytes()
+            .windows(CORPUS_MAGIC_LEN)
+            .enumerate()
+            .filter_map(|(off, window)| {
+                magics
+                    .iter()
+                    .find(|magic| magic[..] == *window)
+                    .map(|magic| (off, *magic))
+            })
+            .collect();
+        if occurrences.is_empty() {
+            return Ok(MutationResult::Skipped);
+        }
+
+        let (off, magic) = *state.rand_mut().choose(&occurrences);
+        let mut variant = magic;
+        while variant == magic {
+            let val = u32::from_le_bytes(magic);
+            variant = match state.rand_mut().below(5) {
+                0 => {
+                    let bit = state.rand_mut().below(8 * CORPUS_MAGIC_LEN as u64);
+                    (val ^ (1 << bit)).to_le_bytes()
+                }
+                1 => {
+                    let mut variant = magic;
+                    variant[state.rand_mut().below(CORPUS_MAGIC_LEN as u64) as usize] =
+                        state.rand_mut().next() as u8;
+                    variant
+                }
+                2 => val.wrapping_add(1).to_le_bytes(),
+                3 => val.wrapping_sub(1).to_le_bytes(),
+                _ => val.swap_bytes().to_le_bytes(),
+            };
+        }
+
+        input.bytes_mut()[off..off + CORPUS_MAGIC_LEN].copy_from_slice(&variant);
+        input.mark_dirty(off..off + CORPUS_MAGIC_LEN);
+        Ok(MutationResult::Mutated)
+    }
+}
+
+impl<C, I, R, S> Named for CorpusMagicMutator<C, I, R, S>
+where
+    C: Corpus<I>,
+    I: Input + HasBytesVec,
+    R: Rand,
+    S: HasRand<R> + HasCorpus<C, I>,
+{
+    fn name(&self) -> &str {
+        "CorpusMagicMutator"
+    }
+}
+
+impl<C, I, R, S> CorpusMagicMutator<C, I, R, S>
+where
+    C: Corpus<I>,
+    I: Input + HasBytesVec,
+    R: Rand,
+    S: HasRand<R> + HasCorpus<C, I>,
+{
+    /// Creates a new [`CorpusMagicMutator`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            magics: None,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The magic values found in the corpus, `None` before the first mutation
+    #[must_use]
+    pub fn magics(&self) -> Option<&[[u8; CORPUS_MAGIC_LEN]]> {
+        self.magics.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::{Corpus, InMemoryCorpus, Testcase},
+        inputs::{BytesInput, HasBytesVec},
+        mutators::{CorpusMagicMutator, MutationResult, Mutator},
+        state::{HasCorpus, StdState},
+    };
+
+    #[test]
+    fn test_corpus_magic_mutator() {
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        for bytes in [
+            &b"\x7fELF\x01\x02\x03"[..],
+            b"abc\x7fELFdef",
+            b"\x00\x00\x00\x00\x00\x7fELF",
+            b"nothing",
+        ] {
+            state
+                .corpus_mut()
+                .add(Testcase::new(BytesInput::new(bytes.to_vec())))
+                .unwrap();
+        }
+
+        let mut mutator = CorpusMagicMutator::new();
+        for _ in 0..16 {
+            let mut input = BytesInput::new(b"xx\x7fELFyy".to_vec());
+            assert_eq!(
+                mutator.mutate(&mut state, &mut input, 0).unwrap(),
+                MutationResult::Mutated
+            );
+            // Only the magic is replaced, with a variant
+            let bytes = input.bytes();
+            assert_eq!(bytes[..2], *b"xx");
+            assert_eq!(bytes[6..], *b"yy");
+            assert_ne!(bytes[2..6], *b"\x7fELF");
+        }
+        assert_eq!(mutator.magics().unwrap(), [*b"\x7fELF"]);
+
+        let mut input = BytesInput::new(b"no magic".to_vec());
+        assert_eq!(
+            mutator.mutate(&mut state, &mut input, 0).unwrap(),
+            MutationResult::Skipped
+        );
+    }
+}
@@ -12,12 +12,35 @@ pub mod mopt_mutator;
 pub use mopt_mutator::*;
 pub mod gramatron;
 pub use gramatron::*;
+pub mod region;
+pub use region::*;
+pub mod frequency;
+pub use frequency::*;
+pub mod boundary;
+pub use boundary::*;
+pub mod template;
+pub use template::*;
+pub mod magic;
+pub use magic::*;
+pub mod shrink;
+pub use shrink::*;
+pub mod fields;
+pub use fields::*;
+pub mod escape;
+pub use escape::*;
+pub mod entropy;
+pub use entropy::*;
 
 #[cfg(feature = "nautilus")]
 pub mod nautilus;
 #[cfg(feature = "nautilus")]
 pub use nautilus::*;
 
+#[cfg(feature = "structured_input")]
+pub mod structured;
+#[cfg(feature = "structured_input")]
+pub use structured::*;
+
 use crate::{
     bolts::tuples::{HasConstLen, Named},
     inputs::Input,
@@ -36,6 +59,10 @@ pub enum MutationResult {
     Mutated,
     /// The [`Mutator`] did not mutate this `Input`. It was `Skipped`.
     Skipped,
+    /// The mutations cancelled each other out, the `Input` is the same as before.
+    /// Only reported if enabled, see [`StdScheduledMutator::with_noop_detection`].
+    /// The mutational stages don't run such an `Input`.
+    Unchanged,
 }
 
 /// A mutator takes input, and mutates it.
@@ -63,6 +90,28 @@ where
     }
 }
 
+/// A [`Mutator`] that can revert its own mutations.
+/// This allows to try a mutation and roll it back, without cloning the whole input.
+pub trait ReversibleMutator<I, S>: Mutator<I, S>
+where
+    I: Input,
+{
+    /// The opaque token recording everything needed to undo a single mutation
+    type Undo;
+
+    /// Mutate a given input, returning an undo token if the input got mutated
+    fn mutate_and_record(
+        &mut self,
+        state: &mut S,
+        input: &mut I,
+        stage_idx: i32,
+    ) -> Result<(MutationResult, Option<Self::Undo>), Error>;
+
+    /// Revert the mutation recorded in `undo`.
+    /// Multiple mutations have to be undone in the reverse order they were applied in.
+    fn undo(&mut self, input: &mut I, undo: Self::Undo) -> Result<(), Error>;
+}
+
 /// A `Tuple` of `Mutators` that can execute multiple `Mutators` in a row.
 pub trait MutatorsTuple<I, S>: HasConstLen
 where
@@ -4,7 +4,7 @@ use crate::{
     bolts::{rands::Rand, tuples::Named},
     corpus::Corpus,
     inputs::{HasBytesVec, Input},
-    mutators::{MutationResult, Mutator},
+    mutators::{MutationResult, Mutator, ReversibleMutator},
     state::{HasCorpus, HasMaxSize, HasRand},
     Error,
 };
@@ -14,6 +14,7 @@ use core::{
     cmp::{max, min},
     marker::PhantomData,
     mem::size_of,
+    ops::Range,
 };
 
 /// Mem move in the own vec
@@ -96,6 +97,38 @@ pub const INTERESTING_32: [i32; 27] = [
     100663045,
     2147483647,
 ];
+/// Malformed UTF-8 and tricky unicode sequences, known to trip up text processing code
+pub const INTERESTING_UNICODE: [&[u8]; 20] = [
+    // Byte order marks (UTF-8, UTF-16 BE/LE)
+    b"\xef\xbb\xbf",
+    b"\xfe\xff",
+    b"\xff\xfe",
+    // Overlong encodings of `/` and NUL
+    b"\xc0\xaf",
+    b"\xe0\x80\xaf",
+    b"\xc0\x80",
+    // UTF-16 surrogate halves, encoded as UTF-8
+    b"\xed\xa0\x80",
+    b"\xed\xbf\xbf",
+    // Truncated multi-byte sequences
+    b"\xc3",
+    b"\xe2\x82",
+    b"\xf0\x9f\x98",
+    // Lone continuation byte and bytes never valid in UTF-8
+    b"\x80",
+    b"\xfe",
+    b"\xff",
+    // Code points beyond U+10FFFF
+    b"\xf4\x90\x80\x80",
+    // Combining characters, stacked on nothing
+    b"\xcc\x81\xcc\x81\xcc\x81",
+    // Zero width joiner and right-to-left override
+    b"\xe2\x80\x8d",
+    b"\xe2\x80\xae",
+    // Non-characters U+FFFE and U+FFFF
+    b"\xef\xbf\xbe",
+    b"\xef\xbf\xbf",
+];
 
 /// Bitflip mutation for inputs with a bytes vector
 #[derive(Default, Debug)]
@@ -124,7 +157,9 @@ where
             Ok(MutationResult::Skipped)
         } else {
             let bit = 1 << state.rand_mut().choose(0..8);
-            let byte = state.rand_mut().choose(input.bytes_mut());
+            let idx = state.rand_mut().below(input.bytes().len() as u64) as usize;
+            input.mark_dirty(idx..idx + 1);
+            let byte = &mut input.bytes_mut()[idx];
             *byte ^= bit;
             Ok(MutationResult::Mutated)
         }
@@ -183,7 +218,9 @@ where
         if input.bytes().is_empty() {
             Ok(MutationResult::Skipped)
         } else {
-            *state.rand_mut().choose(input.bytes_mut()) ^= 0xff;
+            let idx = state.rand_mut().below(input.bytes().len() as u64) as usize;
+            input.mark_dirty(idx..idx + 1);
+            input.bytes_mut()[idx] ^= 0xff;
             Ok(MutationResult::Mutated)
         }
     }
@@ -241,7 +278,9 @@ where
         if input.bytes().is_empty() {
             Ok(MutationResult::Skipped)
         } else {
-            let byte = state.rand_mut().choose(input.bytes_mut());
+            let idx = state.rand_mut().below(input.bytes().len() as u64) as usize;
+            input.mark_dirty(idx..idx + 1);
+            let byte = &mut input.bytes_mut()[idx];
             *byte = byte.wrapping_add(1);
             Ok(MutationResult::Mutated)
         }
@@ -300,7 +339,9 @@ where
         if input.bytes().is_empty() {
             Ok(MutationResult::Skipped)
         } else {
-            let byte = state.rand_mut().choose(input.bytes_mut());
+            let idx = state.rand_mut().below(input.bytes().len() as u64) as usize;
+            input.mark_dirty(idx..idx + 1);
+            let byte = &mut input.bytes_mut()[idx];
             *byte = byte.wrapping_sub(1);
             Ok(MutationResult::Mutated)
         }
@@ -359,7 +400,9 @@ where
         if input.bytes().is_empty() {
             Ok(MutationResult::Skipped)
         } else {
-            let byte = state.rand_mut().choose(input.bytes_mut());
+            let idx = state.rand_mut().below(input.bytes().len() as u64) as usize;
+            input.mark_dirty(idx..idx + 1);
+            let byte = &mut input.bytes_mut()[idx];
             *byte = !*byte;
             Ok(MutationResult::Mutated)
         }
@@ -418,7 +461,9 @@ where
         if input.bytes().is_empty() {
             Ok(MutationResult::Skipped)
         } else {
-            let byte = state.rand_mut().choose(input.bytes_mut());
+            let idx = state.rand_mut().below(input.bytes().len() as u64) as usize;
+            input.mark_dirty(idx..idx + 1);
+            let byte = &mut input.bytes_mut()[idx];
             *byte = state.rand_mut().next() as u8;
             Ok(MutationResult::Mutated)
         }
@@ -451,6 +496,74 @@ where
     }
 }
 
+/// The undo token of single byte mutations, recording the old value of the mutated byte
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ByteUndo {
+    /// The index of the mutated byte
+    pub idx: usize,
+    /// The value of the byte before the mutation
+    pub byte: u8,
+}
+
+// Helper macro that makes a single byte mutation reversible.
+// `$mutate` maps the current value of the chosen byte to its mutated value.
+macro_rules! reversible_byte_mutator_impl {
+    ($name: ident, $mutate: expr) => {
+        impl<I, R, S> ReversibleMutator<I, S> for $name<I, R, S>
+        where
+            I: Input + HasBytesVec,
+            S: HasRand<R>,
+            R: Rand,
+        {
+            type Undo = ByteUndo;
+
+            fn mutate_and_record(
+                &mut self,
+                state: &mut S,
+                input: &mut I,
+                _stage_idx: i32,
+            ) -> Result<(MutationResult, Option<ByteUndo>), Error> {
+                if input.bytes().is_empty() {
+                    return Ok((MutationResult::Skipped, None));
+                }
+                let idx = state.rand_mut().below(input.bytes().len() as u64) as usize;
+                let byte = input.bytes()[idx];
+                #[allow(clippy::redundant_closure_call)]
+                let mutated = ($mutate)(state, byte);
+                input.bytes_mut()[idx] = mutated;
+                input.mark_dirty(idx..idx + 1);
+                Ok((MutationResult::Mutated, Some(ByteUndo { idx, byte })))
+            }
+
+            fn undo(&mut self, input: &mut I, undo: ByteUndo) -> Result<(), Error> {
+                let byte = input.bytes_mut().get_mut(undo.idx).ok_or_else(|| {
+                    Error::IllegalArgument(format!(
+                        "Cannot undo {}, index {} is out of bounds",
+                        stringify!($name),
+                        undo.idx
+                    ))
+                })?;
+                *byte = undo.byte;
+                input.mark_dirty(undo.idx..undo.idx + 1);
+                Ok(())
+            }
+        }
+    };
+}
+
+reversible_byte_mutator_impl!(BitFlipMutator, |state: &mut S, byte: u8| byte
+    ^ (1 << state.rand_mut().choose(0..8)));
+reversible_byte_mutator_impl!(ByteFlipMutator, |_state: &mut S, byte: u8| byte ^ 0xff);
+reversible_byte_mutator_impl!(ByteIncMutator, |_state: &mut S, byte: u8| byte
+    .wrapping_add(1));
+reversible_byte_mutator_impl!(ByteDecMutator, |_state: &mut S, byte: u8| byte
+    .wrapping_sub(1));
+reversible_byte_mutator_impl!(ByteNegMutator, |_state: &mut S, byte: u8| !byte);
+reversible_byte_mutator_impl!(
+    ByteRandMutator,
+    |state: &mut S, _byte: u8| state.rand_mut().next() as u8
+);
+
 // Helper macro that defines the arithmetic addition/subtraction mutations where random slices
 // within the input are treated as u8, u16, u32, or u64, then mutated in place.
 macro_rules! add_mutator_impl {
@@ -498,6 +611,7 @@ macro_rules! add_mutator_impl {
                     };
 
                     // set bytes to mutated value
+                    input.mark_dirty(index..index + size_of::<$size>());
                     let new_bytes = &mut input.bytes_mut()[index..index + size_of::<$size>()];
                     new_bytes.copy_from_slice(&new_val.to_ne_bytes());
                     Ok(MutationResult::Mutated)
@@ -541,8 +655,9 @@ add_mutator_impl!(QwordAddMutator, u64);
 ///////////////////////////
 
 macro_rules! interesting_mutator_impl {
-    ($name: ident, $size: ty, $interesting: ident) => {
-        /// Inserts an interesting value at a random place in the input vector
+    ($name: ident, $size: ty, $value: ty, $interesting: ident) => {
+        /// Inserts an interesting value at a random place in the input vector.
+        /// The values come from the built-in table, or the one given to `with_values`.
         #[derive(Default, Debug)]
         pub struct $name<I, R, S>
         where
@@ -550,6 +665,7 @@ macro_rules! interesting_mutator_impl {
             S: HasRand<R>,
             R: Rand,
         {
+            values: Option<Vec<$value>>,
             phantom: PhantomData<(I, R, S)>,
         }
 
@@ -566,18 +682,24 @@ macro_rules! interesting_mutator_impl {
                 input: &mut I,
                 _stage_idx: i32,
             ) -> Result<MutationResult, Error> {
-                if input.bytes().len() < size_of::<$size>() {
+                if input.bytes().len() < size_of::<$size>()
+                    || self.values.as_ref().map_or(false, Vec::is_empty)
+                {
                     Ok(MutationResult::Skipped)
                 } else {
                     let bytes = input.bytes_mut();
                     let upper_bound = (bytes.len() + 1 - size_of::<$size>()) as u64;
                     let idx = state.rand_mut().below(upper_bound) as usize;
-                    let val = *state.rand_mut().choose(&$interesting) as $size;
+                    let val = match &self.values {
+                        Some(values) => *state.rand_mut().choose(values) as $size,
+                        None => *state.rand_mut().choose(&$interesting) as $size,
+                    };
                     let new_bytes = match state.rand_mut().choose(&[0, 1]) {
                         0 => val.to_be_bytes(),
                         _ => val.to_le_bytes(),
                     };
                     bytes[idx..idx + size_of::<$size>()].copy_from_slice(&new_bytes);
+                    input.mark_dirty(idx..idx + size_of::<$size>());
                     Ok(MutationResult::Mutated)
                 }
             }
@@ -600,10 +722,22 @@ macro_rules! interesting_mutator_impl {
             S: HasRand<R>,
             R: Rand,
         {
-            /// Creates a new [`$name`].
+            /// Creates a new [`$name`], using the built-in interesting values.
             #[must_use]
             pub fn new() -> Self {
                 Self {
+                    values: None,
+                    phantom: PhantomData,
+                }
+            }
+
+            /// Creates a new [`$name`], using the given interesting values instead of the built-in
+            /// ones, for example the magic constants of the target.
+            /// The mutator skips if `values` is empty.
+            #[must_use]
+            pub fn with_values(values: &[$value]) -> Self {
+                Self {
+                    values: Some(values.to_vec()),
                     phantom: PhantomData,
                 }
             }
@@ -611,9 +745,9 @@ macro_rules! interesting_mutator_impl {
     };
 }
 
-interesting_mutator_impl!(ByteInterestingMutator, u8, INTERESTING_8);
-interesting_mutator_impl!(WordInterestingMutator, u16, INTERESTING_16);
-interesting_mutator_impl!(DwordInterestingMutator, u32, INTERESTING_32);
+interesting_mutator_impl!(ByteInterestingMutator, u8, i8, INTERESTING_8);
+interesting_mutator_impl!(WordInterestingMutator, u16, i16, INTERESTING_16);
+interesting_mutator_impl!(DwordInterestingMutator, u32, i32, INTERESTING_32);
 
 /// Bytes delete mutation for inputs with a bytes vector
 #[derive(Default, Debug)]
@@ -646,6 +780,7 @@ where
         let off = state.rand_mut().below(size as u64) as usize;
         let len = state.rand_mut().below((size - off) as u64) as usize;
         input.bytes_mut().drain(off..off + len);
+        input.mark_dirty(off..size);
 
         Ok(MutationResult::Mutated)
     }
@@ -715,6 +850,7 @@ where
 
         input.bytes_mut().resize(size + len, 0);
         buffer_self_copy(input.bytes_mut(), off, off + len, size - off);
+        input.mark_dirty(off..size + len);
 
         Ok(MutationResult::Mutated)
     }
@@ -790,6 +926,7 @@ where
         input.bytes_mut().resize(size + len, 0);
         buffer_self_copy(input.bytes_mut(), off, off + len, size - off);
         buffer_set(input.bytes_mut(), off, len, val);
+        input.mark_dirty(off..size + len);
 
         Ok(MutationResult::Mutated)
     }
@@ -862,6 +999,7 @@ where
         input.bytes_mut().resize(size + len, 0);
         buffer_self_copy(input.bytes_mut(), off, off + len, size - off);
         buffer_set(input.bytes_mut(), off, len, val);
+        input.mark_dirty(off..size + len);
 
         Ok(MutationResult::Mutated)
     }
@@ -893,6 +1031,70 @@ where
     }
 }
 
+/// Inserts one of the [`INTERESTING_UNICODE`] sequences at a random position, for text inputs
+#[derive(Default, Debug)]
+pub struct UnicodeInterestingMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    phantom: PhantomData<(I, R, S)>,
+}
+
+impl<I, R, S> Mutator<I, S> for UnicodeInterestingMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut I,
+        _stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        let max_size = state.max_size();
+        let size = input.bytes().len();
+        let seq = *state.rand_mut().choose(&INTERESTING_UNICODE);
+        if size + seq.len() > max_size {
+            return Ok(MutationResult::Skipped);
+        }
+
+        let off = state.rand_mut().below((size + 1) as u64) as usize;
+        input.bytes_mut().splice(off..off, seq.iter().copied());
+        input.mark_dirty(off..size + seq.len());
+
+        Ok(MutationResult::Mutated)
+    }
+}
+
+impl<I, R, S> Named for UnicodeInterestingMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    fn name(&self) -> &str {
+        "UnicodeInterestingMutator"
+    }
+}
+
+impl<I, R, S> UnicodeInterestingMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    /// Create a new [`UnicodeInterestingMutator`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            phantom: PhantomData,
+        }
+    }
+}
+
 /// Bytes set mutation for inputs with a bytes vector
 #[derive(Default, Debug)]
 pub struct BytesSetMutator<I, R, S>
@@ -926,6 +1128,7 @@ where
         let val = *state.rand_mut().choose(input.bytes());
 
         buffer_set(input.bytes_mut(), off, len, val);
+        input.mark_dirty(off..off + len);
 
         Ok(MutationResult::Mutated)
     }
@@ -990,6 +1193,7 @@ where
         let val = state.rand_mut().next() as u8;
 
         buffer_set(input.bytes_mut(), off, len, val);
+        input.mark_dirty(off..off + len);
 
         Ok(MutationResult::Mutated)
     }
@@ -1054,6 +1258,7 @@ where
         let len = 1 + state.rand_mut().below((size - max(from, to)) as u64) as usize;
 
         buffer_self_copy(input.bytes_mut(), from, to, len);
+        input.mark_dirty(to..to + len);
 
         Ok(MutationResult::Mutated)
     }
@@ -1137,6 +1342,7 @@ where
 
         buffer_self_copy(input.bytes_mut(), off, off + len, size - off);
         buffer_copy(input.bytes_mut(), &self.tmp_buf, 0, off, len);
+        input.mark_dirty(off..size + len);
 
         Ok(MutationResult::Mutated)
     }
@@ -1169,6 +1375,264 @@ where
     }
 }
 
+/// Region duplication mutation for inputs with a bytes vector.
+/// Inserts a copy of a random region of any length right at a random position,
+/// to reach bugs that need a record repeated several times.
+#[derive(Debug, Default)]
+pub struct RegionDuplicateMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    tmp_buf: Vec<u8>,
+    phantom: PhantomData<(I, R, S)>,
+}
+
+impl<I, R, S> Mutator<I, S> for RegionDuplicateMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut I,
+        _stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        let max_size = state.max_size();
+        let size = input.bytes().len();
+        if size == 0 || size >= max_size {
+            return Ok(MutationResult::Skipped);
+        }
+        let len = 1 + state.rand_mut().below(min(size, max_size - size) as u64) as usize;
+        let from = state.rand_mut().below((size - len + 1) as u64) as usize;
+        let to = state.rand_mut().below((size + 1) as u64) as usize;
+
+        self.tmp_buf.resize(len, 0);
+        buffer_copy(&mut self.tmp_buf, input.bytes(), from, 0, len);
+
+        input.bytes_mut().resize(size + len, 0);
+        buffer_self_copy(input.bytes_mut(), to, to + len, size - to);
+        buffer_copy(input.bytes_mut(), &self.tmp_buf, 0, to, len);
+        input.mark_dirty(to..size + len);
+
+        Ok(MutationResult::Mutated)
+    }
+}
+
+impl<I, R, S> Named for RegionDuplicateMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    fn name(&self) -> &str {
+        "RegionDuplicateMutator"
+    }
+}
+
+impl<I, R, S> RegionDuplicateMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    /// Creates a new [`RegionDuplicateMutator`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            tmp_buf: vec![],
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// The default maximum length of the runs inserted by the [`RepeatedByteInsertMutator`]
+pub const DEFAULT_REPEATED_BYTE_MAX_LEN: usize = 4096;
+
+/// Repeated byte insert mutation for inputs with a bytes vector.
+/// Inserts a long run of a single random byte at a random position, like a thousand `A`s,
+/// which random mutations rarely produce, to find overflows and length bugs.
+#[derive(Debug)]
+pub struct RepeatedByteInsertMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    max_len: usize,
+    phantom: PhantomData<(I, R, S)>,
+}
+
+impl<I, R, S> Mutator<I, S> for RepeatedByteInsertMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut I,
+        _stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        let max_size = state.max_size();
+        let size = input.bytes().len();
+        if size >= max_size || self.max_len == 0 {
+            return Ok(MutationResult::Skipped);
+        }
+        let len = 1 + state
+            .rand_mut()
+            .below(min(self.max_len, max_size - size) as u64) as usize;
+        let off = state.rand_mut().below((size + 1) as u64) as usize;
+        let val = state.rand_mut().next() as u8;
+
+        input.bytes_mut().resize(size + len, 0);
+        buffer_self_copy(input.bytes_mut(), off, off + len, size - off);
+        buffer_set(input.bytes_mut(), off, len, val);
+        input.mark_dirty(off..size + len);
+
+        Ok(MutationResult::Mutated)
+    }
+}
+
+impl<I, R, S> Named for RepeatedByteInsertMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    fn name(&self) -> &str {
+        "RepeatedByteInsertMutator"
+    }
+}
+
+impl<I, R, S> RepeatedByteInsertMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    /// Creates a new [`RepeatedByteInsertMutator`], inserting runs of up to
+    /// [`DEFAULT_REPEATED_BYTE_MAX_LEN`] bytes.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_max_len(DEFAULT_REPEATED_BYTE_MAX_LEN)
+    }
+
+    /// Creates a new [`RepeatedByteInsertMutator`], inserting runs of up to `max_len` bytes.
+    #[must_use]
+    pub fn with_max_len(max_len: usize) -> Self {
+        Self {
+            max_len,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<I, R, S> Default for RepeatedByteInsertMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Alphabet byte mutation for inputs with a bytes vector.
+/// Sets a random byte, or inserts a few random bytes, picking only from a configured alphabet,
+/// for example the valid opcodes of a bytecode, to keep inputs in-distribution.
+#[derive(Debug)]
+pub struct AlphabetByteMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    alphabet: Vec<u8>,
+    phantom: PhantomData<(I, R, S)>,
+}
+
+impl<I, R, S> Mutator<I, S> for AlphabetByteMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut I,
+        _stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        if self.alphabet.is_empty() {
+            return Ok(MutationResult::Skipped);
+        }
+        let size = input.bytes().len();
+
+        // Set a byte, or insert bytes if there are none to set
+        if size > 0 && state.rand_mut().below(2) == 0 {
+            let idx = state.rand_mut().below(size as u64) as usize;
+            input.bytes_mut()[idx] = *state.rand_mut().choose(&self.alphabet);
+            input.mark_dirty(idx..idx + 1);
+            return Ok(MutationResult::Mutated);
+        }
+
+        let max_size = state.max_size();
+        if size >= max_size {
+            return Ok(MutationResult::Skipped);
+        }
+        let off = state.rand_mut().below((size + 1) as u64) as usize;
+        let len = 1 + state.rand_mut().below(min(16, max_size - size) as u64) as usize;
+
+        input.bytes_mut().resize(size + len, 0);
+        buffer_self_copy(input.bytes_mut(), off, off + len, size - off);
+        for byte in &mut input.bytes_mut()[off..off + len] {
+            *byte = *state.rand_mut().choose(&self.alphabet);
+        }
+        input.mark_dirty(off..size + len);
+
+        Ok(MutationResult::Mutated)
+    }
+}
+
+impl<I, R, S> Named for AlphabetByteMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    fn name(&self) -> &str {
+        "AlphabetByteMutator"
+    }
+}
+
+impl<I, R, S> AlphabetByteMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    /// Creates a new [`AlphabetByteMutator`], setting and inserting only bytes of the `alphabet`.
+    #[must_use]
+    pub fn new(alphabet: Vec<u8>) -> Self {
+        Self {
+            alphabet,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The valid byte values
+    #[must_use]
+    pub fn alphabet(&self) -> &[u8] {
+        &self.alphabet
+    }
+}
+
 /// Bytes swap mutation for inputs with a bytes vector
 #[derive(Debug, Default)]
 pub struct BytesSwapMutator<I, R, S>
@@ -1204,6 +1668,7 @@ where
         let tmp = input.bytes()[first..(first + len)].to_vec();
         buffer_self_copy(input.bytes_mut(), second, first, len);
         buffer_copy(input.bytes_mut(), &tmp, 0, second, len);
+        input.mark_dirty(min(first, second)..max(first, second) + len);
 
         Ok(MutationResult::Mutated)
     }
@@ -1235,6 +1700,136 @@ where
     }
 }
 
+/// Bytes reverse mutation for inputs with a bytes vector, reversing a random range in place
+#[derive(Debug, Default)]
+pub struct BytesReverseMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R>,
+    R: Rand,
+{
+    phantom: PhantomData<(I, R, S)>,
+}
+
+impl<I, R, S> Mutator<I, S> for BytesReverseMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R>,
+    R: Rand,
+{
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut I,
+        _stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        let size = input.bytes().len();
+        if size <= 1 {
+            return Ok(MutationResult::Skipped);
+        }
+
+        let start = state.rand_mut().below((size - 1) as u64) as usize;
+        let len = 2 + state.rand_mut().below((size - start - 1) as u64) as usize;
+
+        input.bytes_mut()[start..(start + len)].reverse();
+        input.mark_dirty(start..start + len);
+
+        Ok(MutationResult::Mutated)
+    }
+}
+
+impl<I, R, S> Named for BytesReverseMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R>,
+    R: Rand,
+{
+    fn name(&self) -> &str {
+        "BytesReverseMutator"
+    }
+}
+
+impl<I, R, S> BytesReverseMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R>,
+    R: Rand,
+{
+    /// Creates a new [`BytesReverseMutator`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// Bytes rotate mutation for inputs with a bytes vector, rotating a random range in place
+/// to the left by a random amount
+#[derive(Debug, Default)]
+pub struct BytesRotateMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R>,
+    R: Rand,
+{
+    phantom: PhantomData<(I, R, S)>,
+}
+
+impl<I, R, S> Mutator<I, S> for BytesRotateMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R>,
+    R: Rand,
+{
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut I,
+        _stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        let size = input.bytes().len();
+        if size <= 1 {
+            return Ok(MutationResult::Skipped);
+        }
+
+        let start = state.rand_mut().below((size - 1) as u64) as usize;
+        let len = 2 + state.rand_mut().below((size - start - 1) as u64) as usize;
+        let amount = 1 + state.rand_mut().below((len - 1) as u64) as usize;
+
+        input.bytes_mut()[start..(start + len)].rotate_left(amount);
+        input.mark_dirty(start..start + len);
+
+        Ok(MutationResult::Mutated)
+    }
+}
+
+impl<I, R, S> Named for BytesRotateMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R>,
+    R: Rand,
+{
+    fn name(&self) -> &str {
+        "BytesRotateMutator"
+    }
+}
+
+impl<I, R, S> BytesRotateMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R>,
+    R: Rand,
+{
+    /// Creates a new [`BytesRotateMutator`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            phantom: PhantomData,
+        }
+    }
+}
+
 /// Crossover insert mutation for inputs with a bytes vector
 #[derive(Debug, Default)]
 pub struct CrossoverInsertMutator<C, I, R, S>
@@ -1301,6 +1896,7 @@ where
         input.bytes_mut().resize(size + len, 0);
         buffer_self_copy(input.bytes_mut(), to, to + len, size - to);
         buffer_copy(input.bytes_mut(), other.bytes(), from, to, len);
+        input.mark_dirty(to..size + len);
 
         Ok(MutationResult::Mutated)
     }
@@ -1392,6 +1988,7 @@ where
         let other = other_testcase.load_input()?;
 
         buffer_copy(input.bytes_mut(), other.bytes(), from, to, len);
+        input.mark_dirty(to..to + len);
 
         Ok(MutationResult::Mutated)
     }
@@ -1498,6 +2095,7 @@ where
 
         let mut other_testcase = state.corpus().get(idx)?.borrow_mut();
         let other = other_testcase.load_input()?;
+        input.mark_dirty(split_at..max(input.bytes().len(), other.bytes().len()));
         input
             .bytes_mut()
             .splice(split_at.., other.bytes()[split_at..].iter().copied());
@@ -1534,6 +2132,117 @@ where
     }
 }
 
+/// Returns the maximal runs of differing bytes between the given vectors, stopping at the min len
+#[allow(clippy::cast_sign_loss)]
+fn locate_diff_regions(this: &[u8], other: &[u8]) -> Vec<Range<usize>> {
+    let mut regions = vec![];
+    let (first_diff, last_diff) = locate_diffs(this, other);
+    if first_diff < 0 {
+        return regions;
+    }
+    let mut start = None;
+    for i in first_diff as usize..=last_diff as usize {
+        match (this[i] == other[i], start) {
+            (false, None) => start = Some(i),
+            (true, Some(s)) => {
+                regions.push(s..i);
+                start = None;
+            }
+            _ => (),
+        }
+    }
+    if let Some(s) = start {
+        regions.push(s..last_diff as usize + 1);
+    }
+    regions
+}
+
+/// Adopts the bytes of another corpus entry in one of the regions where both inputs differ,
+/// keeping the rest of the input. More surgical than the [`SpliceMutator`], for example to narrow
+/// down which difference between two inputs changes the behavior.
+#[derive(Debug, Default)]
+pub struct DiffRegionAdoptMutator<C, I, R, S>
+where
+    C: Corpus<I>,
+    I: Input + HasBytesVec,
+    R: Rand,
+    S: HasRand<R> + HasCorpus<C, I>,
+{
+    phantom: PhantomData<(C, I, R, S)>,
+}
+
+impl<C, I, R, S> Mutator<I, S> for DiffRegionAdoptMutator<C, I, R, S>
+where
+    C: Corpus<I>,
+    I: Input + HasBytesVec,
+    R: Rand,
+    S: HasRand<R> + HasCorpus<C, I>,
+{
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut I,
+        _stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        // We don't want to use the testcase we're already using as donor
+        let count = state.corpus().count();
+        if count == 0 {
+            return Ok(MutationResult::Skipped);
+        }
+        let idx = state.rand_mut().below(count as u64) as usize;
+        if let Some(cur) = state.corpus().current() {
+            if idx == *cur {
+                return Ok(MutationResult::Skipped);
+            }
+        }
+
+        let regions = {
+            let mut other_testcase = state.corpus().get(idx)?.borrow_mut();
+            let other = other_testcase.load_input()?;
+            locate_diff_regions(input.bytes(), other.bytes())
+        };
+        if regions.is_empty() {
+            return Ok(MutationResult::Skipped);
+        }
+        let region = state.rand_mut().choose(&regions).clone();
+
+        let mut other_testcase = state.corpus().get(idx)?.borrow_mut();
+        let other = other_testcase.load_input()?;
+        input.bytes_mut()[region.clone()].copy_from_slice(&other.bytes()[region.clone()]);
+        input.mark_dirty(region);
+
+        Ok(MutationResult::Mutated)
+    }
+}
+
+impl<C, I, R, S> Named for DiffRegionAdoptMutator<C, I, R, S>
+where
+    C: Corpus<I>,
+    I: Input + HasBytesVec,
+    R: Rand,
+    S: HasRand<R> + HasCorpus<C, I>,
+{
+    fn name(&self) -> &str {
+        "DiffRegionAdoptMutator"
+    }
+}
+
+impl<C, I, R, S> DiffRegionAdoptMutator<C, I, R, S>
+where
+    C: Corpus<I>,
+    I: Input + HasBytesVec,
+    R: Rand,
+    S: HasRand<R> + HasCorpus<C, I>,
+{
+    /// Creates a new [`DiffRegionAdoptMutator`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            phantom: PhantomData,
+        }
+    }
+}
+
 // Converts a hex u8 to its u8 value: 'A' -> 10 etc.
 fn from_hex(hex: u8) -> Result<u8, Error> {
     match hex {
@@ -1585,7 +2294,7 @@ mod tests {
             rands::StdRand,
             tuples::{tuple_list, HasConstLen},
         },
-        corpus::{Corpus, InMemoryCorpus},
+        corpus::{Corpus, InMemoryCorpus, Testcase},
         inputs::BytesInput,
         mutators::MutatorsTuple,
         state::{HasMetadata, StdState},
@@ -1622,7 +2331,11 @@ mod tests {
             BytesSetMutator::new(),
             BytesRandSetMutator::new(),
             BytesCopyMutator::new(),
+            RegionDuplicateMutator::new(),
+            RepeatedByteInsertMutator::new(),
             BytesSwapMutator::new(),
+            BytesReverseMutator::new(),
+            BytesRotateMutator::new(),
         )
     }
 
@@ -1658,11 +2371,352 @@ mod tests {
                         .unwrap()
                     {
                         MutationResult::Mutated => new_testcases.push(mutant),
-                        MutationResult::Skipped => (),
+                        MutationResult::Skipped | MutationResult::Unchanged => (),
                     };
                 }
             }
             inputs.append(&mut new_testcases);
         }
     }
+
+    #[test]
+    fn test_reversible_byte_mutator() {
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let original = BytesInput::new(vec![0x13, 0x37, 0x42, 0x00]);
+        let mut input = original.clone();
+        let mut mutator = ByteRandMutator::new();
+
+        let mut undos = vec![];
+        for _ in 0..16 {
+            let (result, undo) = mutator
+                .mutate_and_record(&mut state, &mut input, 0)
+                .unwrap();
+            assert_eq!(result, MutationResult::Mutated);
+            undos.push(undo.unwrap());
+        }
+        while let Some(undo) = undos.pop() {
+            mutator.undo(&mut input, undo).unwrap();
+        }
+        assert_eq!(input.bytes(), original.bytes());
+
+        let mut empty = BytesInput::new(vec![]);
+        assert_eq!(
+            mutator
+                .mutate_and_record(&mut state, &mut empty, 0)
+                .unwrap(),
+            (MutationResult::Skipped, None)
+        );
+    }
+
+    #[test]
+    fn test_unicode_interesting_mutator() {
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let mut mutator = UnicodeInterestingMutator::new();
+
+        for _ in 0..16 {
+            let mut input = BytesInput::new(b"hello world".to_vec());
+            assert_eq!(
+                mutator.mutate(&mut state, &mut input, 0).unwrap(),
+                MutationResult::Mutated
+            );
+            assert!(INTERESTING_UNICODE.iter().any(|seq| input
+                .bytes()
+                .windows(seq.len())
+                .any(|window| window == *seq)));
+        }
+
+        // Inputs at `max_size` don't grow
+        state.set_max_size(11);
+        let mut input = BytesInput::new(b"hello world".to_vec());
+        assert_eq!(
+            mutator.mutate(&mut state, &mut input, 0).unwrap(),
+            MutationResult::Skipped
+        );
+    }
+
+    #[test]
+    fn test_dirty_range() {
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let original = BytesInput::new(vec![0; 16]);
+        let mut mutator = ByteFlipMutator::new();
+
+        for _ in 0..8 {
+            let mut input = original.clone();
+            assert_eq!(input.dirty_range(), None);
+            mutator.mutate(&mut state, &mut input, 0).unwrap();
+
+            let idx = input.bytes().iter().position(|byte| *byte != 0).unwrap();
+            assert_eq!(input.dirty_range(), Some(idx..idx + 1));
+        }
+    }
+
+    #[test]
+    fn test_region_duplicate_mutator() {
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let original: Vec<u8> = (0..16).collect();
+        let mut mutator = RegionDuplicateMutator::new();
+
+        for _ in 0..32 {
+            let mut input = BytesInput::new(original.clone());
+            assert_eq!(
+                mutator.mutate(&mut state, &mut input, 0).unwrap(),
+                MutationResult::Mutated
+            );
+            let bytes = input.bytes();
+            let len = bytes.len() - original.len();
+            assert!(len > 0);
+
+            // Removing the copy gives back the original, and the copy is a region of the original
+            let to = (0..=original.len())
+                .find(|to| [&bytes[..*to], &bytes[to + len..]].concat() == original)
+                .unwrap();
+            assert!(original
+                .windows(len)
+                .any(|window| window == &bytes[to..to + len]));
+        }
+
+        // Inputs at `max_size` don't grow
+        state.set_max_size(16);
+        let mut input = BytesInput::new(original);
+        assert_eq!(
+            mutator.mutate(&mut state, &mut input, 0).unwrap(),
+            MutationResult::Skipped
+        );
+    }
+
+    #[test]
+    fn test_interesting_mutator_with_values() {
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let magic: i32 = 0x1bad_b002;
+        let mut mutator = DwordInterestingMutator::with_values(&[magic]);
+
+        // Only the custom value gets written, in either endianness
+        for _ in 0..16 {
+            let mut input = BytesInput::new(vec![0; 8]);
+            assert_eq!(
+                mutator.mutate(&mut state, &mut input, 0).unwrap(),
+                MutationResult::Mutated
+            );
+            let (le, be) = (magic.to_le_bytes(), magic.to_be_bytes());
+            assert!(input
+                .bytes()
+                .windows(4)
+                .any(|window| window == le || window == be));
+        }
+
+        let mut mutator = ByteInterestingMutator::with_values(&[]);
+        let mut input = BytesInput::new(vec![0; 8]);
+        assert_eq!(
+            mutator.mutate(&mut state, &mut input, 0).unwrap(),
+            MutationResult::Skipped
+        );
+    }
+
+    #[test]
+    fn test_repeated_byte_insert_mutator() {
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let original: Vec<u8> = (0..16).collect();
+        let mut mutator = RepeatedByteInsertMutator::with_max_len(2000);
+
+        let mut long_run = false;
+        for _ in 0..32 {
+            let mut input = BytesInput::new(original.clone());
+            assert_eq!(
+                mutator.mutate(&mut state, &mut input, 0).unwrap(),
+                MutationResult::Mutated
+            );
+            let bytes = input.bytes();
+            let len = bytes.len() - original.len();
+            assert!(len > 0 && len <= 2000);
+            long_run |= len > 16;
+
+            // Removing a homogeneous run gives back the original
+            assert!((0..=original.len()).any(|to| {
+                bytes[to..to + len].iter().all(|b| *b == bytes[to])
+                    && [&bytes[..to], &bytes[to + len..]].concat() == original
+            }));
+        }
+        assert!(long_run);
+
+        // Inputs at `max_size` don't grow
+        state.set_max_size(16);
+        let mut input = BytesInput::new(original);
+        assert_eq!(
+            mutator.mutate(&mut state, &mut input, 0).unwrap(),
+            MutationResult::Skipped
+        );
+    }
+
+    #[test]
+    fn test_alphabet_byte_mutator() {
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let alphabet = vec![0x10, 0x20, 0x30];
+        let mut mutator = AlphabetByteMutator::new(alphabet.clone());
+
+        for _ in 0..64 {
+            let mut input = BytesInput::new(vec![0xFF; 8]);
+            assert_eq!(
+                mutator.mutate(&mut state, &mut input, 0).unwrap(),
+                MutationResult::Mutated
+            );
+            // All bytes are either untouched, or from the alphabet
+            let touched: Vec<u8> = input
+                .bytes()
+                .iter()
+                .copied()
+                .filter(|byte| *byte != 0xFF)
+                .collect();
+            assert!(!touched.is_empty());
+            assert!(touched.iter().all(|byte| alphabet.contains(byte)));
+        }
+    }
+
+    /// The first and last index where `bytes` differs from `original`
+    fn changed_range(original: &[u8], bytes: &[u8]) -> (usize, usize) {
+        let first = (0..original.len()).find(|i| original[*i] != bytes[*i]);
+        let last = (0..original.len()).rfind(|i| original[*i] != bytes[*i]);
+        (first.unwrap(), last.unwrap())
+    }
+
+    #[test]
+    fn test_bytes_reverse_mutator() {
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let mut mutator = BytesReverseMutator::new();
+        let original: Vec<u8> = (0..32).collect();
+
+        for _ in 0..64 {
+            let mut input = BytesInput::new(original.clone());
+            assert_eq!(
+                mutator.mutate(&mut state, &mut input, 0).unwrap(),
+                MutationResult::Mutated
+            );
+            let bytes = input.bytes();
+            assert_eq!(bytes.len(), original.len());
+
+            // All bytes are distinct, so the changed range is exactly the reversed range
+            let (first, last) = changed_range(&original, bytes);
+            assert_eq!(bytes[..first], original[..first]);
+            assert_eq!(bytes[last + 1..], original[last + 1..]);
+            let mut reversed = original[first..=last].to_vec();
+            reversed.reverse();
+            assert_eq!(bytes[first..=last], reversed);
+        }
+
+        let mut input = BytesInput::new(vec![0x42]);
+        assert_eq!(
+            mutator.mutate(&mut state, &mut input, 0).unwrap(),
+            MutationResult::Skipped
+        );
+    }
+
+    #[test]
+    fn test_bytes_rotate_mutator() {
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let mut mutator = BytesRotateMutator::new();
+        let original: Vec<u8> = (0..32).collect();
+
+        for _ in 0..64 {
+            let mut input = BytesInput::new(original.clone());
+            assert_eq!(
+                mutator.mutate(&mut state, &mut input, 0).unwrap(),
+                MutationResult::Mutated
+            );
+            let bytes = input.bytes();
+            assert_eq!(bytes.len(), original.len());
+
+            // All bytes are distinct, so the changed range is exactly the rotated range
+            let (first, last) = changed_range(&original, bytes);
+            assert_eq!(bytes[..first], original[..first]);
+            assert_eq!(bytes[last + 1..], original[last + 1..]);
+            let range = &original[first..=last];
+            assert!((1..range.len()).any(|amount| {
+                let mut rotated = range.to_vec();
+                rotated.rotate_left(amount);
+                bytes[first..=last] == rotated
+            }));
+        }
+
+        let mut input = BytesInput::new(vec![]);
+        assert_eq!(
+            mutator.mutate(&mut state, &mut input, 0).unwrap(),
+            MutationResult::Skipped
+        );
+    }
+
+    #[test]
+    fn test_diff_region_adopt_mutator() {
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        state
+            .corpus_mut()
+            .add(Testcase::new(BytesInput::new(b"aXXaaaaYYYa".to_vec())))
+            .unwrap();
+        let mut mutator = DiffRegionAdoptMutator::new();
+        let original = b"aaaaaaaaaaa".to_vec();
+
+        // Exactly one of the two differing regions gets adopted from the donor
+        let mut adopted = [false; 2];
+        for _ in 0..32 {
+            let mut input = BytesInput::new(original.clone());
+            assert_eq!(
+                mutator.mutate(&mut state, &mut input, 0).unwrap(),
+                MutationResult::Mutated
+            );
+            match input.bytes() {
+                b"aXXaaaaaaaa" => adopted[0] = true,
+                b"aaaaaaaYYYa" => adopted[1] = true,
+                other => panic!("unexpected mutation {:?}", other),
+            }
+        }
+        assert_eq!(adopted, [true, true]);
+    }
 }
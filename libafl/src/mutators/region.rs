@@ -0,0 +1,171 @@
+//! A [`Mutator`] combinator focusing an inner mutator on a region of the input.
+
+use alloc::string::String;
+use core::{fmt::Debug, marker::PhantomData, ops::Range};
+
+use crate::{
+    bolts::tuples::Named,
+    inputs::{BytesInput, HasBytesVec, Input},
+    mutators::{MutationResult, Mutator},
+    state::HasMaxSize,
+    Error,
+};
+
+/// Applies the inner [`Mutator`] to `input.bytes()[region]` only, for example to the payload
+/// after a fixed header. The region is handed to the inner mutator as a temporary [`BytesInput`],
+/// and spliced back afterwards. The region may grow or shrink, as long as the whole input stays
+/// within `max_size`. An `end` past the input length means "until the end of the input".
+#[derive(Debug)]
+pub struct RegionMutator<I, M, S>
+where
+    I: Input + HasBytesVec,
+    M: Mutator<BytesInput, S>,
+    S: HasMaxSize,
+{
+    inner: M,
+    region: Range<usize>,
+    name: String,
+    phantom: PhantomData<(I, S)>,
+}
+
+impl<I, M, S> Mutator<I, S> for RegionMutator<I, M, S>
+where
+    I: Input + HasBytesVec,
+    M: Mutator<BytesInput, S>,
+    S: HasMaxSize,
+{
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut I,
+        stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        let size = input.bytes().len();
+        if self.region.start >= size {
+            return Ok(MutationResult::Skipped);
+        }
+        let region = self.region.start..self.region.end.min(size);
+
+        let mut sub = BytesInput::new(input.bytes()[region.clone()].to_vec());
+
+        // The inner mutator may only use the space not taken by the rest of the input
+        let max_size = state.max_size();
+        let outside = size - region.len();
+        state.set_max_size(max_size.saturating_sub(outside));
+        let result = self.inner.mutate(state, &mut sub, stage_idx);
+        state.set_max_size(max_size);
+
+        if result? == MutationResult::Skipped {
+            return Ok(MutationResult::Skipped);
+        }
+        if outside + sub.bytes().len() > max_size {
+            return Ok(MutationResult::Skipped);
+        }
+        // If the region changed its length, all following bytes moved
+        let dirty = match sub.dirty_range() {
+            Some(dirty) if sub.bytes().len() == region.len() => {
+                region.start + dirty.start..region.start + dirty.end
+            }
+            _ => region.start..size.max(outside + sub.bytes().len()),
+        };
+        input.bytes_mut().splice(region, sub.bytes_mut().drain(..));
+        input.mark_dirty(dirty);
+
+        Ok(MutationResult::Mutated)
+    }
+
+    fn post_exec(
+        &mut self,
+        state: &mut S,
+        stage_idx: i32,
+        corpus_idx: Option<usize>,
+    ) -> Result<(), Error> {
+        self.inner.post_exec(state, stage_idx, corpus_idx)
+    }
+}
+
+impl<I, M, S> Named for RegionMutator<I, M, S>
+where
+    I: Input + HasBytesVec,
+    M: Mutator<BytesInput, S>,
+    S: HasMaxSize,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<I, M, S> RegionMutator<I, M, S>
+where
+    I: Input + HasBytesVec,
+    M: Mutator<BytesInput, S> + Named,
+    S: HasMaxSize,
+{
+    /// Create a new [`RegionMutator`], applying `inner` to the given `region` of each input.
+    #[must_use]
+    pub fn new(inner: M, region: Range<usize>) -> Self {
+        let name = format!("RegionMutator({})", inner.name());
+        Self {
+            inner,
+            region,
+            name,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The region of the input the inner mutator is applied to
+    #[must_use]
+    pub fn region(&self) -> &Range<usize> {
+        &self.region
+    }
+
+    /// The inner mutator
+    #[must_use]
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::InMemoryCorpus,
+        inputs::{BytesInput, HasBytesVec},
+        mutators::{ByteRandMutator, BytesInsertMutator, MutationResult, Mutator, RegionMutator},
+        state::{HasMaxSize, StdState},
+    };
+
+    #[test]
+    fn test_region_mutator() {
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let original: Vec<u8> = (0..16).collect();
+
+        let mut mutator = RegionMutator::new(ByteRandMutator::new(), 4..12);
+        let mut input = BytesInput::new(original.clone());
+        for _ in 0..64 {
+            assert_eq!(
+                mutator.mutate(&mut state, &mut input, 0).unwrap(),
+                MutationResult::Mutated
+            );
+            assert_eq!(input.bytes().len(), original.len());
+            assert_eq!(input.bytes()[..4], original[..4]);
+            assert_eq!(input.bytes()[12..], original[12..]);
+        }
+
+        // A growing region may only take the space left within `max_size`
+        state.set_max_size(20);
+        let mut mutator = RegionMutator::new(BytesInsertMutator::new(), 4..12);
+        let mut input = BytesInput::new(original.clone());
+        for _ in 0..64 {
+            let _ = mutator.mutate(&mut state, &mut input, 0).unwrap();
+            assert!(input.bytes().len() <= 20);
+            assert_eq!(input.bytes()[..4], original[..4]);
+        }
+    }
+}
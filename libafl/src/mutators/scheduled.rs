@@ -1,6 +1,6 @@
 //! The `ScheduledMutator` schedules multiple mutations internally.
 
-use alloc::{string::String, vec::Vec};
+use alloc::{borrow::ToOwned, string::String, vec::Vec};
 use core::{
     fmt::{self, Debug},
     marker::PhantomData,
@@ -9,8 +9,9 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     bolts::{
+        hash::HashKind,
         rands::Rand,
-        tuples::{tuple_list, tuple_list_type, NamedTuple},
+        tuples::{tuple_list, tuple_list_type, HasConstLen, NamedTuple},
         AsSlice,
     },
     corpus::Corpus,
@@ -104,6 +105,7 @@ where
 {
     mutations: MT,
     max_iterations: u64,
+    noop_detection: bool,
     phantom: PhantomData<(I, R, S)>,
 }
 
@@ -138,10 +140,37 @@ where
         input: &mut I,
         stage_idx: i32,
     ) -> Result<MutationResult, Error> {
-        self.scheduled_mutate(state, input, stage_idx)
+        if !self.noop_detection {
+            return self.scheduled_mutate(state, input, stage_idx);
+        }
+        let before = input_fingerprint(input)?;
+        let result = self.scheduled_mutate(state, input, stage_idx)?;
+        if result == MutationResult::Mutated && input_fingerprint(input)? == before {
+            return Ok(MutationResult::Unchanged);
+        }
+        Ok(result)
+    }
+
+    #[inline]
+    fn post_exec(
+        &mut self,
+        state: &mut S,
+        stage_idx: i32,
+        corpus_idx: Option<usize>,
+    ) -> Result<(), Error> {
+        self.mutations_mut()
+            .post_exec_all(state, stage_idx, corpus_idx)
     }
 }
 
+/// A fingerprint of the serialized input, to tell if the input changed
+fn input_fingerprint<I>(input: &I) -> Result<u64, Error>
+where
+    I: Input,
+{
+    Ok(HashKind::XxHash.hash_bytes(&postcard::to_allocvec(input)?))
+}
+
 impl<I, MT, R, S> ComposedByMutations<I, MT, S> for StdScheduledMutator<I, MT, R, S>
 where
     I: Input,
@@ -193,6 +222,7 @@ where
         StdScheduledMutator {
             mutations,
             max_iterations: 6,
+            noop_detection: false,
             phantom: PhantomData,
         }
     }
@@ -202,14 +232,110 @@ where
         StdScheduledMutator {
             mutations,
             max_iterations,
+            noop_detection: false,
             phantom: PhantomData,
         }
     }
+
+    /// Fingerprints the input before and after the mutations, and returns
+    /// [`MutationResult::Unchanged`] if the mutations cancelled each other out, for example by
+    /// negating the same byte twice, so the mutational stages don't run the same input again.
+    /// Off by default, as fingerprinting costs time for large inputs.
+    #[must_use]
+    pub fn with_noop_detection(mut self, noop_detection: bool) -> Self {
+        self.noop_detection = noop_detection;
+        self
+    }
+
+    /// Returns `true` if the mutations cancelling each other out are detected
+    #[must_use]
+    pub fn noop_detection(&self) -> bool {
+        self.noop_detection
+    }
 }
 
+/// The type of the [`havoc_mutations`] tuple
+pub type HavocMutationsType<C, I, R, S> = tuple_list_type!(
+    BitFlipMutator<I, R, S>,
+    ByteFlipMutator<I, R, S>,
+    ByteIncMutator<I, R, S>,
+    ByteDecMutator<I, R, S>,
+    ByteNegMutator<I, R, S>,
+    ByteRandMutator<I, R, S>,
+    ByteAddMutator<I, R, S>,
+    WordAddMutator<I, R, S>,
+    DwordAddMutator<I, R, S>,
+    QwordAddMutator<I, R, S>,
+    ByteInterestingMutator<I, R, S>,
+    WordInterestingMutator<I, R, S>,
+    DwordInterestingMutator<I, R, S>,
+    BytesDeleteMutator<I, R, S>,
+    BytesDeleteMutator<I, R, S>,
+    BytesDeleteMutator<I, R, S>,
+    BytesDeleteMutator<I, R, S>,
+    BytesExpandMutator<I, R, S>,
+    BytesInsertMutator<I, R, S>,
+    BytesRandInsertMutator<I, R, S>,
+    BytesSetMutator<I, R, S>,
+    BytesRandSetMutator<I, R, S>,
+    BytesCopyMutator<I, R, S>,
+    BytesInsertCopyMutator<I, R, S>,
+    RegionDuplicateMutator<I, R, S>,
+    RepeatedByteInsertMutator<I, R, S>,
+    BytesSwapMutator<I, R, S>,
+    BytesReverseMutator<I, R, S>,
+    BytesRotateMutator<I, R, S>,
+    CrossoverInsertMutator<C, I, R, S>,
+    CrossoverReplaceMutator<C, I, R, S>,
+);
+
 /// Get the mutations that compose the Havoc mutator
 #[must_use]
-pub fn havoc_mutations<C, I, R, S>() -> tuple_list_type!(
+pub fn havoc_mutations<C, I, R, S>() -> HavocMutationsType<C, I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R> + HasCorpus<C, I> + HasMetadata + HasMaxSize,
+    C: Corpus<I>,
+    R: Rand,
+{
+    tuple_list!(
+        BitFlipMutator::new(),
+        ByteFlipMutator::new(),
+        ByteIncMutator::new(),
+        ByteDecMutator::new(),
+        ByteNegMutator::new(),
+        ByteRandMutator::new(),
+        ByteAddMutator::new(),
+        WordAddMutator::new(),
+        DwordAddMutator::new(),
+        QwordAddMutator::new(),
+        ByteInterestingMutator::new(),
+        WordInterestingMutator::new(),
+        DwordInterestingMutator::new(),
+        BytesDeleteMutator::new(),
+        BytesDeleteMutator::new(),
+        BytesDeleteMutator::new(),
+        BytesDeleteMutator::new(),
+        BytesExpandMutator::new(),
+        BytesInsertMutator::new(),
+        BytesRandInsertMutator::new(),
+        BytesSetMutator::new(),
+        BytesRandSetMutator::new(),
+        BytesCopyMutator::new(),
+        BytesInsertCopyMutator::new(),
+        RegionDuplicateMutator::new(),
+        RepeatedByteInsertMutator::new(),
+        BytesSwapMutator::new(),
+        BytesReverseMutator::new(),
+        BytesRotateMutator::new(),
+        CrossoverInsertMutator::new(),
+        CrossoverReplaceMutator::new(),
+    )
+}
+
+/// Get the mutations of the Havoc mutator that only use the input itself, without crossover
+#[must_use]
+pub fn havoc_mutations_no_crossover<I, R, S>() -> tuple_list_type!(
        BitFlipMutator<I, R, S>,
        ByteFlipMutator<I, R, S>,
        ByteIncMutator<I, R, S>,
@@ -234,14 +360,15 @@ pub fn havoc_mutations<C, I, R, S>() -> tuple_list_type!(
        BytesRandSetMutator<I, R, S>,
        BytesCopyMutator<I, R, S>,
        BytesInsertCopyMutator<I, R, S>,
+       RegionDuplicateMutator<I, R, S>,
+       RepeatedByteInsertMutator<I, R, S>,
        BytesSwapMutator<I, R, S>,
-       CrossoverInsertMutator<C, I, R, S>,
-       CrossoverReplaceMutator<C, I, R, S>,
+       BytesReverseMutator<I, R, S>,
+       BytesRotateMutator<I, R, S>,
    )
 where
     I: Input + HasBytesVec,
-    S: HasRand<R> + HasCorpus<C, I> + HasMetadata + HasMaxSize,
-    C: Corpus<I>,
+    S: HasRand<R> + HasMetadata + HasMaxSize,
     R: Rand,
 {
     tuple_list!(
@@ -269,12 +396,324 @@ where
         BytesRandSetMutator::new(),
         BytesCopyMutator::new(),
         BytesInsertCopyMutator::new(),
+        RegionDuplicateMutator::new(),
+        RepeatedByteInsertMutator::new(),
         BytesSwapMutator::new(),
-        CrossoverInsertMutator::new(),
-        CrossoverReplaceMutator::new(),
+        BytesReverseMutator::new(),
+        BytesRotateMutator::new(),
+    )
+}
+
+/// Get the mutations of the Havoc mutator that only overwrite bytes in place,
+/// keeping the length and layout of the input
+#[must_use]
+pub fn havoc_mutations_bytes_only<I, R, S>() -> tuple_list_type!(
+       BitFlipMutator<I, R, S>,
+       ByteFlipMutator<I, R, S>,
+       ByteIncMutator<I, R, S>,
+       ByteDecMutator<I, R, S>,
+       ByteNegMutator<I, R, S>,
+       ByteRandMutator<I, R, S>,
+       ByteAddMutator<I, R, S>,
+       WordAddMutator<I, R, S>,
+       DwordAddMutator<I, R, S>,
+       QwordAddMutator<I, R, S>,
+       ByteInterestingMutator<I, R, S>,
+       WordInterestingMutator<I, R, S>,
+       DwordInterestingMutator<I, R, S>,
+       BytesSetMutator<I, R, S>,
+       BytesRandSetMutator<I, R, S>,
+       BytesCopyMutator<I, R, S>,
+       BytesSwapMutator<I, R, S>,
+       BytesReverseMutator<I, R, S>,
+       BytesRotateMutator<I, R, S>,
+   )
+where
+    I: Input + HasBytesVec,
+    S: HasRand<R> + HasMetadata + HasMaxSize,
+    R: Rand,
+{
+    tuple_list!(
+        BitFlipMutator::new(),
+        ByteFlipMutator::new(),
+        ByteIncMutator::new(),
+        ByteDecMutator::new(),
+        ByteNegMutator::new(),
+        ByteRandMutator::new(),
+        ByteAddMutator::new(),
+        WordAddMutator::new(),
+        DwordAddMutator::new(),
+        QwordAddMutator::new(),
+        ByteInterestingMutator::new(),
+        WordInterestingMutator::new(),
+        DwordInterestingMutator::new(),
+        BytesSetMutator::new(),
+        BytesRandSetMutator::new(),
+        BytesCopyMutator::new(),
+        BytesSwapMutator::new(),
+        BytesReverseMutator::new(),
+        BytesRotateMutator::new(),
     )
 }
 
+/// The category of a mutation in [`havoc_mutations`], see [`HavocMutationConfig`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HavocMutationCategory {
+    /// Overwrites bytes in place, keeping the length and layout of the input
+    Bytes,
+    /// Inserts or deletes bytes, changing the length of the input
+    Resizing,
+    /// Splices in bytes of other testcases of the corpus
+    Crossover,
+}
+
+impl HavocMutationCategory {
+    /// The category of a mutator, by its [`Named::name`], `None` for mutators of no category
+    #[must_use]
+    pub fn of(name: &str) -> Option<Self> {
+        match name {
+            "BitFlipMutator"
+            | "ByteFlipMutator"
+            | "ByteIncMutator"
+            | "ByteDecMutator"
+            | "ByteNegMutator"
+            | "ByteRandMutator"
+            | "ByteAddMutator"
+            | "WordAddMutator"
+            | "DwordAddMutator"
+            | "QwordAddMutator"
+            | "ByteInterestingMutator"
+            | "WordInterestingMutator"
+            | "DwordInterestingMutator"
+            | "BytesSetMutator"
+            | "BytesRandSetMutator"
+            | "BytesCopyMutator"
+            | "BytesSwapMutator"
+            | "BytesReverseMutator"
+            | "BytesRotateMutator" => Some(Self::Bytes),
+            "BytesDeleteMutator"
+            | "BytesExpandMutator"
+            | "BytesInsertMutator"
+            | "BytesRandInsertMutator"
+            | "BytesInsertCopyMutator"
+            | "RegionDuplicateMutator"
+            | "RepeatedByteInsertMutator" => Some(Self::Resizing),
+            "CrossoverInsertMutator" | "CrossoverReplaceMutator" | "SpliceMutator" => {
+                Some(Self::Crossover)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Selects a subset of [`havoc_mutations`] by [`HavocMutationCategory`], to match the mutator
+/// mix to the target. All categories are enabled by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HavocMutationConfig {
+    bytes: bool,
+    resizing: bool,
+    crossover: bool,
+}
+
+impl Default for HavocMutationConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HavocMutationConfig {
+    /// Creates a new [`HavocMutationConfig`], with all categories enabled
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            bytes: true,
+            resizing: true,
+            crossover: true,
+        }
+    }
+
+    /// Enables or disables the [`HavocMutationCategory::Bytes`] mutations
+    #[must_use]
+    pub fn bytes(mut self, enabled: bool) -> Self {
+        self.bytes = enabled;
+        self
+    }
+
+    /// Enables or disables the [`HavocMutationCategory::Resizing`] mutations
+    #[must_use]
+    pub fn resizing(mut self, enabled: bool) -> Self {
+        self.resizing = enabled;
+        self
+    }
+
+    /// Enables or disables the [`HavocMutationCategory::Crossover`] mutations
+    #[must_use]
+    pub fn crossover(mut self, enabled: bool) -> Self {
+        self.crossover = enabled;
+        self
+    }
+
+    /// If mutations of the given category are enabled
+    #[must_use]
+    pub fn is_enabled(&self, category: HavocMutationCategory) -> bool {
+        match category {
+            HavocMutationCategory::Bytes => self.bytes,
+            HavocMutationCategory::Resizing => self.resizing,
+            HavocMutationCategory::Crossover => self.crossover,
+        }
+    }
+
+    /// Builds the [`havoc_mutations`], keeping only the enabled categories.
+    /// The category of each mutator is looked up by name, see [`HavocMutationCategory::of`].
+    ///
+    /// # Errors
+    /// Returns [`Error::IllegalArgument`] if a mutator has no known category,
+    /// or if no mutator is left enabled.
+    pub fn build<C, I, R, S>(
+        &self,
+    ) -> Result<FilteredMutations<HavocMutationsType<C, I, R, S>>, Error>
+    where
+        I: Input + HasBytesVec,
+        S: HasRand<R> + HasCorpus<C, I> + HasMetadata + HasMaxSize,
+        C: Corpus<I>,
+        R: Rand,
+    {
+        let mutations = havoc_mutations();
+        let mut enabled = vec![];
+        for idx in 0..mutations.len() {
+            let name = mutations.name(idx).unwrap();
+            let category = HavocMutationCategory::of(name).ok_or_else(|| {
+                Error::IllegalArgument(format!("No havoc mutation category for {}", name))
+            })?;
+            if self.is_enabled(category) {
+                enabled.push(idx);
+            }
+        }
+        FilteredMutations::new(mutations, enabled)
+    }
+}
+
+/// A [`MutatorsTuple`] exposing only some of the mutators of the wrapped tuple, by index.
+/// Built by [`HavocMutationConfig::build`].
+#[derive(Debug)]
+pub struct FilteredMutations<MT> {
+    mutations: MT,
+    enabled: Vec<usize>,
+}
+
+impl<MT> FilteredMutations<MT>
+where
+    MT: HasConstLen,
+{
+    /// Creates a new [`FilteredMutations`], exposing the mutators at the `enabled` indexes of `mutations`
+    ///
+    /// # Errors
+    /// Returns [`Error::IllegalArgument`] if `enabled` is empty, as a scheduled mutator can't pick
+    /// out of no mutators, or if an index is out of bounds.
+    pub fn new(mutations: MT, enabled: Vec<usize>) -> Result<Self, Error> {
+        if enabled.is_empty() {
+            return Err(Error::IllegalArgument("No mutations enabled".to_owned()));
+        }
+        if let Some(idx) = enabled.iter().find(|idx| **idx >= MT::LEN) {
+            return Err(Error::IllegalArgument(format!(
+                "Mutation index {} out of bounds, only {} mutations",
+                idx,
+                MT::LEN
+            )));
+        }
+        Ok(Self { mutations, enabled })
+    }
+
+    /// The indexes of the exposed mutators in the wrapped tuple
+    #[must_use]
+    pub fn enabled(&self) -> &[usize] {
+        &self.enabled
+    }
+}
+
+impl<MT> HasConstLen for FilteredMutations<MT>
+where
+    MT: HasConstLen,
+{
+    /// The length of the wrapped tuple. Which mutators are enabled is only known at runtime,
+    /// so this is an upper bound of [`HasConstLen::len`], the number of exposed mutators.
+    /// Schedulers pick by [`HasConstLen::len`], never by `LEN`.
+    const LEN: usize = MT::LEN;
+
+    fn len(&self) -> usize {
+        self.enabled.len()
+    }
+}
+
+impl<MT> NamedTuple for FilteredMutations<MT>
+where
+    MT: NamedTuple,
+{
+    fn name(&self, index: usize) -> Option<&str> {
+        self.mutations.name(*self.enabled.get(index)?)
+    }
+}
+
+impl<I, MT, S> MutatorsTuple<I, S> for FilteredMutations<MT>
+where
+    I: Input,
+    MT: MutatorsTuple<I, S>,
+{
+    fn mutate_all(
+        &mut self,
+        state: &mut S,
+        input: &mut I,
+        stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        let mut result = MutationResult::Skipped;
+        for idx in &self.enabled {
+            if self
+                .mutations
+                .get_and_mutate(*idx, state, input, stage_idx)?
+                == MutationResult::Mutated
+            {
+                result = MutationResult::Mutated;
+            }
+        }
+        Ok(result)
+    }
+
+    fn post_exec_all(
+        &mut self,
+        state: &mut S,
+        stage_idx: i32,
+        corpus_idx: Option<usize>,
+    ) -> Result<(), Error> {
+        for idx in &self.enabled {
+            self.mutations
+                .get_and_post_exec(*idx, state, stage_idx, corpus_idx)?;
+        }
+        Ok(())
+    }
+
+    fn get_and_mutate(
+        &mut self,
+        index: usize,
+        state: &mut S,
+        input: &mut I,
+        stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        self.mutations
+            .get_and_mutate(self.enabled[index], state, input, stage_idx)
+    }
+
+    fn get_and_post_exec(
+        &mut self,
+        index: usize,
+        state: &mut S,
+        stage_idx: i32,
+        corpus_idx: Option<usize>,
+    ) -> Result<(), Error> {
+        self.mutations
+            .get_and_post_exec(self.enabled[index], state, stage_idx, corpus_idx)
+    }
+}
+
 /// Get the mutations that uses the Tokens metadata
 #[must_use]
 pub fn tokens_mutations<C, I, R, S>(
@@ -343,9 +782,10 @@ where
     fn post_exec(
         &mut self,
         state: &mut S,
-        _stage_idx: i32,
+        stage_idx: i32,
         corpus_idx: Option<usize>,
     ) -> Result<(), Error> {
+        self.scheduled.post_exec(state, stage_idx, corpus_idx)?;
         if let Some(idx) = corpus_idx {
             let mut testcase = (*state.corpus_mut().get(idx)?).borrow_mut();
             let mut log = Vec::<String>::new();
@@ -450,12 +890,18 @@ where
 #[cfg(test)]
 mod tests {
     use crate::{
-        bolts::rands::{Rand, StdRand, XkcdRand},
+        bolts::{
+            rands::{Rand, StdRand, XkcdRand},
+            tuples::NamedTuple,
+        },
         corpus::{Corpus, InMemoryCorpus, Testcase},
         inputs::{BytesInput, HasBytesVec},
         mutators::{
             mutations::SpliceMutator,
-            scheduled::{havoc_mutations, StdScheduledMutator},
+            scheduled::{
+                havoc_mutations, havoc_mutations_bytes_only, havoc_mutations_no_crossover,
+                HavocMutationConfig, StdScheduledMutator,
+            },
             Mutator,
         },
         state::StdState,
@@ -519,4 +965,70 @@ mod tests {
             assert_ne!(equal_in_a_row, 5);
         }
     }
+
+    type TestState =
+        StdState<InMemoryCorpus<BytesInput>, (), BytesInput, StdRand, InMemoryCorpus<BytesInput>>;
+
+    fn names<NT: NamedTuple>(tuple: &NT) -> Vec<String> {
+        (0..tuple.len())
+            .map(|idx| tuple.name(idx).unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_havoc_mutation_config() {
+        let havoc = havoc_mutations::<InMemoryCorpus<BytesInput>, BytesInput, StdRand, TestState>();
+        let all = HavocMutationConfig::new()
+            .build::<_, BytesInput, StdRand, TestState>()
+            .unwrap();
+        assert_eq!(names(&all), names(&havoc));
+
+        let no_crossover = HavocMutationConfig::new()
+            .crossover(false)
+            .build::<_, BytesInput, StdRand, TestState>()
+            .unwrap();
+        assert!(!names(&no_crossover)
+            .iter()
+            .any(|name| name.starts_with("Crossover")));
+        assert_eq!(
+            names(&no_crossover),
+            names(&havoc_mutations_no_crossover::<
+                BytesInput,
+                StdRand,
+                TestState,
+            >())
+        );
+
+        let bytes_only = HavocMutationConfig::new()
+            .resizing(false)
+            .crossover(false)
+            .build::<InMemoryCorpus<BytesInput>, BytesInput, StdRand, TestState>()
+            .unwrap();
+        assert_eq!(
+            names(&bytes_only),
+            names(&havoc_mutations_bytes_only::<BytesInput, StdRand, TestState>())
+        );
+
+        // Nothing enabled, nothing to schedule
+        assert!(HavocMutationConfig::new()
+            .bytes(false)
+            .resizing(false)
+            .crossover(false)
+            .build::<InMemoryCorpus<BytesInput>, BytesInput, StdRand, TestState>()
+            .is_err());
+
+        // Bytes-only mutations never change the length
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let mut mutator = StdScheduledMutator::new(bytes_only);
+        let mut input = BytesInput::new(vec![0x42; 32]);
+        for _ in 0..64 {
+            mutator.mutate(&mut state, &mut input, 0).unwrap();
+            assert_eq!(input.bytes().len(), 32);
+        }
+    }
 }
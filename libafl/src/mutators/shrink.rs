@@ -0,0 +1,186 @@
+//! The [`ShrinkExpandMutator`] deletes a large part of the input, then grows it again with
+//! tokens and random bytes, to escape local structural optima in a single step.
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use crate::{
+    bolts::{rands::Rand, tuples::Named},
+    inputs::{HasBytesVec, Input},
+    mutators::{MutationResult, Mutator, Tokens},
+    state::{HasMaxSize, HasMetadata, HasRand},
+    Error,
+};
+
+/// The default share of the input, in percent, the [`ShrinkExpandMutator`] deletes at most
+pub const DEFAULT_SHRINK_EXPAND_AGGRESSIVENESS: u64 = 50;
+
+/// The maximum length of a random bytes chunk the [`ShrinkExpandMutator`] inserts
+const SHRINK_EXPAND_MAX_CHUNK: u64 = 16;
+
+/// Deletes a block of between half of and the whole aggressiveness share of the input, then
+/// inserts up to as many bytes again, in chunks at random positions. The chunks are
+/// [`Tokens`], if the state has some, or random bytes.
+/// The result is related to, but structurally different from, the input, and fits in the
+/// [`HasMaxSize::max_size`] of the state.
+#[derive(Debug)]
+pub struct ShrinkExpandMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasMetadata + HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    /// The share of the input deleted at most, in percent
+    aggressiveness: u64,
+    phantom: PhantomData<(I, R, S)>,
+}
+
+impl<I, R, S> Mutator<I, S> for ShrinkExpandMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasMetadata + HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut I,
+        _stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        let size = input.bytes().len();
+        if size < 2 {
+            return Ok(MutationResult::Skipped);
+        }
+
+        // Shrink
+        let max_del = (size as u64 * self.aggressiveness / 100).max(1);
+        let del_len = state.rand_mut().between((max_del / 2).max(1), max_del) as usize;
+        let del_off = state.rand_mut().below((size - del_len + 1) as u64) as usize;
+        input.bytes_mut().drain(del_off..del_off + del_len);
+
+        // Re-expand
+        let max_size = state.max_size();
+        let mut budget = state.rand_mut().between(1, del_len as u64) as usize;
+        while budget > 0 && input.bytes().len() < max_size {
+            let tokens_len = state
+                .metadata()
+                .get::<Tokens>()
+                .map_or(0, |tokens| tokens.tokens().len());
+            let mut chunk: Vec<u8> = if tokens_len > 0 && state.rand_mut().below(2) == 0 {
+                let token_idx = state.rand_mut().below(tokens_len as u64) as usize;
+                let tokens = state.metadata().get::<Tokens>().unwrap();
+                tokens.tokens()[token_idx].clone()
+            } else {
+                let len = state.rand_mut().between(1, SHRINK_EXPAND_MAX_CHUNK);
+                (0..len).map(|_| state.rand_mut().next() as u8).collect()
+            };
+            chunk.truncate(budget.min(max_size - input.bytes().len()));
+            budget -= chunk.len();
+
+            let off = state.rand_mut().below((input.bytes().len() + 1) as u64) as usize;
+            input.bytes_mut().splice(off..off, chunk);
+        }
+
+        let len = input.bytes().len();
+        input.mark_dirty(0..len.max(size));
+        Ok(MutationResult::Mutated)
+    }
+}
+
+impl<I, R, S> Named for ShrinkExpandMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasMetadata + HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    fn name(&self) -> &str {
+        "ShrinkExpandMutator"
+    }
+}
+
+impl<I, R, S> ShrinkExpandMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasMetadata + HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    /// Creates a new [`ShrinkExpandMutator`], deleting up to
+    /// [`DEFAULT_SHRINK_EXPAND_AGGRESSIVENESS`] percent of the input
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_aggressiveness(DEFAULT_SHRINK_EXPAND_AGGRESSIVENESS)
+    }
+
+    /// Creates a new [`ShrinkExpandMutator`], deleting up to `aggressiveness` percent of the input
+    ///
+    /// # Panics
+    /// Panics if `aggressiveness` is not between `1` and `100`
+    #[must_use]
+    pub fn with_aggressiveness(aggressiveness: u64) -> Self {
+        assert!(
+            (1..=100).contains(&aggressiveness),
+            "The aggressiveness is a percentage, between 1 and 100"
+        );
+        Self {
+            aggressiveness,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<I, R, S> Default for ShrinkExpandMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasMetadata + HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::InMemoryCorpus,
+        inputs::{BytesInput, HasBytesVec},
+        mutators::{MutationResult, Mutator, ShrinkExpandMutator},
+        state::{HasMaxSize, StdState},
+    };
+
+    #[test]
+    fn test_shrink_expand_mutator() {
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        state.set_max_size(80);
+        let original: Vec<u8> = (0..64).collect();
+        let mut mutator = ShrinkExpandMutator::new();
+
+        for _ in 0..32 {
+            let mut input = BytesInput::new(original.clone());
+            assert_eq!(
+                mutator.mutate(&mut state, &mut input, 0).unwrap(),
+                MutationResult::Mutated
+            );
+            // Between 16 and 32 bytes deleted, then up to as many inserted
+            let bytes = input.bytes();
+            assert!((32..=64).contains(&bytes.len()));
+            // A whole block of the original is gone
+            let missing = original.iter().filter(|b| !bytes.contains(b)).count();
+            assert!(missing >= 8);
+        }
+
+        // Nothing is inserted past the max size
+        state.set_max_size(40);
+        for _ in 0..32 {
+            let mut input = BytesInput::new(original.clone());
+            mutator.mutate(&mut state, &mut input, 0).unwrap();
+            assert!(input.bytes().len() <= 48);
+        }
+    }
+}
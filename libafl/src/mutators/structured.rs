@@ -0,0 +1,134 @@
+//! Mutators for the [`StructuredInput`], fuzzing typed values generated with `arbitrary`.
+
+use alloc::vec::Vec;
+use arbitrary::Arbitrary;
+use core::{cmp::min, marker::PhantomData};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    bolts::{rands::Rand, tuples::Named},
+    inputs::{HasBytesVec, StructuredInput},
+    mutators::{MutationResult, Mutator},
+    state::{HasMaxSize, HasRand},
+    Error,
+};
+
+/// How often the [`ArbitraryRegenerateMutator`] draws new bytes before giving up
+const ARBITRARY_REGENERATE_TRIES: usize = 8;
+
+/// Replaces the bytes of a [`StructuredInput`] with random bytes of a similar length, between
+/// half and twice the current length, generating a whole new value.
+/// Retries a few times if the value can't be generated from the new bytes, then skips.
+#[derive(Default, Debug)]
+pub struct ArbitraryRegenerateMutator<R, S, T>
+where
+    R: Rand,
+    S: HasRand<R> + HasMaxSize,
+    T: for<'a> Arbitrary<'a> + Serialize + DeserializeOwned,
+{
+    phantom: PhantomData<(R, S, T)>,
+}
+
+impl<R, S, T> Mutator<StructuredInput<T>, S> for ArbitraryRegenerateMutator<R, S, T>
+where
+    R: Rand,
+    S: HasRand<R> + HasMaxSize,
+    T: for<'a> Arbitrary<'a> + Serialize + DeserializeOwned,
+{
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut StructuredInput<T>,
+        _stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        let size = input.bytes().len().max(1);
+        let max_len = min(2 * size, state.max_size());
+        let min_len = min(size / 2, max_len);
+
+        for _ in 0..ARBITRARY_REGENERATE_TRIES {
+            let len = state.rand_mut().between(min_len as u64, max_len as u64) as usize;
+            let bytes: Vec<u8> = (0..len).map(|_| state.rand_mut().next() as u8).collect();
+            let regenerated = StructuredInput::new(bytes);
+            if regenerated.value().is_ok() {
+                *input = regenerated;
+                return Ok(MutationResult::Mutated);
+            }
+        }
+        Ok(MutationResult::Skipped)
+    }
+}
+
+impl<R, S, T> Named for ArbitraryRegenerateMutator<R, S, T>
+where
+    R: Rand,
+    S: HasRand<R> + HasMaxSize,
+    T: for<'a> Arbitrary<'a> + Serialize + DeserializeOwned,
+{
+    fn name(&self) -> &str {
+        "ArbitraryRegenerateMutator"
+    }
+}
+
+impl<R, S, T> ArbitraryRegenerateMutator<R, S, T>
+where
+    R: Rand,
+    S: HasRand<R> + HasMaxSize,
+    T: for<'a> Arbitrary<'a> + Serialize + DeserializeOwned,
+{
+    /// Creates a new [`ArbitraryRegenerateMutator`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            phantom: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arbitrary::{Arbitrary, Unstructured};
+    use serde::{Deserialize, Serialize};
+
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::InMemoryCorpus,
+        inputs::{HasBytesVec, StructuredInput},
+        mutators::{ArbitraryRegenerateMutator, MutationResult, Mutator},
+        state::StdState,
+    };
+
+    /// A value only some bytes generate
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Even(u8);
+
+    impl<'a> Arbitrary<'a> for Even {
+        fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+            let val: u8 = u.arbitrary()?;
+            if val % 2 == 0 {
+                Ok(Self(val))
+            } else {
+                Err(arbitrary::Error::IncorrectFormat)
+            }
+        }
+    }
+
+    #[test]
+    fn test_arbitrary_regenerate_mutator() {
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<StructuredInput<Even>>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let mut mutator = ArbitraryRegenerateMutator::new();
+        let mut input = StructuredInput::<Even>::new(vec![0, 0, 0, 0]);
+        for _ in 0..64 {
+            let size = input.bytes().len().max(1);
+            if mutator.mutate(&mut state, &mut input, 0).unwrap() == MutationResult::Mutated {
+                assert!((size / 2..=size * 2).contains(&input.bytes().len()));
+            }
+            // Skipped mutations keep the previous, valid, bytes
+            assert_eq!(input.value().unwrap().0 % 2, 0);
+        }
+    }
+}
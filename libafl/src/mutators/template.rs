@@ -0,0 +1,177 @@
+//! Templates with holes, like `GET <path> HTTP/1.1\r\n`, filled with bytes of the input.
+//! They sit between flat [`crate::mutators::Tokens`] and full grammars, for protocol fuzzing.
+
+use alloc::vec::Vec;
+use core::{cmp::min, marker::PhantomData};
+
+use crate::{
+    bolts::{rands::Rand, tuples::Named},
+    inputs::{HasBytesVec, Input},
+    mutators::{MutationResult, Mutator},
+    state::{HasMaxSize, HasRand},
+    Error,
+};
+
+/// Splits `template` at each occurrence of `marker`
+fn split_at_marker(template: &[u8], marker: &[u8]) -> Vec<Vec<u8>> {
+    let mut parts = vec![];
+    let mut start = 0;
+    let mut idx = 0;
+    while idx + marker.len() <= template.len() {
+        if &template[idx..idx + marker.len()] == marker {
+            parts.push(template[start..idx].to_vec());
+            idx += marker.len();
+            start = idx;
+        } else {
+            idx += 1;
+        }
+    }
+    parts.push(template[start..].to_vec());
+    parts
+}
+
+/// Replaces the input with one of its templates, filling each hole with a random slice of the
+/// input. The slices are shortened so that the result stays within `max_size`.
+/// Skips if the input is empty, or no bytes fit in the holes of the chosen template.
+#[derive(Debug, Clone)]
+pub struct TemplateMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    R: Rand,
+    S: HasRand<R> + HasMaxSize,
+{
+    /// The templates, each split at its holes
+    templates: Vec<Vec<Vec<u8>>>,
+    phantom: PhantomData<(I, R, S)>,
+}
+
+impl<I, R, S> Mutator<I, S> for TemplateMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    R: Rand,
+    S: HasRand<R> + HasMaxSize,
+{
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut I,
+        _stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        let size = input.bytes().len();
+        if size == 0 || self.templates.is_empty() {
+            return Ok(MutationResult::Skipped);
+        }
+
+        let parts = state.rand_mut().choose(&self.templates);
+        let holes = parts.len() - 1;
+        let fixed: usize = parts.iter().map(Vec::len).sum();
+        let max_size = state.max_size();
+        if fixed > max_size {
+            return Ok(MutationResult::Skipped);
+        }
+        let max_len = match (max_size - fixed).checked_div(holes) {
+            Some(0) => return Ok(MutationResult::Skipped),
+            Some(budget) => min(size, budget),
+            None => 0,
+        };
+
+        let mut templated = Vec::with_capacity(fixed + holes * max_len);
+        for (i, part) in parts.iter().enumerate() {
+            templated.extend_from_slice(part);
+            if i < holes {
+                let len = 1 + state.rand_mut().below(max_len as u64) as usize;
+                let off = state.rand_mut().below((size - len + 1) as u64) as usize;
+                templated.extend_from_slice(&input.bytes()[off..off + len]);
+            }
+        }
+
+        let len = templated.len();
+        *input.bytes_mut() = templated;
+        input.mark_dirty(0..len);
+        Ok(MutationResult::Mutated)
+    }
+}
+
+impl<I, R, S> Named for TemplateMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    R: Rand,
+    S: HasRand<R> + HasMaxSize,
+{
+    fn name(&self) -> &str {
+        "TemplateMutator"
+    }
+}
+
+impl<I, R, S> TemplateMutator<I, R, S>
+where
+    I: Input + HasBytesVec,
+    R: Rand,
+    S: HasRand<R> + HasMaxSize,
+{
+    /// Creates a new [`TemplateMutator`] for the given templates, with holes marked by `marker`
+    ///
+    /// # Panics
+    /// Panics if `marker` is empty
+    #[must_use]
+    pub fn new(templates: &[Vec<u8>], marker: &[u8]) -> Self {
+        assert!(!marker.is_empty(), "The template marker must not be empty");
+        Self {
+            templates: templates
+                .iter()
+                .map(|template| split_at_marker(template, marker))
+                .collect(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::InMemoryCorpus,
+        inputs::{BytesInput, HasBytesVec},
+        mutators::{MutationResult, Mutator, TemplateMutator},
+        state::{HasMaxSize, StdState},
+    };
+
+    #[test]
+    fn test_template_mutator() {
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let mut mutator = TemplateMutator::new(&[b"GET <path> HTTP/1.1\r\n".to_vec()], b"<path>");
+        let seed = b"/index.html";
+
+        // The hole is filled with a slice of the input, shortened to fit in max_size
+        for max_size in [1024, 18] {
+            state.set_max_size(max_size);
+            for _ in 0..32 {
+                let mut input = BytesInput::new(seed.to_vec());
+                assert_eq!(
+                    mutator.mutate(&mut state, &mut input, 0).unwrap(),
+                    MutationResult::Mutated
+                );
+                let bytes = input.bytes();
+                assert!(bytes.len() <= max_size);
+                assert!(bytes.starts_with(b"GET "));
+                assert!(bytes.ends_with(b" HTTP/1.1\r\n"));
+                let path = &bytes[4..bytes.len() - 11];
+                assert!(!path.is_empty());
+                assert!(seed.windows(path.len()).any(|window| window == path));
+            }
+        }
+
+        // Nothing fits in the hole
+        state.set_max_size(15);
+        let mut input = BytesInput::new(seed.to_vec());
+        assert_eq!(
+            mutator.mutate(&mut state, &mut input, 0).unwrap(),
+            MutationResult::Skipped
+        );
+    }
+}
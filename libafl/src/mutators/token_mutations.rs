@@ -22,10 +22,76 @@ use crate::{
     Error,
 };
 
+/// How often a token led to an interesting input, tracked per token in [`Tokens`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenStats {
+    /// Interesting inputs produced by a [`TokenInsert`] of this token
+    pub insert_successes: u64,
+    /// Interesting inputs produced by a [`TokenReplace`] with this token
+    pub replace_successes: u64,
+}
+
+impl TokenStats {
+    /// The number of interesting inputs produced by this token, regardless of the mutation
+    #[must_use]
+    pub fn successes(&self) -> u64 {
+        self.insert_successes + self.replace_successes
+    }
+}
+
+/// Signatures of common file formats, see [`Tokens::common_format_magics()`]
+pub const COMMON_FORMAT_MAGICS: [&[u8]; 14] = [
+    // PNG
+    b"\x89PNG\r\n\x1a\n",
+    b"IHDR",
+    b"IEND",
+    // JPEG (SOI, JFIF and Exif APP markers, EOI)
+    b"\xff\xd8\xff",
+    b"\xff\xd8\xff\xe0",
+    b"\xff\xd8\xff\xe1",
+    b"\xff\xd9",
+    // ELF
+    b"\x7fELF",
+    // ZIP (local file header, central directory, end of central directory)
+    b"PK\x03\x04",
+    b"PK\x01\x02",
+    b"PK\x05\x06",
+    // PDF
+    b"%PDF-",
+    // GIF
+    b"GIF87a",
+    b"GIF89a",
+];
+
 /// A state metadata holding a list of tokens
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(from = "TokensData")]
 pub struct Tokens {
     token_vec: Vec<Vec<u8>>,
+    token_stats: Vec<TokenStats>,
+}
+
+/// The serialized form of [`Tokens`], also accepting tokens stored without stats
+#[derive(Deserialize)]
+struct TokensData {
+    token_vec: Vec<Vec<u8>>,
+    #[serde(default)]
+    token_stats: Vec<TokenStats>,
+}
+
+impl From<TokensData> for Tokens {
+    fn from(data: TokensData) -> Self {
+        let TokensData {
+            token_vec,
+            mut token_stats,
+        } = data;
+        // Keep one entry of stats per token
+        token_stats.resize(token_vec.len(), TokenStats::default());
+        Self {
+            token_vec,
+            token_stats,
+        }
+    }
 }
 
 crate::impl_serdeany!(Tokens);
@@ -35,7 +101,33 @@ impl Tokens {
     /// Creates a new tokens metadata (old-skool afl name: `dictornary`)
     #[must_use]
     pub fn new(token_vec: Vec<Vec<u8>>) -> Self {
-        Self { token_vec }
+        let token_stats = vec![TokenStats::default(); token_vec.len()];
+        Self {
+            token_vec,
+            token_stats,
+        }
+    }
+
+    /// Creates a new tokens metadata, prefilled with the magic bytes of common file formats,
+    /// such as PNG, JPEG, ELF, ZIP, PDF and GIF headers.
+    /// Use it as a starter dictionary, and [`Tokens::merge()`] your own tokens into it.
+    #[must_use]
+    pub fn common_format_magics() -> Self {
+        let mut ret = Self::new(vec![]);
+        for magic in COMMON_FORMAT_MAGICS {
+            ret.add_token(&magic.to_vec());
+        }
+        ret
+    }
+
+    /// Adds all tokens of `other` to this dictionary, skipping duplicates.
+    /// Returns the count of new entries.
+    pub fn merge(&mut self, other: &Self) -> usize {
+        other
+            .tokens()
+            .iter()
+            .filter(|token| self.add_token(token))
+            .count()
     }
 
     /// Creates a new instance from a file
@@ -57,6 +149,7 @@ impl Tokens {
             return false;
         }
         self.token_vec.push(token.clone());
+        self.token_stats.push(TokenStats::default());
         true
     }
 
@@ -123,6 +216,46 @@ impl Tokens {
     pub fn tokens(&self) -> &[Vec<u8>] {
         &self.token_vec
     }
+
+    /// Gets the success stats of the tokens, with the same indices as [`Tokens::tokens()`].
+    /// Tokens that never produced an interesting input are candidates for pruning.
+    #[must_use]
+    pub fn token_stats(&self) -> &[TokenStats] {
+        &self.token_stats
+    }
+
+    /// The weight of each token for weighted selection, see [`TokenInsertWeighted`]:
+    /// its number of successes, plus one, so tokens without successes keep a chance.
+    #[must_use]
+    pub fn selection_weights(&self) -> Vec<u64> {
+        self.token_stats
+            .iter()
+            .map(|stats| stats.successes() + 1)
+            .collect()
+    }
+
+    /// Gets the success stats of the tokens (mut)
+    pub fn token_stats_mut(&mut self) -> &mut [TokenStats] {
+        &mut self.token_stats
+    }
+
+    /// Keeps only the tokens for which `keep` returns `true`, together with their stats.
+    /// Returns the count of removed entries.
+    pub fn retain<F>(&mut self, mut keep: F) -> usize
+    where
+        F: FnMut(&[u8], &TokenStats) -> bool,
+    {
+        let before = self.token_vec.len();
+        let (token_vec, token_stats) = self
+            .token_vec
+            .drain(..)
+            .zip(self.token_stats.drain(..))
+            .filter(|(token, stats)| keep(token, stats))
+            .unzip();
+        self.token_vec = token_vec;
+        self.token_stats = token_stats;
+        before - self.token_vec.len()
+    }
 }
 
 /// Inserts a random token at a random position in the `Input`.
@@ -133,6 +266,10 @@ where
     S: HasMetadata + HasRand<R> + HasMaxSize,
     R: Rand,
 {
+    /// The tokens inserted since the last execution
+    used_tokens: Vec<usize>,
+    /// Select tokens by their success, instead of uniformly
+    weighted: bool,
     phantom: PhantomData<(I, R, S)>,
 }
 
@@ -149,7 +286,7 @@ where
         _stage_idx: i32,
     ) -> Result<MutationResult, Error> {
         let max_size = state.max_size();
-        let tokens_len = {
+        let (tokens_len, weights) = {
             let meta = state.metadata().get::<Tokens>();
             if meta.is_none() {
                 return Ok(MutationResult::Skipped);
@@ -157,9 +294,17 @@ where
             if meta.unwrap().tokens().is_empty() {
                 return Ok(MutationResult::Skipped);
             }
-            meta.unwrap().tokens().len()
+            let weights = if self.weighted {
+                Some(meta.unwrap().selection_weights())
+            } else {
+                None
+            };
+            (meta.unwrap().tokens().len(), weights)
+        };
+        let token_idx = match weights {
+            Some(weights) => state.rand_mut().choose_weighted(&weights).unwrap(),
+            None => state.rand_mut().below(tokens_len as u64) as usize,
         };
-        let token_idx = state.rand_mut().below(tokens_len as u64) as usize;
 
         let size = input.bytes().len();
         let off = state.rand_mut().below((size + 1) as u64) as usize;
@@ -179,9 +324,30 @@ where
         input.bytes_mut().resize(size + len, 0);
         buffer_self_copy(input.bytes_mut(), off, off + len, size - off);
         buffer_copy(input.bytes_mut(), token, 0, off, len);
+        input.mark_dirty(off..size + len);
 
+        self.used_tokens.push(token_idx);
         Ok(MutationResult::Mutated)
     }
+
+    fn post_exec(
+        &mut self,
+        state: &mut S,
+        _stage_idx: i32,
+        corpus_idx: Option<usize>,
+    ) -> Result<(), Error> {
+        if corpus_idx.is_some() {
+            if let Some(meta) = state.metadata_mut().get_mut::<Tokens>() {
+                for &token_idx in &self.used_tokens {
+                    if let Some(stats) = meta.token_stats_mut().get_mut(token_idx) {
+                        stats.insert_successes += 1;
+                    }
+                }
+            }
+        }
+        self.used_tokens.clear();
+        Ok(())
+    }
 }
 
 impl<I, R, S> Named for TokenInsert<I, R, S>
@@ -205,11 +371,93 @@ where
     #[must_use]
     pub fn new() -> Self {
         Self {
+            used_tokens: vec![],
+            weighted: false,
             phantom: PhantomData,
         }
     }
 }
 
+/// A [`TokenInsert`] that selects tokens proportionally to their recorded successes, see
+/// [`Tokens::selection_weights`], instead of uniformly.
+#[derive(Debug)]
+pub struct TokenInsertWeighted<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasMetadata + HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    inner: TokenInsert<I, R, S>,
+}
+
+impl<I, R, S> Mutator<I, S> for TokenInsertWeighted<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasMetadata + HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    #[inline]
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut I,
+        stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        self.inner.mutate(state, input, stage_idx)
+    }
+
+    #[inline]
+    fn post_exec(
+        &mut self,
+        state: &mut S,
+        stage_idx: i32,
+        corpus_idx: Option<usize>,
+    ) -> Result<(), Error> {
+        self.inner.post_exec(state, stage_idx, corpus_idx)
+    }
+}
+
+impl<I, R, S> Named for TokenInsertWeighted<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasMetadata + HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    fn name(&self) -> &str {
+        "TokenInsertWeighted"
+    }
+}
+
+impl<I, R, S> TokenInsertWeighted<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasMetadata + HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    /// Create a `TokenInsertWeighted` `Mutation`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            inner: TokenInsert {
+                used_tokens: vec![],
+                weighted: true,
+                phantom: PhantomData,
+            },
+        }
+    }
+}
+
+impl<I, R, S> Default for TokenInsertWeighted<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasMetadata + HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// A `TokenReplace` [`Mutator`] replaces a random part of the input with one of a range of tokens.
 /// From AFL terms, this is called as `Dictionary` mutation (which doesn't really make sense ;) ).
 #[derive(Debug, Default)]
@@ -219,6 +467,10 @@ where
     S: HasMetadata + HasRand<R> + HasMaxSize,
     R: Rand,
 {
+    /// The tokens written since the last execution
+    used_tokens: Vec<usize>,
+    /// Select tokens by their success, instead of uniformly
+    weighted: bool,
     phantom: PhantomData<(I, R, S)>,
 }
 
@@ -239,7 +491,7 @@ where
             return Ok(MutationResult::Skipped);
         }
 
-        let tokens_len = {
+        let (tokens_len, weights) = {
             let meta = state.metadata().get::<Tokens>();
             if meta.is_none() {
                 return Ok(MutationResult::Skipped);
@@ -247,9 +499,17 @@ where
             if meta.unwrap().tokens().is_empty() {
                 return Ok(MutationResult::Skipped);
             }
-            meta.unwrap().tokens().len()
+            let weights = if self.weighted {
+                Some(meta.unwrap().selection_weights())
+            } else {
+                None
+            };
+            (meta.unwrap().tokens().len(), weights)
+        };
+        let token_idx = match weights {
+            Some(weights) => state.rand_mut().choose_weighted(&weights).unwrap(),
+            None => state.rand_mut().below(tokens_len as u64) as usize,
         };
-        let token_idx = state.rand_mut().below(tokens_len as u64) as usize;
 
         let off = state.rand_mut().below(size as u64) as usize;
 
@@ -261,9 +521,30 @@ where
         }
 
         buffer_copy(input.bytes_mut(), token, 0, off, len);
+        input.mark_dirty(off..off + len);
 
+        self.used_tokens.push(token_idx);
         Ok(MutationResult::Mutated)
     }
+
+    fn post_exec(
+        &mut self,
+        state: &mut S,
+        _stage_idx: i32,
+        corpus_idx: Option<usize>,
+    ) -> Result<(), Error> {
+        if corpus_idx.is_some() {
+            if let Some(meta) = state.metadata_mut().get_mut::<Tokens>() {
+                for &token_idx in &self.used_tokens {
+                    if let Some(stats) = meta.token_stats_mut().get_mut(token_idx) {
+                        stats.replace_successes += 1;
+                    }
+                }
+            }
+        }
+        self.used_tokens.clear();
+        Ok(())
+    }
 }
 
 impl<I, R, S> Named for TokenReplace<I, R, S>
@@ -287,11 +568,93 @@ where
     #[must_use]
     pub fn new() -> Self {
         Self {
+            used_tokens: vec![],
+            weighted: false,
             phantom: PhantomData,
         }
     }
 }
 
+/// A [`TokenReplace`] that selects tokens proportionally to their recorded successes, see
+/// [`Tokens::selection_weights`], instead of uniformly.
+#[derive(Debug)]
+pub struct TokenReplaceWeighted<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasMetadata + HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    inner: TokenReplace<I, R, S>,
+}
+
+impl<I, R, S> Mutator<I, S> for TokenReplaceWeighted<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasMetadata + HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    #[inline]
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut I,
+        stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        self.inner.mutate(state, input, stage_idx)
+    }
+
+    #[inline]
+    fn post_exec(
+        &mut self,
+        state: &mut S,
+        stage_idx: i32,
+        corpus_idx: Option<usize>,
+    ) -> Result<(), Error> {
+        self.inner.post_exec(state, stage_idx, corpus_idx)
+    }
+}
+
+impl<I, R, S> Named for TokenReplaceWeighted<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasMetadata + HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    fn name(&self) -> &str {
+        "TokenReplaceWeighted"
+    }
+}
+
+impl<I, R, S> TokenReplaceWeighted<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasMetadata + HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    /// Create a `TokenReplaceWeighted` `Mutation`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            inner: TokenReplace {
+                used_tokens: vec![],
+                weighted: true,
+                phantom: PhantomData,
+            },
+        }
+    }
+}
+
+impl<I, R, S> Default for TokenReplaceWeighted<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasMetadata + HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// A `I2SRandReplace` [`Mutator`] replaces a random matching input-2-state comparison operand with the other.
 /// it needs a valid [`CmpValuesMetadata`] in the state.
 #[derive(Debug, Default)]
@@ -444,22 +807,28 @@ where
                 }
             }
             CmpValues::Bytes(v) => {
+                // The operands may also be stored reversed in the input, e.g. for a `memcmp`
+                // of a byte-swapped integer
+                let rev_0: Vec<u8> = v.0.iter().rev().copied().collect();
+                let rev_1: Vec<u8> = v.1.iter().rev().copied().collect();
+                let candidates = [
+                    (&v.0, &v.1),
+                    (&rev_0, &rev_1),
+                    (&v.1, &v.0),
+                    (&rev_1, &rev_0),
+                ];
                 'outer: for i in off..len {
-                    let mut size = core::cmp::min(v.0.len(), len - i);
-                    while size != 0 {
-                        if v.0[0..size] == input.bytes()[i..i + size] {
-                            buffer_copy(input.bytes_mut(), &v.1, 0, i, size);
-                            break 'outer;
+                    for (pattern, replacement) in candidates {
+                        let mut size = core::cmp::min(pattern.len(), len - i);
+                        while size != 0 {
+                            if pattern[0..size] == input.bytes()[i..i + size] {
+                                let size = core::cmp::min(size, replacement.len());
+                                buffer_copy(input.bytes_mut(), replacement, 0, i, size);
+                                result = MutationResult::Mutated;
+                                break 'outer;
+                            }
+                            size -= 1;
                         }
-                        size -= 1;
-                    }
-                    size = core::cmp::min(v.1.len(), len - i);
-                    while size != 0 {
-                        if v.1[0..size] == input.bytes()[i..i + size] {
-                            buffer_copy(input.bytes_mut(), &v.0, 0, i, size);
-                            break 'outer;
-                        }
-                        size -= 1;
                     }
                 }
             }
@@ -467,6 +836,10 @@ where
 
         //println!("{:?}", result);
 
+        if result == MutationResult::Mutated {
+            // The replaced bytes can be anywhere after `off`
+            input.mark_dirty(off..len);
+        }
         Ok(result)
     }
 }
@@ -497,13 +870,161 @@ where
     }
 }
 
+/// The largest distance from the comparand [`I2SBoundaryReplace`] writes, besides `±1`
+const I2S_BOUNDARY_MAX_DELTA: u64 = 8;
+
+/// Reads a native endian integer of `size` bytes
+fn read_uint(bytes: &[u8], size: usize) -> u64 {
+    match size {
+        1 => u64::from(bytes[0]),
+        2 => u64::from(u16::from_ne_bytes(bytes[..2].try_into().unwrap())),
+        4 => u64::from(u32::from_ne_bytes(bytes[..4].try_into().unwrap())),
+        _ => u64::from_ne_bytes(bytes[..8].try_into().unwrap()),
+    }
+}
+
+/// Writes `val` as native endian integer of `size` bytes, truncating it
+#[allow(clippy::cast_possible_truncation)]
+fn write_uint(bytes: &mut [u8], val: u64, size: usize) {
+    match size {
+        1 => bytes[0] = val as u8,
+        2 => bytes[..2].copy_from_slice(&(val as u16).to_ne_bytes()),
+        4 => bytes[..4].copy_from_slice(&(val as u32).to_ne_bytes()),
+        _ => bytes[..8].copy_from_slice(&val.to_ne_bytes()),
+    }
+}
+
+/// A `I2SBoundaryReplace` [`Mutator`] replaces a matching integer comparison operand in the input
+/// with the other one, like [`I2SRandReplace`], or with a value next to it: `±1`, or a bit further
+/// away, to probe off-by-one bugs at the boundaries of comparisons.
+/// It needs a valid [`CmpValuesMetadata`] in the state, and skips comparisons of byte sequences.
+#[derive(Debug, Default)]
+pub struct I2SBoundaryReplace<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasMetadata + HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    phantom: PhantomData<(I, R, S)>,
+}
+
+impl<I, R, S> Mutator<I, S> for I2SBoundaryReplace<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasMetadata + HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut I,
+        _stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        let len = input.bytes().len();
+        let cmps_len = state
+            .metadata()
+            .get::<CmpValuesMetadata>()
+            .map_or(0, |meta| meta.list.len());
+        if len == 0 || cmps_len == 0 {
+            return Ok(MutationResult::Skipped);
+        }
+        let idx = state.rand_mut().below(cmps_len as u64) as usize;
+
+        let meta = state.metadata().get::<CmpValuesMetadata>().unwrap();
+        let (v0, v1, size) = match &meta.list[idx] {
+            CmpValues::U8(v) => (u64::from(v.0), u64::from(v.1), size_of::<u8>()),
+            CmpValues::U16(v) => (u64::from(v.0), u64::from(v.1), size_of::<u16>()),
+            CmpValues::U32(v) => (u64::from(v.0), u64::from(v.1), size_of::<u32>()),
+            CmpValues::U64(v) => (v.0, v.1, size_of::<u64>()),
+            CmpValues::Bytes(_) => return Ok(MutationResult::Skipped),
+        };
+        if len < size {
+            return Ok(MutationResult::Skipped);
+        }
+
+        let delta = match state.rand_mut().below(4) {
+            0 => 0,
+            1 => 1,
+            2 => u64::MAX,
+            _ => {
+                let delta = state.rand_mut().between(2, I2S_BOUNDARY_MAX_DELTA);
+                if state.rand_mut().below(2) == 0 {
+                    delta
+                } else {
+                    delta.wrapping_neg()
+                }
+            }
+        };
+        let off = state.rand_mut().below((len - size + 1) as u64) as usize;
+
+        let bytes = input.bytes_mut();
+        for i in off..=len - size {
+            let val = read_uint(&bytes[i..], size);
+            let swapped = val.swap_bytes() >> (64 - 8 * size);
+            let replacement = if val == v0 || swapped == v0 {
+                v1.wrapping_add(delta)
+            } else if val == v1 || swapped == v1 {
+                v0.wrapping_add(delta)
+            } else {
+                continue;
+            };
+            // Keep the byte order of the matched operand
+            let replacement = if val == v0 || val == v1 {
+                replacement
+            } else {
+                replacement.swap_bytes() >> (64 - 8 * size)
+            };
+            write_uint(&mut bytes[i..], replacement, size);
+            input.mark_dirty(i..i + size);
+            return Ok(MutationResult::Mutated);
+        }
+        Ok(MutationResult::Skipped)
+    }
+}
+
+impl<I, R, S> Named for I2SBoundaryReplace<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasMetadata + HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    fn name(&self) -> &str {
+        "I2SBoundaryReplace"
+    }
+}
+
+impl<I, R, S> I2SBoundaryReplace<I, R, S>
+where
+    I: Input + HasBytesVec,
+    S: HasMetadata + HasRand<R> + HasMaxSize,
+    R: Rand,
+{
+    /// Creates a new `I2SBoundaryReplace` struct.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            phantom: PhantomData,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[cfg(feature = "std")]
     use std::fs;
 
-    #[cfg(feature = "std")]
     use super::Tokens;
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::InMemoryCorpus,
+        inputs::{BytesInput, HasBytesVec},
+        mutators::{
+            tokens_mutations, I2SBoundaryReplace, I2SRandReplace, MutationResult, Mutator,
+            StdScheduledMutator, TokenInsertWeighted,
+        },
+        observers::{CmpValues, CmpValuesMetadata},
+        state::{HasMetadata, StdState},
+    };
 
     #[cfg(feature = "std")]
     #[test]
@@ -523,4 +1044,135 @@ token2="B"
         assert_eq!(tokens.tokens().len(), 2);
         let _res = fs::remove_file("test.tkns");
     }
+
+    #[test]
+    fn test_common_format_magics() {
+        let magics = Tokens::common_format_magics();
+        for magic in [&b"\x89PNG\r\n\x1a\n"[..], b"\x7fELF", b"%PDF-", b"GIF89a"] {
+            assert!(magics.tokens().iter().any(|token| token == magic));
+        }
+
+        let mut tokens = Tokens::new(vec![b"MAGIC".to_vec(), b"\x7fELF".to_vec()]);
+        assert_eq!(tokens.merge(&magics), magics.tokens().len() - 1);
+        assert_eq!(tokens.merge(&magics), 0);
+        assert_eq!(tokens.tokens().len(), magics.tokens().len() + 1);
+    }
+
+    #[test]
+    fn test_token_stats() {
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        state.add_metadata(Tokens::new(vec![b"MAGIC".to_vec()]));
+
+        let mut mutator = StdScheduledMutator::new(tokens_mutations());
+        let mut input = BytesInput::new(b"0123456789".to_vec());
+
+        // Not interesting, nothing gets counted
+        mutator.mutate(&mut state, &mut input, 0).unwrap();
+        mutator.post_exec(&mut state, 0, None).unwrap();
+        let tokens = state.metadata().get::<Tokens>().unwrap();
+        assert_eq!(tokens.token_stats()[0].successes(), 0);
+
+        // The token led to new coverage, so the resulting input got added to the corpus
+        mutator.mutate(&mut state, &mut input, 0).unwrap();
+        mutator.post_exec(&mut state, 0, Some(0)).unwrap();
+        let tokens = state.metadata().get::<Tokens>().unwrap();
+        assert!(tokens.token_stats()[0].successes() > 0);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_tokens_without_stats() {
+        // Stored before the stats were tracked
+        let tokens: Tokens = serde_json::from_str(r#"{"token_vec":[[65],[66]]}"#).unwrap();
+        assert_eq!(tokens.tokens().len(), 2);
+        assert_eq!(tokens.token_stats().len(), 2);
+        assert_eq!(tokens.token_stats()[1].successes(), 0);
+    }
+
+    #[test]
+    fn test_i2s_reversed_bytes() {
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let mut meta = CmpValuesMetadata::new();
+        meta.list
+            .push(CmpValues::Bytes((b"ABCD".to_vec(), b"WXYZ".to_vec())));
+        state.add_metadata(meta);
+
+        // Only the reversed operand matches, and only at the start of the input
+        let mut mutator = I2SRandReplace::new();
+        let mut mutated = 0;
+        for _ in 0..32 {
+            let mut input = BytesInput::new(b"DCB".to_vec());
+            if mutator.mutate(&mut state, &mut input, 0).unwrap() == MutationResult::Mutated {
+                assert_eq!(input.bytes(), b"ZYX");
+                mutated += 1;
+            }
+        }
+        assert!(mutated > 0);
+    }
+
+    #[test]
+    fn test_i2s_boundary_replace() {
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let mut meta = CmpValuesMetadata::new();
+        meta.list.push(CmpValues::U32((0x1122_3344, 1000)));
+        state.add_metadata(meta);
+
+        let mut mutator = I2SBoundaryReplace::new();
+        let mut written = vec![];
+        for _ in 0..64 {
+            let mut input = BytesInput::new(0x1122_3344_u32.to_ne_bytes().to_vec());
+            if mutator.mutate(&mut state, &mut input, 0).unwrap() == MutationResult::Mutated {
+                let val = u32::from_ne_bytes(input.bytes().try_into().unwrap());
+                assert!((992..=1008).contains(&val));
+                written.push(val);
+            }
+        }
+        // The exact comparand and both its neighbors
+        assert!(written.contains(&1000));
+        assert!(written.contains(&999));
+        assert!(written.contains(&1001));
+    }
+
+    #[test]
+    fn test_token_insert_weighted() {
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let mut tokens = Tokens::new(vec![b"A".to_vec(), b"B".to_vec()]);
+        tokens.token_stats_mut()[0].insert_successes = 9;
+        state.add_metadata(tokens);
+
+        // "A" has a weight of 10, "B" of 1
+        let mut mutator = TokenInsertWeighted::new();
+        let mut inserted_a = 0;
+        for _ in 0..1100 {
+            let mut input = BytesInput::new(vec![]);
+            assert_eq!(
+                mutator.mutate(&mut state, &mut input, 0).unwrap(),
+                MutationResult::Mutated
+            );
+            if input.bytes() == b"A" {
+                inserted_a += 1;
+            }
+        }
+        assert!((900..1050).contains(&inserted_a));
+    }
 }
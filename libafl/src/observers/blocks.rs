@@ -0,0 +1,84 @@
+//! The [`BlockCountObserver`] reads the number of basic blocks the target executed in a run.
+
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::{ownedref::OwnedRefMut, tuples::Named},
+    observers::Observer,
+    Error,
+};
+
+/// Observes the total number of basic blocks executed during a run, counted by the harness.
+///
+/// The contract with the harness: the counter is a global `u64`. Instrumentation increments it
+/// once for each basic block executed, loop iterations included, and never resets it.
+/// The observer zeroes the counter before each run, so after the run it holds the blocks executed
+/// by this run only. Unlike edge coverage, the count keeps growing with loop depth.
+/// Use a [`crate::feedbacks::BlockCountFeedback`] to find inputs executing more blocks than any before.
+#[derive(Serialize, Deserialize, Debug)]
+#[allow(clippy::unsafe_derive_deserialize)]
+pub struct BlockCountObserver<'a> {
+    counter: OwnedRefMut<'a, u64>,
+    name: String,
+}
+
+impl<'a, I, S> Observer<I, S> for BlockCountObserver<'a> {
+    #[inline]
+    fn pre_exec(&mut self, _state: &mut S, _input: &I) -> Result<(), Error> {
+        *self.counter.as_mut() = 0;
+        Ok(())
+    }
+}
+
+impl<'a> Named for BlockCountObserver<'a> {
+    #[inline]
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
+impl<'a> BlockCountObserver<'a> {
+    /// Creates a new [`BlockCountObserver`] for the given counter
+    #[must_use]
+    pub fn new(name: &'static str, counter: &'a mut u64) -> Self {
+        Self {
+            counter: OwnedRefMut::Ref(counter),
+            name: name.to_string(),
+        }
+    }
+
+    /// Creates a new [`BlockCountObserver`] with an owned counter
+    #[must_use]
+    pub fn new_owned(name: &'static str) -> Self {
+        Self {
+            counter: OwnedRefMut::Owned(Box::new(0)),
+            name: name.to_string(),
+        }
+    }
+
+    /// Creates a new [`BlockCountObserver`] from a raw pointer to the harness counter
+    ///
+    /// # Safety
+    /// Will dereference the `counter_ptr`, which has to stay valid for `'a`.
+    pub unsafe fn new_from_ptr(name: &'static str, counter_ptr: *mut u64) -> Self {
+        Self {
+            counter: OwnedRefMut::Ref(&mut *counter_ptr),
+            name: name.to_string(),
+        }
+    }
+
+    /// The number of basic blocks executed in the last run
+    #[must_use]
+    pub fn count(&self) -> u64 {
+        *self.counter.as_ref()
+    }
+
+    /// The block counter (mut)
+    pub fn count_mut(&mut self) -> &mut u64 {
+        self.counter.as_mut()
+    }
+}
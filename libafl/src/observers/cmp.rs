@@ -78,6 +78,17 @@ impl CmpValuesMetadata {
     }
 }
 
+/// Bounds the number of comparisons [`CmpObserver::add_cmpvalues_meta`] records
+/// in the [`struct@CmpValuesMetadata`], to keep its memory and the scan of mutators like
+/// `I2SRandReplace` bounded for targets with many comparisons.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum CmpValuesCap {
+    /// Keep the first `n` recorded comparisons of a run
+    KeepFirst(usize),
+    /// Keep the `n` most recently recorded comparisons of a run
+    KeepLast(usize),
+}
+
 /// A [`CmpMap`] traces comparisons during the current execution
 pub trait CmpMap: Debug {
     /// Get the number of cmps
@@ -116,8 +127,14 @@ where
     /// Get the `CmpMap` (mut)
     fn map_mut(&mut self) -> &mut CM;
 
+    /// The maximum number of comparisons to record in the metadata (unbounded by default)
+    fn cmpvalues_cap(&self) -> Option<CmpValuesCap> {
+        None
+    }
+
     /// Add [`struct@CmpValuesMetadata`] to the State including the logged values.
     /// This routine does a basic loop filtering because loop index cmps are not interesting.
+    /// At most [`CmpObserver::cmpvalues_cap`] values are kept.
     fn add_cmpvalues_meta(&mut self, state: &mut S)
     where
         S: HasMetadata,
@@ -130,8 +147,9 @@ where
             state.metadata_mut().get_mut::<CmpValuesMetadata>().unwrap()
         };
         meta.list.clear();
+        let cap = self.cmpvalues_cap();
         let count = self.usable_count();
-        for i in 0..count {
+        'cmps: for i in 0..count {
             let execs = self.map().usable_executions_for(i);
             if execs > 0 {
                 // Recongize loops and discard if needed
@@ -173,10 +191,21 @@ where
                     }
                 }
                 for j in 0..execs {
+                    if let Some(CmpValuesCap::KeepFirst(max)) = cap {
+                        if meta.list.len() >= max {
+                            break 'cmps;
+                        }
+                    }
                     meta.list.push(self.map().values_of(i, j));
                 }
             }
         }
+        if let Some(CmpValuesCap::KeepLast(max)) = cap {
+            let len = meta.list.len();
+            if len > max {
+                meta.list.drain(..len - max);
+            }
+        }
     }
 }
 
@@ -189,6 +218,7 @@ where
 {
     map: OwnedRefMut<'a, CM>,
     size: Option<OwnedRefMut<'a, usize>>,
+    cap: Option<CmpValuesCap>,
     name: String,
 }
 
@@ -211,6 +241,10 @@ where
     fn map_mut(&mut self) -> &mut CM {
         self.map.as_mut()
     }
+
+    fn cmpvalues_cap(&self) -> Option<CmpValuesCap> {
+        self.cap
+    }
 }
 
 impl<'a, CM, I, S> Observer<I, S> for StdCmpObserver<'a, CM>
@@ -242,6 +276,19 @@ where
         Self {
             name: name.to_string(),
             size: None,
+            cap: None,
+            map: OwnedRefMut::Ref(map),
+        }
+    }
+
+    /// Creates a new [`StdCmpObserver`] with the given name and map,
+    /// recording at most `cap` comparisons per run in the [`struct@CmpValuesMetadata`].
+    #[must_use]
+    pub fn with_cmpvalues_cap(name: &'static str, map: &'a mut CM, cap: CmpValuesCap) -> Self {
+        Self {
+            name: name.to_string(),
+            size: None,
+            cap: Some(cap),
             map: OwnedRefMut::Ref(map),
         }
     }
@@ -252,7 +299,79 @@ where
         Self {
             name: name.to_string(),
             size: Some(OwnedRefMut::Ref(size)),
+            cap: None,
             map: OwnedRefMut::Ref(map),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use crate::{
+        bolts::{rands::StdRand, AsSlice},
+        corpus::InMemoryCorpus,
+        inputs::BytesInput,
+        observers::{
+            CmpMap, CmpObserver, CmpValues, CmpValuesCap, CmpValuesMetadata, StdCmpObserver,
+        },
+        state::{HasMetadata, StdState},
+        Error,
+    };
+
+    /// Logs one execution of `len` different comparisons, comparing `i` to `i`
+    #[derive(Debug, Serialize, Deserialize)]
+    struct TestCmpMap {
+        len: usize,
+    }
+
+    impl CmpMap for TestCmpMap {
+        fn len(&self) -> usize {
+            self.len
+        }
+
+        fn executions_for(&self, _idx: usize) -> usize {
+            1
+        }
+
+        fn usable_executions_for(&self, _idx: usize) -> usize {
+            1
+        }
+
+        fn values_of(&self, idx: usize, _execution: usize) -> CmpValues {
+            CmpValues::U64((idx as u64, idx as u64))
+        }
+
+        fn reset(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    fn recorded(cap: CmpValuesCap) -> Vec<u64> {
+        let mut map = TestCmpMap { len: 10 };
+        let mut observer = StdCmpObserver::with_cmpvalues_cap("cmp", &mut map, cap);
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        CmpObserver::<_, BytesInput, _>::add_cmpvalues_meta(&mut observer, &mut state);
+        state
+            .metadata()
+            .get::<CmpValuesMetadata>()
+            .unwrap()
+            .as_slice()
+            .iter()
+            .map(|v| v.to_u64_tuple().unwrap().0)
+            .collect()
+    }
+
+    #[test]
+    fn test_cmpvalues_cap() {
+        assert_eq!(recorded(CmpValuesCap::KeepFirst(3)), vec![0, 1, 2]);
+        assert_eq!(recorded(CmpValuesCap::KeepLast(3)), vec![7, 8, 9]);
+        assert_eq!(recorded(CmpValuesCap::KeepLast(20)).len(), 10);
+    }
+}
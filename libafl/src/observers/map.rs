@@ -16,6 +16,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     bolts::{
+        hash::HashKind,
         ownedref::{OwnedRefMut, OwnedSliceMut},
         tuples::Named,
         HasLen,
@@ -127,6 +128,11 @@ where
     fn pre_exec(&mut self, _state: &mut S, _input: &I) -> Result<(), Error> {
         self.reset_map()
     }
+
+    #[inline]
+    fn coverage(&self) -> Option<usize> {
+        Some(self.count_bytes() as usize)
+    }
 }
 
 impl<'a, T> Named for StdMapObserver<'a, T>
@@ -242,6 +248,11 @@ where
     fn pre_exec(&mut self, _state: &mut S, _input: &I) -> Result<(), Error> {
         self.reset_map()
     }
+
+    #[inline]
+    fn coverage(&self) -> Option<usize> {
+        Some(self.count_bytes() as usize)
+    }
 }
 
 impl<'a, T, const N: usize> Named for ConstMapObserver<'a, T, N>
@@ -364,6 +375,11 @@ where
     fn pre_exec(&mut self, _state: &mut S, _input: &I) -> Result<(), Error> {
         self.reset_map()
     }
+
+    #[inline]
+    fn coverage(&self) -> Option<usize> {
+        Some(self.count_bytes() as usize)
+    }
 }
 
 impl<'a, T> Named for VariableMapObserver<'a, T>
@@ -498,6 +514,11 @@ where
         }
         self.base.post_exec(state, input)
     }
+
+    #[inline]
+    fn coverage(&self) -> Option<usize> {
+        Some(self.count_bytes() as usize)
+    }
 }
 
 impl<M> Named for HitcountsMapObserver<M>
@@ -565,6 +586,159 @@ where
     }
 }
 
+/// Folds a large map into a smaller one, by hashing the indices of the base map with a
+/// [`HashKind`], XXH3 by default.
+/// Feedbacks then only have to compare the small map, trading precision (colliding indices)
+/// for throughput on enormous targets. The small map is `downsample_factor` times smaller.
+/// The base observer is wrapped, so it's not part of the observers tuple on its own.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(bound = "M: serde::de::DeserializeOwned, T: serde::de::DeserializeOwned")]
+pub struct DownsamplingMapObserver<M, T>
+where
+    M: Serialize + serde::de::DeserializeOwned,
+    T: PrimInt + Default + Copy + 'static + Serialize + serde::de::DeserializeOwned,
+{
+    base: M,
+    map: Vec<T>,
+    initial: T,
+    downsample_factor: usize,
+    hash_kind: HashKind,
+}
+
+impl<I, S, M, T> Observer<I, S> for DownsamplingMapObserver<M, T>
+where
+    M: MapObserver<T> + Observer<I, S>,
+    T: PrimInt + Default + Copy + 'static + Serialize + serde::de::DeserializeOwned + Debug,
+{
+    #[inline]
+    fn pre_exec(&mut self, state: &mut S, input: &I) -> Result<(), Error> {
+        self.reset_map()?;
+        self.base.pre_exec(state, input)
+    }
+
+    #[inline]
+    fn post_exec(&mut self, state: &mut S, input: &I) -> Result<(), Error> {
+        self.base.post_exec(state, input)?;
+        let base_initial = self.base.initial();
+        for i in 0..self.base.usable_count() {
+            let val = *self.base.get(i);
+            if val != base_initial {
+                let folded = self.fold_index(i);
+                if self.map[folded] == self.initial || self.map[folded] < val {
+                    self.map[folded] = val;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn coverage(&self) -> Option<usize> {
+        Some(self.count_bytes() as usize)
+    }
+}
+
+impl<M, T> Named for DownsamplingMapObserver<M, T>
+where
+    M: Named + Serialize + serde::de::DeserializeOwned,
+    T: PrimInt + Default + Copy + 'static + Serialize + serde::de::DeserializeOwned,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        self.base.name()
+    }
+}
+
+impl<M, T> HasLen for DownsamplingMapObserver<M, T>
+where
+    M: Serialize + serde::de::DeserializeOwned,
+    T: PrimInt + Default + Copy + 'static + Serialize + serde::de::DeserializeOwned,
+{
+    #[inline]
+    fn len(&self) -> usize {
+        self.map.len()
+    }
+}
+
+impl<M, T> MapObserver<T> for DownsamplingMapObserver<M, T>
+where
+    M: MapObserver<T>,
+    T: PrimInt + Default + Copy + 'static + Serialize + serde::de::DeserializeOwned + Debug,
+{
+    #[inline]
+    fn map(&self) -> Option<&[T]> {
+        Some(&self.map)
+    }
+
+    #[inline]
+    fn map_mut(&mut self) -> Option<&mut [T]> {
+        Some(&mut self.map)
+    }
+
+    #[inline]
+    fn initial(&self) -> T {
+        self.initial
+    }
+
+    #[inline]
+    fn initial_mut(&mut self) -> &mut T {
+        &mut self.initial
+    }
+
+    #[inline]
+    fn set_initial(&mut self, initial: T) {
+        self.initial = initial;
+    }
+}
+
+impl<M, T> DownsamplingMapObserver<M, T>
+where
+    M: MapObserver<T>,
+    T: PrimInt + Default + Copy + 'static + Serialize + serde::de::DeserializeOwned + Debug,
+{
+    /// Creates a new [`DownsamplingMapObserver`], folding the map of `base` into a map
+    /// `downsample_factor` times smaller.
+    #[must_use]
+    pub fn new(base: M, downsample_factor: usize) -> Self {
+        let downsample_factor = downsample_factor.max(1);
+        let len = ((base.len() + downsample_factor - 1) / downsample_factor).max(1);
+        let initial = base.initial();
+        Self {
+            base,
+            map: vec![initial; len],
+            initial,
+            downsample_factor,
+            hash_kind: HashKind::default(),
+        }
+    }
+
+    /// Folds the indices with the given [`HashKind`], to tune the collisions for the map size
+    #[must_use]
+    pub fn with_hash_kind(mut self, hash_kind: HashKind) -> Self {
+        self.hash_kind = hash_kind;
+        self
+    }
+
+    /// The factor the base map is downsampled by
+    #[must_use]
+    pub fn downsample_factor(&self) -> usize {
+        self.downsample_factor
+    }
+
+    /// The index in the small map, the given index of the base map is folded into
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn fold_index(&self, idx: usize) -> usize {
+        (self.hash_kind.hash_u64(idx as u64) % self.map.len() as u64) as usize
+    }
+
+    /// The wrapped observer, observing the full map
+    #[must_use]
+    pub fn base(&self) -> &M {
+        &self.base
+    }
+}
+
 /// The Multi Map Observer merge different maps into one observer
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(bound = "T: serde::de::DeserializeOwned")]
@@ -589,6 +763,11 @@ where
     fn pre_exec(&mut self, _state: &mut S, _input: &I) -> Result<(), Error> {
         self.reset_map()
     }
+
+    #[inline]
+    fn coverage(&self) -> Option<usize> {
+        Some(self.count_bytes() as usize)
+    }
 }
 
 impl<'a, T> Named for MultiMapObserver<'a, T>
@@ -757,3 +936,38 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::HasLen,
+        observers::{DownsamplingMapObserver, MapObserver, Observer, StdMapObserver},
+    };
+
+    #[test]
+    fn test_downsampling_map_observer() {
+        let hit = [3, 17, 512, 1000, 4095];
+        let mut observers: [DownsamplingMapObserver<StdMapObserver<u8>, u8>; 2] =
+            [0, 1].map(|_| {
+                DownsamplingMapObserver::new(StdMapObserver::new_owned("map", vec![0; 4096]), 16)
+            });
+
+        for observer in &mut observers {
+            assert_eq!(observer.len(), 256);
+            Observer::<(), ()>::pre_exec(observer, &mut (), &()).unwrap();
+            let base = observer.base.map_mut().unwrap();
+            for &idx in &hit {
+                base[idx] = 1;
+            }
+            Observer::<(), ()>::post_exec(observer, &mut (), &()).unwrap();
+        }
+
+        // Folding is deterministic, and every hit index is covered in the small map
+        assert_eq!(observers[0].map(), observers[1].map());
+        for &idx in &hit {
+            assert_eq!(*observers[0].get(observers[0].fold_index(idx)), 1);
+        }
+        let covered = observers[0].count_bytes();
+        assert!(covered > 0 && covered <= hit.len() as u64);
+    }
+}
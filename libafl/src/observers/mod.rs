@@ -8,6 +8,20 @@ pub use cmp::*;
 
 pub mod concolic;
 
+pub mod timeout;
+pub use timeout::*;
+
+pub mod value;
+pub use value::ValueProfileObserver;
+
+pub mod blocks;
+pub use blocks::BlockCountObserver;
+
+#[cfg(feature = "std")]
+pub mod stdio;
+#[cfg(feature = "std")]
+pub use stdio::StdErrObserver;
+
 use alloc::string::{String, ToString};
 use core::{fmt::Debug, time::Duration};
 use serde::{Deserialize, Serialize};
@@ -41,6 +55,30 @@ pub trait Observer<I, S>: Named + Debug {
     fn post_exec(&mut self, _state: &mut S, _input: &I) -> Result<(), Error> {
         Ok(())
     }
+
+    /// If this observer wants to receive the `stderr` output of the target.
+    /// Executors only capture `stderr` if at least one observer asks for it.
+    #[inline]
+    fn observes_stderr(&self) -> bool {
+        false
+    }
+
+    /// Called by executors capturing `stderr`, after the run finished.
+    #[inline]
+    fn observe_stderr(&mut self, _stderr: &[u8]) {}
+
+    /// The number of coverage map entries the last run hit, `None` if this is no coverage map.
+    #[inline]
+    fn coverage(&self) -> Option<usize> {
+        None
+    }
+
+    /// A hash identifying where the last run crashed, such as a hash of its backtrace.
+    /// `None` if this observer knows nothing about crashes, or the last run didn't crash.
+    #[inline]
+    fn crash_hash(&self) -> Option<u64> {
+        None
+    }
 }
 
 /// A haskell-style tuple of observers
@@ -50,6 +88,19 @@ pub trait ObserversTuple<I, S>: MatchName + Debug {
 
     /// This is called right after the last execution
     fn post_exec_all(&mut self, state: &mut S, input: &I) -> Result<(), Error>;
+
+    /// Returns true if any observer in this tuple wants the `stderr` output of the target.
+    fn observes_stderr(&self) -> bool;
+
+    /// Hands the captured `stderr` output to all observers in this tuple.
+    fn observe_stderr(&mut self, stderr: &[u8]);
+
+    /// The number of coverage map entries the last run hit, summed over all coverage maps in
+    /// this tuple, see [`Observer::coverage`]. `None` if there is no coverage map.
+    fn coverage_all(&self) -> Option<usize>;
+
+    /// The crash hash of the first observer in this tuple that has one, see [`Observer::crash_hash`]
+    fn crash_hash_all(&self) -> Option<u64>;
 }
 
 impl<I, S> ObserversTuple<I, S> for () {
@@ -60,6 +111,20 @@ impl<I, S> ObserversTuple<I, S> for () {
     fn post_exec_all(&mut self, _state: &mut S, _input: &I) -> Result<(), Error> {
         Ok(())
     }
+
+    fn observes_stderr(&self) -> bool {
+        false
+    }
+
+    fn observe_stderr(&mut self, _stderr: &[u8]) {}
+
+    fn coverage_all(&self) -> Option<usize> {
+        None
+    }
+
+    fn crash_hash_all(&self) -> Option<u64> {
+        None
+    }
 }
 
 impl<Head, Tail, I, S> ObserversTuple<I, S> for (Head, Tail)
@@ -76,6 +141,26 @@ where
         self.0.post_exec(state, input)?;
         self.1.post_exec_all(state, input)
     }
+
+    fn observes_stderr(&self) -> bool {
+        self.0.observes_stderr() || self.1.observes_stderr()
+    }
+
+    fn observe_stderr(&mut self, stderr: &[u8]) {
+        self.0.observe_stderr(stderr);
+        self.1.observe_stderr(stderr);
+    }
+
+    fn coverage_all(&self) -> Option<usize> {
+        match (self.0.coverage(), self.1.coverage_all()) {
+            (Some(head), Some(tail)) => Some(head + tail),
+            (head, tail) => head.or(tail),
+        }
+    }
+
+    fn crash_hash_all(&self) -> Option<u64> {
+        self.0.crash_hash().or_else(|| self.1.crash_hash_all())
+    }
 }
 
 /// A simple observer, just overlooking the runtime of the target.
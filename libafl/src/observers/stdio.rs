@@ -0,0 +1,90 @@
+//! The `StdErrObserver` captures what the target wrote to `stderr` during the last run.
+//! Executors spawning a child process (such as the [`crate::executors::CommandExecutor`])
+//! hand the collected output to all observers that ask for it.
+
+use ahash::AHasher;
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::hash::Hasher;
+use serde::{Deserialize, Serialize};
+
+use crate::{bolts::tuples::Named, observers::Observer, Error};
+
+/// An observer that stores the `stderr` output of the last execution of the target.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StdErrObserver {
+    name: String,
+    stderr: Option<Vec<u8>>,
+}
+
+impl StdErrObserver {
+    /// Creates a new [`StdErrObserver`] with the given name.
+    #[must_use]
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name: name.to_string(),
+            stderr: None,
+        }
+    }
+
+    /// The `stderr` output of the last execution, if any was captured.
+    #[must_use]
+    pub fn stderr(&self) -> Option<&[u8]> {
+        self.stderr.as_deref()
+    }
+}
+
+impl<I, S> Observer<I, S> for StdErrObserver {
+    fn pre_exec(&mut self, _state: &mut S, _input: &I) -> Result<(), Error> {
+        self.stderr = None;
+        Ok(())
+    }
+
+    #[inline]
+    fn observes_stderr(&self) -> bool {
+        true
+    }
+
+    fn observe_stderr(&mut self, stderr: &[u8]) {
+        self.stderr = Some(stderr.to_vec());
+    }
+
+    /// Hashes the frames of the first stack trace a sanitizer, such as ASAN, printed.
+    /// Addresses are left out, so the hash is stable under ASLR.
+    fn crash_hash(&self) -> Option<u64> {
+        let stderr = String::from_utf8_lossy(self.stderr.as_deref()?);
+        if !stderr.contains("Sanitizer") {
+            return None;
+        }
+        let mut hasher = AHasher::new_with_keys(0, 0);
+        let mut frames = 0;
+        for line in stderr.lines() {
+            // Frames look like `    #0 0x4f1a2b in func /src/file.c:10:5`
+            let frame = line.trim_start();
+            let location = frame
+                .strip_prefix('#')
+                .and_then(|frame| frame.find(" in ").map(|pos| &frame[pos + 4..]));
+            match location {
+                Some(location) => {
+                    hasher.write(location.as_bytes());
+                    frames += 1;
+                }
+                None if frames > 0 => break,
+                None => (),
+            }
+        }
+        if frames == 0 {
+            None
+        } else {
+            Some(hasher.finish())
+        }
+    }
+}
+
+impl Named for StdErrObserver {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
@@ -0,0 +1,143 @@
+//! The [`TimeoutClassifierObserver`] labels runs as fast, slow or hanging, based on their runtime.
+
+use alloc::string::{String, ToString};
+use core::time::Duration;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    observers::{Observer, TimeObserver},
+    Error,
+};
+
+/// The speed class of a run, see [`TimeoutClassifierObserver`]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RunSpeed {
+    /// The run finished below the soft threshold
+    Fast,
+    /// The run took longer than the soft threshold, but finished before the timeout
+    Slow,
+    /// The run hit the hard timeout
+    Hang,
+}
+
+/// A testcase metadata holding the [`RunSpeed`] of the run that added it to the corpus
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RunSpeedMetadata {
+    /// The speed class of the run
+    pub speed: RunSpeed,
+}
+
+crate::impl_serdeany!(RunSpeedMetadata);
+
+/// An observer classifying each run as [`RunSpeed::Fast`], [`RunSpeed::Slow`] or [`RunSpeed::Hang`].
+/// It measures the runtime with an inner [`TimeObserver`] and compares it to a soft threshold
+/// and the hard timeout configured for the executor.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TimeoutClassifierObserver {
+    name: String,
+    time_observer: TimeObserver,
+    slow_threshold: Duration,
+    timeout: Duration,
+    last_speed: Option<RunSpeed>,
+}
+
+impl TimeoutClassifierObserver {
+    /// Creates a new [`TimeoutClassifierObserver`].
+    /// Runs longer than `slow_threshold` are [`RunSpeed::Slow`],
+    /// runs reaching `timeout` are [`RunSpeed::Hang`].
+    #[must_use]
+    pub fn new(name: &'static str, slow_threshold: Duration, timeout: Duration) -> Self {
+        Self {
+            name: name.to_string(),
+            time_observer: TimeObserver::new(name),
+            slow_threshold,
+            timeout,
+            last_speed: None,
+        }
+    }
+
+    /// Classifies the given runtime
+    #[must_use]
+    pub fn classify(&self, runtime: Duration) -> RunSpeed {
+        if runtime >= self.timeout {
+            RunSpeed::Hang
+        } else if runtime > self.slow_threshold {
+            RunSpeed::Slow
+        } else {
+            RunSpeed::Fast
+        }
+    }
+
+    /// Gets the [`RunSpeed`] of the last execution of the target.
+    #[must_use]
+    pub fn last_speed(&self) -> Option<RunSpeed> {
+        self.last_speed
+    }
+
+    /// The inner [`TimeObserver`], measuring the runtime
+    #[must_use]
+    pub fn time_observer(&self) -> &TimeObserver {
+        &self.time_observer
+    }
+
+    /// The soft threshold, above which a run is considered slow
+    #[must_use]
+    pub fn slow_threshold(&self) -> Duration {
+        self.slow_threshold
+    }
+
+    /// The hard timeout
+    #[must_use]
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+}
+
+impl<I, S> Observer<I, S> for TimeoutClassifierObserver {
+    fn pre_exec(&mut self, state: &mut S, input: &I) -> Result<(), Error> {
+        self.last_speed = None;
+        self.time_observer.pre_exec(state, input)
+    }
+
+    fn post_exec(&mut self, state: &mut S, input: &I) -> Result<(), Error> {
+        self.time_observer.post_exec(state, input)?;
+        self.last_speed = self
+            .time_observer
+            .last_runtime()
+            .map(|runtime| self.classify(runtime));
+        Ok(())
+    }
+}
+
+impl Named for TimeoutClassifierObserver {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use crate::observers::{RunSpeed, TimeoutClassifierObserver};
+
+    #[test]
+    fn test_timeout_classifier() {
+        let observer = TimeoutClassifierObserver::new(
+            "speed",
+            Duration::from_millis(100),
+            Duration::from_secs(1),
+        );
+        for (millis, expected) in [
+            (0, RunSpeed::Fast),
+            (100, RunSpeed::Fast),
+            (101, RunSpeed::Slow),
+            (999, RunSpeed::Slow),
+            (1000, RunSpeed::Hang),
+            (5000, RunSpeed::Hang),
+        ] {
+            assert_eq!(observer.classify(Duration::from_millis(millis)), expected);
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! The [`ValueProfileObserver`] reads values the target computed at key points, for value profiling.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::slice::from_raw_parts_mut;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::{ownedref::OwnedSliceMut, tuples::Named},
+    observers::Observer,
+    Error,
+};
+
+/// Observes an array of "interesting computed values", one per slot, filled by the harness.
+///
+/// The contract with the harness: the array is a global of `u64`s, with a fixed slot for each
+/// point of interest in the target. During a run, the harness (or instrumentation) stores the
+/// value computed at a point of interest into its slot. The observer zeroes all slots before each
+/// run, and a slot still `0` after the run counts as not written, so a value of `0` is never seen.
+/// Use a [`crate::feedbacks::ValueProfileFeedback`] to find inputs producing new values.
+#[derive(Serialize, Deserialize, Debug)]
+#[allow(clippy::unsafe_derive_deserialize)]
+pub struct ValueProfileObserver<'a> {
+    values: OwnedSliceMut<'a, u64>,
+    name: String,
+}
+
+impl<'a, I, S> Observer<I, S> for ValueProfileObserver<'a> {
+    #[inline]
+    fn pre_exec(&mut self, _state: &mut S, _input: &I) -> Result<(), Error> {
+        self.values.as_mut_slice().iter_mut().for_each(|v| *v = 0);
+        Ok(())
+    }
+}
+
+impl<'a> Named for ValueProfileObserver<'a> {
+    #[inline]
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
+impl<'a> ValueProfileObserver<'a> {
+    /// Creates a new [`ValueProfileObserver`] for the given value slots
+    #[must_use]
+    pub fn new(name: &'static str, values: &'a mut [u64]) -> Self {
+        Self {
+            values: OwnedSliceMut::Ref(values),
+            name: name.to_string(),
+        }
+    }
+
+    /// Creates a new [`ValueProfileObserver`] with owned value slots
+    #[must_use]
+    pub fn new_owned(name: &'static str, values: Vec<u64>) -> Self {
+        Self {
+            values: OwnedSliceMut::Owned(values),
+            name: name.to_string(),
+        }
+    }
+
+    /// Creates a new [`ValueProfileObserver`] from a raw pointer to the harness array
+    ///
+    /// # Safety
+    /// Will dereference the `values_ptr` with up to len elements.
+    pub unsafe fn new_from_ptr(name: &'static str, values_ptr: *mut u64, len: usize) -> Self {
+        Self {
+            values: OwnedSliceMut::Ref(from_raw_parts_mut(values_ptr, len)),
+            name: name.to_string(),
+        }
+    }
+
+    /// The values of the last run, one per slot
+    #[must_use]
+    pub fn values(&self) -> &[u64] {
+        self.values.as_slice()
+    }
+
+    /// The values of the last run (mut)
+    pub fn values_mut(&mut self) -> &mut [u64] {
+        self.values.as_mut_slice()
+    }
+}
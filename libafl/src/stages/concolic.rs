@@ -15,6 +15,17 @@ use crate::{
 
 use super::{Stage, TracingStage};
 
+/// Selects the inputs the [`ConcolicTracingStage`] traces, to reserve solver time for promising inputs
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SamplePolicy {
+    /// Trace every input
+    All,
+    /// Trace one in `n` inputs, starting with the first one
+    EveryN(usize),
+    /// Trace only the `n` testcases most recently added to the corpus, which just increased coverage
+    Recent(usize),
+}
+
 /// Wraps a [`TracingStage`] to add concolic observing.
 #[derive(Clone, Debug)]
 pub struct ConcolicTracingStage<C, EM, I, OT, S, TE, Z>
@@ -27,6 +38,9 @@ where
 {
     inner: TracingStage<C, EM, I, OT, S, TE, Z>,
     observer_name: String,
+    sampling: SamplePolicy,
+    /// The number of inputs this stage was performed on
+    performed: usize,
 }
 
 impl<E, C, EM, I, OT, S, TE, Z> Stage<E, EM, S, Z> for ConcolicTracingStage<C, EM, I, OT, S, TE, Z>
@@ -46,6 +60,16 @@ where
         manager: &mut EM,
         corpus_idx: usize,
     ) -> Result<(), Error> {
+        let sampled = match self.sampling {
+            SamplePolicy::All => true,
+            SamplePolicy::EveryN(n) => self.performed % n.max(1) == 0,
+            SamplePolicy::Recent(n) => corpus_idx + n >= state.corpus().count(),
+        };
+        self.performed += 1;
+        if !sampled {
+            return Ok(());
+        }
+
         self.inner
             .perform(fuzzer, executor, state, manager, corpus_idx)?;
         if let Some(observer) = self
@@ -80,8 +104,23 @@ where
         Self {
             inner,
             observer_name,
+            sampling: SamplePolicy::All,
+            performed: 0,
         }
     }
+
+    /// Only traces the inputs selected by the given [`SamplePolicy`]
+    #[must_use]
+    pub fn with_sampling(mut self, sampling: SamplePolicy) -> Self {
+        self.sampling = sampling;
+        self
+    }
+
+    /// The [`SamplePolicy`] of this stage
+    #[must_use]
+    pub fn sampling(&self) -> SamplePolicy {
+        self.sampling
+    }
 }
 
 #[cfg(feature = "concolic_mutation")]
@@ -411,3 +450,44 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::{Corpus, InMemoryCorpus, Testcase},
+        events::NopEventManager,
+        executors::NopExecutor,
+        inputs::BytesInput,
+        stages::{concolic::SamplePolicy, ConcolicTracingStage, Stage, TracingStage},
+        state::{HasCorpus, HasExecutions, StdState},
+    };
+
+    #[test]
+    fn test_concolic_sampling() {
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let idx = state
+            .corpus_mut()
+            .add(Testcase::new(BytesInput::new(vec![0; 4])))
+            .unwrap();
+        let mut mgr = NopEventManager {};
+        let mut executor = NopExecutor::new();
+
+        let mut tracing =
+            ConcolicTracingStage::new(TracingStage::new(NopExecutor::new()), "concolic".into())
+                .with_sampling(SamplePolicy::EveryN(4));
+
+        // Each trace is one execution of the tracer
+        for i in 0..8 {
+            tracing
+                .perform(&mut (), &mut executor, &mut state, &mut mgr, idx)
+                .unwrap();
+            assert_eq!(*state.executions(), i / 4 + 1);
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! The [`ConditionalStage`] runs a stage only on the testcases a predicate selects,
+//! for pipelines running different stages on different inputs.
+
+use core::marker::PhantomData;
+
+use crate::{corpus::Corpus, inputs::Input, stages::Stage, state::HasCorpus, Error};
+
+/// Decides if a stage should run on the input of the current testcase
+pub type StagePredicate<I, S> = fn(&I, &S) -> bool;
+
+/// A [`Stage`] wrapping another stage, and running it only if the [`StagePredicate`] returns
+/// `true` for the input of the current testcase.
+/// For example, a concolic stage can be limited to inputs that increased coverage.
+#[derive(Clone, Debug)]
+pub struct ConditionalStage<C, I, S, ST> {
+    stage: ST,
+    predicate: StagePredicate<I, S>,
+    phantom: PhantomData<C>,
+}
+
+impl<C, E, EM, I, S, ST, Z> Stage<E, EM, S, Z> for ConditionalStage<C, I, S, ST>
+where
+    C: Corpus<I>,
+    I: Input,
+    S: HasCorpus<C, I>,
+    ST: Stage<E, EM, S, Z>,
+{
+    #[inline]
+    fn perform(
+        &mut self,
+        fuzzer: &mut Z,
+        executor: &mut E,
+        state: &mut S,
+        manager: &mut EM,
+        corpus_idx: usize,
+    ) -> Result<(), Error> {
+        state.corpus().get(corpus_idx)?.borrow_mut().load_input()?;
+        let selected = {
+            let testcase = state.corpus().get(corpus_idx)?.borrow();
+            (self.predicate)(testcase.input().as_ref().unwrap(), state)
+        };
+
+        if selected {
+            self.stage
+                .perform(fuzzer, executor, state, manager, corpus_idx)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<C, I, S, ST> ConditionalStage<C, I, S, ST>
+where
+    C: Corpus<I>,
+    I: Input,
+    S: HasCorpus<C, I>,
+{
+    /// Creates a new [`ConditionalStage`], running `stage` on the testcases `predicate` selects
+    #[must_use]
+    pub fn new(stage: ST, predicate: StagePredicate<I, S>) -> Self {
+        Self {
+            stage,
+            predicate,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The wrapped stage
+    #[must_use]
+    pub fn inner(&self) -> &ST {
+        &self.stage
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::{Corpus, InMemoryCorpus, Testcase},
+        inputs::{BytesInput, HasBytesVec},
+        stages::{ClosureStage, ConditionalStage, Stage},
+        state::{HasCorpus, StdState},
+        Error,
+    };
+
+    #[test]
+    fn test_conditional_stage() {
+        type State = StdState<
+            InMemoryCorpus<BytesInput>,
+            (),
+            BytesInput,
+            StdRand,
+            InMemoryCorpus<BytesInput>,
+        >;
+
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        for bytes in [&b"a"[..], b"long", b"b"] {
+            state
+                .corpus_mut()
+                .add(Testcase::new(BytesInput::new(bytes.to_vec())))
+                .unwrap();
+        }
+
+        // Only runs on inputs longer than one byte
+        let mut runs = vec![];
+        {
+            let counting = ClosureStage::new(
+                |_fuzzer: &mut (),
+                 _executor: &mut (),
+                 _state: &mut State,
+                 _manager: &mut (),
+                 corpus_idx: usize| {
+                    runs.push(corpus_idx);
+                    Ok::<(), Error>(())
+                },
+            );
+            let mut conditional =
+                ConditionalStage::new(counting, |input: &BytesInput, _: &State| {
+                    input.bytes().len() > 1
+                });
+            for corpus_idx in 0..state.corpus().count() {
+                conditional
+                    .perform(&mut (), &mut (), &mut state, &mut (), corpus_idx)
+                    .unwrap();
+            }
+        }
+        assert_eq!(runs, [1]);
+    }
+}
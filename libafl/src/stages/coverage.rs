@@ -0,0 +1,240 @@
+//! The [`CoverageBroadcastStage`] periodically sends the accumulated coverage to the broker,
+//! for live coverage views.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt::Debug, marker::PhantomData, time::Duration};
+use num_traits::PrimInt;
+use serde::Serialize;
+
+use crate::{
+    bolts::{current_time, tuples::Named},
+    events::{Event, EventFirer},
+    feedbacks::{FeedbackStatesTuple, MapFeedbackState},
+    inputs::Input,
+    stages::Stage,
+    state::HasFeedbackStates,
+    Error,
+};
+
+/// Maps with more entries than this are sent as deltas, after the first snapshot
+pub const DEFAULT_COVERAGE_DELTA_THRESHOLD: usize = 1 << 16;
+
+/// A [`Stage`] firing an [`Event::CoverageSnapshot`] with the covered entries of a
+/// [`MapFeedbackState`], at most once per `interval`.
+/// For maps larger than the delta threshold, only the entries covered since the previous
+/// snapshot are sent, unless an entry got uncovered, for example after a reset.
+#[derive(Clone, Debug)]
+pub struct CoverageBroadcastStage<FT, I, S, T> {
+    map_state_name: String,
+    interval: Duration,
+    delta_threshold: usize,
+    last_time: Duration,
+    epoch: u64,
+    /// The coverage sent with the previous snapshot
+    last_coverage: Vec<u8>,
+    phantom: PhantomData<(FT, I, S, T)>,
+}
+
+impl<E, EM, FT, I, S, T, Z> Stage<E, EM, S, Z> for CoverageBroadcastStage<FT, I, S, T>
+where
+    EM: EventFirer<I>,
+    FT: FeedbackStatesTuple,
+    I: Input,
+    S: HasFeedbackStates<FT>,
+    T: PrimInt + Default + Copy + 'static + Serialize + serde::de::DeserializeOwned + Debug,
+{
+    #[inline]
+    fn perform(
+        &mut self,
+        _fuzzer: &mut Z,
+        _executor: &mut E,
+        state: &mut S,
+        manager: &mut EM,
+        _corpus_idx: usize,
+    ) -> Result<(), Error> {
+        let cur = current_time();
+        if cur.checked_sub(self.last_time).unwrap_or_default() < self.interval {
+            return Ok(());
+        }
+        self.last_time = cur;
+
+        let coverage: Vec<u8> = state
+            .feedback_states()
+            .match_name::<MapFeedbackState<T>>(&self.map_state_name)
+            .ok_or_else(|| Error::KeyNotFound(format!("{} not found", self.map_state_name)))?
+            .history_map
+            .iter()
+            .map(|entry| u8::from(*entry != T::min_value()))
+            .collect();
+
+        let delta = self.epoch > 0
+            && coverage.len() > self.delta_threshold
+            && coverage.len() == self.last_coverage.len()
+            && coverage
+                .iter()
+                .zip(&self.last_coverage)
+                .all(|(cur, last)| cur >= last);
+        let map = if delta {
+            coverage
+                .iter()
+                .zip(&self.last_coverage)
+                .enumerate()
+                .filter(|(_, (cur, last))| cur > last)
+                .flat_map(|(idx, _)| (idx as u32).to_le_bytes())
+                .collect()
+        } else {
+            coverage.clone()
+        };
+
+        manager.fire(
+            state,
+            Event::CoverageSnapshot {
+                map,
+                epoch: self.epoch,
+                delta,
+                phantom: PhantomData,
+            },
+        )?;
+        self.epoch += 1;
+        self.last_coverage = coverage;
+        Ok(())
+    }
+}
+
+impl<FT, I, S, T> CoverageBroadcastStage<FT, I, S, T>
+where
+    T: PrimInt + Default + Copy + 'static + Serialize + serde::de::DeserializeOwned + Debug,
+{
+    /// Creates a new [`CoverageBroadcastStage`] for the coverage of the given map state, sending
+    /// a snapshot at most once per `interval`, the first one after `interval` passed
+    #[must_use]
+    pub fn new(map_state: &MapFeedbackState<T>, interval: Duration) -> Self {
+        Self {
+            map_state_name: map_state.name().to_string(),
+            interval,
+            delta_threshold: DEFAULT_COVERAGE_DELTA_THRESHOLD,
+            last_time: current_time(),
+            epoch: 0,
+            last_coverage: vec![],
+            phantom: PhantomData,
+        }
+    }
+
+    /// Sets the number of map entries above which snapshots are sent as deltas
+    #[must_use]
+    pub fn with_delta_threshold(mut self, delta_threshold: usize) -> Self {
+        self.delta_threshold = delta_threshold;
+        self
+    }
+
+    /// The minimum time between two snapshots
+    #[must_use]
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+    use core::time::Duration;
+    use std::thread::sleep;
+
+    use crate::{
+        bolts::{
+            rands::StdRand,
+            tuples::{tuple_list, tuple_list_type, MatchName},
+        },
+        corpus::InMemoryCorpus,
+        events::{Event, EventFirer},
+        feedbacks::MapFeedbackState,
+        inputs::BytesInput,
+        monitors::ClientStats,
+        stages::{CoverageBroadcastStage, Stage},
+        state::{HasFeedbackStates, StdState},
+        Error,
+    };
+
+    /// Keeps all fired events
+    #[derive(Debug, Default)]
+    struct RecordingEventManager {
+        events: Vec<Event<BytesInput>>,
+    }
+
+    impl EventFirer<BytesInput> for RecordingEventManager {
+        fn fire<S>(&mut self, _state: &mut S, event: Event<BytesInput>) -> Result<(), Error> {
+            self.events.push(event);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_coverage_broadcast_stage() {
+        type State = StdState<
+            InMemoryCorpus<BytesInput>,
+            tuple_list_type!(MapFeedbackState<u8>),
+            BytesInput,
+            StdRand,
+            InMemoryCorpus<BytesInput>,
+        >;
+
+        let map_state = MapFeedbackState::<u8>::new("edges", 8);
+        let mut broadcast = CoverageBroadcastStage::<_, BytesInput, _, _>::new(
+            &map_state,
+            Duration::from_millis(20),
+        )
+        .with_delta_threshold(4);
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            tuple_list!(map_state),
+        );
+        let mut mgr = RecordingEventManager::default();
+        let cover = |state: &mut State, idx: usize| {
+            state
+                .feedback_states_mut()
+                .match_name_mut::<MapFeedbackState<u8>>("edges")
+                .unwrap()
+                .history_map[idx] = 1;
+        };
+        let mut client = ClientStats::default();
+
+        // Nothing is sent before the interval passed
+        cover(&mut state, 0);
+        cover(&mut state, 1);
+        broadcast
+            .perform(&mut (), &mut (), &mut state, &mut mgr, 0)
+            .unwrap();
+        assert!(mgr.events.is_empty());
+
+        // The first snapshot holds the whole coverage, the next one only the new entry
+        sleep(Duration::from_millis(30));
+        broadcast
+            .perform(&mut (), &mut (), &mut state, &mut mgr, 0)
+            .unwrap();
+        cover(&mut state, 5);
+        sleep(Duration::from_millis(30));
+        broadcast
+            .perform(&mut (), &mut (), &mut state, &mut mgr, 0)
+            .unwrap();
+        assert_eq!(mgr.events.len(), 2);
+
+        for (i, event) in mgr.events.iter().enumerate() {
+            if let Event::CoverageSnapshot {
+                map, epoch, delta, ..
+            } = event
+            {
+                assert_eq!(*epoch, i as u64);
+                assert_eq!(*delta, i == 1);
+                client.update_coverage(map, *epoch, *delta);
+            } else {
+                panic!("unexpected event {:?}", event);
+            }
+        }
+        assert_eq!(client.coverage, [1, 1, 0, 0, 0, 1, 0, 0]);
+    }
+}
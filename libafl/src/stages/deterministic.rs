@@ -0,0 +1,196 @@
+//! The [`DeterministicTokenStage`] overwrites each offset of a testcase with each dictionary token,
+//! like the deterministic dictionary stage of AFL.
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    corpus::{Corpus, OriginMetadata},
+    fuzzer::Evaluator,
+    inputs::{HasBytesVec, Input},
+    mark_feature_time,
+    mutators::{buffer_copy, Tokens},
+    stages::Stage,
+    start_timer,
+    state::{HasClientPerfMonitor, HasCorpus, HasMetadata},
+    Error,
+};
+
+#[cfg(feature = "introspection")]
+use crate::monitors::PerfFeature;
+
+/// A testcase metadata, marking that the [`DeterministicTokenStage`] already ran on this testcase
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct DeterministicTokensDoneMetadata {
+    /// The number of executions the stage spent on this testcase
+    pub executions: usize,
+}
+
+crate::impl_serdeany!(DeterministicTokensDoneMetadata);
+
+/// A [`Stage`] that, once per testcase, overwrites each offset with each of the [`Tokens`] of the
+/// state and executes the result, to systematically probe string comparisons.
+/// Tokens that do not fit at an offset are skipped there.
+/// The testcase is marked with [`DeterministicTokensDoneMetadata`] afterwards, and skipped from
+/// then on. The stage does nothing if the state has no [`Tokens`].
+#[derive(Clone, Debug)]
+pub struct DeterministicTokenStage<C, E, EM, I, S, Z>
+where
+    C: Corpus<I>,
+    I: Input + HasBytesVec,
+    S: HasClientPerfMonitor + HasCorpus<C, I> + HasMetadata,
+    Z: Evaluator<E, EM, I, S>,
+{
+    #[allow(clippy::type_complexity)]
+    phantom: PhantomData<(C, E, EM, I, S, Z)>,
+}
+
+impl<C, E, EM, I, S, Z> Stage<E, EM, S, Z> for DeterministicTokenStage<C, E, EM, I, S, Z>
+where
+    C: Corpus<I>,
+    I: Input + HasBytesVec,
+    S: HasClientPerfMonitor + HasCorpus<C, I> + HasMetadata,
+    Z: Evaluator<E, EM, I, S>,
+{
+    #[inline]
+    fn perform(
+        &mut self,
+        fuzzer: &mut Z,
+        executor: &mut E,
+        state: &mut S,
+        manager: &mut EM,
+        corpus_idx: usize,
+    ) -> Result<(), Error> {
+        let tokens: Vec<Vec<u8>> = match state.metadata().get::<Tokens>() {
+            Some(tokens) => tokens.tokens().to_vec(),
+            None => return Ok(()),
+        };
+
+        start_timer!(state);
+        let input = {
+            let mut testcase = state.corpus().get(corpus_idx)?.borrow_mut();
+            if testcase.has_metadata::<DeterministicTokensDoneMetadata>() {
+                return Ok(());
+            }
+            testcase.load_input()?.clone()
+        };
+        mark_feature_time!(state, PerfFeature::GetInputFromCorpus);
+
+        let size = input.bytes().len();
+        let mut executions = 0;
+        for off in 0..size {
+            for token in &tokens {
+                let len = token.len();
+                if len == 0 || off + len > size {
+                    continue;
+                }
+                let mut overwritten = input.clone();
+                buffer_copy(overwritten.bytes_mut(), token, 0, off, len);
+                overwritten.mark_dirty(off..off + len);
+
+                // Time is measured directly the `evaluate_input` function
+                let (_, new_idx) = fuzzer.evaluate_input(state, executor, manager, overwritten)?;
+                OriginMetadata::Mutated.tag(state.corpus(), new_idx)?;
+                executions += 1;
+            }
+        }
+
+        state
+            .corpus()
+            .get(corpus_idx)?
+            .borrow_mut()
+            .add_metadata(DeterministicTokensDoneMetadata { executions });
+        Ok(())
+    }
+}
+
+impl<C, E, EM, I, S, Z> DeterministicTokenStage<C, E, EM, I, S, Z>
+where
+    C: Corpus<I>,
+    I: Input + HasBytesVec,
+    S: HasClientPerfMonitor + HasCorpus<C, I> + HasMetadata,
+    Z: Evaluator<E, EM, I, S>,
+{
+    /// Creates a new [`DeterministicTokenStage`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<C, E, EM, I, S, Z> Default for DeterministicTokenStage<C, E, EM, I, S, Z>
+where
+    C: Corpus<I>,
+    I: Input + HasBytesVec,
+    S: HasClientPerfMonitor + HasCorpus<C, I> + HasMetadata,
+    Z: Evaluator<E, EM, I, S>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::{Corpus, InMemoryCorpus, QueueCorpusScheduler, Testcase},
+        events::NopEventManager,
+        executors::NopExecutor,
+        feedbacks::CrashFeedback,
+        fuzzer::bytes_fuzzer,
+        inputs::BytesInput,
+        mutators::Tokens,
+        stages::{DeterministicTokenStage, DeterministicTokensDoneMetadata, Stage},
+        state::{HasCorpus, HasExecutions, HasMetadata, StdState},
+    };
+
+    #[test]
+    fn test_deterministic_token_stage() {
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        state
+            .corpus_mut()
+            .add(Testcase::new(BytesInput::new(b"seed".to_vec())))
+            .unwrap();
+        state.add_metadata(Tokens::new(vec![
+            b"AB".to_vec(),
+            b"CD".to_vec(),
+            b"EF".to_vec(),
+        ]));
+        let mut fuzzer = bytes_fuzzer::<_, _, _, (), _>(
+            QueueCorpusScheduler::new(),
+            CrashFeedback::new(),
+            CrashFeedback::new(),
+        );
+        let mut executor = NopExecutor::new();
+        let mut mgr = NopEventManager {};
+
+        // 3 tokens of length 2 fit at 3 offsets of the 4 byte input
+        let mut deterministic = DeterministicTokenStage::new();
+        deterministic
+            .perform(&mut fuzzer, &mut executor, &mut state, &mut mgr, 0)
+            .unwrap();
+        assert_eq!(*state.executions(), 9);
+        let testcase = state.corpus().get(0).unwrap().borrow();
+        let meta = testcase
+            .metadata()
+            .get::<DeterministicTokensDoneMetadata>()
+            .unwrap();
+        assert_eq!(meta.executions, 9);
+        drop(testcase);
+
+        // The testcase is done, and not probed again
+        deterministic
+            .perform(&mut fuzzer, &mut executor, &mut state, &mut mgr, 0)
+            .unwrap();
+        assert_eq!(*state.executions(), 9);
+    }
+}
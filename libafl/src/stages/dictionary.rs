@@ -0,0 +1,441 @@
+//! The [`CmpDictionaryStage`] keeps the [`Tokens`] dictionary tuned to the target, harvesting
+//! comparison operands logged by cmplog and pruning tokens that never led to new coverage.
+//! The [`SolutionToDictStage`] adds notable byte runs of the solutions to it.
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use hashbrown::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    corpus::Corpus,
+    inputs::{HasBytesVec, Input},
+    mutators::Tokens,
+    observers::cmp::{CmpValues, CmpValuesMetadata},
+    stages::Stage,
+    state::{HasMetadata, HasSolutions},
+    Error,
+};
+
+/// The default number of occurrences after which an integer operand becomes a token
+pub const DEFAULT_MIN_INT_OCCURRENCES: usize = 2;
+
+/// The minimum length of a byte run the [`SolutionToDictStage`] takes from a solution
+pub const SOLUTION_TOKEN_MIN_LEN: usize = 4;
+
+/// The maximum length of a byte run the [`SolutionToDictStage`] takes from a solution
+pub const SOLUTION_TOKEN_MAX_LEN: usize = 32;
+
+/// The maximum number of tokens the [`SolutionToDictStage`] takes from a single solution
+pub const SOLUTION_MAX_TOKENS: usize = 16;
+
+/// A state metadata, the progress of the [`CmpDictionaryStage`] between harvests and prunes
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CmpDictionaryMetadata {
+    /// The number of runs of the stage
+    pub runs: usize,
+    /// In how many harvests since the last prune each integer operand occurred, by value and size
+    pub int_occurrences: HashMap<(u64, usize), usize>,
+    /// The tokens present at the last prune
+    pub prune_candidates: HashSet<Vec<u8>>,
+}
+
+crate::impl_serdeany!(CmpDictionaryMetadata);
+
+/// A maintenance [`Stage`] for the [`Tokens`] dictionary, working on the [`CmpValuesMetadata`]
+/// of the last cmplog run.
+/// Every `harvest_interval` runs, it adds the operands of byte comparisons, and the integer
+/// operands that recurred in at least `min_int_occurrences` harvests since the last prune,
+/// to the [`Tokens`].
+/// Integers below `0x100` are skipped, as the byte mutators reach them easily.
+/// Every `prune_interval` runs, it removes the tokens that did not produce any interesting input,
+/// according to their [`crate::mutators::TokenStats`], since the previous prune.
+/// The progress is kept in the [`CmpDictionaryMetadata`] of the state, so it survives restarts.
+#[derive(Clone, Debug)]
+pub struct CmpDictionaryStage<S>
+where
+    S: HasMetadata,
+{
+    harvest_interval: usize,
+    prune_interval: usize,
+    min_int_occurrences: usize,
+    phantom: PhantomData<S>,
+}
+
+impl<E, EM, S, Z> Stage<E, EM, S, Z> for CmpDictionaryStage<S>
+where
+    S: HasMetadata,
+{
+    #[inline]
+    fn perform(
+        &mut self,
+        _fuzzer: &mut Z,
+        _executor: &mut E,
+        state: &mut S,
+        _manager: &mut EM,
+        _corpus_idx: usize,
+    ) -> Result<(), Error> {
+        if state.metadata().get::<CmpDictionaryMetadata>().is_none() {
+            state.add_metadata(CmpDictionaryMetadata::default());
+        }
+        let meta = state
+            .metadata_mut()
+            .get_mut::<CmpDictionaryMetadata>()
+            .unwrap();
+        meta.runs += 1;
+        let runs = meta.runs;
+
+        if runs % self.harvest_interval == 0 {
+            self.harvest(state);
+        }
+        if runs % self.prune_interval == 0 {
+            Self::prune(state);
+        }
+        Ok(())
+    }
+}
+
+impl<S> CmpDictionaryStage<S>
+where
+    S: HasMetadata,
+{
+    /// Creates a new [`CmpDictionaryStage`], harvesting every `harvest_interval` runs
+    /// and pruning every `prune_interval` runs.
+    #[must_use]
+    pub fn new(harvest_interval: usize, prune_interval: usize) -> Self {
+        Self {
+            harvest_interval: harvest_interval.max(1),
+            prune_interval: prune_interval.max(1),
+            min_int_occurrences: DEFAULT_MIN_INT_OCCURRENCES,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Sets in how many harvests an integer operand has to occur to become a token
+    #[must_use]
+    pub fn with_min_int_occurrences(mut self, min_int_occurrences: usize) -> Self {
+        self.min_int_occurrences = min_int_occurrences.max(1);
+        self
+    }
+
+    /// The number of runs between two harvests
+    #[must_use]
+    pub fn harvest_interval(&self) -> usize {
+        self.harvest_interval
+    }
+
+    /// The number of runs between two prunes
+    #[must_use]
+    pub fn prune_interval(&self) -> usize {
+        self.prune_interval
+    }
+
+    /// Adds the operands of the last logged comparisons to the [`Tokens`], skipping duplicates
+    fn harvest(&self, state: &mut S) {
+        let mut new_tokens = vec![];
+        let mut ints = HashSet::new();
+        if let Some(meta) = state.metadata().get::<CmpValuesMetadata>() {
+            for cmp in &meta.list {
+                match cmp {
+                    CmpValues::Bytes((first, second)) => {
+                        new_tokens.extend(
+                            [first, second]
+                                .into_iter()
+                                .filter(|v| !v.is_empty())
+                                .cloned(),
+                        );
+                    }
+                    CmpValues::U16((first, second)) => {
+                        ints.extend([(u64::from(*first), 2), (u64::from(*second), 2)]);
+                    }
+                    CmpValues::U32((first, second)) => {
+                        ints.extend([(u64::from(*first), 4), (u64::from(*second), 4)]);
+                    }
+                    CmpValues::U64((first, second)) => {
+                        ints.extend([(*first, 8), (*second, 8)]);
+                    }
+                    CmpValues::U8(_) => (),
+                }
+            }
+        }
+        let meta = state
+            .metadata_mut()
+            .get_mut::<CmpDictionaryMetadata>()
+            .unwrap();
+        for (value, size) in ints {
+            if value < 0x100 {
+                continue;
+            }
+            let occurrences = meta.int_occurrences.entry((value, size)).or_insert(0);
+            *occurrences += 1;
+            if *occurrences == self.min_int_occurrences {
+                new_tokens.push(value.to_le_bytes()[..size].to_vec());
+            }
+        }
+        if new_tokens.is_empty() {
+            return;
+        }
+
+        if state.metadata().get::<Tokens>().is_none() {
+            state.add_metadata(Tokens::new(vec![]));
+        }
+        let tokens = state.metadata_mut().get_mut::<Tokens>().unwrap();
+        for token in &new_tokens {
+            tokens.add_token(token);
+        }
+    }
+
+    /// Removes the tokens that were present at the last prune, and never produced an interesting input.
+    /// Also forgets the integer operand occurrences, so they are only counted within a prune window.
+    fn prune(state: &mut S) {
+        let meta = state
+            .metadata_mut()
+            .get_mut::<CmpDictionaryMetadata>()
+            .unwrap();
+        meta.int_occurrences.clear();
+        let candidates = core::mem::take(&mut meta.prune_candidates);
+        let prune_candidates = match state.metadata_mut().get_mut::<Tokens>() {
+            Some(tokens) => {
+                tokens.retain(|token, stats| stats.successes() > 0 || !candidates.contains(token));
+                tokens.tokens().iter().cloned().collect()
+            }
+            None => candidates,
+        };
+        state
+            .metadata_mut()
+            .get_mut::<CmpDictionaryMetadata>()
+            .unwrap()
+            .prune_candidates = prune_candidates;
+    }
+}
+
+/// Finds the notable byte runs of a solution, at most [`SOLUTION_MAX_TOKENS`]:
+/// the runs of printable characters, and the sequences of [`SOLUTION_TOKEN_MIN_LEN`] bytes
+/// occurring more than once, both between [`SOLUTION_TOKEN_MIN_LEN`] and
+/// [`SOLUTION_TOKEN_MAX_LEN`] bytes long. Runs of a single byte, like padding, are not notable.
+#[must_use]
+pub fn notable_byte_runs(bytes: &[u8]) -> Vec<Vec<u8>> {
+    let trivial = |run: &[u8]| run.iter().all(|b| *b == run[0]);
+    let mut runs: Vec<Vec<u8>> = vec![];
+
+    for run in bytes.split(|b| !(b.is_ascii_graphic() || *b == b' ')) {
+        if run.len() >= SOLUTION_TOKEN_MIN_LEN && !trivial(run) {
+            runs.push(run[..run.len().min(SOLUTION_TOKEN_MAX_LEN)].to_vec());
+        }
+    }
+
+    let mut seen = HashSet::new();
+    for window in bytes.windows(SOLUTION_TOKEN_MIN_LEN) {
+        if !seen.insert(window) && !trivial(window) {
+            // Repeated, and not already part of a printable run
+            if !runs
+                .iter()
+                .any(|run| run.windows(window.len()).any(|w| w == window))
+            {
+                runs.push(window.to_vec());
+            }
+        }
+    }
+
+    runs.truncate(SOLUTION_MAX_TOKENS);
+    runs
+}
+
+/// A [`Stage`] adding the [`notable_byte_runs`] of each new solution to the [`Tokens`],
+/// so the values that led to a crash help finding related ones.
+#[derive(Clone, Debug)]
+pub struct SolutionToDictStage<I, S, SC>
+where
+    I: Input + HasBytesVec,
+    S: HasMetadata + HasSolutions<SC, I>,
+    SC: Corpus<I>,
+{
+    /// The number of solutions already looked at
+    checked_count: usize,
+    phantom: PhantomData<(I, S, SC)>,
+}
+
+impl<E, EM, I, S, SC, Z> Stage<E, EM, S, Z> for SolutionToDictStage<I, S, SC>
+where
+    I: Input + HasBytesVec,
+    S: HasMetadata + HasSolutions<SC, I>,
+    SC: Corpus<I>,
+{
+    #[inline]
+    fn perform(
+        &mut self,
+        _fuzzer: &mut Z,
+        _executor: &mut E,
+        state: &mut S,
+        _manager: &mut EM,
+        _corpus_idx: usize,
+    ) -> Result<(), Error> {
+        let count = state.solutions().count();
+        if self.checked_count > count {
+            // Solutions got removed, start over
+            self.checked_count = 0;
+        }
+
+        let mut new_tokens = vec![];
+        for idx in self.checked_count..count {
+            let mut testcase = state.solutions().get(idx)?.borrow_mut();
+            new_tokens.extend(notable_byte_runs(testcase.load_input()?.bytes()));
+        }
+        self.checked_count = count;
+        if new_tokens.is_empty() {
+            return Ok(());
+        }
+
+        if state.metadata().get::<Tokens>().is_none() {
+            state.add_metadata(Tokens::new(vec![]));
+        }
+        let tokens = state.metadata_mut().get_mut::<Tokens>().unwrap();
+        for token in &new_tokens {
+            tokens.add_token(token);
+        }
+        Ok(())
+    }
+}
+
+impl<I, S, SC> SolutionToDictStage<I, S, SC>
+where
+    I: Input + HasBytesVec,
+    S: HasMetadata + HasSolutions<SC, I>,
+    SC: Corpus<I>,
+{
+    /// Creates a new [`SolutionToDictStage`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            checked_count: 0,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<I, S, SC> Default for SolutionToDictStage<I, S, SC>
+where
+    I: Input + HasBytesVec,
+    S: HasMetadata + HasSolutions<SC, I>,
+    SC: Corpus<I>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::{Corpus, InMemoryCorpus, Testcase},
+        inputs::BytesInput,
+        mutators::Tokens,
+        observers::cmp::{CmpValues, CmpValuesMetadata},
+        stages::{CmpDictionaryMetadata, CmpDictionaryStage, SolutionToDictStage, Stage},
+        state::{HasMetadata, HasSolutions, StdState},
+    };
+
+    #[test]
+    fn test_cmp_dictionary_stage() {
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        state.add_metadata(Tokens::new(vec![b"UNUSED".to_vec()]));
+        state.add_metadata(CmpValuesMetadata {
+            list: vec![
+                CmpValues::Bytes((b"MAGIC".to_vec(), b"AAAAA".to_vec())),
+                CmpValues::U32((0xdead_beef, 7)),
+            ],
+        });
+        let mut dictionary = CmpDictionaryStage::new(1, 2);
+
+        // The byte operands are added right away, the integer once it recurs
+        dictionary
+            .perform(&mut (), &mut (), &mut state, &mut (), 0)
+            .unwrap();
+        let tokens = state.metadata().get::<Tokens>().unwrap().tokens();
+        assert_eq!(
+            tokens,
+            [b"UNUSED".to_vec(), b"MAGIC".to_vec(), b"AAAAA".to_vec()]
+        );
+        dictionary
+            .perform(&mut (), &mut (), &mut state, &mut (), 0)
+            .unwrap();
+        let tokens = state.metadata().get::<Tokens>().unwrap().tokens();
+        assert_eq!(tokens.len(), 4);
+        assert_eq!(tokens[3], 0xdead_beef_u32.to_le_bytes());
+        // The integer occurrences are only counted within a prune window
+        let meta = state.metadata().get::<CmpDictionaryMetadata>().unwrap();
+        assert_eq!(meta.runs, 2);
+        assert!(meta.int_occurrences.is_empty());
+
+        // MAGIC led to new coverage, the other tokens did not since the first prune
+        let tokens = state.metadata_mut().get_mut::<Tokens>().unwrap();
+        tokens.token_stats_mut()[1].insert_successes += 1;
+        for _ in 0..2 {
+            dictionary
+                .perform(&mut (), &mut (), &mut state, &mut (), 0)
+                .unwrap();
+        }
+        let tokens = state.metadata().get::<Tokens>().unwrap();
+        assert_eq!(tokens.tokens(), [b"MAGIC".to_vec()]);
+        assert_eq!(tokens.token_stats()[0].successes(), 1);
+
+        // A restarted stage picks up where the last one left off
+        state.add_metadata(CmpValuesMetadata {
+            list: vec![CmpValues::U32((0xcafe_babe, 7))],
+        });
+        let mut restarted = CmpDictionaryStage::new(1, 2);
+        restarted
+            .perform(&mut (), &mut (), &mut state, &mut (), 0)
+            .unwrap();
+        let meta = state.metadata().get::<CmpDictionaryMetadata>().unwrap();
+        assert_eq!(meta.runs, 5);
+        assert_eq!(meta.int_occurrences.get(&(0xcafe_babe, 4)), Some(&1));
+        restarted
+            .perform(&mut (), &mut (), &mut state, &mut (), 0)
+            .unwrap();
+        assert_eq!(state.metadata().get::<Tokens>().unwrap().tokens().len(), 2);
+    }
+
+    #[test]
+    fn test_solution_to_dict_stage() {
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let mut solution_to_dict = SolutionToDictStage::new();
+        solution_to_dict
+            .perform(&mut (), &mut (), &mut state, &mut (), 0)
+            .unwrap();
+        assert!(state.metadata().get::<Tokens>().is_none());
+
+        // A printable run, a repeated binary value, and padding
+        let mut bytes = vec![0_u8; 8];
+        bytes.extend_from_slice(b"\x01GIMME_SHELL\x02\xde\xad\xbe\xef\x03\xde\xad\xbe\xef");
+        state
+            .solutions_mut()
+            .add(Testcase::new(BytesInput::new(bytes)))
+            .unwrap();
+        solution_to_dict
+            .perform(&mut (), &mut (), &mut state, &mut (), 0)
+            .unwrap();
+        let tokens = state.metadata().get::<Tokens>().unwrap().tokens();
+        assert_eq!(
+            tokens,
+            [b"GIMME_SHELL".to_vec(), vec![0xde, 0xad, 0xbe, 0xef]]
+        );
+
+        // Solutions are only looked at once
+        solution_to_dict
+            .perform(&mut (), &mut (), &mut state, &mut (), 0)
+            .unwrap();
+        assert_eq!(state.metadata().get::<Tokens>().unwrap().tokens().len(), 2);
+    }
+}
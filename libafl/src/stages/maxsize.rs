@@ -0,0 +1,253 @@
+//! The [`AdaptiveMaxSizeStage`] starts with a small `max_size` and grows it, up to a hard ceiling,
+//! whenever interesting inputs show up close to the current cap.
+//! The [`ExecRampMaxSizeStage`] grows it on a fixed schedule of executions instead.
+
+use core::marker::PhantomData;
+
+use crate::{
+    bolts::HasLen,
+    corpus::Corpus,
+    inputs::Input,
+    stages::Stage,
+    state::{HasCorpus, HasExecutions, HasMaxSize},
+    Error,
+};
+
+/// The default percentage of the current `max_size` an input needs to reach to count as near the cap
+pub const DEFAULT_NEAR_CAP_PERCENT: usize = 90;
+
+/// The default number of executions after which the [`ExecRampMaxSizeStage`] doubles `max_size`
+pub const DEFAULT_EXECS_PER_DOUBLING: usize = 100_000;
+
+/// A [`Stage`] adapting [`HasMaxSize::max_size`] to the sizes of the interesting inputs found so far.
+/// Set a small initial `max_size` on the state; each time a new corpus entry is near the current cap,
+/// the cap gets doubled, but never beyond the configured ceiling.
+#[derive(Clone, Debug)]
+pub struct AdaptiveMaxSizeStage<C, I, S>
+where
+    C: Corpus<I>,
+    I: Input + HasLen,
+    S: HasCorpus<C, I> + HasMaxSize,
+{
+    ceiling: usize,
+    near_cap_percent: usize,
+    checked_count: usize,
+    phantom: PhantomData<(C, I, S)>,
+}
+
+impl<C, E, EM, I, S, Z> Stage<E, EM, S, Z> for AdaptiveMaxSizeStage<C, I, S>
+where
+    C: Corpus<I>,
+    I: Input + HasLen,
+    S: HasCorpus<C, I> + HasMaxSize,
+{
+    #[inline]
+    fn perform(
+        &mut self,
+        _fuzzer: &mut Z,
+        _executor: &mut E,
+        state: &mut S,
+        _manager: &mut EM,
+        _corpus_idx: usize,
+    ) -> Result<(), Error> {
+        let count = state.corpus().count();
+        if self.checked_count > count {
+            // Entries got removed, start over
+            self.checked_count = 0;
+        }
+
+        let mut largest = 0;
+        for idx in self.checked_count..count {
+            let len = state.corpus().get(idx)?.borrow_mut().cached_len()?;
+            largest = largest.max(len);
+        }
+        self.checked_count = count;
+
+        let max_size = state.max_size();
+        if max_size < self.ceiling && largest * 100 >= max_size * self.near_cap_percent {
+            state.set_max_size(max_size.saturating_mul(2).clamp(1, self.ceiling));
+        }
+
+        Ok(())
+    }
+}
+
+impl<C, I, S> AdaptiveMaxSizeStage<C, I, S>
+where
+    C: Corpus<I>,
+    I: Input + HasLen,
+    S: HasCorpus<C, I> + HasMaxSize,
+{
+    /// Creates a new [`AdaptiveMaxSizeStage`], never growing `max_size` beyond `ceiling`.
+    #[must_use]
+    pub fn new(ceiling: usize) -> Self {
+        Self::with_near_cap_percent(ceiling, DEFAULT_NEAR_CAP_PERCENT)
+    }
+
+    /// Creates a new [`AdaptiveMaxSizeStage`], growing `max_size` as soon as an interesting input
+    /// reaches `near_cap_percent` percent of the current `max_size`.
+    #[must_use]
+    pub fn with_near_cap_percent(ceiling: usize, near_cap_percent: usize) -> Self {
+        Self {
+            ceiling,
+            near_cap_percent,
+            checked_count: 0,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The hard ceiling for `max_size`
+    #[must_use]
+    pub fn ceiling(&self) -> usize {
+        self.ceiling
+    }
+}
+
+/// A [`Stage`] ramping [`HasMaxSize::max_size`] up with the total executions: it starts at `initial`,
+/// and doubles every `execs_per_doubling` executions, up to the `ceiling`.
+/// This spends the first executions on small inputs, which are cheap to run.
+#[derive(Clone, Debug)]
+pub struct ExecRampMaxSizeStage<S>
+where
+    S: HasExecutions + HasMaxSize,
+{
+    initial: usize,
+    ceiling: usize,
+    execs_per_doubling: usize,
+    phantom: PhantomData<S>,
+}
+
+impl<E, EM, S, Z> Stage<E, EM, S, Z> for ExecRampMaxSizeStage<S>
+where
+    S: HasExecutions + HasMaxSize,
+{
+    #[inline]
+    fn perform(
+        &mut self,
+        _fuzzer: &mut Z,
+        _executor: &mut E,
+        state: &mut S,
+        _manager: &mut EM,
+        _corpus_idx: usize,
+    ) -> Result<(), Error> {
+        let max_size = self.max_size_at(*state.executions());
+        if state.max_size() != max_size {
+            state.set_max_size(max_size);
+        }
+        Ok(())
+    }
+}
+
+impl<S> ExecRampMaxSizeStage<S>
+where
+    S: HasExecutions + HasMaxSize,
+{
+    /// Creates a new [`ExecRampMaxSizeStage`], doubling `max_size` from `initial` up to `ceiling`
+    /// every [`DEFAULT_EXECS_PER_DOUBLING`] executions.
+    #[must_use]
+    pub fn new(initial: usize, ceiling: usize) -> Self {
+        Self::with_execs_per_doubling(initial, ceiling, DEFAULT_EXECS_PER_DOUBLING)
+    }
+
+    /// Creates a new [`ExecRampMaxSizeStage`], doubling `max_size` from `initial` up to `ceiling`
+    /// every `execs_per_doubling` executions.
+    ///
+    /// # Panics
+    /// Panics if `execs_per_doubling` or `initial` is `0`
+    #[must_use]
+    pub fn with_execs_per_doubling(
+        initial: usize,
+        ceiling: usize,
+        execs_per_doubling: usize,
+    ) -> Self {
+        assert!(execs_per_doubling > 0, "execs_per_doubling must not be 0");
+        assert!(initial > 0, "The initial max_size must not be 0");
+        Self {
+            initial,
+            ceiling,
+            execs_per_doubling,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The `max_size` after the given number of executions
+    #[must_use]
+    pub fn max_size_at(&self, executions: usize) -> usize {
+        let mut max_size = self.initial;
+        for _ in 0..executions / self.execs_per_doubling {
+            if max_size >= self.ceiling {
+                break;
+            }
+            max_size = max_size.saturating_mul(2);
+        }
+        max_size.min(self.ceiling)
+    }
+
+    /// The hard ceiling for `max_size`
+    #[must_use]
+    pub fn ceiling(&self) -> usize {
+        self.ceiling
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::{Corpus, InMemoryCorpus, Testcase},
+        inputs::BytesInput,
+        stages::{AdaptiveMaxSizeStage, ExecRampMaxSizeStage, Stage},
+        state::{HasCorpus, HasExecutions, HasMaxSize, StdState},
+    };
+
+    #[test]
+    fn test_adaptive_max_size() {
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        state.set_max_size(16);
+        let mut max_size_stage = AdaptiveMaxSizeStage::new(64);
+
+        // Small inputs don't change anything, inputs near the cap grow it up to the ceiling
+        for (len, expected_max_size) in [(4, 16), (15, 32), (32, 64), (64, 64)] {
+            state
+                .corpus_mut()
+                .add(Testcase::new(BytesInput::new(vec![0; len])))
+                .unwrap();
+            max_size_stage
+                .perform(&mut (), &mut (), &mut state, &mut (), 0)
+                .unwrap();
+            assert_eq!(state.max_size(), expected_max_size);
+        }
+    }
+
+    #[test]
+    fn test_exec_ramp_max_size() {
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let mut max_size_stage = ExecRampMaxSizeStage::with_execs_per_doubling(16, 100, 1000);
+
+        // Doubles at each milestone, then stays at the ceiling
+        for (executions, expected_max_size) in [
+            (0, 16),
+            (999, 16),
+            (1000, 32),
+            (2500, 64),
+            (3000, 100),
+            (1_000_000, 100),
+        ] {
+            *state.executions_mut() = executions;
+            max_size_stage
+                .perform(&mut (), &mut (), &mut state, &mut (), 0)
+                .unwrap();
+            assert_eq!(state.max_size(), expected_max_size);
+        }
+    }
+}
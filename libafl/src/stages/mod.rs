@@ -8,6 +8,9 @@ Other stages may enrich [`crate::corpus::Testcase`]s with metadata.
 pub mod mutational;
 pub use mutational::{MutationalStage, StdMutationalStage};
 
+pub mod recipe;
+pub use recipe::{replay_recipe, MutationRecipe, RecipeMutationalStage};
+
 pub mod push;
 
 pub mod tracing;
@@ -16,15 +19,52 @@ pub use tracing::{ShadowTracingStage, TracingStage};
 pub mod calibrate;
 pub use calibrate::{CalibrationStage, PowerScheduleMetadata};
 
+pub mod recalibrate;
+pub use recalibrate::RecalibrationStage;
+
 pub mod power;
 pub use power::PowerMutationalStage;
 
+pub mod maxsize;
+pub use maxsize::{AdaptiveMaxSizeStage, ExecRampMaxSizeStage};
+
+pub mod reseed;
+pub use reseed::RngReseedStage;
+
+pub mod dictionary;
+pub use dictionary::{
+    notable_byte_runs, CmpDictionaryMetadata, CmpDictionaryStage, SolutionToDictStage,
+};
+
+pub mod plateau;
+pub use plateau::{PlateauDetectorStage, PlateauMetadata};
+
+pub mod deterministic;
+pub use deterministic::{DeterministicTokenStage, DeterministicTokensDoneMetadata};
+
+pub mod coverage;
+pub use coverage::{CoverageBroadcastStage, DEFAULT_COVERAGE_DELTA_THRESHOLD};
+
+pub mod report;
+pub use report::TopCoverageReportStage;
+
+pub mod conditional;
+pub use conditional::{ConditionalStage, StagePredicate};
+
+pub mod warmup;
+pub use warmup::WarmupStage;
+
 #[cfg(feature = "std")]
 pub mod concolic;
 #[cfg(feature = "std")]
-pub use concolic::ConcolicTracingStage;
-#[cfg(feature = "std")]
 pub use concolic::SimpleConcolicMutationalStage;
+#[cfg(feature = "std")]
+pub use concolic::{ConcolicTracingStage, SamplePolicy};
+
+#[cfg(feature = "std")]
+pub mod status;
+#[cfg(feature = "std")]
+pub use status::StatusFileStage;
 
 #[cfg(feature = "std")]
 pub mod sync;
@@ -87,10 +127,19 @@ impl<E, EM, S, Z> StagesTuple<E, EM, S, Z> for () {
     }
 }
 
+/// The name of a stage type, without module path and generics, for the introspection monitor
+#[cfg(feature = "introspection")]
+fn stage_name<T>() -> &'static str {
+    let name = core::any::type_name::<T>();
+    let name = name.split('<').next().unwrap_or(name);
+    name.rsplit("::").next().unwrap_or(name)
+}
+
 impl<Head, Tail, E, EM, S, Z> StagesTuple<E, EM, S, Z> for (Head, Tail)
 where
     Head: Stage<E, EM, S, Z>,
     Tail: StagesTuple<E, EM, S, Z>,
+    S: HasClientPerfMonitor,
 {
     fn perform_all(
         &mut self,
@@ -100,9 +149,16 @@ where
         manager: &mut EM,
         corpus_idx: usize,
     ) -> Result<(), Error> {
-        // Perform the current stage
+        // Perform the current stage, and record how many cycles it took
+        #[cfg(feature = "introspection")]
+        let start = crate::bolts::cpu::read_time_counter();
         self.0
             .perform(fuzzer, executor, state, manager, corpus_idx)?;
+        #[cfg(feature = "introspection")]
+        state.introspection_monitor_mut().mark_stage_cycles(
+            stage_name::<Head>(),
+            crate::bolts::cpu::read_time_counter().wrapping_sub(start),
+        );
 
         // Execute the remaining stages
         self.1
@@ -258,3 +314,59 @@ where
             .deinit(fuzzer, state, event_mgr, executor.observers_mut())
     }
 }
+
+#[cfg(test)]
+#[cfg(feature = "introspection")]
+mod tests {
+    use crate::{
+        bolts::{rands::StdRand, tuples::tuple_list},
+        corpus::InMemoryCorpus,
+        inputs::BytesInput,
+        stages::{ClosureStage, StagesTuple},
+        state::{HasClientPerfMonitor, StdState},
+        Error,
+    };
+
+    #[test]
+    fn test_stage_cycles() {
+        type State = StdState<
+            InMemoryCorpus<BytesInput>,
+            (),
+            BytesInput,
+            StdRand,
+            InMemoryCorpus<BytesInput>,
+        >;
+
+        let work = |_: &mut (), _: &mut (), _: &mut State, _: &mut (), _: usize| {
+            let _work: Vec<u64> = (0..10_000).collect();
+            Ok::<(), Error>(())
+        };
+        let mut stages = tuple_list!(ClosureStage::new(work), ClosureStage::new(work));
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+
+        // Each stage gets its own entry, accumulated over the fuzz loop iterations
+        let mut last = [0; 2];
+        for _ in 0..2 {
+            state.introspection_monitor_mut().reset_stage_index();
+            stages
+                .perform_all(&mut (), &mut (), &mut state, &mut (), 0)
+                .unwrap();
+            let stage_cycles = state.introspection_monitor().stage_cycles();
+            assert_eq!(stage_cycles.len(), 2);
+            for ((name, cycles), last) in stage_cycles.iter().zip(&mut last) {
+                assert_eq!(name, "ClosureStage");
+                assert!(*cycles > *last);
+                *last = *cycles;
+            }
+        }
+        assert!(state
+            .introspection_monitor()
+            .to_string()
+            .contains(": ClosureStage"));
+    }
+}
@@ -5,14 +5,14 @@ use core::marker::PhantomData;
 
 use crate::{
     bolts::rands::Rand,
-    corpus::Corpus,
-    fuzzer::Evaluator,
+    corpus::{Corpus, OriginMetadata},
+    fuzzer::{Evaluator, ExecuteInputResult},
     inputs::Input,
     mark_feature_time,
-    mutators::Mutator,
+    mutators::{MutationResult, Mutator},
     stages::Stage,
     start_timer,
-    state::{HasClientPerfMonitor, HasCorpus, HasRand},
+    state::{HasClientPerfMonitor, HasCorpus, HasMaxSize, HasRand},
     Error,
 };
 
@@ -29,7 +29,7 @@ where
     C: Corpus<I>,
     M: Mutator<I, S>,
     I: Input,
-    S: HasClientPerfMonitor + HasCorpus<C, I>,
+    S: HasClientPerfMonitor + HasCorpus<C, I> + HasMaxSize,
     Z: Evaluator<E, EM, I, S>,
 {
     /// The mutator registered for this stage
@@ -41,7 +41,16 @@ where
     /// Gets the number of iterations this mutator should run for.
     fn iterations(&self, state: &mut S, corpus_idx: usize) -> Result<usize, Error>;
 
-    /// Runs this (mutational) stage for the given testcase
+    /// The number of consecutive mutations without any interesting input, after which the stage
+    /// stops early for the current testcase. `None` always runs all iterations.
+    fn early_stop(&self) -> Option<usize> {
+        None
+    }
+
+    /// Runs this (mutational) stage for the given testcase.
+    /// Each mutated input is truncated to [`HasMaxSize::max_size`] before it runs, see [`Input::truncate`],
+    /// so no input exceeds the max size, even if a mutator does not respect it.
+    /// Inputs the mutator returned [`MutationResult::Unchanged`] for are not run.
     #[allow(clippy::cast_possible_wrap)] // more than i32 stages on 32 bit system - highly unlikely...
     fn perform_mutational(
         &mut self,
@@ -52,6 +61,8 @@ where
         corpus_idx: usize,
     ) -> Result<(), Error> {
         let num = self.iterations(state, corpus_idx)?;
+        let early_stop = self.early_stop();
+        let mut uninteresting = 0;
 
         for i in 0..num {
             start_timer!(state);
@@ -64,15 +75,34 @@ where
             mark_feature_time!(state, PerfFeature::GetInputFromCorpus);
 
             start_timer!(state);
-            self.mutator_mut().mutate(state, &mut input, i as i32)?;
+            let mutated = self.mutator_mut().mutate(state, &mut input, i as i32)?;
+            // A safety net, in case a mutator ignored the max size
+            input.truncate(state.max_size());
             mark_feature_time!(state, PerfFeature::Mutate);
 
+            // The input did not change, running it again would be a waste
+            if mutated == MutationResult::Unchanged {
+                // Drops what the mutator recorded for this run, such as the used tokens
+                self.mutator_mut().post_exec(state, i as i32, None)?;
+                continue;
+            }
+
             // Time is measured directly the `evaluate_input` function
-            let (_, corpus_idx) = fuzzer.evaluate_input(state, executor, manager, input)?;
+            let (res, corpus_idx) = fuzzer.evaluate_input(state, executor, manager, input)?;
+            OriginMetadata::Mutated.tag(state.corpus(), corpus_idx)?;
 
             start_timer!(state);
             self.mutator_mut().post_exec(state, i as i32, corpus_idx)?;
             mark_feature_time!(state, PerfFeature::MutatePostExec);
+
+            if res == ExecuteInputResult::None {
+                uninteresting += 1;
+                if early_stop.map_or(false, |m| uninteresting >= m) {
+                    break;
+                }
+            } else {
+                uninteresting = 0;
+            }
         }
         Ok(())
     }
@@ -90,10 +120,11 @@ where
     M: Mutator<I, S>,
     I: Input,
     R: Rand,
-    S: HasClientPerfMonitor + HasCorpus<C, I> + HasRand<R>,
+    S: HasClientPerfMonitor + HasCorpus<C, I> + HasMaxSize + HasRand<R>,
     Z: Evaluator<E, EM, I, S>,
 {
     mutator: M,
+    early_stop: Option<usize>,
     #[allow(clippy::type_complexity)]
     phantom: PhantomData<(C, E, EM, I, R, S, Z)>,
 }
@@ -105,7 +136,7 @@ where
     M: Mutator<I, S>,
     I: Input,
     R: Rand,
-    S: HasClientPerfMonitor + HasCorpus<C, I> + HasRand<R>,
+    S: HasClientPerfMonitor + HasCorpus<C, I> + HasMaxSize + HasRand<R>,
     Z: Evaluator<E, EM, I, S>,
 {
     /// The mutator, added to this stage
@@ -124,6 +155,11 @@ where
     fn iterations(&self, state: &mut S, _corpus_idx: usize) -> Result<usize, Error> {
         Ok(1 + state.rand_mut().below(DEFAULT_MUTATIONAL_MAX_ITERATIONS) as usize)
     }
+
+    #[inline]
+    fn early_stop(&self) -> Option<usize> {
+        self.early_stop
+    }
 }
 
 impl<C, E, EM, I, M, R, S, Z> Stage<E, EM, S, Z> for StdMutationalStage<C, E, EM, I, M, R, S, Z>
@@ -132,7 +168,7 @@ where
     M: Mutator<I, S>,
     I: Input,
     R: Rand,
-    S: HasClientPerfMonitor + HasCorpus<C, I> + HasRand<R>,
+    S: HasClientPerfMonitor + HasCorpus<C, I> + HasMaxSize + HasRand<R>,
     Z: Evaluator<E, EM, I, S>,
 {
     #[inline]
@@ -160,14 +196,200 @@ where
     M: Mutator<I, S>,
     I: Input,
     R: Rand,
-    S: HasClientPerfMonitor + HasCorpus<C, I> + HasRand<R>,
+    S: HasClientPerfMonitor + HasCorpus<C, I> + HasMaxSize + HasRand<R>,
     Z: Evaluator<E, EM, I, S>,
 {
     /// Creates a new default mutational stage
     pub fn new(mutator: M) -> Self {
         Self {
             mutator,
+            early_stop: None,
             phantom: PhantomData,
         }
     }
+
+    /// Stops mutating the current testcase after `m` consecutive mutations without any
+    /// interesting input, moving the energy on to more productive testcases.
+    #[must_use]
+    pub fn with_early_stop(mut self, m: usize) -> Self {
+        self.early_stop = Some(m);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::rands::StdRand,
+        bolts::tuples::tuple_list,
+        corpus::{Corpus, InMemoryCorpus, QueueCorpusScheduler, Testcase},
+        events::NopEventManager,
+        executors::{Executor, ExitKind, HasObservers, NopExecutor},
+        feedbacks::CrashFeedback,
+        fuzzer::bytes_fuzzer,
+        inputs::{BytesInput, HasBytesVec},
+        mutators::{havoc_mutations, ByteNegMutator, MutationResult, Mutator, StdScheduledMutator},
+        stages::{Stage, StdMutationalStage},
+        state::{HasCorpus, HasExecutions, HasMaxSize, StdState},
+        Error,
+    };
+
+    #[test]
+    fn test_mutational_early_stop() {
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        state
+            .corpus_mut()
+            .add(Testcase::new(BytesInput::new(b"seed".to_vec())))
+            .unwrap();
+        // Nothing is ever interesting
+        let mut fuzzer = bytes_fuzzer::<_, _, _, (), _>(
+            QueueCorpusScheduler::new(),
+            CrashFeedback::new(),
+            CrashFeedback::new(),
+        );
+        let mut executor = NopExecutor::new();
+        let mut mgr = NopEventManager {};
+
+        let mut mutational =
+            StdMutationalStage::new(StdScheduledMutator::new(havoc_mutations())).with_early_stop(3);
+        // The random iteration count, in 1..=128, is cut down to 3 uninteresting mutations
+        for _ in 0..8 {
+            let executions = *state.executions();
+            mutational
+                .perform(&mut fuzzer, &mut executor, &mut state, &mut mgr, 0)
+                .unwrap();
+            assert_eq!(*state.executions() - executions, 3);
+        }
+        assert_eq!(state.corpus().count(), 1);
+    }
+
+    /// Grows the input way past any max size
+    #[derive(Debug)]
+    struct OvergrowingMutator;
+
+    impl<S> Mutator<BytesInput, S> for OvergrowingMutator {
+        fn mutate(
+            &mut self,
+            _state: &mut S,
+            input: &mut BytesInput,
+            _stage_idx: i32,
+        ) -> Result<MutationResult, Error> {
+            input.bytes_mut().extend_from_slice(&[0x41; 1024]);
+            Ok(MutationResult::Mutated)
+        }
+    }
+
+    /// Remembers the longest input it ran
+    #[derive(Debug)]
+    struct LongestExecutor {
+        observers: (),
+        longest: usize,
+    }
+
+    impl<EM, S, Z> Executor<EM, BytesInput, S, Z> for LongestExecutor {
+        fn run_target(
+            &mut self,
+            _fuzzer: &mut Z,
+            _state: &mut S,
+            _mgr: &mut EM,
+            input: &BytesInput,
+        ) -> Result<ExitKind, Error> {
+            self.longest = self.longest.max(input.bytes().len());
+            Ok(ExitKind::Ok)
+        }
+    }
+
+    impl<S> HasObservers<BytesInput, (), S> for LongestExecutor {
+        fn observers(&self) -> &() {
+            &self.observers
+        }
+
+        fn observers_mut(&mut self) -> &mut () {
+            &mut self.observers
+        }
+    }
+
+    #[test]
+    fn test_mutational_max_size() {
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        state
+            .corpus_mut()
+            .add(Testcase::new(BytesInput::new(b"seed".to_vec())))
+            .unwrap();
+        state.set_max_size(16);
+        let mut fuzzer = bytes_fuzzer::<_, _, _, (), _>(
+            QueueCorpusScheduler::new(),
+            CrashFeedback::new(),
+            CrashFeedback::new(),
+        );
+        let mut executor = LongestExecutor {
+            observers: (),
+            longest: 0,
+        };
+        let mut mgr = NopEventManager {};
+
+        // The stage truncates what the mutator produced, before running it
+        let mut mutational = StdMutationalStage::new(OvergrowingMutator);
+        mutational
+            .perform(&mut fuzzer, &mut executor, &mut state, &mut mgr, 0)
+            .unwrap();
+        assert_eq!(executor.longest, 16);
+    }
+
+    #[test]
+    fn test_mutational_noop_detection() {
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        state
+            .corpus_mut()
+            .add(Testcase::new(BytesInput::new(vec![0x42])))
+            .unwrap();
+        let mut fuzzer = bytes_fuzzer::<_, _, _, (), _>(
+            QueueCorpusScheduler::new(),
+            CrashFeedback::new(),
+            CrashFeedback::new(),
+        );
+        let mut executor = NopExecutor::new();
+        let mut mgr = NopEventManager {};
+
+        // The scheduled mutator stacks an even number of mutations,
+        // negating the only byte an even number of times changes nothing
+        let mut mutator = StdScheduledMutator::new(tuple_list!(ByteNegMutator::new()));
+        let mut input = BytesInput::new(vec![0x42]);
+        assert_eq!(
+            mutator.mutate(&mut state, &mut input, 0).unwrap(),
+            MutationResult::Mutated
+        );
+        assert_eq!(input.bytes(), [0x42]);
+
+        let mut mutational = StdMutationalStage::new(mutator.with_noop_detection(true));
+        for _ in 0..8 {
+            mutational
+                .perform(&mut fuzzer, &mut executor, &mut state, &mut mgr, 0)
+                .unwrap();
+        }
+        assert_eq!(*state.executions(), 0);
+
+        // Without noop detection, the same inputs run
+        let mut mutational =
+            StdMutationalStage::new(StdScheduledMutator::new(tuple_list!(ByteNegMutator::new())));
+        mutational
+            .perform(&mut fuzzer, &mut executor, &mut state, &mut mgr, 0)
+            .unwrap();
+        assert!(*state.executions() > 0);
+    }
 }
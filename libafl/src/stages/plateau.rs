@@ -0,0 +1,210 @@
+//! The [`PlateauDetectorStage`] detects when the coverage stopped growing, and records it in the
+//! [`PlateauMetadata`] of the state, for adaptive strategies to react to.
+
+use alloc::string::{String, ToString};
+use core::{fmt::Debug, marker::PhantomData};
+use num_traits::PrimInt;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    events::{EventFirer, LogSeverity},
+    feedbacks::{FeedbackStatesTuple, MapFeedbackState},
+    inputs::Input,
+    stages::Stage,
+    state::{HasFeedbackStates, HasMetadata},
+    Error,
+};
+
+/// A state metadata, telling whether the coverage is currently on a plateau
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct PlateauMetadata {
+    /// `true` while the coverage does not grow
+    pub on_plateau: bool,
+    /// How many plateaus were detected so far
+    pub plateaus: usize,
+    /// The number of covered map entries, at the last run of the [`PlateauDetectorStage`]
+    pub coverage: usize,
+}
+
+crate::impl_serdeany!(PlateauMetadata);
+
+/// A [`Stage`] tracking the number of covered entries of a [`MapFeedbackState`].
+/// Once the coverage grew by less than `threshold` entries for `window` consecutive runs of this
+/// stage, it sets [`PlateauMetadata::on_plateau`] and logs an [`crate::events::Event::Log`].
+/// The flag is cleared, and the window starts over, as soon as the coverage grows by `threshold`.
+#[derive(Clone, Debug)]
+pub struct PlateauDetectorStage<FT, I, S, T> {
+    map_state_name: String,
+    window: usize,
+    threshold: usize,
+    /// The coverage at the start of the current window
+    baseline: usize,
+    stagnant: usize,
+    phantom: PhantomData<(FT, I, S, T)>,
+}
+
+impl<E, EM, FT, I, S, T, Z> Stage<E, EM, S, Z> for PlateauDetectorStage<FT, I, S, T>
+where
+    EM: EventFirer<I>,
+    FT: FeedbackStatesTuple,
+    I: Input,
+    S: HasFeedbackStates<FT> + HasMetadata,
+    T: PrimInt + Default + Copy + 'static + Serialize + serde::de::DeserializeOwned + Debug,
+{
+    #[inline]
+    fn perform(
+        &mut self,
+        _fuzzer: &mut Z,
+        _executor: &mut E,
+        state: &mut S,
+        manager: &mut EM,
+        _corpus_idx: usize,
+    ) -> Result<(), Error> {
+        let coverage = state
+            .feedback_states()
+            .match_name::<MapFeedbackState<T>>(&self.map_state_name)
+            .ok_or_else(|| Error::KeyNotFound(format!("{} not found", self.map_state_name)))?
+            .history_map
+            .iter()
+            .filter(|entry| **entry != T::min_value())
+            .count();
+
+        if state.metadata().get::<PlateauMetadata>().is_none() {
+            state.add_metadata(PlateauMetadata::default());
+        }
+        let meta = state.metadata_mut().get_mut::<PlateauMetadata>().unwrap();
+        meta.coverage = coverage;
+
+        if coverage >= self.baseline + self.threshold {
+            self.baseline = coverage;
+            self.stagnant = 0;
+            meta.on_plateau = false;
+            return Ok(());
+        }
+
+        self.stagnant += 1;
+        if self.stagnant >= self.window && !meta.on_plateau {
+            meta.on_plateau = true;
+            meta.plateaus += 1;
+            manager.log(
+                state,
+                LogSeverity::Info,
+                format!(
+                    "Coverage plateau: {} entries, no growth for {} runs",
+                    coverage, self.stagnant
+                ),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl<FT, I, S, T> PlateauDetectorStage<FT, I, S, T>
+where
+    T: PrimInt + Default + Copy + 'static + Serialize + serde::de::DeserializeOwned + Debug,
+{
+    /// Creates a new [`PlateauDetectorStage`] for the coverage of the given map state, detecting
+    /// a plateau after `window` runs without any new entry.
+    #[must_use]
+    pub fn new(map_state: &MapFeedbackState<T>, window: usize) -> Self {
+        Self::with_threshold(map_state, window, 1)
+    }
+
+    /// Creates a new [`PlateauDetectorStage`] for the coverage of the given map state, detecting
+    /// a plateau after `window` runs with less than `threshold` new entries.
+    #[must_use]
+    pub fn with_threshold(
+        map_state: &MapFeedbackState<T>,
+        window: usize,
+        threshold: usize,
+    ) -> Self {
+        Self {
+            map_state_name: map_state.name().to_string(),
+            window: window.max(1),
+            threshold: threshold.max(1),
+            baseline: 0,
+            stagnant: 0,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The number of runs without enough growth that make a plateau
+    #[must_use]
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// The number of new entries that end a plateau
+    #[must_use]
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::{
+            rands::StdRand,
+            tuples::{tuple_list, tuple_list_type, MatchName},
+        },
+        corpus::InMemoryCorpus,
+        events::NopEventManager,
+        feedbacks::MapFeedbackState,
+        inputs::BytesInput,
+        stages::{PlateauDetectorStage, PlateauMetadata, Stage},
+        state::{HasFeedbackStates, HasMetadata, StdState},
+    };
+
+    #[test]
+    fn test_plateau_detector_stage() {
+        type State = StdState<
+            InMemoryCorpus<BytesInput>,
+            tuple_list_type!(MapFeedbackState<u8>),
+            BytesInput,
+            StdRand,
+            InMemoryCorpus<BytesInput>,
+        >;
+
+        let map_state = MapFeedbackState::<u8>::new("edges", 8);
+        let mut plateau = PlateauDetectorStage::<_, BytesInput, _, _>::new(&map_state, 3);
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            tuple_list!(map_state),
+        );
+        let mut mgr = NopEventManager {};
+        let cover = |state: &mut State, entries: usize| {
+            let map_state = state
+                .feedback_states_mut()
+                .match_name_mut::<MapFeedbackState<u8>>("edges")
+                .unwrap();
+            map_state.history_map[..entries].fill(1);
+        };
+
+        // Growing coverage, then a flat series
+        cover(&mut state, 2);
+        plateau
+            .perform(&mut (), &mut (), &mut state, &mut mgr, 0)
+            .unwrap();
+        for run in 1..=3 {
+            plateau
+                .perform(&mut (), &mut (), &mut state, &mut mgr, 0)
+                .unwrap();
+            let meta = state.metadata().get::<PlateauMetadata>().unwrap();
+            assert_eq!(meta.on_plateau, run == 3);
+            assert_eq!(meta.coverage, 2);
+        }
+
+        // New coverage ends the plateau
+        cover(&mut state, 3);
+        plateau
+            .perform(&mut (), &mut (), &mut state, &mut mgr, 0)
+            .unwrap();
+        let meta = state.metadata().get::<PlateauMetadata>().unwrap();
+        assert!(!meta.on_plateau);
+        assert_eq!(meta.plateaus, 1);
+    }
+}
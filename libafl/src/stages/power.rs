@@ -5,14 +5,14 @@ use core::{fmt::Debug, marker::PhantomData};
 use num_traits::PrimInt;
 
 use crate::{
-    corpus::{Corpus, IsFavoredMetadata, PowerScheduleTestcaseMetaData, Testcase},
+    corpus::{Corpus, IsFavoredMetadata, OriginMetadata, PowerScheduleTestcaseMetaData, Testcase},
     executors::{Executor, HasObservers},
     fuzzer::Evaluator,
     inputs::Input,
-    mutators::Mutator,
+    mutators::{MutationResult, Mutator},
     observers::{MapObserver, ObserversTuple},
     stages::{MutationalStage, PowerScheduleMetadata, Stage},
-    state::{HasClientPerfMonitor, HasCorpus, HasMetadata},
+    state::{HasClientPerfMonitor, HasCorpus, HasMaxSize, HasMetadata},
     Error,
 };
 
@@ -43,7 +43,7 @@ where
     M: Mutator<I, S>,
     O: MapObserver<T>,
     OT: ObserversTuple<I, S>,
-    S: HasClientPerfMonitor + HasCorpus<C, I> + HasMetadata,
+    S: HasClientPerfMonitor + HasCorpus<C, I> + HasMaxSize + HasMetadata,
     Z: Evaluator<E, EM, I, S>,
 {
     map_observer_name: String,
@@ -64,7 +64,7 @@ where
     M: Mutator<I, S>,
     O: MapObserver<T>,
     OT: ObserversTuple<I, S>,
-    S: HasClientPerfMonitor + HasCorpus<C, I> + HasMetadata,
+    S: HasClientPerfMonitor + HasCorpus<C, I> + HasMaxSize + HasMetadata,
     Z: Evaluator<E, EM, I, S>,
 {
     /// The mutator, added to this stage
@@ -115,9 +115,17 @@ where
                 .load_input()?
                 .clone();
 
-            self.mutator_mut().mutate(state, &mut input, i as i32)?;
+            let mutated = self.mutator_mut().mutate(state, &mut input, i as i32)?;
+            input.truncate(state.max_size());
+            // The input did not change, running it again would be a waste
+            if mutated == MutationResult::Unchanged {
+                // Drops what the mutator recorded for this run, such as the used tokens
+                self.mutator_mut().post_exec(state, i as i32, None)?;
+                continue;
+            }
 
             let (_, corpus_idx) = fuzzer.evaluate_input(state, executor, manager, input)?;
+            OriginMetadata::Mutated.tag(state.corpus(), corpus_idx)?;
 
             let observer = executor
                 .observers()
@@ -165,7 +173,7 @@ where
     M: Mutator<I, S>,
     O: MapObserver<T>,
     OT: ObserversTuple<I, S>,
-    S: HasClientPerfMonitor + HasCorpus<C, I> + HasMetadata,
+    S: HasClientPerfMonitor + HasCorpus<C, I> + HasMaxSize + HasMetadata,
     Z: Evaluator<E, EM, I, S>,
 {
     #[inline]
@@ -192,7 +200,7 @@ where
     M: Mutator<I, S>,
     O: MapObserver<T>,
     OT: ObserversTuple<I, S>,
-    S: HasClientPerfMonitor + HasCorpus<C, I> + HasMetadata,
+    S: HasClientPerfMonitor + HasCorpus<C, I> + HasMaxSize + HasMetadata,
     Z: Evaluator<E, EM, I, S>,
 {
     /// Creates a new [`PowerMutationalStage`]
@@ -0,0 +1,362 @@
+//! The recalibration stage periodically re-runs a sample of the corpus,
+//! to keep the stability measurements fresh over long campaigns.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt::Debug, marker::PhantomData, time::Duration};
+use num_traits::PrimInt;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::{current_time, rands::Rand},
+    corpus::Corpus,
+    executors::{Executor, ExitKind, HasObservers},
+    inputs::Input,
+    observers::{MapObserver, ObserversTuple},
+    stages::Stage,
+    state::{HasCorpus, HasMetadata, HasRand},
+    Error,
+};
+
+/// The default amount of corpus entries re-calibrated each time
+pub const DEFAULT_RECALIBRATION_SAMPLE: usize = 8;
+/// The default amount of runs per re-calibrated entry
+pub const DEFAULT_RECALIBRATION_RUNS: usize = 4;
+
+/// A testcase metadata holding the stability measured by the [`RecalibrationStage`]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct TestcaseStabilityMetadata {
+    /// The ratio of map entries hit by this testcase that stayed the same across all runs
+    pub stability: f32,
+    /// The time of the last measurement
+    pub measured_at: Duration,
+}
+
+crate::impl_serdeany!(TestcaseStabilityMetadata);
+
+/// A testcase metadata marking a testcase measured below the minimum stability of the
+/// [`RecalibrationStage`], for the [`crate::corpus::StableCorpusScheduler`] to skip it
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnstableTestcaseMetadata {}
+
+crate::impl_serdeany!(UnstableTestcaseMetadata);
+
+/// A [`Stage`] re-running a random sample of the corpus on a timer, and updating their
+/// [`TestcaseStabilityMetadata`]. Coverage maps can drift over long runs (ASLR, JIT),
+/// so earlier calibration results get stale.
+/// If configured with a minimum stability, entries below it get an [`UnstableTestcaseMetadata`],
+/// and are skipped by a [`crate::corpus::StableCorpusScheduler`]. They stay in the corpus, so the
+/// indices the scheduler and later stages hold stay valid.
+#[derive(Clone, Debug)]
+pub struct RecalibrationStage<C, E, EM, I, O, OT, R, S, T, Z>
+where
+    T: PrimInt + Default + Copy + 'static + Serialize + serde::de::DeserializeOwned + Debug,
+    C: Corpus<I>,
+    E: Executor<EM, I, S, Z> + HasObservers<I, OT, S>,
+    I: Input,
+    O: MapObserver<T>,
+    OT: ObserversTuple<I, S>,
+    R: Rand,
+    S: HasCorpus<C, I> + HasRand<R>,
+{
+    map_observer_name: String,
+    interval: Duration,
+    last_run: Duration,
+    sample_size: usize,
+    runs: usize,
+    min_stability: Option<f32>,
+    #[allow(clippy::type_complexity)]
+    phantom: PhantomData<(C, E, EM, I, O, OT, R, S, T, Z)>,
+}
+
+impl<C, E, EM, I, O, OT, R, S, T, Z> Stage<E, EM, S, Z>
+    for RecalibrationStage<C, E, EM, I, O, OT, R, S, T, Z>
+where
+    T: PrimInt + Default + Copy + 'static + Serialize + serde::de::DeserializeOwned + Debug,
+    C: Corpus<I>,
+    E: Executor<EM, I, S, Z> + HasObservers<I, OT, S>,
+    I: Input,
+    O: MapObserver<T>,
+    OT: ObserversTuple<I, S>,
+    R: Rand,
+    S: HasCorpus<C, I> + HasRand<R>,
+{
+    #[inline]
+    fn perform(
+        &mut self,
+        fuzzer: &mut Z,
+        executor: &mut E,
+        state: &mut S,
+        mgr: &mut EM,
+        _corpus_idx: usize,
+    ) -> Result<(), Error> {
+        let now = current_time();
+        if now.saturating_sub(self.last_run) < self.interval {
+            return Ok(());
+        }
+        self.last_run = now;
+
+        let count = state.corpus().count();
+        if count == 0 {
+            return Ok(());
+        }
+        let mut sample: Vec<usize> = (0..self.sample_size.min(count))
+            .map(|_| state.rand_mut().below(count as u64) as usize)
+            .collect();
+        sample.sort_unstable();
+        sample.dedup();
+
+        for &idx in &sample {
+            let stability = self.measure(fuzzer, executor, state, mgr, idx)?;
+            let mut testcase = state.corpus().get(idx)?.borrow_mut();
+            testcase.add_metadata(TestcaseStabilityMetadata {
+                stability,
+                measured_at: now,
+            });
+            // Entries can turn stable again, for example after the target got fixed
+            if self.min_stability.map_or(false, |min| stability < min) {
+                testcase.add_metadata(UnstableTestcaseMetadata {});
+            } else {
+                let _ = testcase.metadata_mut().remove::<UnstableTestcaseMetadata>();
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<C, E, EM, I, O, OT, R, S, T, Z> RecalibrationStage<C, E, EM, I, O, OT, R, S, T, Z>
+where
+    T: PrimInt + Default + Copy + 'static + Serialize + serde::de::DeserializeOwned + Debug,
+    C: Corpus<I>,
+    E: Executor<EM, I, S, Z> + HasObservers<I, OT, S>,
+    I: Input,
+    O: MapObserver<T>,
+    OT: ObserversTuple<I, S>,
+    R: Rand,
+    S: HasCorpus<C, I> + HasRand<R>,
+{
+    /// Create a new [`RecalibrationStage`], re-calibrating entries every `interval`.
+    #[must_use]
+    pub fn new(map_observer: &O, interval: Duration) -> Self {
+        Self {
+            map_observer_name: map_observer.name().to_string(),
+            interval,
+            last_run: current_time(),
+            sample_size: DEFAULT_RECALIBRATION_SAMPLE,
+            runs: DEFAULT_RECALIBRATION_RUNS,
+            min_stability: None,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Sets the amount of corpus entries re-calibrated each time, and the runs per entry.
+    #[must_use]
+    pub fn with_sample(mut self, sample_size: usize, runs: usize) -> Self {
+        self.sample_size = sample_size;
+        self.runs = runs.max(2);
+        self
+    }
+
+    /// Marks re-calibrated entries with a stability below `min_stability` as unstable, with an
+    /// [`UnstableTestcaseMetadata`].
+    #[must_use]
+    pub fn with_min_stability(mut self, min_stability: f32) -> Self {
+        self.min_stability = Some(min_stability);
+        self
+    }
+
+    /// Runs the given corpus entry multiple times, returning the ratio of hit map entries
+    /// that stayed the same across all runs.
+    #[allow(clippy::cast_precision_loss)]
+    fn measure(
+        &self,
+        fuzzer: &mut Z,
+        executor: &mut E,
+        state: &mut S,
+        mgr: &mut EM,
+        idx: usize,
+    ) -> Result<f32, Error> {
+        let input = state.corpus().get(idx)?.borrow_mut().load_input()?.clone();
+
+        let mut first: Option<Vec<T>> = None;
+        let mut unstable: Vec<bool> = vec![];
+        for _ in 0..self.runs {
+            executor.observers_mut().pre_exec_all(state, &input)?;
+            let exit_kind = executor.run_target(fuzzer, state, mgr, &input)?;
+            executor.observers_mut().post_exec_all(state, &input)?;
+            if exit_kind != ExitKind::Ok {
+                continue;
+            }
+
+            let observer = executor
+                .observers()
+                .match_name::<O>(&self.map_observer_name)
+                .ok_or_else(|| Error::KeyNotFound("MapObserver not found".to_string()))?;
+            let size = observer.usable_count();
+            match &first {
+                None => {
+                    first = Some((0..size).map(|i| *observer.get(i)).collect());
+                    unstable = vec![false; size];
+                }
+                Some(first) => {
+                    for (i, first_val) in first.iter().enumerate().take(size) {
+                        if *observer.get(i) != *first_val {
+                            unstable[i] = true;
+                        }
+                    }
+                }
+            }
+        }
+
+        let first = match first {
+            Some(first) => first,
+            // The entry never finished cleanly
+            None => return Ok(0.0),
+        };
+        let initial = T::default();
+        let hit = first
+            .iter()
+            .zip(&unstable)
+            .filter(|(val, unstable)| **val != initial || **unstable)
+            .count();
+        if hit == 0 {
+            return Ok(1.0);
+        }
+        let unstable_count = unstable.iter().filter(|unstable| **unstable).count();
+        Ok((hit - unstable_count) as f32 / hit as f32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use crate::{
+        bolts::{
+            rands::StdRand,
+            tuples::{tuple_list, tuple_list_type},
+        },
+        corpus::{
+            Corpus, CorpusScheduler, InMemoryCorpus, QueueCorpusScheduler, StableCorpusScheduler,
+            Testcase,
+        },
+        executors::{Executor, ExitKind, HasObservers},
+        inputs::{BytesInput, Input},
+        observers::{MapObserver, StdMapObserver},
+        stages::{
+            recalibrate::{TestcaseStabilityMetadata, UnstableTestcaseMetadata},
+            RecalibrationStage, Stage,
+        },
+        state::{HasCorpus, HasMetadata, StdState},
+        Error,
+    };
+
+    /// Hits the same edge each run, and, if `flaky`, another edge every other run
+    #[derive(Debug)]
+    struct FlakyExecutor {
+        observers: tuple_list_type!(StdMapObserver<'static, u8>),
+        flaky: bool,
+        runs: usize,
+    }
+
+    impl<EM, I: Input, S, Z> Executor<EM, I, S, Z> for FlakyExecutor {
+        fn run_target(
+            &mut self,
+            _fuzzer: &mut Z,
+            _state: &mut S,
+            _mgr: &mut EM,
+            _input: &I,
+        ) -> Result<ExitKind, Error> {
+            self.runs += 1;
+            let map = &mut self.observers.0;
+            *map.get_mut(0) = 1;
+            if self.flaky && self.runs % 2 == 0 {
+                *map.get_mut(1) = 1;
+            }
+            Ok(ExitKind::Ok)
+        }
+    }
+
+    impl<I: Input, S> HasObservers<I, tuple_list_type!(StdMapObserver<'static, u8>), S>
+        for FlakyExecutor
+    {
+        fn observers(&self) -> &tuple_list_type!(StdMapObserver<'static, u8>) {
+            &self.observers
+        }
+
+        fn observers_mut(&mut self) -> &mut tuple_list_type!(StdMapObserver<'static, u8>) {
+            &mut self.observers
+        }
+    }
+
+    fn stability<S: HasCorpus<InMemoryCorpus<BytesInput>, BytesInput>>(state: &S) -> f32 {
+        state
+            .corpus()
+            .get(0)
+            .unwrap()
+            .borrow()
+            .metadata()
+            .get::<TestcaseStabilityMetadata>()
+            .unwrap()
+            .stability
+    }
+
+    fn is_unstable<S: HasCorpus<InMemoryCorpus<BytesInput>, BytesInput>>(state: &S) -> bool {
+        state
+            .corpus()
+            .get(0)
+            .unwrap()
+            .borrow()
+            .metadata()
+            .get::<UnstableTestcaseMetadata>()
+            .is_some()
+    }
+
+    #[test]
+    fn test_recalibration() {
+        let observer = StdMapObserver::new_owned("map", vec![0_u8; 4]);
+        let mut recalibration_stage =
+            RecalibrationStage::new(&observer, Duration::ZERO).with_min_stability(0.9);
+        let mut executor = FlakyExecutor {
+            observers: tuple_list!(observer),
+            flaky: false,
+            runs: 0,
+        };
+
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        for _ in 0..2 {
+            state
+                .corpus_mut()
+                .add(Testcase::new(BytesInput::new(vec![0])))
+                .unwrap();
+        }
+
+        recalibration_stage
+            .perform(&mut (), &mut executor, &mut state, &mut (), 0)
+            .unwrap();
+        assert!((stability(&state) - 1.0).abs() < f32::EPSILON);
+        assert!(!is_unstable(&state));
+
+        // The target turned flaky since the last measurement
+        executor.flaky = true;
+        recalibration_stage
+            .perform(&mut (), &mut executor, &mut state, &mut (), 0)
+            .unwrap();
+        assert!(stability(&state) < 0.9);
+        assert!(is_unstable(&state));
+        // Unstable entries stay in the corpus, but get skipped
+        assert_eq!(state.corpus().count(), 2);
+        let scheduler = StableCorpusScheduler::new(QueueCorpusScheduler::new());
+        for _ in 0..4 {
+            assert_eq!(scheduler.next(&mut state).unwrap(), 1);
+        }
+    }
+}
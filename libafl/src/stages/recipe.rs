@@ -0,0 +1,268 @@
+//! A mutational stage recording a [`MutationRecipe`] for each input it adds to the corpus or the solutions,
+//! so that the exact mutation can be re-derived from the seed later, using [`replay_recipe`].
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::rands::Rand,
+    corpus::{Corpus, OriginMetadata},
+    fuzzer::{Evaluator, ExecuteInputResult},
+    inputs::Input,
+    mark_feature_time,
+    mutators::Mutator,
+    stages::{mutational::DEFAULT_MUTATIONAL_MAX_ITERATIONS, MutationalStage, Stage},
+    start_timer,
+    state::{HasClientPerfMonitor, HasCorpus, HasMaxSize, HasMetadata, HasRand, HasSolutions},
+    Error,
+};
+
+#[cfg(feature = "introspection")]
+use crate::monitors::PerfFeature;
+
+/// How an input was derived from its seed: the state of the rand right before the mutation.
+/// Replaying the same mutator from this rand state re-derives the same input, see [`replay_recipe`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MutationRecipe {
+    /// The index of the seed in the corpus, at the time of the mutation
+    pub seed_idx: usize,
+    /// The `stage_idx` the mutator was called with
+    pub stage_idx: i32,
+    /// The serialized rand, right before the mutation
+    pub rand: Vec<u8>,
+}
+
+crate::impl_serdeany!(MutationRecipe);
+
+impl MutationRecipe {
+    /// Records the current rand of the `state`, right before mutating the seed at `seed_idx`
+    pub fn record<R, S>(state: &S, seed_idx: usize, stage_idx: i32) -> Result<Self, Error>
+    where
+        R: Rand,
+        S: HasRand<R>,
+    {
+        Ok(Self {
+            seed_idx,
+            stage_idx,
+            rand: postcard::to_allocvec(state.rand())?,
+        })
+    }
+}
+
+/// Re-derives an input from its `seed` and the [`MutationRecipe`] recorded when it was mutated.
+/// The `mutator` has to be the one that was used, with the same configuration, and must only
+/// depend on the rand and on the `seed` (crossover mutators also depend on the corpus).
+/// Like the mutational stages, the result is truncated to the max size of the `state`.
+/// The rand of the `state` is left untouched.
+pub fn replay_recipe<I, M, R, S>(
+    state: &mut S,
+    mutator: &mut M,
+    seed: &I,
+    recipe: &MutationRecipe,
+) -> Result<I, Error>
+where
+    I: Input,
+    M: Mutator<I, S>,
+    R: Rand,
+    S: HasMaxSize + HasRand<R>,
+{
+    let rand = postcard::from_bytes(&recipe.rand)?;
+    let rand = core::mem::replace(state.rand_mut(), rand);
+
+    let mut input = seed.clone();
+    let result = mutator.mutate(state, &mut input, recipe.stage_idx);
+    input.truncate(state.max_size());
+
+    *state.rand_mut() = rand;
+    result?;
+    Ok(input)
+}
+
+/// A [`MutationalStage`] like the [`crate::stages::StdMutationalStage`], that also adds a
+/// [`MutationRecipe`] to the metadata of each testcase it adds to the corpus or the solutions.
+#[derive(Clone, Debug)]
+pub struct RecipeMutationalStage<C, E, EM, I, M, R, S, SC, Z>
+where
+    C: Corpus<I>,
+    M: Mutator<I, S>,
+    I: Input,
+    R: Rand,
+    S: HasClientPerfMonitor + HasCorpus<C, I> + HasMaxSize + HasSolutions<SC, I> + HasRand<R>,
+    SC: Corpus<I>,
+    Z: Evaluator<E, EM, I, S>,
+{
+    mutator: M,
+    #[allow(clippy::type_complexity)]
+    phantom: PhantomData<(C, E, EM, I, R, S, SC, Z)>,
+}
+
+impl<C, E, EM, I, M, R, S, SC, Z> MutationalStage<C, E, EM, I, M, S, Z>
+    for RecipeMutationalStage<C, E, EM, I, M, R, S, SC, Z>
+where
+    C: Corpus<I>,
+    M: Mutator<I, S>,
+    I: Input,
+    R: Rand,
+    S: HasClientPerfMonitor + HasCorpus<C, I> + HasMaxSize + HasSolutions<SC, I> + HasRand<R>,
+    SC: Corpus<I>,
+    Z: Evaluator<E, EM, I, S>,
+{
+    /// The mutator, added to this stage
+    #[inline]
+    fn mutator(&self) -> &M {
+        &self.mutator
+    }
+
+    /// The list of mutators, added to this stage (as mutable ref)
+    #[inline]
+    fn mutator_mut(&mut self) -> &mut M {
+        &mut self.mutator
+    }
+
+    /// Gets the number of iterations as a random number
+    fn iterations(&self, state: &mut S, _corpus_idx: usize) -> Result<usize, Error> {
+        Ok(1 + state.rand_mut().below(DEFAULT_MUTATIONAL_MAX_ITERATIONS) as usize)
+    }
+
+    /// Runs this (mutational) stage for the given testcase, recording a [`MutationRecipe`] per new testcase
+    #[allow(clippy::cast_possible_wrap)] // more than i32 stages on 32 bit system - highly unlikely...
+    fn perform_mutational(
+        &mut self,
+        fuzzer: &mut Z,
+        executor: &mut E,
+        state: &mut S,
+        manager: &mut EM,
+        corpus_idx: usize,
+    ) -> Result<(), Error> {
+        let num = self.iterations(state, corpus_idx)?;
+
+        for i in 0..num {
+            start_timer!(state);
+            let mut input = state
+                .corpus()
+                .get(corpus_idx)?
+                .borrow_mut()
+                .load_input()?
+                .clone();
+            mark_feature_time!(state, PerfFeature::GetInputFromCorpus);
+
+            let recipe = MutationRecipe::record(state, corpus_idx, i as i32)?;
+
+            start_timer!(state);
+            self.mutator_mut().mutate(state, &mut input, i as i32)?;
+            input.truncate(state.max_size());
+            mark_feature_time!(state, PerfFeature::Mutate);
+
+            // Time is measured directly the `evaluate_input` function
+            let (res, new_idx) = fuzzer.evaluate_input(state, executor, manager, input)?;
+            OriginMetadata::Mutated.tag(state.corpus(), new_idx)?;
+            match (res, new_idx) {
+                (_, Some(idx)) => state.corpus().get(idx)?.borrow_mut().add_metadata(recipe),
+                (ExecuteInputResult::Solution, None) => {
+                    let idx = state.solutions().count() - 1;
+                    state
+                        .solutions()
+                        .get(idx)?
+                        .borrow_mut()
+                        .add_metadata(recipe);
+                }
+                _ => (),
+            }
+
+            start_timer!(state);
+            self.mutator_mut().post_exec(state, i as i32, new_idx)?;
+            mark_feature_time!(state, PerfFeature::MutatePostExec);
+        }
+        Ok(())
+    }
+}
+
+impl<C, E, EM, I, M, R, S, SC, Z> Stage<E, EM, S, Z>
+    for RecipeMutationalStage<C, E, EM, I, M, R, S, SC, Z>
+where
+    C: Corpus<I>,
+    M: Mutator<I, S>,
+    I: Input,
+    R: Rand,
+    S: HasClientPerfMonitor + HasCorpus<C, I> + HasMaxSize + HasSolutions<SC, I> + HasRand<R>,
+    SC: Corpus<I>,
+    Z: Evaluator<E, EM, I, S>,
+{
+    #[inline]
+    #[allow(clippy::let_and_return)]
+    fn perform(
+        &mut self,
+        fuzzer: &mut Z,
+        executor: &mut E,
+        state: &mut S,
+        manager: &mut EM,
+        corpus_idx: usize,
+    ) -> Result<(), Error> {
+        let ret = self.perform_mutational(fuzzer, executor, state, manager, corpus_idx);
+
+        #[cfg(feature = "introspection")]
+        state.introspection_monitor_mut().finish_stage();
+
+        ret
+    }
+}
+
+impl<C, E, EM, I, M, R, S, SC, Z> RecipeMutationalStage<C, E, EM, I, M, R, S, SC, Z>
+where
+    C: Corpus<I>,
+    M: Mutator<I, S>,
+    I: Input,
+    R: Rand,
+    S: HasClientPerfMonitor + HasCorpus<C, I> + HasMaxSize + HasSolutions<SC, I> + HasRand<R>,
+    SC: Corpus<I>,
+    Z: Evaluator<E, EM, I, S>,
+{
+    /// Creates a new [`RecipeMutationalStage`]
+    pub fn new(mutator: M) -> Self {
+        Self {
+            mutator,
+            phantom: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::InMemoryCorpus,
+        inputs::{BytesInput, HasBytesVec},
+        mutators::{havoc_mutations_no_crossover, Mutator, StdScheduledMutator},
+        stages::{replay_recipe, MutationRecipe},
+        state::{HasRand, StdState},
+    };
+
+    #[test]
+    fn test_replay_recipe() {
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let seed = BytesInput::new(b"a seed input, mutated many times".to_vec());
+        let mut mutator = StdScheduledMutator::new(havoc_mutations_no_crossover());
+
+        let mut recorded = vec![];
+        for i in 0..16 {
+            let recipe = MutationRecipe::record(&state, 0, i).unwrap();
+            let mut input = seed.clone();
+            mutator.mutate(&mut state, &mut input, i).unwrap();
+            recorded.push((recipe, input));
+        }
+
+        // Replaying leaves the rand of the state untouched
+        let rand = postcard::to_allocvec(state.rand()).unwrap();
+        for (recipe, input) in recorded.iter().rev() {
+            let replayed = replay_recipe(&mut state, &mut mutator, &seed, recipe).unwrap();
+            assert_eq!(replayed.bytes(), input.bytes());
+        }
+        assert_eq!(postcard::to_allocvec(state.rand()).unwrap(), rand);
+    }
+}
@@ -0,0 +1,139 @@
+//! The [`TopCoverageReportStage`] periodically logs the corpus entries covering the most edges.
+
+use alloc::{string::String, vec::Vec};
+use core::{fmt::Write, marker::PhantomData, time::Duration};
+
+use crate::{
+    bolts::{current_time, HasLen},
+    corpus::Corpus,
+    events::{EventFirer, LogSeverity},
+    feedbacks::MapIndexesMetadata,
+    inputs::Input,
+    stages::Stage,
+    state::{HasCorpus, HasMetadata},
+    Error,
+};
+
+/// A [`Stage`] firing an [`crate::events::Event::Log`], at most once per `interval`, listing the
+/// `top_n` corpus entries with the most covered map entries, with their index and size.
+/// The coverage of each entry is read from its [`MapIndexesMetadata`], so the map feedback has to
+/// track indexes. Entries without it are not ranked.
+#[derive(Clone, Debug)]
+pub struct TopCoverageReportStage<C, I, S> {
+    top_n: usize,
+    interval: Duration,
+    last_time: Duration,
+    phantom: PhantomData<(C, I, S)>,
+}
+
+impl<C, E, EM, I, S, Z> Stage<E, EM, S, Z> for TopCoverageReportStage<C, I, S>
+where
+    C: Corpus<I>,
+    EM: EventFirer<I>,
+    I: Input + HasLen,
+    S: HasCorpus<C, I>,
+{
+    #[inline]
+    fn perform(
+        &mut self,
+        _fuzzer: &mut Z,
+        _executor: &mut E,
+        state: &mut S,
+        manager: &mut EM,
+        _corpus_idx: usize,
+    ) -> Result<(), Error> {
+        let cur = current_time();
+        if cur.checked_sub(self.last_time).unwrap_or_default() < self.interval {
+            return Ok(());
+        }
+        self.last_time = cur;
+
+        let mut message = String::from("Top coverage:");
+        for (idx, edges) in self.top_entries(state)? {
+            let len = state.corpus().get(idx)?.borrow_mut().cached_len()?;
+            write!(message, " #{} ({} edges, {} bytes)", idx, edges, len).unwrap();
+        }
+        manager.log(state, LogSeverity::Info, message)
+    }
+}
+
+impl<C, I, S> TopCoverageReportStage<C, I, S>
+where
+    C: Corpus<I>,
+    I: Input,
+    S: HasCorpus<C, I>,
+{
+    /// Creates a new [`TopCoverageReportStage`], logging the `top_n` entries at most once per
+    /// `interval`, the first time after `interval` passed
+    #[must_use]
+    pub fn new(top_n: usize, interval: Duration) -> Self {
+        Self {
+            top_n,
+            interval,
+            last_time: current_time(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// The index and the number of covered map entries of the `top_n` corpus entries covering
+    /// the most map entries, most first. Ties are ordered by index.
+    pub fn top_entries(&self, state: &S) -> Result<Vec<(usize, usize)>, Error> {
+        let corpus = state.corpus();
+        let mut ranking = vec![];
+        for idx in 0..corpus.count() {
+            if let Some(meta) = corpus
+                .get(idx)?
+                .borrow()
+                .metadata()
+                .get::<MapIndexesMetadata>()
+            {
+                ranking.push((idx, meta.list.len()));
+            }
+        }
+        ranking.sort_by(|(_, a), (_, b)| b.cmp(a));
+        ranking.truncate(self.top_n);
+        Ok(ranking)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::{Corpus, InMemoryCorpus, Testcase},
+        events::NopEventManager,
+        feedbacks::MapIndexesMetadata,
+        inputs::BytesInput,
+        stages::{Stage, TopCoverageReportStage},
+        state::{HasCorpus, HasMetadata, StdState},
+    };
+
+    #[test]
+    fn test_top_coverage_report_stage() {
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        for edges in [Some(2), Some(5), None, Some(3), Some(5)] {
+            let mut testcase = Testcase::new(BytesInput::new(vec![0; 4]));
+            if let Some(edges) = edges {
+                testcase.add_metadata(MapIndexesMetadata::new((0..edges).collect()));
+            }
+            state.corpus_mut().add(testcase).unwrap();
+        }
+
+        // Most edges first, ties by index, entries without coverage are not ranked
+        let mut report = TopCoverageReportStage::new(3, Duration::ZERO);
+        assert_eq!(
+            report.top_entries(&state).unwrap(),
+            [(1, 5), (4, 5), (3, 3)]
+        );
+        report
+            .perform(&mut (), &mut (), &mut state, &mut NopEventManager {}, 0)
+            .unwrap();
+    }
+}
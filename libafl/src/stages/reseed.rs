@@ -0,0 +1,164 @@
+//! The [`RngReseedStage`] reseeds the rand of the state when the corpus stopped growing for a while,
+//! to perturb the search trajectory of long campaigns stuck in a narrow region.
+
+use core::marker::PhantomData;
+
+use crate::{
+    bolts::{current_nanos, rands::Rand},
+    corpus::Corpus,
+    inputs::Input,
+    stages::Stage,
+    state::{HasCorpus, HasRand},
+    Error,
+};
+
+/// A [`Stage`] reseeding [`HasRand::rand_mut`] with a fresh seed once no new corpus entry, i.e. no
+/// new coverage, showed up for `window` consecutive runs of this stage.
+/// The window starts over after each reseed, and each time the corpus grows.
+#[derive(Clone, Debug)]
+pub struct RngReseedStage<C, I, R, S>
+where
+    C: Corpus<I>,
+    I: Input,
+    R: Rand,
+    S: HasCorpus<C, I> + HasRand<R>,
+{
+    window: usize,
+    last_count: usize,
+    stagnant: usize,
+    reseeds: usize,
+    last_seed: Option<u64>,
+    phantom: PhantomData<(C, I, R, S)>,
+}
+
+impl<C, E, EM, I, R, S, Z> Stage<E, EM, S, Z> for RngReseedStage<C, I, R, S>
+where
+    C: Corpus<I>,
+    I: Input,
+    R: Rand,
+    S: HasCorpus<C, I> + HasRand<R>,
+{
+    #[inline]
+    fn perform(
+        &mut self,
+        _fuzzer: &mut Z,
+        _executor: &mut E,
+        state: &mut S,
+        _manager: &mut EM,
+        _corpus_idx: usize,
+    ) -> Result<(), Error> {
+        let count = state.corpus().count();
+        if count > self.last_count {
+            self.last_count = count;
+            self.stagnant = 0;
+            return Ok(());
+        }
+        // Entries may also get removed
+        self.last_count = count;
+
+        self.stagnant += 1;
+        if self.stagnant >= self.window {
+            let seed = state.rand_mut().next() ^ current_nanos();
+            state.rand_mut().set_seed(seed);
+            self.last_seed = Some(seed);
+            self.reseeds += 1;
+            self.stagnant = 0;
+        }
+        Ok(())
+    }
+}
+
+impl<C, I, R, S> RngReseedStage<C, I, R, S>
+where
+    C: Corpus<I>,
+    I: Input,
+    R: Rand,
+    S: HasCorpus<C, I> + HasRand<R>,
+{
+    /// Creates a new [`RngReseedStage`], reseeding after `window` runs without corpus growth.
+    #[must_use]
+    pub fn new(window: usize) -> Self {
+        Self {
+            window: window.max(1),
+            last_count: 0,
+            stagnant: 0,
+            reseeds: 0,
+            last_seed: None,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The number of runs without corpus growth that trigger a reseed
+    #[must_use]
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// How often the rand got reseeded so far
+    #[must_use]
+    pub fn reseeds(&self) -> usize {
+        self.reseeds
+    }
+
+    /// The seed of the last reseed, if any
+    #[must_use]
+    pub fn last_seed(&self) -> Option<u64> {
+        self.last_seed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::rands::{Rand, StdRand},
+        corpus::{Corpus, InMemoryCorpus, Testcase},
+        inputs::BytesInput,
+        stages::{RngReseedStage, Stage},
+        state::{HasCorpus, HasRand, StdState},
+    };
+
+    #[test]
+    fn test_rng_reseed_stage() {
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let mut reference = StdRand::with_seed(0);
+        let mut reseed_stage = RngReseedStage::new(3);
+
+        // A growing corpus never triggers a reseed
+        for i in 0..4 {
+            state
+                .corpus_mut()
+                .add(Testcase::new(BytesInput::new(vec![i])))
+                .unwrap();
+            reseed_stage
+                .perform(&mut (), &mut (), &mut state, &mut (), 0)
+                .unwrap();
+        }
+        assert_eq!(reseed_stage.reseeds(), 0);
+        assert_eq!(state.rand_mut().next(), reference.next());
+
+        // Three runs without growth do
+        for _ in 0..2 {
+            reseed_stage
+                .perform(&mut (), &mut (), &mut state, &mut (), 0)
+                .unwrap();
+        }
+        assert_eq!(reseed_stage.reseeds(), 0);
+        reseed_stage
+            .perform(&mut (), &mut (), &mut state, &mut (), 0)
+            .unwrap();
+        assert_eq!(reseed_stage.reseeds(), 1);
+
+        // The rand continues from the new seed, not from where it was
+        let mut reseeded = StdRand::with_seed(reseed_stage.last_seed().unwrap());
+        // The draw used for the new seed
+        reference.next();
+        let next = state.rand_mut().next();
+        assert_eq!(next, reseeded.next());
+        assert_ne!(next, reference.next());
+    }
+}
@@ -0,0 +1,208 @@
+//! The [`StatusFileStage`] periodically writes the status of the campaign to a file, like the
+//! `fuzzer_stats` file of AFL, for external monitoring.
+
+use alloc::{string::String, vec::Vec};
+use core::{fmt::Write, marker::PhantomData, time::Duration};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    bolts::current_time,
+    corpus::Corpus,
+    inputs::Input,
+    stages::Stage,
+    state::{HasClientPerfMonitor, HasCorpus, HasExecutions, HasSolutions, HasStartTime},
+    Error,
+};
+
+/// A [`Stage`] writing the status of the campaign to a file, at most once per `interval`, as one
+/// `key : value` pair per line: the executions, executions per second, corpus size, objectives,
+/// the time of the last new corpus entry, and the stability.
+/// Times are in seconds since the epoch, `0` for never. The stability is `-` until measured.
+/// The file gets replaced atomically, so readers never see a partial status.
+#[derive(Clone, Debug)]
+pub struct StatusFileStage<C, I, S, SC> {
+    path: PathBuf,
+    interval: Duration,
+    last_time: Duration,
+    /// The corpus size at the last run of this stage
+    corpus_count: usize,
+    /// The time the corpus last grew
+    last_find: Duration,
+    phantom: PhantomData<(C, I, S, SC)>,
+}
+
+impl<C, E, EM, I, S, SC, Z> Stage<E, EM, S, Z> for StatusFileStage<C, I, S, SC>
+where
+    C: Corpus<I>,
+    I: Input,
+    S: HasCorpus<C, I> + HasSolutions<SC, I> + HasExecutions + HasStartTime + HasClientPerfMonitor,
+    SC: Corpus<I>,
+{
+    #[inline]
+    fn perform(
+        &mut self,
+        _fuzzer: &mut Z,
+        _executor: &mut E,
+        state: &mut S,
+        _manager: &mut EM,
+        _corpus_idx: usize,
+    ) -> Result<(), Error> {
+        let cur = current_time();
+        let corpus_count = state.corpus().count();
+        if corpus_count > self.corpus_count {
+            self.last_find = cur;
+        }
+        self.corpus_count = corpus_count;
+
+        if cur.checked_sub(self.last_time).unwrap_or_default() < self.interval {
+            return Ok(());
+        }
+        self.last_time = cur;
+        self.write_status(state, cur)
+    }
+}
+
+impl<C, I, S, SC> StatusFileStage<C, I, S, SC>
+where
+    C: Corpus<I>,
+    I: Input,
+    S: HasCorpus<C, I> + HasSolutions<SC, I> + HasExecutions + HasStartTime + HasClientPerfMonitor,
+    SC: Corpus<I>,
+{
+    /// Creates a new [`StatusFileStage`], writing the status to `path` at most once per
+    /// `interval`, the first time after `interval` passed
+    #[must_use]
+    pub fn new<P>(path: P, interval: Duration) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            interval,
+            last_time: current_time(),
+            corpus_count: 0,
+            last_find: Duration::ZERO,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The path of the status file
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The status of the campaign at time `cur`, as key/value pairs
+    #[allow(clippy::cast_precision_loss)]
+    pub fn status(&self, state: &S, cur: Duration) -> Vec<(&'static str, String)> {
+        let start_time = *state.start_time();
+        let run_time = cur.checked_sub(start_time).unwrap_or_default();
+        let executions = *state.executions();
+        let execs_per_sec = if run_time.is_zero() {
+            0.0
+        } else {
+            executions as f64 / run_time.as_secs_f64()
+        };
+        let stability = match state.stability() {
+            Some(stability) => format!("{:.2}%", stability * 100.0),
+            None => String::from("-"),
+        };
+        vec![
+            ("start_time", start_time.as_secs().to_string()),
+            ("last_update", cur.as_secs().to_string()),
+            ("run_time", run_time.as_secs().to_string()),
+            ("execs_done", executions.to_string()),
+            ("execs_per_sec", format!("{:.2}", execs_per_sec)),
+            ("corpus_count", state.corpus().count().to_string()),
+            ("objectives", state.solutions().count().to_string()),
+            ("last_find", self.last_find.as_secs().to_string()),
+            ("stability", stability),
+        ]
+    }
+
+    /// Writes the status of the campaign at time `cur` to the status file
+    pub fn write_status(&self, state: &S, cur: Duration) -> Result<(), Error> {
+        let mut contents = String::new();
+        for (key, value) in self.status(state, cur) {
+            writeln!(contents, "{:<16}: {}", key, value).unwrap();
+        }
+        // Write to a temporary file first, then rename it, which is atomic
+        let mut tmp = self.path.clone().into_os_string();
+        tmp.push(".tmp");
+        fs::write(&tmp, contents)?;
+        fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+    use std::{fs, thread};
+
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::{Corpus, InMemoryCorpus, Testcase},
+        events::NopEventManager,
+        inputs::BytesInput,
+        stages::{Stage, StatusFileStage},
+        state::{HasCorpus, HasExecutions, StdState},
+    };
+
+    #[test]
+    fn test_status_file_stage() {
+        let path = std::env::temp_dir().join("libafl_test_status_file");
+        let _ = fs::remove_file(&path);
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        state
+            .corpus_mut()
+            .add(Testcase::new(BytesInput::new(vec![0])))
+            .unwrap();
+        *state.executions_mut() = 42;
+
+        let mut status_stage = StatusFileStage::new(&path, Duration::from_millis(50));
+        status_stage
+            .perform(&mut (), &mut (), &mut state, &mut NopEventManager {}, 0)
+            .unwrap();
+        // The interval did not pass yet
+        assert!(!path.exists());
+
+        thread::sleep(Duration::from_millis(60));
+        status_stage
+            .perform(&mut (), &mut (), &mut state, &mut NopEventManager {}, 0)
+            .unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        let keys: Vec<&str> = contents
+            .lines()
+            .map(|line| line.split(':').next().unwrap().trim_end())
+            .collect();
+        assert_eq!(
+            keys,
+            [
+                "start_time",
+                "last_update",
+                "run_time",
+                "execs_done",
+                "execs_per_sec",
+                "corpus_count",
+                "objectives",
+                "last_find",
+                "stability",
+            ]
+        );
+        assert!(contents.contains("execs_done      : 42\n"));
+        assert!(contents.contains("corpus_count    : 1\n"));
+        assert!(contents.contains("objectives      : 0\n"));
+        assert!(contents.contains("stability       : -\n"));
+        assert!(!contents.contains("last_find       : 0\n"));
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,148 @@
+//! The [`WarmupStage`] holds back heavy mutations until the corpus is big enough,
+//! so fuzzing from a single seed does not drift away from valid inputs right away.
+
+use core::marker::PhantomData;
+
+use crate::{corpus::Corpus, inputs::Input, stages::Stage, state::HasCorpus, Error};
+
+/// A [`Stage`] running a warmup stage, for example one generating or importing inputs,
+/// as long as the corpus holds fewer than `min_corpus_before_havoc` testcases, and the
+/// wrapped stage, usually the havoc stage, afterwards. The warmup stage is not run anymore once
+/// the corpus is big enough.
+#[derive(Clone, Debug)]
+pub struct WarmupStage<C, I, S, WS, ST> {
+    min_corpus_before_havoc: usize,
+    warmup: WS,
+    stage: ST,
+    phantom: PhantomData<(C, I, S)>,
+}
+
+impl<C, E, EM, I, S, ST, WS, Z> Stage<E, EM, S, Z> for WarmupStage<C, I, S, WS, ST>
+where
+    C: Corpus<I>,
+    I: Input,
+    S: HasCorpus<C, I>,
+    WS: Stage<E, EM, S, Z>,
+    ST: Stage<E, EM, S, Z>,
+{
+    #[inline]
+    fn perform(
+        &mut self,
+        fuzzer: &mut Z,
+        executor: &mut E,
+        state: &mut S,
+        manager: &mut EM,
+        corpus_idx: usize,
+    ) -> Result<(), Error> {
+        if self.is_warm(state) {
+            self.stage
+                .perform(fuzzer, executor, state, manager, corpus_idx)
+        } else {
+            self.warmup
+                .perform(fuzzer, executor, state, manager, corpus_idx)
+        }
+    }
+}
+
+impl<C, I, S, WS, ST> WarmupStage<C, I, S, WS, ST>
+where
+    C: Corpus<I>,
+    I: Input,
+    S: HasCorpus<C, I>,
+{
+    /// Creates a new [`WarmupStage`], running `warmup` until the corpus holds
+    /// `min_corpus_before_havoc` testcases, and `stage` from then on
+    #[must_use]
+    pub fn new(min_corpus_before_havoc: usize, warmup: WS, stage: ST) -> Self {
+        Self {
+            min_corpus_before_havoc,
+            warmup,
+            stage,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The minimum number of testcases in the corpus before the wrapped stage runs
+    #[must_use]
+    pub fn min_corpus_before_havoc(&self) -> usize {
+        self.min_corpus_before_havoc
+    }
+
+    /// Returns `true` if the corpus is big enough to run the wrapped stage
+    #[must_use]
+    pub fn is_warm(&self, state: &S) -> bool {
+        state.corpus().count() >= self.min_corpus_before_havoc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::{Corpus, InMemoryCorpus, Testcase},
+        inputs::BytesInput,
+        stages::{ClosureStage, Stage, WarmupStage},
+        state::{HasCorpus, StdState},
+        Error,
+    };
+
+    #[test]
+    fn test_warmup_stage() {
+        type State = StdState<
+            InMemoryCorpus<BytesInput>,
+            (),
+            BytesInput,
+            StdRand,
+            InMemoryCorpus<BytesInput>,
+        >;
+
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        state
+            .corpus_mut()
+            .add(Testcase::new(BytesInput::new(vec![0])))
+            .unwrap();
+
+        let mut havoc_runs = 0;
+        {
+            // Generates one input per run
+            let generating = ClosureStage::new(
+                |_fuzzer: &mut (),
+                 _executor: &mut (),
+                 state: &mut State,
+                 _manager: &mut (),
+                 _corpus_idx: usize| {
+                    let count = state.corpus().count() as u8;
+                    state
+                        .corpus_mut()
+                        .add(Testcase::new(BytesInput::new(vec![count])))?;
+                    Ok::<(), Error>(())
+                },
+            );
+            let havoc = ClosureStage::new(
+                |_fuzzer: &mut (),
+                 _executor: &mut (),
+                 _state: &mut State,
+                 _manager: &mut (),
+                 _corpus_idx: usize| {
+                    havoc_runs += 1;
+                    Ok::<(), Error>(())
+                },
+            );
+            let mut warmup = WarmupStage::new(3, generating, havoc);
+            for _ in 0..5 {
+                warmup
+                    .perform(&mut (), &mut (), &mut state, &mut (), 0)
+                    .unwrap();
+            }
+            assert!(warmup.is_warm(&state));
+        }
+        // Two runs to grow the corpus from 1 to 3 testcases, then only havoc
+        assert_eq!(state.corpus().count(), 3);
+        assert_eq!(havoc_runs, 3);
+    }
+}
@@ -1,10 +1,15 @@
 //! The fuzzer, and state are the core pieces of every good fuzzer
 
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
 use core::{fmt::Debug, marker::PhantomData, time::Duration};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 #[cfg(feature = "std")]
 use std::{
     fs,
+    io::Write,
     path::{Path, PathBuf},
 };
 
@@ -13,9 +18,9 @@ use crate::{
         rands::Rand,
         serdeany::{SerdeAny, SerdeAnyMap},
     },
-    corpus::Corpus,
+    corpus::{Corpus, IsFavoredMetadata, OriginMetadata, PowerScheduleTestcaseMetaData},
     events::{Event, EventFirer, LogSeverity},
-    feedbacks::FeedbackStatesTuple,
+    feedbacks::{FeedbackState, FeedbackStatesTuple, MapIndexesMetadata},
     fuzzer::{Evaluator, ExecuteInputResult},
     generators::Generator,
     inputs::Input,
@@ -26,6 +31,24 @@ use crate::{
 /// The maximum size of a testcase
 pub const DEFAULT_MAX_SIZE: usize = 1_048_576;
 
+/// What [`StdState::load_from_directory`] does with seed files larger than
+/// [`HasMaxSize::max_size`]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OversizedSeedPolicy {
+    /// Load them as they are, the default
+    Load,
+    /// Skip them, with a warning
+    Skip,
+    /// Load them, and truncate them to `max_size` with [`Input::truncate`]
+    Truncate,
+}
+
+impl Default for OversizedSeedPolicy {
+    fn default() -> Self {
+        Self::Load
+    }
+}
+
 /// The [`State`] of the fuzzer
 /// Contains all important information about the current run
 /// Will be used to restart the fuzzing process at any timme.
@@ -125,6 +148,25 @@ where
 
     /// The feedback states (mut)
     fn feedback_states_mut(&mut self) -> &mut FT;
+
+    /// Resets all the feedback states, for example at the start of an epoch, to measure the
+    /// discovery rate again. The corpus is kept, but the feedbacks forget what they have seen:
+    /// inputs that were known before count as interesting again.
+    fn reset_feedback_states(&mut self) -> Result<(), Error> {
+        self.feedback_states_mut().reset_all()
+    }
+
+    /// Resets the feedback state of type `T` with the given name, see
+    /// [`HasFeedbackStates::reset_feedback_states`]
+    fn reset_feedback_state<T>(&mut self, name: &str) -> Result<(), Error>
+    where
+        T: FeedbackState,
+    {
+        self.feedback_states_mut()
+            .match_name_mut::<T>(name)
+            .ok_or_else(|| Error::KeyNotFound(format!("No feedback state named {}", name)))?
+            .reset()
+    }
 }
 
 /// Trait for the execution counter
@@ -172,6 +214,8 @@ where
     metadata: SerdeAnyMap,
     /// MaxSize testcase size for mutators that appreciate it
     max_size: usize,
+    /// What to do with seed files larger than `max_size`
+    oversized_seeds: OversizedSeedPolicy,
     /// The stability of the current fuzzing process
     stability: Option<f32>,
 
@@ -356,6 +400,135 @@ where
     }
 }
 
+/// A row of the corpus report, summarizing the scheduling metadata of a single corpus entry.
+/// Fields are `None` if the corresponding metadata is not tracked in this campaign.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CorpusReportEntry {
+    /// The index of the entry in the corpus
+    pub idx: usize,
+    /// The path depth, from [`PowerScheduleTestcaseMetaData`]
+    pub depth: Option<u64>,
+    /// The execution time, in microseconds
+    pub exec_time_us: Option<u128>,
+    /// How often this entry got fuzzed, from [`PowerScheduleTestcaseMetaData`]
+    pub fuzz_level: Option<u64>,
+    /// If this entry is favored by the minimizer, see [`IsFavoredMetadata`]
+    pub favored: bool,
+    /// The number of map entries this entry discovered, from [`MapIndexesMetadata`]
+    pub discovering_edges: Option<usize>,
+    /// Where this entry came from, see [`OriginMetadata`]
+    pub origin: Option<OriginMetadata>,
+}
+
+impl CorpusReportEntry {
+    /// The columns of the csv corpus report
+    pub const CSV_HEADER: &'static str =
+        "idx,depth,exec_time_us,fuzz_level,favored,discovering_edges,origin";
+
+    /// Formats this entry as a line of the csv corpus report
+    #[must_use]
+    pub fn to_csv_line(&self) -> String {
+        fn opt<T: ToString>(val: Option<T>) -> String {
+            val.map(|val| val.to_string()).unwrap_or_default()
+        }
+        format!(
+            "{},{},{},{},{},{},{}",
+            self.idx,
+            opt(self.depth),
+            opt(self.exec_time_us),
+            opt(self.fuzz_level),
+            self.favored,
+            opt(self.discovering_edges),
+            opt(self.origin)
+        )
+    }
+}
+
+/// How many corpus entries came from each origin, see [`OriginMetadata`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OriginBreakdown {
+    /// Entries loaded from disk
+    pub imported: usize,
+    /// Entries produced by a generator
+    pub generated: usize,
+    /// Entries produced by mutation
+    pub mutated: usize,
+    /// Entries without [`OriginMetadata`], for example received from other nodes
+    pub unknown: usize,
+}
+
+impl<C, FT, I, R, SC> StdState<C, FT, I, R, SC>
+where
+    C: Corpus<I>,
+    I: Input,
+    R: Rand,
+    FT: FeedbackStatesTuple,
+    SC: Corpus<I>,
+{
+    /// Counts the corpus entries per [`OriginMetadata`], answering how many finds came from seeds
+    pub fn origin_breakdown(&self) -> Result<OriginBreakdown, Error> {
+        let mut breakdown = OriginBreakdown::default();
+        for idx in 0..self.corpus().count() {
+            match self
+                .corpus()
+                .get(idx)?
+                .borrow()
+                .metadata()
+                .get::<OriginMetadata>()
+            {
+                Some(OriginMetadata::Imported) => breakdown.imported += 1,
+                Some(OriginMetadata::Generated) => breakdown.generated += 1,
+                Some(OriginMetadata::Mutated) => breakdown.mutated += 1,
+                None => breakdown.unknown += 1,
+            }
+        }
+        Ok(breakdown)
+    }
+
+    /// Collects the scheduling metadata of all entries in the corpus, for offline analysis
+    pub fn corpus_report(&self) -> Result<Vec<CorpusReportEntry>, Error> {
+        (0..self.corpus().count())
+            .map(|idx| {
+                let testcase = self.corpus().get(idx)?.borrow();
+                let power = testcase.metadata().get::<PowerScheduleTestcaseMetaData>();
+                Ok(CorpusReportEntry {
+                    idx,
+                    depth: power.map(PowerScheduleTestcaseMetaData::depth),
+                    exec_time_us: testcase.exec_time().map(|time| time.as_micros()),
+                    fuzz_level: power.map(PowerScheduleTestcaseMetaData::fuzz_level),
+                    favored: testcase.has_metadata::<IsFavoredMetadata>(),
+                    discovering_edges: testcase
+                        .metadata()
+                        .get::<MapIndexesMetadata>()
+                        .map(|meta| meta.list.len()),
+                    origin: testcase.metadata().get::<OriginMetadata>().copied(),
+                })
+            })
+            .collect()
+    }
+
+    /// Writes the [`StdState::corpus_report`] to a file.
+    /// If the file extension is `json`, the report is written as json array, else as csv table.
+    #[cfg(feature = "std")]
+    pub fn export_corpus_report<P>(&self, path: P) -> Result<(), Error>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let report = self.corpus_report()?;
+        let mut file = fs::File::create(path)?;
+        if path.extension().map_or(false, |ext| ext == "json") {
+            serde_json::to_writer_pretty(&mut file, &report)?;
+        } else {
+            writeln!(file, "{}", CorpusReportEntry::CSV_HEADER)?;
+            for entry in &report {
+                writeln!(file, "{}", entry.to_csv_line())?;
+            }
+        }
+        Ok(())
+    }
+}
+
 #[cfg(feature = "std")]
 impl<C, FT, I, R, SC> StdState<C, FT, I, R, SC>
 where
@@ -365,9 +538,21 @@ where
     FT: FeedbackStatesTuple,
     SC: Corpus<I>,
 {
+    /// What [`StdState::load_from_directory`] does with seed files larger than `max_size`
+    #[must_use]
+    pub fn oversized_seed_policy(&self) -> OversizedSeedPolicy {
+        self.oversized_seeds
+    }
+
+    /// Sets what [`StdState::load_from_directory`] does with seed files larger than `max_size`
+    pub fn set_oversized_seed_policy(&mut self, policy: OversizedSeedPolicy) {
+        self.oversized_seeds = policy;
+    }
+
     /// loads inputs from a directory
     /// If `forced` is `true`, the value will be loaded,
     /// even if it's not considered to be `interesting`.
+    /// Files larger than `max_size` are handled according to the [`OversizedSeedPolicy`].
     pub fn load_from_directory<E, EM, Z>(
         &mut self,
         fuzzer: &mut Z,
@@ -392,16 +577,29 @@ where
             let attr = attributes?;
 
             if attr.is_file() && attr.len() > 0 {
+                let oversized = attr.len() > self.max_size as u64;
+                if oversized && self.oversized_seeds == OversizedSeedPolicy::Skip {
+                    println!(
+                        "Warning: file {:?} is larger than the max size of {} bytes, skipped.",
+                        &path, self.max_size
+                    );
+                    continue;
+                }
                 println!("Loading file {:?} ...", &path);
-                let input = loader(fuzzer, self, &path)?;
-                if forced {
-                    let _ = fuzzer.add_input(self, executor, manager, input)?;
+                let mut input = loader(fuzzer, self, &path)?;
+                if oversized && self.oversized_seeds == OversizedSeedPolicy::Truncate {
+                    input.truncate(self.max_size);
+                }
+                let idx = if forced {
+                    Some(fuzzer.add_input(self, executor, manager, input)?)
                 } else {
-                    let (res, _) = fuzzer.evaluate_input(self, executor, manager, input)?;
+                    let (res, idx) = fuzzer.evaluate_input(self, executor, manager, input)?;
                     if res == ExecuteInputResult::None {
                         println!("File {:?} was not interesting, skipped.", &path);
                     }
-                }
+                    idx
+                };
+                OriginMetadata::Imported.tag(self.corpus(), idx)?;
             } else if attr.is_dir() {
                 self.load_from_directory(fuzzer, executor, manager, &path, forced, loader)?;
             }
@@ -503,15 +701,17 @@ where
         let mut added = 0;
         for _ in 0..num {
             let input = generator.generate(self)?;
-            if forced {
-                let _ = fuzzer.add_input(self, executor, manager, input)?;
+            let idx = if forced {
                 added += 1;
+                Some(fuzzer.add_input(self, executor, manager, input)?)
             } else {
-                let (res, _) = fuzzer.evaluate_input(self, executor, manager, input)?;
+                let (res, idx) = fuzzer.evaluate_input(self, executor, manager, input)?;
                 if res != ExecuteInputResult::None {
                     added += 1;
                 }
-            }
+                idx
+            };
+            OriginMetadata::Generated.tag(self.corpus(), idx)?;
         }
         manager.fire(
             self,
@@ -570,6 +770,7 @@ where
             feedback_states,
             solutions,
             max_size: DEFAULT_MAX_SIZE,
+            oversized_seeds: OversizedSeedPolicy::default(),
             #[cfg(feature = "introspection")]
             introspection_monitor: ClientPerfMonitor::new(),
             phantom: PhantomData,
@@ -636,3 +837,177 @@ where
         &mut self.stability
     }
 }
+
+#[cfg(feature = "std")]
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::{
+            Corpus, InMemoryCorpus, IsFavoredMetadata, OriginMetadata,
+            PowerScheduleTestcaseMetaData, QueueCorpusScheduler, Testcase,
+        },
+        events::NopEventManager,
+        executors::NopExecutor,
+        feedbacks::{CrashFeedback, MapIndexesMetadata, NotFeedback},
+        fuzzer::bytes_fuzzer,
+        generators::RandBytesGenerator,
+        inputs::{BytesInput, HasBytesVec},
+        mutators::{havoc_mutations, StdScheduledMutator},
+        stages::{Stage, StdMutationalStage},
+        state::{
+            CorpusReportEntry, HasCorpus, HasMaxSize, HasMetadata, OriginBreakdown,
+            OversizedSeedPolicy, StdState,
+        },
+    };
+
+    #[test]
+    fn test_origin_metadata() {
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        // Every input that does not crash is interesting
+        let mut fuzzer = bytes_fuzzer::<_, _, _, (), _>(
+            QueueCorpusScheduler::new(),
+            NotFeedback::new(CrashFeedback::new()),
+            CrashFeedback::new(),
+        );
+        let mut executor = NopExecutor::new();
+        let mut mgr = NopEventManager {};
+
+        let dir = env::temp_dir().join(format!("libafl_origin_seeds_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("seed"), b"seed").unwrap();
+        state
+            .load_initial_inputs(
+                &mut fuzzer,
+                &mut executor,
+                &mut mgr,
+                core::slice::from_ref(&dir),
+            )
+            .unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let mut generator = RandBytesGenerator::new(8);
+        state
+            .generate_initial_inputs(&mut fuzzer, &mut executor, &mut generator, &mut mgr, 2)
+            .unwrap();
+
+        let mut mutational = StdMutationalStage::new(StdScheduledMutator::new(havoc_mutations()));
+        mutational
+            .perform(&mut fuzzer, &mut executor, &mut state, &mut mgr, 0)
+            .unwrap();
+
+        let origin = |idx| {
+            *state
+                .corpus()
+                .get(idx)
+                .unwrap()
+                .borrow()
+                .metadata()
+                .get::<OriginMetadata>()
+                .unwrap()
+        };
+        assert_eq!(origin(0), OriginMetadata::Imported);
+        assert_eq!(origin(1), OriginMetadata::Generated);
+        assert_eq!(origin(2), OriginMetadata::Generated);
+        let num_mutated = state.corpus().count() - 3;
+        assert!(num_mutated > 0);
+        for idx in 3..state.corpus().count() {
+            assert_eq!(origin(idx), OriginMetadata::Mutated);
+        }
+        assert_eq!(
+            state.origin_breakdown().unwrap(),
+            OriginBreakdown {
+                imported: 1,
+                generated: 2,
+                mutated: num_mutated,
+                unknown: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_oversized_seed_policy() {
+        let dir = env::temp_dir().join(format!("libafl_oversized_seeds_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("small"), b"seed").unwrap();
+        fs::write(dir.join("big"), [b'A'; 64]).unwrap();
+
+        let load = |policy| {
+            let mut state = StdState::new(
+                StdRand::with_seed(0),
+                InMemoryCorpus::<BytesInput>::new(),
+                InMemoryCorpus::new(),
+                (),
+            );
+            state.set_max_size(16);
+            state.set_oversized_seed_policy(policy);
+            let mut fuzzer = bytes_fuzzer::<_, _, _, (), _>(QueueCorpusScheduler::new(), (), ());
+            state
+                .load_initial_inputs_forced(
+                    &mut fuzzer,
+                    &mut NopExecutor::new(),
+                    &mut NopEventManager {},
+                    core::slice::from_ref(&dir),
+                )
+                .unwrap();
+            let mut lens: Vec<usize> = (0..state.corpus().count())
+                .map(|idx| {
+                    let testcase = state.corpus().get(idx).unwrap().borrow();
+                    testcase.input().as_ref().unwrap().bytes().len()
+                })
+                .collect();
+            lens.sort_unstable();
+            lens
+        };
+
+        assert_eq!(load(OversizedSeedPolicy::Load), [4, 64]);
+        // The oversized seed is skipped
+        assert_eq!(load(OversizedSeedPolicy::Skip), [4]);
+        assert_eq!(load(OversizedSeedPolicy::Truncate), [4, 16]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_export_corpus_report() {
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+
+        let mut testcase = Testcase::new(BytesInput::new(vec![1, 2, 3]));
+        let mut power = PowerScheduleTestcaseMetaData::new(2);
+        power.set_fuzz_level(7);
+        testcase.add_metadata(power);
+        testcase.add_metadata(IsFavoredMetadata {});
+        testcase.add_metadata(MapIndexesMetadata::new(vec![1, 5, 9]));
+        state.corpus_mut().add(testcase).unwrap();
+        state
+            .corpus_mut()
+            .add(Testcase::new(BytesInput::new(vec![4])))
+            .unwrap();
+
+        let path = env::temp_dir().join(format!("libafl_corpus_report_{}.csv", std::process::id()));
+        state.export_corpus_report(&path).unwrap();
+        let csv = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                CorpusReportEntry::CSV_HEADER,
+                "0,2,,7,true,3,",
+                "1,,,,false,,"
+            ]
+        );
+    }
+}
@@ -6,6 +6,7 @@ use nix::{
 };
 
 use backtrace::Backtrace;
+use libafl::SerdeAny;
 #[cfg(any(
     target_os = "linux",
     all(target_arch = "aarch64", target_os = "android")
@@ -42,6 +43,7 @@ pub struct Allocator {
     total_allocation_size: usize,
     base_mapping_addr: usize,
     current_mapping_addr: usize,
+    stats: AllocationStats,
 }
 
 #[cfg(target_vendor = "apple")]
@@ -55,6 +57,21 @@ macro_rules! map_to_shadow {
     };
 }
 
+/// Allocation counters for a single run of the target, see [`Allocator::stats`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, SerdeAny)]
+pub struct AllocationStats {
+    /// The number of allocations served
+    pub allocations: usize,
+    /// The sum of the requested allocation sizes, in bytes
+    pub total_bytes: usize,
+}
+
+/// The [`AllocationStats`] of the last finished run, published by [`Allocator::reset`]
+pub static mut ALLOCATION_STATS: AllocationStats = AllocationStats {
+    allocations: 0,
+    total_bytes: 0,
+};
+
 /// Metadata for an allocation
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[allow(missing_docs)]
@@ -179,6 +196,7 @@ impl Allocator {
             total_allocation_size: 0,
             base_mapping_addr: addr + addr + addr,
             current_mapping_addr: addr + addr + addr,
+            stats: AllocationStats::default(),
         }
     }
 
@@ -289,9 +307,16 @@ impl Allocator {
             size,
         );
         let address = (metadata.address + self.page_size) as *mut c_void;
+        if let Some(fill) = self.options.asan_alloc_fill() {
+            memset(address, fill.into(), size);
+        }
 
         self.allocations
             .insert(metadata.address + self.page_size, metadata);
+        self.stats.allocations += 1;
+        if !is_malloc_zero {
+            self.stats.total_bytes += size;
+        }
         //println!("serving address: {:?}, size: {:x}", address, size);
         address
     }
@@ -324,6 +349,10 @@ impl Allocator {
             metadata.release_site_backtrace = Some(Backtrace::new_unresolved());
         }
 
+        if let Some(fill) = self.options.asan_free_fill() {
+            memset(ptr, fill.into(), metadata.size);
+        }
+
         // poison the shadow memory for the allocation
         Self::poison(shadow_mapping_start, metadata.size);
     }
@@ -385,6 +414,18 @@ impl Allocator {
         }
 
         self.total_allocation_size = 0;
+
+        // Publish the stats of the finished run, for the `AllocCountObserver`
+        unsafe {
+            ALLOCATION_STATS = self.stats;
+        }
+        self.stats = AllocationStats::default();
+    }
+
+    /// The [`AllocationStats`] since the last [`Allocator::reset`], i.e. of the current run
+    #[must_use]
+    pub fn stats(&self) -> AllocationStats {
+        self.stats
     }
 
     /// Gets the usable size of the allocation, by allocated pointer
@@ -518,3 +559,29 @@ impl Allocator {
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{ffi::c_void, slice};
+
+    use crate::{alloc::Allocator, FridaOptions};
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_alloc_fill_pattern() {
+        let mut allocator = Allocator::new(FridaOptions {
+            asan_alloc_fill: Some(0xbe),
+            asan_free_fill: Some(0xde),
+            ..FridaOptions::default()
+        });
+
+        unsafe {
+            let ptr = allocator.alloc(64, 8) as *mut u8;
+            assert!(!ptr.is_null());
+            assert!(slice::from_raw_parts(ptr, 64).iter().all(|b| *b == 0xbe));
+
+            allocator.release(ptr as *mut c_void);
+            assert!(slice::from_raw_parts(ptr, 64).iter().all(|b| *b == 0xde));
+        }
+    }
+}
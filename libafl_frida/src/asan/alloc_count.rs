@@ -0,0 +1,213 @@
+//! Observe the number of allocations per run, to find allocation-amplification bugs.
+use libafl::{
+    bolts::{ownedref::OwnedPtr, tuples::Named},
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::Feedback,
+    inputs::Input,
+    observers::{Observer, ObserversTuple},
+    state::{HasClientPerfMonitor, HasMetadata},
+    Error,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::alloc::{AllocationStats, ALLOCATION_STATS};
+
+/// An observer for the [`AllocationStats`] of the frida [`crate::alloc::Allocator`] for a run
+#[derive(Debug, Serialize, Deserialize)]
+#[allow(clippy::unsafe_derive_deserialize)]
+pub struct AllocCountObserver {
+    stats: OwnedPtr<AllocationStats>,
+    last_stats: AllocationStats,
+}
+
+impl<I, S> Observer<I, S> for AllocCountObserver {
+    fn pre_exec(&mut self, _state: &mut S, _input: &I) -> Result<(), Error> {
+        self.last_stats = AllocationStats::default();
+        Ok(())
+    }
+
+    fn post_exec(&mut self, _state: &mut S, _input: &I) -> Result<(), Error> {
+        self.last_stats = *self.stats.as_ref();
+        Ok(())
+    }
+}
+
+impl Named for AllocCountObserver {
+    #[inline]
+    fn name(&self) -> &str {
+        "AllocCount"
+    }
+}
+
+impl AllocCountObserver {
+    /// Creates a new `AllocCountObserver`, reading the stats the allocator publishes after each run
+    #[must_use]
+    pub fn new() -> Self {
+        Self::new_from_ptr(unsafe { &ALLOCATION_STATS as *const AllocationStats })
+    }
+
+    /// Creates a new `AllocCountObserver`, reading the [`AllocationStats`] from a raw ptr
+    #[must_use]
+    pub fn new_from_ptr(stats: *const AllocationStats) -> Self {
+        Self {
+            stats: OwnedPtr::Ptr(stats),
+            last_stats: AllocationStats::default(),
+        }
+    }
+
+    /// Gets the [`AllocationStats`] of the previous run
+    #[must_use]
+    pub fn stats(&self) -> &AllocationStats {
+        &self.last_stats
+    }
+}
+
+impl Default for AllocCountObserver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A feedback reporting runs with more allocations, or more allocated bytes,
+/// than the configured thresholds, based on an [`AllocCountObserver`].
+/// Use it as objective to catch resource-exhaustion bugs.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AllocCountFeedback {
+    max_allocations: usize,
+    max_total_bytes: Option<usize>,
+    stats: Option<AllocationStats>,
+}
+
+impl<I, S> Feedback<I, S> for AllocCountFeedback
+where
+    I: Input,
+    S: HasClientPerfMonitor,
+{
+    fn is_interesting<EM, OT>(
+        &mut self,
+        _state: &mut S,
+        _manager: &mut EM,
+        _input: &I,
+        observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<I>,
+        OT: ObserversTuple<I, S>,
+    {
+        let observer = observers
+            .match_name::<AllocCountObserver>("AllocCount")
+            .expect("An AllocCountFeedback needs an AllocCountObserver");
+        let stats = *observer.stats();
+        if self.exceeds_thresholds(&stats) {
+            self.stats = Some(stats);
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn append_metadata(&mut self, _state: &mut S, testcase: &mut Testcase<I>) -> Result<(), Error> {
+        if let Some(stats) = self.stats.take() {
+            testcase.add_metadata(stats);
+        }
+
+        Ok(())
+    }
+
+    fn discard_metadata(&mut self, _state: &mut S, _input: &I) -> Result<(), Error> {
+        self.stats = None;
+        Ok(())
+    }
+}
+
+impl Named for AllocCountFeedback {
+    #[inline]
+    fn name(&self) -> &str {
+        "AllocCount"
+    }
+}
+
+impl AllocCountFeedback {
+    /// Create a new `AllocCountFeedback`, reporting runs with more than `max_allocations` allocations
+    #[must_use]
+    pub fn new(max_allocations: usize) -> Self {
+        Self {
+            max_allocations,
+            max_total_bytes: None,
+            stats: None,
+        }
+    }
+
+    /// Additionally report runs allocating more than `max_total_bytes` bytes in total
+    #[must_use]
+    pub fn with_max_total_bytes(mut self, max_total_bytes: usize) -> Self {
+        self.max_total_bytes = Some(max_total_bytes);
+        self
+    }
+
+    /// Returns true if the given [`AllocationStats`] cross one of the thresholds
+    #[must_use]
+    pub fn exceeds_thresholds(&self, stats: &AllocationStats) -> bool {
+        stats.allocations > self.max_allocations
+            || self
+                .max_total_bytes
+                .map_or(false, |max_total_bytes| stats.total_bytes > max_total_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use libafl::{
+        bolts::{rands::StdRand, tuples::tuple_list},
+        corpus::InMemoryCorpus,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::Feedback,
+        inputs::BytesInput,
+        observers::Observer,
+        state::StdState,
+    };
+
+    use crate::{
+        alloc::AllocationStats,
+        asan::alloc_count::{AllocCountFeedback, AllocCountObserver},
+    };
+
+    #[test]
+    fn test_alloc_count_threshold() {
+        // A mock stats source, in place of the allocator
+        let mut mock_stats = AllocationStats::default();
+        let mock_stats_ptr = &mut mock_stats as *mut AllocationStats;
+        let mut observers = tuple_list!(AllocCountObserver::new_from_ptr(mock_stats_ptr));
+        let mut feedback = AllocCountFeedback::new(1000);
+
+        let mut mgr = NopEventManager {};
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            (),
+        );
+        let input = BytesInput::new(vec![]);
+
+        for (allocations, expected) in [(10, false), (1000, false), (1001, true)] {
+            observers.0.pre_exec(&mut state, &input).unwrap();
+            unsafe {
+                mock_stats_ptr.write(AllocationStats {
+                    allocations,
+                    total_bytes: allocations * 16,
+                });
+            }
+            observers.0.post_exec(&mut state, &input).unwrap();
+            assert_eq!(
+                feedback
+                    .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                    .unwrap(),
+                expected
+            );
+        }
+    }
+}
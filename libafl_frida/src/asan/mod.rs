@@ -1,4 +1,5 @@
 //! Address sanitization using [`frida`](https://frida.re/)
+pub mod alloc_count;
 pub mod asan_rt;
 pub mod errors;
 #[allow(missing_docs)]
@@ -84,6 +84,8 @@ pub struct FridaOptions {
     asan_max_allocation: usize,
     asan_max_total_allocation: usize,
     asan_max_allocation_panics: bool,
+    asan_alloc_fill: Option<u8>,
+    asan_free_fill: Option<u8>,
     enable_coverage: bool,
     enable_drcov: bool,
     instrument_suppress_locations: Option<Vec<(String, usize)>>,
@@ -131,6 +133,14 @@ impl FridaOptions {
                     "asan-max-allocation-panics" => {
                         options.asan_max_allocation_panics = value.parse().unwrap();
                     }
+                    "asan-alloc-fill" => {
+                        options.asan_alloc_fill =
+                            Some(u8::from_str_radix(value.trim_start_matches("0x"), 16).unwrap());
+                    }
+                    "asan-free-fill" => {
+                        options.asan_free_fill =
+                            Some(u8::from_str_radix(value.trim_start_matches("0x"), 16).unwrap());
+                    }
                     "asan-cores" => {
                         asan_cores = Cores::from_cmdline(value).ok();
                     }
@@ -271,6 +281,22 @@ impl FridaOptions {
         self.asan_max_allocation_panics
     }
 
+    /// The byte ASAN fills newly allocated memory with, to surface uses of uninitialized memory.
+    /// Set with `asan-alloc-fill=be`, as hex byte. `None` (the default) leaves the memory as is.
+    #[must_use]
+    #[inline]
+    pub fn asan_alloc_fill(&self) -> Option<u8> {
+        self.asan_alloc_fill
+    }
+
+    /// The byte ASAN fills freed memory with, to surface uses after free.
+    /// Set with `asan-free-fill=de`, as hex byte. `None` (the default) leaves the memory as is.
+    #[must_use]
+    #[inline]
+    pub fn asan_free_fill(&self) -> Option<u8> {
+        self.asan_free_fill
+    }
+
     /// Should ASAN continue after a memory error is detected
     #[must_use]
     #[inline]
@@ -310,6 +336,8 @@ impl Default for FridaOptions {
             asan_max_allocation: 1 << 30,
             asan_max_total_allocation: 1 << 32,
             asan_max_allocation_panics: false,
+            asan_alloc_fill: None,
+            asan_free_fill: None,
             enable_coverage: true,
             enable_drcov: false,
             instrument_suppress_locations: None,
@@ -6,7 +6,7 @@ use core::fmt::{self, Debug, Formatter};
 
 use libafl::{
     bolts::{ownedref::OwnedRefMut, tuples::Named},
-    observers::{CmpMap, CmpObserver, CmpValues, Observer},
+    observers::{CmpMap, CmpObserver, CmpValues, CmpValuesCap, Observer},
     state::HasMetadata,
     Error,
 };
@@ -172,6 +172,7 @@ pub struct CmpLogObserver<'a> {
     map: OwnedRefMut<'a, CmpLogMap>,
     size: Option<OwnedRefMut<'a, usize>>,
     add_meta: bool,
+    cap: Option<CmpValuesCap>,
     name: String,
 }
 
@@ -194,6 +195,10 @@ where
     fn map_mut(&mut self) -> &mut CmpLogMap {
         self.map.as_mut()
     }
+
+    fn cmpvalues_cap(&self) -> Option<CmpValuesCap> {
+        self.cap
+    }
 }
 
 impl<'a, I, S> Observer<I, S> for CmpLogObserver<'a>
@@ -234,6 +239,25 @@ impl<'a> CmpLogObserver<'a> {
             name: name.to_string(),
             size: None,
             add_meta,
+            cap: None,
+            map: OwnedRefMut::Ref(map),
+        }
+    }
+
+    /// Creates a new [`CmpLogObserver`] with the given name,
+    /// recording at most `cap` comparisons per run in the `CmpValuesMetadata`.
+    #[must_use]
+    pub fn with_cmpvalues_cap(
+        name: &'static str,
+        map: &'a mut CmpLogMap,
+        add_meta: bool,
+        cap: CmpValuesCap,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            size: None,
+            add_meta,
+            cap: Some(cap),
             map: OwnedRefMut::Ref(map),
         }
     }